@@ -1,19 +1,44 @@
 use crate::config::config::Config;
+use crate::identity::identity_provider::IdentityProvider;
+use crate::notification::webhook_target::WebhookTargetPolicy;
+use crate::service::approval_service::ApprovalService;
+use crate::service::audit_service::AuditService;
+use crate::service::backup_service::BackupService;
 use crate::service::certificate_service::CertificateService;
 use crate::service::client_service::ClientService;
-use crate::service::keycloak_service::KeycloakService;
+use crate::service::enrollment_token_service::EnrollmentTokenService;
+use crate::service::health_service::HealthService;
+use crate::service::metrics_service::MetricsService;
+use crate::service::quota_service::QuotaService;
 use crate::service::root_certificate_service::RootCertificateService;
 use crate::service::signing_request_service::SigningRequestService;
+use crate::service::team_service::TeamService;
+use crate::service::token_key_service::TokenKeyService;
 use crate::service::token_service::TokenService;
+use crate::service::transparency_log_service::TransparencyLogService;
 use crate::service::user_service::UserService;
+use crate::service::webhook_service::WebhookService;
+use std::sync::Arc;
 
 pub struct AppState {
     pub config: Config,
-    pub keycloak_service: KeycloakService,
+    pub identity_provider: Arc<dyn IdentityProvider>,
+    pub webhook_target_policy: WebhookTargetPolicy,
     pub client_service: ClientService,
     pub user_service: UserService,
     pub signing_request_service: SigningRequestService,
     pub token_service: TokenService,
     pub certificate_service: CertificateService,
     pub root_certificate_service: RootCertificateService,
+    pub webhook_service: WebhookService,
+    pub audit_service: AuditService,
+    pub transparency_log_service: TransparencyLogService,
+    pub metrics_service: MetricsService,
+    pub health_service: HealthService,
+    pub quota_service: QuotaService,
+    pub approval_service: ApprovalService,
+    pub token_key_service: TokenKeyService,
+    pub enrollment_token_service: EnrollmentTokenService,
+    pub backup_service: BackupService,
+    pub team_service: TeamService,
 }
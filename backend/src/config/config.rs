@@ -1,6 +1,10 @@
+use crate::entity::token_key::TokenKeyAlgorithm;
+use crate::identity::identity_provider::IdentityProviderType;
+use crate::notification::webhook_target::IpNetwork;
 use dotenv::dotenv;
 use envconfig::Envconfig;
 use log::warn;
+use shared::util::key_algorithm::KeyAlgorithm;
 use std::error::Error;
 
 #[derive(Debug, Clone, Envconfig)]
@@ -11,36 +15,64 @@ pub struct Config {
     pub log_level: String,
     #[envconfig(from = "ENABLE_SWAGGER", default = "false")]
     pub enable_swagger: bool,
+    #[envconfig(from = "ENABLE_METRICS", default = "true")]
+    pub enable_metrics: bool,
+    /// The secret client tokens were signed with before asymmetric token keys
+    /// were introduced. Tokens without a key id are rejected if this is not set.
     #[envconfig(from = "JWT_SECRET")]
-    pub jwt_secret: String,
+    pub jwt_secret: Option<String>,
     #[envconfig(from = "JWT_EXPIRES_IN")]
     pub jwt_expires_in: String,
     #[envconfig(from = "JWT_MAX_AGE")]
     pub jwt_max_age: i32,
+    /// The full database url, e.g. `sqlite://ca.db?mode=rwc` or `sqlite::memory:`.
+    /// Overrides all other `DB_` connection settings if set.
+    #[envconfig(from = "DB_URL")]
+    pub db_url: Option<String>,
     #[envconfig(from = "DB_VENDOR", default = "postgres")]
     pub db_vendor: String,
     #[envconfig(from = "DB_HOST")]
-    pub db_host: String,
+    pub db_host: Option<String>,
     #[envconfig(from = "DB_PORT", default = "5432")]
     pub db_port: String,
     #[envconfig(from = "DB_NAME", default = "ca")]
     pub db_name: String,
     #[envconfig(from = "DB_USER")]
-    pub db_user: String,
+    pub db_user: Option<String>,
     #[envconfig(from = "DB_PASSWORD")]
-    pub db_password: String,
+    pub db_password: Option<String>,
+    /// Whether pending database migrations are applied when the server starts
+    #[envconfig(from = "DB_MIGRATE_ON_STARTUP", default = "true")]
+    pub db_migrate_on_startup: bool,
+    /// The identity provider users authenticate with, either keycloak or oidc
+    #[envconfig(from = "IDENTITY_PROVIDER", default = "keycloak")]
+    pub identity_provider: String,
     #[envconfig(from = "KEYCLOAK_URL")]
-    pub keycloak_url: String,
+    pub keycloak_url: Option<String>,
     #[envconfig(from = "KEYCLOAK_USER")]
-    pub keycloak_user: String,
+    pub keycloak_user: Option<String>,
     #[envconfig(from = "KEYCLOAK_PASSWORD")]
-    pub keycloak_password: String,
+    pub keycloak_password: Option<String>,
     #[envconfig(from = "KEYCLOAK_REALM", default = "ca")]
     pub keycloak_realm: String,
     #[envconfig(from = "KEYCLOAK_INIT_REALM", default = "true")]
     pub keycloak_init_realm: bool,
     #[envconfig(from = "KEYCLOAK_DEFAULT_EMAIL_VERIFIED", default = "true")]
     pub keycloak_default_email_verified: bool,
+    /// The issuer of the tokens if the oidc identity provider is used.
+    /// The provider is configured using its discovery document.
+    #[envconfig(from = "OIDC_ISSUER_URL")]
+    pub oidc_issuer_url: Option<String>,
+    /// The audience tokens must be issued for, usually the client id of the CA.
+    /// Required when using the oidc identity provider.
+    #[envconfig(from = "OIDC_AUDIENCE")]
+    pub oidc_audience: Option<String>,
+    /// The claim containing the name of the user
+    #[envconfig(from = "OIDC_USERNAME_CLAIM", default = "preferred_username")]
+    pub oidc_username_claim: String,
+    /// The claim containing the roles of the user, nested claims are separated by dots
+    #[envconfig(from = "OIDC_ROLES_CLAIM", default = "roles")]
+    pub oidc_roles_claim: String,
     #[envconfig(from = "ADMIN_USER", default = "admin")]
     pub admin_user: String,
     #[envconfig(from = "ADMIN_PASSWORD", default = "admin")]
@@ -68,6 +100,95 @@ pub struct Config {
     /// The number of days a certificate signed by this CA is valid
     #[envconfig(from = "CERT_VALIDITY_DAYS", default = "31")]
     pub cert_validity_days: u32,
+    /// The number of seconds between two expiry checks
+    #[envconfig(from = "NOTIFICATION_INTERVAL_SECONDS", default = "3600")]
+    pub notification_interval_seconds: u64,
+    /// The number of days before expiry at which notifications are sent,
+    /// separated by commas
+    #[envconfig(from = "NOTIFICATION_THRESHOLDS_DAYS", default = "30,7,1")]
+    pub notification_thresholds_days: String,
+    /// The number of times the delivery of a notification is attempted
+    #[envconfig(from = "NOTIFICATION_MAX_ATTEMPTS", default = "5")]
+    pub notification_max_attempts: i32,
+    /// The url expiry notifications are posted to.
+    /// Webhook notifications are disabled if this is not set.
+    #[envconfig(from = "NOTIFICATION_WEBHOOK_URL")]
+    pub notification_webhook_url: Option<String>,
+    /// The smtp server used to send expiry notifications.
+    /// Email notifications are disabled if this is not set.
+    #[envconfig(from = "SMTP_HOST")]
+    pub smtp_host: Option<String>,
+    #[envconfig(from = "SMTP_PORT", default = "25")]
+    pub smtp_port: u16,
+    /// Whether to use STARTTLS when connecting to the smtp server
+    #[envconfig(from = "SMTP_STARTTLS", default = "false")]
+    pub smtp_starttls: bool,
+    #[envconfig(from = "SMTP_USER")]
+    pub smtp_user: Option<String>,
+    #[envconfig(from = "SMTP_PASSWORD")]
+    pub smtp_password: Option<String>,
+    #[envconfig(from = "SMTP_FROM", default = "CA <ca@localhost>")]
+    pub smtp_from: String,
+    /// The recipients of expiry notifications, separated by commas
+    #[envconfig(from = "SMTP_TO", default = "")]
+    pub smtp_to: String,
+    /// The number of seconds between two webhook delivery runs
+    #[envconfig(from = "WEBHOOK_DISPATCH_INTERVAL_SECONDS", default = "5")]
+    pub webhook_dispatch_interval_seconds: u64,
+    /// The number of times the delivery of a webhook event is attempted
+    #[envconfig(from = "WEBHOOK_MAX_ATTEMPTS", default = "8")]
+    pub webhook_max_attempts: i32,
+    /// The delay before the first retry of a failed webhook delivery.
+    /// The delay is doubled with every further attempt.
+    #[envconfig(from = "WEBHOOK_RETRY_BASE_SECONDS", default = "30")]
+    pub webhook_retry_base_seconds: i64,
+    /// The number of seconds after which a webhook request times out
+    #[envconfig(from = "WEBHOOK_TIMEOUT_SECONDS", default = "10")]
+    pub webhook_timeout_seconds: u64,
+    /// The networks webhook events may be delivered to in addition to public addresses,
+    /// in CIDR notation and separated by commas, e.g. `10.0.0.0/8,fd00::/8`.
+    /// Loopback, private and link-local addresses are rejected unless listed here.
+    #[envconfig(from = "WEBHOOK_ALLOWED_NETWORKS", default = "")]
+    pub webhook_allowed_networks: String,
+    /// The number of days before expiry at which the readiness
+    /// check reports a CA certificate as degraded
+    #[envconfig(from = "HEALTH_EXPIRY_WARNING_DAYS", default = "14")]
+    pub health_expiry_warning_days: i64,
+    /// The length of the window certificate issuances are counted in
+    #[envconfig(from = "RATE_LIMIT_WINDOW_SECONDS", default = "3600")]
+    pub rate_limit_window_seconds: i64,
+    /// The number of certificates a client may request per window, 0 disables the limit
+    #[envconfig(from = "RATE_LIMIT_CLIENT", default = "0")]
+    pub rate_limit_client: i32,
+    /// The number of certificates the clients of a user may request per window,
+    /// 0 disables the limit
+    #[envconfig(from = "RATE_LIMIT_USER", default = "0")]
+    pub rate_limit_user: i32,
+    /// The number of certificates the CA issues per window, 0 disables the limit
+    #[envconfig(from = "RATE_LIMIT_GLOBAL", default = "0")]
+    pub rate_limit_global: i32,
+    /// The number of active clients a user may own, 0 disables the limit
+    #[envconfig(from = "QUOTA_MAX_CLIENTS_PER_USER", default = "0")]
+    pub quota_max_clients_per_user: i32,
+    /// The number of valid certificates a client may have, 0 disables the limit
+    #[envconfig(from = "QUOTA_MAX_VALID_CERTIFICATES_PER_CLIENT", default = "0")]
+    pub quota_max_valid_certificates_per_client: i32,
+    /// Whether the signing requests of all new clients must be approved by an admin
+    #[envconfig(from = "CLIENT_REQUIRES_APPROVAL", default = "false")]
+    pub client_requires_approval: bool,
+    /// The maximum number of minutes an enrollment token can be redeemed for
+    #[envconfig(from = "ENROLLMENT_TOKEN_TTL_MINUTES", default = "60")]
+    pub enrollment_token_ttl_minutes: i64,
+    /// The maximum size of a backup archive which can be restored
+    #[envconfig(from = "BACKUP_MAX_SIZE_MB", default = "100")]
+    pub backup_max_size_mb: usize,
+    /// The algorithm used to generate key pairs on the server,
+    /// one of ec-p256, ec-p384, rsa-2048 or rsa-4096
+    #[envconfig(from = "KEY_ALGORITHM", default = "ec-p256")]
+    pub key_algorithm: KeyAlgorithm,
+    /// The algorithm new token keys are generated for, either ES256 or EdDSA
+    #[envconfig(from = "TOKEN_KEY_ALGORITHM", default = "ES256")]
+    pub token_key_algorithm: String,
 }
 
 impl Config {
@@ -78,4 +199,77 @@ impl Config {
 
         Config::init_from_env().map_err(|e| e.into())
     }
+
+    pub fn token_key_algorithm(&self) -> Result<TokenKeyAlgorithm, Box<dyn Error>> {
+        match self.token_key_algorithm.to_uppercase().as_str() {
+            "ES256" => Ok(TokenKeyAlgorithm::Es256),
+            "EDDSA" => Ok(TokenKeyAlgorithm::EdDsa),
+            _ => Err(format!(
+                "Unsupported token key algorithm '{}', expected ES256 or EdDSA",
+                self.token_key_algorithm
+            )
+            .into()),
+        }
+    }
+
+    pub fn identity_provider(&self) -> Result<IdentityProviderType, Box<dyn Error>> {
+        match self.identity_provider.to_lowercase().as_str() {
+            "keycloak" => Ok(IdentityProviderType::Keycloak),
+            "oidc" => Ok(IdentityProviderType::Oidc),
+            _ => Err(format!(
+                "Unsupported identity provider '{}', expected keycloak or oidc",
+                self.identity_provider
+            )
+            .into()),
+        }
+    }
+
+    /// Get the url of the database, either `DB_URL` or
+    /// the url built from the other `DB_` settings
+    pub fn database_url(&self) -> Result<String, Box<dyn Error>> {
+        if let Some(url) = &self.db_url {
+            return Ok(url.clone());
+        }
+
+        let required = |value: &Option<String>, name: &str| {
+            value
+                .clone()
+                .ok_or_else(|| format!("Either DB_URL or {} must be set", name))
+        };
+
+        Ok(format!(
+            "{}://{}:{}@{}:{}/{}",
+            self.db_vendor,
+            required(&self.db_user, "DB_USER")?,
+            required(&self.db_password, "DB_PASSWORD")?,
+            required(&self.db_host, "DB_HOST")?,
+            self.db_port,
+            self.db_name
+        ))
+    }
+
+    /// Get the notification thresholds in days, sorted in ascending order
+    pub fn notification_thresholds(&self) -> Result<Vec<i64>, Box<dyn Error>> {
+        let mut thresholds = self
+            .notification_thresholds_days
+            .split(',')
+            .map(|t| t.trim())
+            .filter(|t| !t.is_empty())
+            .map(|t| t.parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        thresholds.sort_unstable();
+        thresholds.dedup();
+        Ok(thresholds)
+    }
+
+    /// Get the networks webhook events may be delivered to in addition to public addresses
+    pub fn webhook_allowed_networks(&self) -> Result<Vec<IpNetwork>, Box<dyn Error>> {
+        self.webhook_allowed_networks
+            .split(',')
+            .map(|n| n.trim())
+            .filter(|n| !n.is_empty())
+            .map(|n| n.parse::<IpNetwork>())
+            .collect()
+    }
 }
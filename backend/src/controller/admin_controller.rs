@@ -1,7 +1,7 @@
 use crate::config::app_state::AppState;
-use crate::middleware::extractors::KeycloakUserClaims;
-use crate::middleware::keycloak_middleware;
-use crate::middleware::keycloak_roles::AdminRole;
+use crate::middleware::extractors::UserClaims;
+use crate::middleware::identity_middleware;
+use crate::middleware::user_roles::AdminRole;
 use crate::register_module;
 use crate::util::types::WebResult;
 use actix_web::get;
@@ -14,6 +14,7 @@ use actix_web::web::{Data, Json};
     operation_id = "listRoles",
     responses(
         (status = 200, description = "Ok", body = Vec<String>),
+        (status = 400, description = "Bad request", body = ErrorDto),
         (status = 401, description = "Unauthorized", body = ErrorDto),
         (status = 424, description = "Failed dependency", body = ErrorDto),
         (status = 500, description = "Internal server error", body = ErrorDto),
@@ -22,23 +23,12 @@ use actix_web::web::{Data, Json};
         ("oauth2" = [])
     )
 )]
-#[get("/admin/roles", wrap = "keycloak_middleware::Keycloak")]
+#[get("/admin/roles", wrap = "identity_middleware::Identity")]
 async fn list_roles(
     data: Data<AppState>,
-    _claims: KeycloakUserClaims<AdminRole>,
+    _claims: UserClaims<AdminRole>,
 ) -> WebResult<Json<Vec<String>>> {
-    Ok(Json(
-        data.keycloak_service
-            .get_roles()
-            .await?
-            .into_iter()
-            .filter(|r| {
-                !r.starts_with("default-roles-")
-                    && r != "uma_authorization"
-                    && r != "offline_access"
-            })
-            .collect(),
-    ))
+    Ok(Json(data.identity_provider.get_roles().await?))
 }
 
 register_module!(list_roles);
@@ -0,0 +1,229 @@
+use crate::config::app_state::AppState;
+use crate::controller::certificate_controller::{notify_issued, sign_certificate};
+use crate::entity::approval_request;
+use crate::entity::approval_request::ApprovalStatus;
+use crate::entity::audit_event::AuditAction;
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::middleware::audit_middleware::{set_audit_target, Audit};
+use crate::middleware::extractors::UserClaims;
+use crate::middleware::identity_middleware;
+use crate::middleware::user_roles::AdminRole;
+use crate::model::approval_request_dto::ApprovalRequestDto;
+use crate::model::page_dto::PageDto;
+use crate::model::reject_approval_dto::RejectApprovalDto;
+use crate::model::webhook_event::WebhookEvent;
+use crate::register_module;
+use crate::util::metrics::CERTIFICATES_ISSUED;
+use crate::util::pagination::Pagination;
+use crate::util::traits::from_model::FromModel;
+use crate::util::types::WebResult;
+use actix_web::web::{Data, Json, Path, Query};
+use actix_web::{get, post, HttpRequest};
+use openssl::x509::X509Req;
+use serde::Deserialize;
+use shared::model::signing_request_dto::SigningRequestDto;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct ApprovalQuery {
+    /// Only return requests with this status.
+    /// Returns requests of all states if not set.
+    #[param(inline)]
+    pub status: Option<ApprovalStatus>,
+    /// The zero-based index of the page to return.
+    /// Defaults to 0.
+    pub page: Option<u64>,
+    /// The number of elements per page.
+    /// Defaults to 50.
+    #[serde(rename = "pageSize")]
+    pub page_size: Option<u64>,
+}
+
+async fn find_request(data: &Data<AppState>, id: i32) -> WebResult<approval_request::Model> {
+    data.approval_service
+        .find_by_id(id)
+        .await?
+        .ok_or(HttpResponseError::not_found(Some(
+            "Approval request not found",
+        )))
+}
+
+/// List signing requests which require approval, oldest first
+#[utoipa::path(
+    get,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "listApprovalRequests",
+    params(ApprovalQuery),
+    responses(
+        (status = 200, description = "Ok", body = ApprovalRequestPageDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get("/admin/approval", wrap = "identity_middleware::Identity")]
+async fn list(
+    data: Data<AppState>,
+    query: Query<ApprovalQuery>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<PageDto<ApprovalRequestDto>>> {
+    let pagination = Pagination::new(query.page, query.page_size)?;
+    let (items, total) = data
+        .approval_service
+        .find_page(query.status, &pagination)
+        .await?;
+
+    Ok(Json(PageDto::new(
+        items
+            .into_iter()
+            .map(ApprovalRequestDto::from_model)
+            .collect(),
+        total,
+        &pagination,
+    )))
+}
+
+/// Approve a signing request and issue the certificate.
+/// Requests must be approved by someone other than the requester,
+/// the owner of the client or the maintainers of the team owning it.
+#[utoipa::path(
+    post,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "approveSigningRequest",
+    params(
+        ("id", description = "Id of the approval request"),
+    ),
+    responses(
+        (status = 200, description = "Ok", body = ApprovalRequestDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post(
+    "/admin/approval/{id}/approve",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::CertificateApprove)"
+)]
+async fn approve(
+    req: HttpRequest,
+    path: Path<i32>,
+    data: Data<AppState>,
+    claims: UserClaims<AdminRole>,
+) -> WebResult<Json<ApprovalRequestDto>> {
+    let model = find_request(&data, path.into_inner()).await?;
+    set_audit_target(&req, format!("approval:{}", model.id));
+    if model.status != ApprovalStatus::Pending {
+        return Err(HttpResponseError::bad_request(Some(
+            "The request has already been decided",
+        )));
+    }
+
+    let client = data
+        .client_service
+        .find_by_id(&model.client_id, false)
+        .await?
+        .ok_or(HttpResponseError::bad_request(Some(
+            "The client is not active",
+        )))?;
+    if model.requested_by == Some(claims.user.id)
+        || client.user_id == claims.user.id
+        || data
+            .team_service
+            .can_manage_client(&client, &claims.user.id)
+            .await?
+    {
+        return Err(HttpResponseError::bad_request(Some(
+            "Requests must be approved by someone other than the requester or the owners of the client",
+        )));
+    }
+
+    let csr = X509Req::from_pem(model.request.as_bytes())
+        .map_internal_error(Some("Failed to parse signing request"))?;
+    let signed = sign_certificate(
+        &data,
+        Some(&client),
+        model.requested_by,
+        &csr,
+        &model.alternative_names(),
+    )
+    .await?;
+    let (model, issued) = data
+        .approval_service
+        .approve(model.id, &claims.user.id, signed.model, signed.pem.clone())
+        .await?;
+    CERTIFICATES_ISSUED.with_label_values(&[&client.name]).inc();
+    notify_issued(&data, Some(&client), issued, signed.pem).await;
+
+    Ok(Json(ApprovalRequestDto::from_model(model)))
+}
+
+/// Reject a signing request
+#[utoipa::path(
+    post,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "rejectSigningRequest",
+    request_body = RejectApprovalDto,
+    params(
+        ("id", description = "Id of the approval request"),
+    ),
+    responses(
+        (status = 200, description = "Ok", body = ApprovalRequestDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post(
+    "/admin/approval/{id}/reject",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::CertificateReject)"
+)]
+async fn reject(
+    req: HttpRequest,
+    path: Path<i32>,
+    body: Json<RejectApprovalDto>,
+    data: Data<AppState>,
+    claims: UserClaims<AdminRole>,
+) -> WebResult<Json<ApprovalRequestDto>> {
+    let model = find_request(&data, path.into_inner()).await?;
+    set_audit_target(&req, format!("approval:{}", model.id));
+
+    let model = data
+        .approval_service
+        .reject(model.id, &claims.user.id, body.into_inner().reason)
+        .await?;
+    let owner = data
+        .client_service
+        .find_by_id(&model.client_id, true)
+        .await?
+        .map(|c| c.user_id);
+    data.webhook_service
+        .emit(
+            WebhookEvent::CertificateRejected,
+            owner.as_ref(),
+            &SigningRequestDto::from_model(model.clone()),
+        )
+        .await;
+
+    Ok(Json(ApprovalRequestDto::from_model(model)))
+}
+
+register_module!(list, approve, reject);
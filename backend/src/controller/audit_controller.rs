@@ -0,0 +1,168 @@
+use crate::config::app_state::AppState;
+use crate::entity::audit_event::{AuditAction, AuditOutcome};
+use crate::error::http_response_error::MapHttpResponseError;
+use crate::middleware::extractors::UserClaims;
+use crate::middleware::identity_middleware;
+use crate::middleware::user_roles::AdminRole;
+use crate::model::audit_event_dto::AuditEventDto;
+use crate::model::audit_event_filter::AuditEventFilter;
+use crate::model::page_dto::PageDto;
+use crate::register_module;
+use crate::util::date::parse_date;
+use crate::util::pagination::Pagination;
+use crate::util::traits::from_model::FromModel;
+use crate::util::types::WebResult;
+use actix_web::web::{Bytes, Data, Json, Query};
+use actix_web::{get, HttpResponse, Responder};
+use futures::stream;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+/// The number of events which are loaded at once when exporting the audit log
+const EXPORT_BATCH_SIZE: u64 = 500;
+
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct AuditEventQuery {
+    /// Only return events of this action
+    #[param(inline)]
+    pub action: Option<AuditAction>,
+    /// Only return events performed by this user or client
+    #[serde(rename = "actorId")]
+    pub actor_id: Option<String>,
+    /// Only return events performed on this object
+    pub target: Option<String>,
+    /// Only return events with this outcome
+    #[param(inline)]
+    pub outcome: Option<AuditOutcome>,
+    /// Only return events which occurred at or after this time (RFC 3339)
+    pub from: Option<String>,
+    /// Only return events which occurred at or before this time (RFC 3339)
+    pub to: Option<String>,
+}
+
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct AuditPageQuery {
+    /// The zero-based index of the page to return.
+    /// Defaults to 0.
+    pub page: Option<u64>,
+    /// The number of elements per page.
+    /// Defaults to 50.
+    #[serde(rename = "pageSize")]
+    pub page_size: Option<u64>,
+}
+
+impl AuditEventQuery {
+    fn parse_date(date: &Option<String>) -> WebResult<Option<DateTimeWithTimeZone>> {
+        date.as_deref().map(parse_date).transpose()
+    }
+
+    fn to_filter(&self) -> WebResult<AuditEventFilter> {
+        Ok(AuditEventFilter {
+            action: self.action,
+            actor_id: self.actor_id.clone(),
+            target: self.target.clone(),
+            outcome: self.outcome,
+            from: Self::parse_date(&self.from)?,
+            to: Self::parse_date(&self.to)?,
+        })
+    }
+}
+
+/// List the audit log, newest events first
+#[utoipa::path(
+    get,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "listAuditEvents",
+    params(AuditEventQuery, AuditPageQuery),
+    responses(
+        (status = 200, description = "Ok", body = AuditEventPageDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get("/admin/audit", wrap = "identity_middleware::Identity")]
+async fn list(
+    data: Data<AppState>,
+    query: Query<AuditEventQuery>,
+    page: Query<AuditPageQuery>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<PageDto<AuditEventDto>>> {
+    let pagination = Pagination::new(page.page, page.page_size)?;
+    let (items, total) = data
+        .audit_service
+        .find_page(&query.to_filter()?, &pagination)
+        .await?;
+
+    Ok(Json(PageDto::new(
+        items.into_iter().map(AuditEventDto::from_model).collect(),
+        total,
+        &pagination,
+    )))
+}
+
+/// Export the audit log as JSON lines, oldest events first.
+/// The events are streamed in batches, so exports of any size use a bounded amount of memory.
+#[utoipa::path(
+    get,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "exportAuditEvents",
+    params(AuditEventQuery),
+    responses(
+        (status = 200, description = "One AuditEventDto per line", content_type = "application/x-ndjson", body = String),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get("/admin/audit/export", wrap = "identity_middleware::Identity")]
+async fn export(
+    data: Data<AppState>,
+    query: Query<AuditEventQuery>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<impl Responder> {
+    let filter = query.to_filter()?;
+    let events = stream::try_unfold(
+        (data, filter, None, false),
+        |(data, filter, after, done)| async move {
+            if done {
+                return Ok(None);
+            }
+
+            let events = data
+                .audit_service
+                .find_batch(&filter, after, EXPORT_BATCH_SIZE)
+                .await?;
+            let done = (events.len() as u64) < EXPORT_BATCH_SIZE;
+            let after = events.last().map(|e| e.id).or(after);
+
+            let mut chunk = String::new();
+            for event in events {
+                chunk.push_str(
+                    &serde_json::to_string(&AuditEventDto::from_model(event))
+                        .map_internal_error(Some("Failed to serialize audit event"))?,
+                );
+                chunk.push('\n');
+            }
+
+            Ok::<_, actix_web::Error>(Some((Bytes::from(chunk), (data, filter, after, done))))
+        },
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .streaming(events))
+}
+
+register_module!(list, export);
@@ -0,0 +1,108 @@
+use crate::config::app_state::AppState;
+use crate::entity::audit_event::AuditAction;
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::middleware::audit_middleware::Audit;
+use crate::middleware::extractors::UserClaims;
+use crate::middleware::identity_middleware;
+use crate::middleware::user_roles::AdminRole;
+use crate::model::backup_archive_dto::BackupArchiveDto;
+use crate::model::backup_summary_dto::BackupSummaryDto;
+use crate::model::create_backup_dto::CreateBackupDto;
+use crate::model::restore_backup_dto::RestoreBackupDto;
+use crate::register_module;
+use crate::util::types::WebResult;
+use actix_web::post;
+use actix_web::web::{BytesMut, Data, Json, Payload};
+use futures_util::StreamExt;
+
+/// Export the entire state of the CA.
+/// This includes the root and intermediate certificates, the intermediate
+/// and token signing keys, users, clients, the token metadata and all
+/// issued and revoked certificates including the issuance log.
+/// The archive is encrypted with the supplied passphrase.
+#[utoipa::path(
+    post,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "exportBackup",
+    request_body = CreateBackupDto,
+    responses(
+        (status = 200, description = "Ok", body = BackupArchiveDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post(
+    "/admin/backup",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::BackupExport)"
+)]
+async fn export(
+    data: Data<AppState>,
+    body: Json<CreateBackupDto>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<BackupArchiveDto>> {
+    Ok(Json(data.backup_service.export(&body.passphrase).await?))
+}
+
+/// Restore a backup into a CA which has no certificates and clients yet.
+/// The backup is verified before anything is restored,
+/// e.g. that the intermediate certificates chain to the root certificates.
+/// Users which already exist are matched by name.
+#[utoipa::path(
+    post,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "restoreBackup",
+    request_body = RestoreBackupDto,
+    responses(
+        (status = 200, description = "Ok", body = BackupSummaryDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post(
+    "/admin/backup/restore",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::BackupRestore)"
+)]
+async fn restore(
+    data: Data<AppState>,
+    mut payload: Payload,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<BackupSummaryDto>> {
+    // Backups easily exceed the default json size limit
+    let limit = data.config.backup_max_size_mb * 1024 * 1024;
+    let mut body = BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_bad_request(Some("Failed to read the backup"))?;
+        if body.len() + chunk.len() > limit {
+            return Err(HttpResponseError::bad_request(Some(format!(
+                "The backup exceeds the maximum size of {} MB",
+                data.config.backup_max_size_mb
+            ))));
+        }
+
+        body.extend_from_slice(&chunk);
+    }
+
+    let body = serde_json::from_slice::<RestoreBackupDto>(&body)
+        .map_bad_request(Some("Invalid backup supplied"))?;
+    Ok(Json(
+        data.backup_service
+            .restore(&body.passphrase, &body.archive)
+            .await?,
+    ))
+}
+
+register_module!(export, restore);
@@ -1,23 +1,52 @@
 use crate::config::app_state::AppState;
-use crate::entity::{certificate, root_certificate, signing_request};
+use crate::controller::client_controller::{sign_token, token_model};
+use crate::entity::approval_request::ApprovalStatus;
+use crate::entity::audit_event::AuditAction;
+use crate::entity::{
+    approval_request, certificate, client, root_certificate, signing_request, user,
+};
 use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
-use crate::middleware::extractors::{JwtClientClaims, KeycloakUserClaims};
-use crate::middleware::keycloak_middleware;
-use crate::middleware::keycloak_roles::AdminRole;
+use crate::middleware::audit_middleware::{set_audit_target, Audit, AuditActor};
+use crate::middleware::extractors::{CertificateClientClaims, JwtClientClaims, UserClaims};
+use crate::middleware::identity_middleware;
+use crate::middleware::metrics_middleware::SigningMetrics;
+use crate::middleware::token_scopes::{GenerateScope, ReadScope, SignScope};
+use crate::middleware::user_roles::{AdminRole, NoRoles};
 use crate::model::ca_certificate_dto::CACertificateDto;
+use crate::model::generate_certificate_dto::GenerateCertificateDto;
 use crate::model::generate_intermediate_dto::GenerateIntermediateDto;
+use crate::model::generated_certificate_dto::GeneratedCertificateDto;
+use crate::model::token_scope::TokenScope;
+use crate::model::user_signing_request_dto::UserSigningRequestDto;
+use crate::model::verification_result_dto::VerificationResultDto;
+use crate::model::verify_certificate_dto::VerifyCertificateDto;
+use crate::model::webhook_event::WebhookEvent;
 use crate::register_module;
-use crate::util::ca_certificate::CACertificate;
+use crate::service::enrollment_token_service::Redemption;
+use crate::util::ca_certificate::{asn1_time_to_date_time, CACertificate};
+use crate::util::ca_store::CAStore;
+use crate::util::certificate_info::chain_entry;
+use crate::util::pkcs12;
 use crate::util::traits::from_model::FromModel;
 use crate::util::types::WebResult;
-use actix_web::web::{Data, Json};
-use actix_web::{get, post};
+use actix_web::web::{Data, Json, Path};
+use actix_web::{get, post, HttpMessage, HttpRequest, HttpResponse};
+use openssl::base64;
 use openssl::hash::MessageDigest;
-use openssl::x509::X509Req;
-use sea_orm::{ActiveValue, TryIntoModel};
+use openssl::x509::{X509NameRef, X509Req, X509};
+use sea_orm::ActiveValue;
+use shared::model::certificate_status::CertificateStatus;
+use shared::model::enrollment_request_dto::EnrollmentRequestDto;
+use shared::model::enrollment_result_dto::EnrollmentResultDto;
 use shared::model::new_signing_request_dto::NewSigningRequestDto;
 use shared::model::signing_request_dto::SigningRequestDto;
 use shared::util::traits::u8_vec_to_string::U8VecToString;
+use shared::util::types::BasicResult;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// The minimum length of the password a generated PKCS#12 archive is encrypted with
+const MIN_PKCS12_PASSWORD_LENGTH: usize = 8;
 
 /// Get the CA's intermediate certificate
 /// This is the certificate that is used to sign the client certificates
@@ -57,11 +86,16 @@ async fn get_intermediate(data: Data<AppState>) -> WebResult<Option<Json<CACerti
         ("oauth2" = [])
     )
 )]
-#[post("/intermediate/generate", wrap = "keycloak_middleware::Keycloak")]
+#[post(
+    "/intermediate/generate",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::IntermediateGenerate)"
+)]
 async fn generate_intermediate(
+    req: HttpRequest,
     data: Data<AppState>,
     body: Json<GenerateIntermediateDto>,
-    _claims: KeycloakUserClaims<AdminRole>,
+    _claims: UserClaims<AdminRole>,
 ) -> WebResult<Json<CACertificateDto>> {
     let root = data.root_certificate_service.find_active().await?.ok_or(
         HttpResponseError::bad_request(Some("Root certificate does not exist")),
@@ -93,36 +127,69 @@ async fn generate_intermediate(
             ..Default::default()
         })
         .await?;
+    set_audit_target(&req, model.id);
 
-    Ok(Json(CACertificateDto::from_model(model)))
+    let dto = CACertificateDto::from_model(model);
+    data.webhook_service
+        .emit(WebhookEvent::IntermediateRotated, None, &dto)
+        .await;
+    Ok(Json(dto))
 }
 
-/// Sign a certificate signing request
-/// using the server's CA certificate
-#[utoipa::path(
-    post,
-    context_path = "/api/v1/certificate",
-    tag = "Certificates",
-    operation_id = "signCertificate",
-    request_body = NewSigningRequestDto,
-    responses(
-        (status = 200, description = "Ok", body = SigningRequestDto),
-        (status = 400, description = "Bad request", body = ErrorDto),
-        (status = 401, description = "Unauthorized", body = ErrorDto),
-        (status = 500, description = "Internal server error", body = ErrorDto),
-    ),
-    security(
-        ("jwt" = [])
-    )
-)]
-#[post("/sign")]
-async fn sign(
-    request: Json<NewSigningRequestDto>,
-    data: Data<AppState>,
-    claims: JwtClientClaims,
-) -> WebResult<Json<SigningRequestDto>> {
-    println!("Signing request");
-    let req = X509Req::from_pem(request.request.as_bytes()).map_internal_error(None)?;
+/// The common name of a certificate or signing request subject
+fn common_name(name: &X509NameRef) -> WebResult<String> {
+    Ok(name
+        .entries_by_nid(openssl::nid::Nid::COMMONNAME)
+        .next()
+        .ok_or(HttpResponseError::bad_request(Some(
+            "No common name in subject name",
+        )))?
+        .data()
+        .as_utf8()
+        .map_internal_error(None)?
+        .to_string())
+}
+
+/// Personal certificates are bound to the user they are issued to, so only the
+/// user's name may be used as their common name and no alternative names may be added.
+/// Certificates with arbitrary subjects must be issued to a client.
+fn check_personal_certificate(
+    user: &user::Model,
+    subject_name: &str,
+    alternative_names: &Option<Vec<String>>,
+) -> WebResult<()> {
+    if subject_name != user.name {
+        return Err(HttpResponseError::bad_request(Some(format!(
+            "The subject of a personal certificate must be the common name '{}'",
+            user.name
+        ))));
+    }
+    if alternative_names.as_ref().is_some_and(|n| !n.is_empty()) {
+        return Err(HttpResponseError::bad_request(Some(
+            "Personal certificates can't have alternative names",
+        )));
+    }
+
+    Ok(())
+}
+
+/// A certificate which has been signed but not stored yet
+pub struct SignedCertificate {
+    /// The signing request to store for the certificate
+    pub model: signing_request::ActiveModel,
+    /// The PEM encoded certificate
+    pub pem: String,
+}
+
+/// Sign a signing request of a client or a personal certificate
+/// of a user using the active intermediate certificate
+pub async fn sign_certificate(
+    data: &AppState,
+    client: Option<&client::Model>,
+    requested_by: Option<Uuid>,
+    req: &X509Req,
+    alternative_names: &Option<Vec<String>>,
+) -> WebResult<SignedCertificate> {
     let ca_cert: CACertificate = data
         .certificate_service
         .find_active()
@@ -134,14 +201,14 @@ async fn sign(
         .map_internal_error(Some("Failed to map model"))?;
 
     let signed = ca_cert
-        .sign_request(&req, &request.alternative_names, &data.config, false)
+        .sign_request(req, alternative_names, &data.config, false)
         .map_internal_error(None)?;
 
-    let req = data
-        .signing_request_service
-        .save(signing_request::ActiveModel {
+    Ok(SignedCertificate {
+        model: signing_request::ActiveModel {
             id: ActiveValue::NotSet,
-            client_id: ActiveValue::Set(claims.client.id),
+            client_id: ActiveValue::Set(client.map(|c| c.id)),
+            requested_by: ActiveValue::Set(requested_by),
             hash: ActiveValue::Set(
                 signed
                     .digest(MessageDigest::sha256())
@@ -149,19 +216,7 @@ async fn sign(
                     .to_vec()
                     .to_hex_string(":"),
             ),
-            subject_name: ActiveValue::Set(
-                signed
-                    .subject_name()
-                    .entries_by_nid(openssl::nid::Nid::COMMONNAME)
-                    .next()
-                    .ok_or(HttpResponseError::bad_request(Some(
-                        "No common name in subject name",
-                    )))?
-                    .data()
-                    .as_utf8()
-                    .map_internal_error(None)?
-                    .to_string(),
-            ),
+            subject_name: ActiveValue::Set(common_name(signed.subject_name())?),
             serial_number: ActiveValue::Set(
                 signed
                     .serial_number()
@@ -171,21 +226,788 @@ async fn sign(
                     .map_internal_error(None)?
                     .to_string(),
             ),
+            alternative_names: ActiveValue::Set(
+                alternative_names
+                    .as_ref()
+                    .filter(|names| !names.is_empty())
+                    .map(|names| names.join(",")),
+            ),
             issued_at: ActiveValue::Set(chrono::Utc::now().into()),
-        })
-        .await?;
-
-    let mut dto = SigningRequestDto::from_model(
-        req.try_into_model()
-            .map_internal_error(Some("Failed to map model"))?,
-    );
-    dto.certificate = Some(
-        signed
+            valid_until: ActiveValue::Set(
+                asn1_time_to_date_time(signed.not_after())
+                    .map_internal_error(Some("Failed to get certificate validity"))?,
+            ),
+            revoked_at: ActiveValue::Set(None),
+        },
+        pem: signed
             .to_pem()
             .map_internal_error(Some("Failed to stringify certificate"))?
             .to_string(),
-    );
-    Ok(Json(dto))
+    })
+}
+
+/// Notify the webhook subscribers about a stored certificate.
+/// Returns the issued certificate including its PEM encoding.
+pub async fn notify_issued(
+    data: &AppState,
+    client: Option<&client::Model>,
+    req: signing_request::Model,
+    pem: String,
+) -> SigningRequestDto {
+    let owner = client.map(|c| c.user_id).or(req.requested_by);
+    let mut dto = SigningRequestDto::from_model(req);
+    dto.certificate = Some(pem);
+
+    data.webhook_service
+        .emit(WebhookEvent::CertificateIssued, owner.as_ref(), &dto)
+        .await;
+    dto
+}
+
+/// Issue a certificate for a signing request of a client or a personal
+/// certificate of a user using the active intermediate certificate,
+/// log it and notify the webhook subscribers.
+/// Returns the issued certificate including its PEM encoding.
+pub async fn issue_certificate(
+    data: &AppState,
+    client: Option<&client::Model>,
+    requested_by: Option<Uuid>,
+    req: &X509Req,
+    alternative_names: &Option<Vec<String>>,
+) -> WebResult<SigningRequestDto> {
+    let signed = sign_certificate(data, client, requested_by, req, alternative_names).await?;
+    let req = data.signing_request_service.issue(signed.model).await?;
+
+    Ok(notify_issued(data, client, req, signed.pem).await)
+}
+
+/// Find the client a user requests a certificate for and check the
+/// issuance quotas. Returns `None` if a personal certificate is requested.
+async fn user_client(
+    data: &AppState,
+    user_id: &Uuid,
+    client_id: &Option<String>,
+) -> WebResult<Option<client::Model>> {
+    let client = match client_id {
+        Some(id) => {
+            let id = Uuid::from_str(id).map_bad_request(Some("Invalid client id supplied"))?;
+            let client = data
+                .client_service
+                .find_by_id(&id, false)
+                .await?
+                .ok_or(HttpResponseError::not_found(Some("Client not found")))?;
+            if !data
+                .team_service
+                .can_manage_client(&client, user_id)
+                .await?
+            {
+                return Err(HttpResponseError::not_found(Some("Client not found")));
+            }
+
+            Some(client)
+        }
+        None => None,
+    };
+
+    match &client {
+        Some(client) => {
+            data.quota_service
+                .check_issuance(&data.config, client)
+                .await?
+        }
+        None if data.config.client_requires_approval => {
+            return Err(HttpResponseError::bad_request(Some(
+                "Personal certificates can't be issued while all certificates require approval",
+            )))
+        }
+        None => {
+            data.quota_service
+                .check_user_issuance(&data.config, user_id)
+                .await?
+        }
+    }
+
+    Ok(client)
+}
+
+/// Notify the webhook subscribers about a request waiting for approval.
+/// Returns the pending request.
+async fn notify_pending(
+    http_req: &HttpRequest,
+    data: &AppState,
+    client: &client::Model,
+    approval: approval_request::Model,
+) -> SigningRequestDto {
+    set_audit_target(http_req, format!("approval:{}", approval.id));
+
+    let dto = SigningRequestDto::from_model(approval);
+    data.webhook_service
+        .emit(
+            WebhookEvent::CertificatePending,
+            Some(&client.user_id),
+            &dto,
+        )
+        .await;
+    dto
+}
+
+/// Issue a certificate for a signing request or, if the client
+/// requires approval, store the request until an admin decides on it.
+/// Returns the issued certificate or the pending request.
+async fn submit_or_issue_request(
+    http_req: &HttpRequest,
+    data: &AppState,
+    client: Option<&client::Model>,
+    requested_by: Option<Uuid>,
+    request: &NewSigningRequestDto,
+) -> WebResult<SigningRequestDto> {
+    let req = X509Req::from_pem(request.request.as_bytes())
+        .map_bad_request(Some("Invalid signing request supplied"))?;
+    if let Some(client) = client.filter(|c| c.requires_approval) {
+        let approval = data
+            .approval_service
+            .submit(
+                client,
+                requested_by,
+                &req,
+                common_name(req.subject_name())?,
+                &request.alternative_names,
+            )
+            .await?;
+        return Ok(notify_pending(http_req, data, client, approval).await);
+    }
+
+    let dto =
+        issue_certificate(data, client, requested_by, &req, &request.alternative_names).await?;
+    if let Some(serial_number) = &dto.serial_number {
+        set_audit_target(http_req, serial_number);
+    }
+
+    Ok(dto)
+}
+
+/// Issue a certificate for a signing request or, if the client
+/// requires approval, store the request until an admin decides on it
+async fn submit_or_issue(
+    http_req: &HttpRequest,
+    data: &AppState,
+    client: Option<&client::Model>,
+    requested_by: Option<Uuid>,
+    request: &NewSigningRequestDto,
+) -> WebResult<HttpResponse> {
+    let dto = submit_or_issue_request(http_req, data, client, requested_by, request).await?;
+    Ok(match dto.status {
+        CertificateStatus::Pending => HttpResponse::Accepted().json(dto),
+        _ => HttpResponse::Ok().json(dto),
+    })
+}
+
+/// Generate a key pair, issue a certificate for it and return both as a
+/// PKCS#12 archive along with the certificate chain.
+/// The private key is only part of the response and never stored.
+async fn generate_and_issue(
+    http_req: &HttpRequest,
+    data: &AppState,
+    client: Option<&client::Model>,
+    requested_by: Option<Uuid>,
+    body: &GenerateCertificateDto,
+) -> WebResult<GeneratedCertificateDto> {
+    if body.password.len() < MIN_PKCS12_PASSWORD_LENGTH {
+        return Err(HttpResponseError::bad_request(Some(format!(
+            "The password must be at least {} characters long",
+            MIN_PKCS12_PASSWORD_LENGTH
+        ))));
+    }
+    if matches!(client, Some(c) if c.requires_approval) {
+        return Err(HttpResponseError::bad_request(Some(
+            "The client requires approval, submit a signing request instead",
+        )));
+    }
+
+    let key_pair = data
+        .config
+        .key_algorithm
+        .generate()
+        .map_internal_error(Some("Failed to generate key pair"))?;
+    let req = pkcs12::signing_request(&key_pair, &body.subject_name)
+        .map_bad_request(Some("Invalid subject name supplied"))?;
+
+    let dto = issue_certificate(data, client, requested_by, &req, &body.alternative_names).await?;
+    if let Some(serial_number) = &dto.serial_number {
+        set_audit_target(http_req, serial_number);
+    }
+
+    let cert = X509::from_pem(dto.certificate.as_deref().unwrap_or_default().as_bytes())
+        .map_internal_error(Some("Failed to parse the issued certificate"))?;
+    let mut chain = vec![];
+    if let Some(intermediate) = data.certificate_service.find_active().await? {
+        chain.push(
+            X509::from_pem(&intermediate.public)
+                .map_internal_error(Some("Failed to parse intermediate certificate"))?,
+        );
+    }
+    if let Some(root) = data.root_certificate_service.find_active().await? {
+        chain.push(
+            X509::from_pem(&root.public)
+                .map_internal_error(Some("Failed to parse root certificate"))?,
+        );
+    }
+
+    let archive = pkcs12::build_pkcs12(&key_pair, &cert, chain, &body.subject_name, &body.password)
+        .map_internal_error(Some("Failed to build PKCS#12 archive"))?;
+
+    Ok(GeneratedCertificateDto {
+        certificate: dto,
+        pkcs12: base64::encode_block(&archive),
+    })
+}
+
+/// The current state of a signing request which requires approval.
+/// Contains the certificate once the request has been approved.
+async fn signing_status(
+    data: &AppState,
+    approval: approval_request::Model,
+) -> WebResult<HttpResponse> {
+    Ok(match approval.status {
+        ApprovalStatus::Pending => {
+            HttpResponse::Accepted().json(SigningRequestDto::from_model(approval))
+        }
+        ApprovalStatus::Rejected => {
+            HttpResponse::Ok().json(SigningRequestDto::from_model(approval))
+        }
+        ApprovalStatus::Approved => {
+            let issued = match approval.signing_request_id {
+                Some(id) => data.signing_request_service.find_by_id(id).await?,
+                None => None,
+            };
+
+            let mut dto = match issued {
+                Some(issued) => SigningRequestDto::from_model(issued),
+                None => SigningRequestDto::from_model(approval.clone()),
+            };
+            dto.certificate = approval.certificate;
+            HttpResponse::Ok().json(dto)
+        }
+    })
+}
+
+/// Sign a certificate signing request
+/// using the server's CA certificate.
+/// If the client requires approval, the request is stored and
+/// a pending request is returned, see `getSigningRequestStatus`.
+/// Tokens with the `renew-only` scope may only renew valid certificates of the client.
+#[utoipa::path(
+    post,
+    context_path = "/api/v1/certificate",
+    tag = "Certificates",
+    operation_id = "signCertificate",
+    request_body = NewSigningRequestDto,
+    responses(
+        (status = 200, description = "Ok", body = SigningRequestDto),
+        (status = 202, description = "The request is waiting for approval", body = SigningRequestDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 429, description = "Rate limit or quota exceeded, see the Retry-After header", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+#[post(
+    "/sign",
+    wrap = "SigningMetrics",
+    wrap = "Audit::new(AuditAction::CertificateSign)"
+)]
+async fn sign(
+    http_req: HttpRequest,
+    request: Json<NewSigningRequestDto>,
+    data: Data<AppState>,
+    claims: JwtClientClaims<SignScope>,
+) -> WebResult<HttpResponse> {
+    data.quota_service
+        .check_issuance(&data.config, &claims.client)
+        .await?;
+
+    if !claims.has_scope(TokenScope::Sign) {
+        let req = X509Req::from_pem(request.request.as_bytes())
+            .map_bad_request(Some("Invalid signing request supplied"))?;
+        let is_renewal = data
+            .signing_request_service
+            .is_renewal(
+                &claims.client.id,
+                &common_name(req.subject_name())?,
+                &request.alternative_names,
+            )
+            .await?;
+        if !is_renewal {
+            return Err(HttpResponseError::unauthorized(Some(
+                "The token may only renew valid certificates of the client",
+            )));
+        }
+    }
+
+    submit_or_issue(&http_req, &data, Some(&claims.client), None, &request).await
+}
+
+/// Get the status of a signing request which requires approval.
+/// Contains the certificate once the request has been approved.
+#[utoipa::path(
+    get,
+    context_path = "/api/v1/certificate",
+    tag = "Certificates",
+    operation_id = "getSigningRequestStatus",
+    params(
+        ("id", description = "The id returned by signCertificate"),
+    ),
+    responses(
+        (status = 200, description = "The request has been approved or rejected", body = SigningRequestDto),
+        (status = 202, description = "The request is waiting for approval", body = SigningRequestDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+#[get("/sign/{id}")]
+async fn get_signing_status(
+    path: Path<i32>,
+    data: Data<AppState>,
+    claims: JwtClientClaims<ReadScope>,
+) -> WebResult<HttpResponse> {
+    let approval = data
+        .approval_service
+        .find_by_id(path.into_inner())
+        .await?
+        .filter(|a| a.client_id == claims.client.id)
+        .ok_or(HttpResponseError::not_found(Some(
+            "Signing request not found",
+        )))?;
+
+    signing_status(&data, approval).await
+}
+
+/// Renew a valid certificate of a client without a token.
+/// The request must be signed with the key of the certificate, which is
+/// sent along with the timestamp and signature in the `X-Client-Certificate`,
+/// `X-Client-Timestamp` and `X-Client-Signature` headers.
+/// The signature covers the method, the path, the timestamp and the SHA-256 hash
+/// of the body. Each timestamp may only be used once with the same certificate.
+/// The signing request must use the same key and subject as the certificate.
+/// If the client requires approval, the request is stored and
+/// a pending request is returned, see `getRenewalStatus`.
+#[utoipa::path(
+    post,
+    context_path = "/api/v1/certificate",
+    tag = "Certificates",
+    operation_id = "renewCertificate",
+    request_body = NewSigningRequestDto,
+    responses(
+        (status = 200, description = "Ok", body = SigningRequestDto),
+        (status = 202, description = "The request is waiting for approval", body = SigningRequestDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 429, description = "Rate limit or quota exceeded, see the Retry-After header", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("certificate" = [])
+    )
+)]
+#[post(
+    "/renew",
+    wrap = "SigningMetrics",
+    wrap = "Audit::new(AuditAction::CertificateSign)"
+)]
+async fn renew(
+    http_req: HttpRequest,
+    data: Data<AppState>,
+    claims: CertificateClientClaims,
+) -> WebResult<HttpResponse> {
+    let request = claims.json::<NewSigningRequestDto>()?;
+    let req = X509Req::from_pem(request.request.as_bytes())
+        .map_bad_request(Some("Invalid signing request supplied"))?;
+    let key = claims
+        .x509
+        .public_key()
+        .map_internal_error(Some("Failed to get the certificate's public key"))?;
+    let same_key = req
+        .public_key()
+        .map_bad_request(Some("Invalid signing request supplied"))?
+        .public_eq(&key);
+    if !same_key {
+        return Err(HttpResponseError::bad_request(Some(
+            "The signing request must use the key of the certificate",
+        )));
+    }
+
+    let mut requested = request.alternative_names.clone().unwrap_or_default();
+    requested.sort_unstable();
+    requested.dedup();
+    let mut names = claims.certificate.alternative_names();
+    names.sort_unstable();
+    names.dedup();
+    if common_name(req.subject_name())? != claims.certificate.subject_name || requested != names {
+        return Err(HttpResponseError::bad_request(Some(
+            "The signing request must have the same subject as the certificate",
+        )));
+    }
+
+    data.quota_service
+        .check_issuance(&data.config, &claims.client)
+        .await?;
+    submit_or_issue(&http_req, &data, Some(&claims.client), None, &request).await
+}
+
+/// Get the status of a renewal which requires approval.
+/// Authenticated the same way as `renewCertificate`.
+/// Contains the certificate once the request has been approved.
+#[utoipa::path(
+    get,
+    context_path = "/api/v1/certificate",
+    tag = "Certificates",
+    operation_id = "getRenewalStatus",
+    params(
+        ("id", description = "The id returned by renewCertificate"),
+    ),
+    responses(
+        (status = 200, description = "The request has been approved or rejected", body = SigningRequestDto),
+        (status = 202, description = "The request is waiting for approval", body = SigningRequestDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("certificate" = [])
+    )
+)]
+#[get("/renew/{id}")]
+async fn get_renewal_status(
+    path: Path<i32>,
+    data: Data<AppState>,
+    claims: CertificateClientClaims,
+) -> WebResult<HttpResponse> {
+    let approval = data
+        .approval_service
+        .find_by_id(path.into_inner())
+        .await?
+        .filter(|a| a.client_id == claims.client.id)
+        .ok_or(HttpResponseError::not_found(Some(
+            "Signing request not found",
+        )))?;
+
+    signing_status(&data, approval).await
+}
+
+/// Redeem a one-time enrollment token for the first certificate of a client.
+/// Each token can only be redeemed once, later attempts are rejected.
+/// Renewals are authenticated using the issued certificate, see `renewCertificate`,
+/// or the renew-only token which can be requested along with the certificate.
+/// If the client requires approval, a renew-only token is always issued and
+/// the pending request can be checked using `getSigningRequestStatus`.
+#[utoipa::path(
+    post,
+    context_path = "/api/v1/certificate",
+    tag = "Certificates",
+    operation_id = "enroll",
+    request_body = EnrollmentRequestDto,
+    responses(
+        (status = 200, description = "Ok", body = EnrollmentResultDto),
+        (status = 202, description = "The request is waiting for approval", body = EnrollmentResultDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 429, description = "Rate limit or quota exceeded, see the Retry-After header", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+)]
+#[post(
+    "/enroll",
+    wrap = "SigningMetrics",
+    wrap = "Audit::new(AuditAction::CertificateEnroll)"
+)]
+async fn enroll(
+    http_req: HttpRequest,
+    body: Json<EnrollmentRequestDto>,
+    data: Data<AppState>,
+) -> WebResult<HttpResponse> {
+    let enrollment = data
+        .enrollment_token_service
+        .find_by_token(&body.token)
+        .await?
+        .ok_or(HttpResponseError::unauthorized(Some(
+            "Invalid enrollment token",
+        )))?;
+    let client = data
+        .client_service
+        .find_by_id(&enrollment.client_id, false)
+        .await?
+        .ok_or(HttpResponseError::unauthorized(Some("Client not found")))?;
+    http_req
+        .extensions_mut()
+        .insert(AuditActor::client(&client, &enrollment.id));
+
+    if !enrollment.is_redeemable() {
+        return Err(HttpResponseError::unauthorized(Some(
+            "The enrollment token has already been redeemed or has expired",
+        )));
+    }
+
+    let req = X509Req::from_pem(body.request.as_bytes())
+        .map_bad_request(Some("Invalid signing request supplied"))?;
+    data.quota_service
+        .check_issuance(&data.config, &client)
+        .await?;
+
+    let (token, renewal_token) = if body.renewal_token.unwrap_or(false) || client.requires_approval
+    {
+        let scopes = Some(vec![TokenScope::RenewOnly, TokenScope::Read]);
+        let (token_id, token, token_hash) = sign_token(&data, client.valid_until, &scopes).await?;
+        (
+            Some(token),
+            Some(token_model(token_id, token_hash, client.id, &scopes)),
+        )
+    } else {
+        (None, None)
+    };
+
+    // The token is redeemed in the transaction storing the certificate or the
+    // pending request, so it can be redeemed again if storing them fails
+    let redemption = Redemption {
+        id: enrollment.id,
+        redeemed_from: http_req.peer_addr().map(|addr| addr.ip().to_string()),
+        renewal_token,
+    };
+    let certificate = if client.requires_approval {
+        let approval = data
+            .enrollment_token_service
+            .redeem_and_submit(
+                redemption,
+                &client,
+                &req,
+                common_name(req.subject_name())?,
+                &body.alternative_names,
+            )
+            .await?;
+        notify_pending(&http_req, &data, &client, approval).await
+    } else {
+        let signed =
+            sign_certificate(&data, Some(&client), None, &req, &body.alternative_names).await?;
+        let issued = data
+            .enrollment_token_service
+            .redeem_and_issue(redemption, signed.model)
+            .await?;
+        set_audit_target(&http_req, &issued.serial_number);
+        notify_issued(&data, Some(&client), issued, signed.pem).await
+    };
+
+    let pending = certificate.status == CertificateStatus::Pending;
+    let dto = EnrollmentResultDto { certificate, token };
+    Ok(if pending {
+        HttpResponse::Accepted().json(dto)
+    } else {
+        HttpResponse::Ok().json(dto)
+    })
+}
+
+/// Sign a certificate signing request as the current user,
+/// either for one of the user's clients or as a personal certificate.
+/// If the client requires approval, the request is stored and
+/// a pending request is returned, see `getUserSigningRequestStatus`.
+#[utoipa::path(
+    post,
+    context_path = "/api/v1/certificate",
+    tag = "Certificates",
+    operation_id = "signCertificateAsUser",
+    request_body = UserSigningRequestDto,
+    responses(
+        (status = 200, description = "Ok", body = SigningRequestDto),
+        (status = 202, description = "The request is waiting for approval", body = SigningRequestDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Client not found", body = ErrorDto),
+        (status = 429, description = "Rate limit or quota exceeded, see the Retry-After header", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post(
+    "/user/sign",
+    wrap = "identity_middleware::Identity",
+    wrap = "SigningMetrics",
+    wrap = "Audit::new(AuditAction::CertificateSign)"
+)]
+async fn sign_as_user(
+    http_req: HttpRequest,
+    body: Json<UserSigningRequestDto>,
+    data: Data<AppState>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<HttpResponse> {
+    let body = body.into_inner();
+    let client = user_client(&data, &claims.user.id, &body.client_id).await?;
+    if client.is_none() {
+        let req = X509Req::from_pem(body.request.as_bytes())
+            .map_bad_request(Some("Invalid signing request supplied"))?;
+        let subject = req.subject_name();
+        if subject.entries().count() != 1 {
+            return Err(HttpResponseError::bad_request(Some(
+                "The subject of a personal certificate must only contain a common name",
+            )));
+        }
+
+        check_personal_certificate(
+            &claims.user,
+            &common_name(subject)?,
+            &body.alternative_names,
+        )?;
+    }
+
+    let request = NewSigningRequestDto {
+        request: body.request,
+        alternative_names: body.alternative_names,
+    };
+    submit_or_issue(
+        &http_req,
+        &data,
+        client.as_ref(),
+        Some(claims.user.id),
+        &request,
+    )
+    .await
+}
+
+/// Get the status of a signing request submitted by the current user
+/// which requires approval.
+/// Contains the certificate once the request has been approved.
+#[utoipa::path(
+    get,
+    context_path = "/api/v1/certificate",
+    tag = "Certificates",
+    operation_id = "getUserSigningRequestStatus",
+    params(
+        ("id", description = "The id returned by signCertificateAsUser"),
+    ),
+    responses(
+        (status = 200, description = "The request has been approved or rejected", body = SigningRequestDto),
+        (status = 202, description = "The request is waiting for approval", body = SigningRequestDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get("/user/sign/{id}", wrap = "identity_middleware::Identity")]
+async fn get_user_signing_status(
+    path: Path<i32>,
+    data: Data<AppState>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<HttpResponse> {
+    let approval = data
+        .approval_service
+        .find_by_id(path.into_inner())
+        .await?
+        .filter(|a| a.requested_by == Some(claims.user.id))
+        .ok_or(HttpResponseError::not_found(Some(
+            "Signing request not found",
+        )))?;
+
+    signing_status(&data, approval).await
+}
+
+/// Generate a key pair on the server and issue a certificate for it.
+/// Returns a password protected PKCS#12 archive containing the private key,
+/// the certificate and the certificate chain. The private key is not stored.
+#[utoipa::path(
+    post,
+    context_path = "/api/v1/certificate",
+    tag = "Certificates",
+    operation_id = "generateCertificate",
+    request_body = GenerateCertificateDto,
+    responses(
+        (status = 200, description = "Ok", body = GeneratedCertificateDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 429, description = "Rate limit or quota exceeded, see the Retry-After header", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+#[post(
+    "/generate",
+    wrap = "SigningMetrics",
+    wrap = "Audit::new(AuditAction::CertificateGenerate)"
+)]
+async fn generate_certificate(
+    http_req: HttpRequest,
+    body: Json<GenerateCertificateDto>,
+    data: Data<AppState>,
+    claims: JwtClientClaims<GenerateScope>,
+) -> WebResult<Json<GeneratedCertificateDto>> {
+    data.quota_service
+        .check_issuance(&data.config, &claims.client)
+        .await?;
+
+    Ok(Json(
+        generate_and_issue(&http_req, &data, Some(&claims.client), None, &body).await?,
+    ))
+}
+
+/// Generate a key pair on the server and issue a certificate for it as
+/// the current user, either for one of the user's clients or as a personal certificate.
+/// Returns a password protected PKCS#12 archive containing the private key,
+/// the certificate and the certificate chain. The private key is not stored.
+#[utoipa::path(
+    post,
+    context_path = "/api/v1/certificate",
+    tag = "Certificates",
+    operation_id = "generateCertificateAsUser",
+    request_body = GenerateCertificateDto,
+    responses(
+        (status = 200, description = "Ok", body = GeneratedCertificateDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Client not found", body = ErrorDto),
+        (status = 429, description = "Rate limit or quota exceeded, see the Retry-After header", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post(
+    "/user/generate",
+    wrap = "identity_middleware::Identity",
+    wrap = "SigningMetrics",
+    wrap = "Audit::new(AuditAction::CertificateGenerate)"
+)]
+async fn generate_certificate_as_user(
+    http_req: HttpRequest,
+    body: Json<GenerateCertificateDto>,
+    data: Data<AppState>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<Json<GeneratedCertificateDto>> {
+    let client = user_client(&data, &claims.user.id, &body.client_id).await?;
+    if client.is_none() {
+        check_personal_certificate(&claims.user, &body.subject_name, &body.alternative_names)?;
+    }
+
+    Ok(Json(
+        generate_and_issue(
+            &http_req,
+            &data,
+            client.as_ref(),
+            Some(claims.user.id),
+            &body,
+        )
+        .await?,
+    ))
 }
 
 /// Get the root CA certificate
@@ -229,10 +1051,15 @@ async fn get_root_certificate(data: Data<AppState>) -> WebResult<Option<Json<CAC
         ("oauth2" = [])
     )
 )]
-#[post("/root/generate", wrap = "keycloak_middleware::Keycloak")]
+#[post(
+    "/root/generate",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::RootGenerate)"
+)]
 async fn generate_root_certificate(
+    req: HttpRequest,
     data: Data<AppState>,
-    claims: KeycloakUserClaims<AdminRole>,
+    claims: UserClaims<AdminRole>,
 ) -> WebResult<Json<CACertificateDto>> {
     let root = CACertificate::generate_root(&data.config)
         .map_internal_error(Some("Failed to generate root certificate"))?;
@@ -252,7 +1079,15 @@ async fn generate_root_certificate(
             ..Default::default()
         })
         .await?;
+    set_audit_target(&req, model.id);
 
+    data.webhook_service
+        .emit(
+            WebhookEvent::RootGenerated,
+            None,
+            &CACertificateDto::from_root_model(model.clone(), None),
+        )
+        .await;
     Ok(Json(CACertificateDto::from_root_model(
         model,
         Some(
@@ -262,11 +1097,106 @@ async fn generate_root_certificate(
     )))
 }
 
+/// Verify a certificate against the current and historical
+/// root and intermediate certificates of this CA.
+/// Also checks the revocation status and the intended purpose.
+#[utoipa::path(
+    post,
+    context_path = "/api/v1/certificate",
+    tag = "Certificates",
+    operation_id = "verifyCertificate",
+    request_body = VerifyCertificateDto,
+    responses(
+        (status = 200, description = "Ok", body = VerificationResultDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post("/verify", wrap = "identity_middleware::Identity")]
+async fn verify(
+    data: Data<AppState>,
+    body: Json<VerifyCertificateDto>,
+    _claims: UserClaims<NoRoles>,
+) -> WebResult<Json<VerificationResultDto>> {
+    let cert = X509::from_pem(body.certificate.as_bytes())
+        .map_bad_request(Some("Invalid certificate supplied"))?;
+    let untrusted = body
+        .chain
+        .iter()
+        .flatten()
+        .map(|pem| X509::from_pem(pem.as_bytes()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_bad_request(Some("Invalid chain certificate supplied"))?;
+
+    let store = CAStore::load(&data).await?;
+    let verification = store
+        .verify(&cert, &untrusted, None)
+        .map_internal_error(Some("Failed to verify certificate"))?;
+
+    let chain = if verification.chain.is_empty() {
+        vec![chain_entry(&cert)]
+    } else {
+        verification.chain.iter().map(|c| chain_entry(c)).collect()
+    }
+    .into_iter()
+    .collect::<BasicResult<Vec<_>>>()
+    .map_internal_error(Some("Failed to decode certificate chain"))?;
+    let time_valid = chain.iter().all(|c| !c.expired && !c.not_yet_valid);
+
+    let purpose_valid = match body.purpose {
+        Some(purpose) if verification.valid => Some(
+            store
+                .verify(&cert, &untrusted, Some(purpose.into()))
+                .map_internal_error(Some("Failed to verify certificate purpose"))?
+                .valid,
+        ),
+        _ => None,
+    };
+
+    let status = if verification.valid {
+        data.signing_request_service
+            .find_by_serial_number(&chain[0].serial_number)
+            .await?
+            .map(|req| req.status())
+    } else {
+        None
+    };
+
+    Ok(Json(VerificationResultDto {
+        valid: verification.valid
+            && time_valid
+            && purpose_valid.unwrap_or(true)
+            && status != Some(CertificateStatus::Revoked),
+        chain_valid: verification.valid,
+        error: verification.error,
+        error_depth: verification.error_depth,
+        chain,
+        time_valid,
+        purpose: body.purpose,
+        purpose_valid,
+        status,
+    }))
+}
+
 register_module!(
     "/certificate",
     get_intermediate,
     generate_intermediate,
     sign,
+    get_signing_status,
+    renew,
+    get_renewal_status,
+    enroll,
+    sign_as_user,
+    get_user_signing_status,
+    generate_certificate,
+    generate_certificate_as_user,
     generate_root_certificate,
-    get_root_certificate
+    get_root_certificate,
+    verify
 );
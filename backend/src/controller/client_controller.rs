@@ -1,24 +1,31 @@
 use crate::config::app_state::AppState;
+use crate::entity::audit_event::AuditAction;
 use crate::entity::{client, token};
 use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
-use crate::middleware::extractors::KeycloakUserClaims;
-use crate::middleware::keycloak_middleware;
-use crate::middleware::keycloak_roles::NoRoles;
+use crate::middleware::audit_middleware::{set_audit_target, Audit};
+use crate::middleware::extractors::UserClaims;
+use crate::middleware::identity_middleware;
+use crate::middleware::user_roles::{AdminRole, NoRoles};
 use crate::model::client_dto::ClientDto;
+use crate::model::client_filter::{ClientFilter, ClientStatus};
+use crate::model::client_policy_dto::ClientPolicyDto;
 use crate::model::create_client_dto::CreateClientDto;
+use crate::model::page_dto::PageDto;
 use crate::model::token_claims::TokenClaims;
+use crate::model::token_scope::TokenScope;
+use crate::model::transfer_client_dto::TransferClientDto;
+use crate::model::webhook_event::WebhookEvent;
 use crate::register_module;
+use crate::service::token_service::TokenService;
+use crate::util::date::parse_date;
+use crate::util::pagination::Pagination;
 use crate::util::types::WebResult;
 use actix_web::web::{Data, Json, Path, Query};
-use actix_web::{delete, get, post, put, HttpResponse, Responder};
+use actix_web::{delete, get, post, put, HttpRequest, HttpResponse, Responder};
 use chrono::{DateTime, FixedOffset};
-use jsonwebtoken::EncodingKey;
 use log::debug;
-use openssl::sha::Sha256;
-use sea_orm::prelude::DateTimeWithTimeZone;
 use sea_orm::{ActiveValue, IntoActiveModel};
 use serde::Deserialize;
-use shared::util::traits::u8_vec_to_string::U8VecToString;
 use utoipa::IntoParams;
 use uuid::Uuid;
 
@@ -30,6 +37,71 @@ pub struct ClientQuery {
     pub include_inactive: Option<bool>,
 }
 
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct ClientListQuery {
+    /// Whether to include inactive clients in the result.
+    /// Defaults to false.
+    #[serde(rename = "includeInactive")]
+    pub include_inactive: Option<bool>,
+    /// The zero-based index of the page to return.
+    /// Defaults to 0.
+    pub page: Option<u64>,
+    /// The number of elements per page.
+    /// Defaults to 50.
+    #[serde(rename = "pageSize")]
+    pub page_size: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct AdminClientListQuery {
+    /// Only return clients owned by this user
+    #[serde(rename = "userId")]
+    pub user_id: Option<String>,
+    /// Only return clients owned by this team
+    #[serde(rename = "teamId")]
+    pub team_id: Option<String>,
+    /// Only return clients whose name contains this value
+    pub name: Option<String>,
+    /// Only return clients with this status.
+    /// Returns clients of any status by default.
+    #[param(inline)]
+    pub status: Option<ClientStatus>,
+    /// Only return clients whose token expires at or after this time (RFC 3339)
+    #[serde(rename = "expiresAfter")]
+    pub expires_after: Option<String>,
+    /// Only return clients whose token expires at or before this time (RFC 3339)
+    #[serde(rename = "expiresBefore")]
+    pub expires_before: Option<String>,
+    /// The zero-based index of the page to return.
+    /// Defaults to 0.
+    pub page: Option<u64>,
+    /// The number of elements per page.
+    /// Defaults to 50.
+    #[serde(rename = "pageSize")]
+    pub page_size: Option<u64>,
+}
+
+impl AdminClientListQuery {
+    fn to_filter(&self) -> WebResult<ClientFilter> {
+        Ok(ClientFilter {
+            user_id: self
+                .user_id
+                .as_ref()
+                .map(|id| Uuid::parse_str(id).map_bad_request(Some("Invalid user id supplied")))
+                .transpose()?,
+            team_id: self
+                .team_id
+                .as_ref()
+                .map(|id| Uuid::parse_str(id).map_bad_request(Some("Invalid team id supplied")))
+                .transpose()?,
+            name: self.name.clone(),
+            status: self.status,
+            expires_after: self.expires_after.as_deref().map(parse_date).transpose()?,
+            expires_before: self.expires_before.as_deref().map(parse_date).transpose()?,
+        })
+    }
+}
+
 #[derive(Deserialize, Debug, IntoParams)]
 struct DeleteQuery {
     /// Whether to delete the client rather than just deactivating it.
@@ -42,51 +114,127 @@ async fn create_token(
     client: &Json<CreateClientDto>,
     data: &Data<AppState>,
 ) -> WebResult<(DateTime<FixedOffset>, Uuid, String, String)> {
-    let expiry_date = DateTimeWithTimeZone::parse_from_rfc3339(&client.valid_until)
-        .map_bad_request(Some("Invalid date supplied"))?;
+    let expiry_date = parse_date(&client.valid_until)?;
     if expiry_date < chrono::Utc::now() {
         return Err(HttpResponseError::bad_request(Some(
             "Expiry date must be in the future",
         )));
     }
 
+    if matches!(&client.scopes, Some(scopes) if scopes.is_empty()) {
+        return Err(HttpResponseError::bad_request(Some(
+            "At least one scope must be supplied",
+        )));
+    }
+
+    let (token_id, token, token_hash) = sign_token(data, expiry_date, &client.scopes).await?;
+    Ok((expiry_date, token_id, token, token_hash))
+}
+
+/// Sign a new client token, returns the token's id, the token and its hash
+pub async fn sign_token(
+    data: &AppState,
+    expiry_date: DateTime<FixedOffset>,
+    scopes: &Option<Vec<TokenScope>>,
+) -> WebResult<(Uuid, String, String)> {
     let token_id = data.token_service.generate_id().await?;
-    let token = jsonwebtoken::encode(
-        &jsonwebtoken::Header::default(),
-        &TokenClaims {
+    let token = data
+        .token_key_service
+        .encode(&TokenClaims {
             sub: token_id.to_string(),
             iat: chrono::Utc::now().timestamp() as usize,
             exp: expiry_date.timestamp() as usize,
-        },
-        &EncodingKey::from_secret(data.config.jwt_secret.as_bytes()),
-    )
-    .map_internal_error(Some("Failed to encode jwt"))?;
+            scope: scopes.as_ref().map(|scopes| TokenScope::join(scopes, " ")),
+        })
+        .await?;
 
-    let token_hash = {
-        let mut hash = Sha256::new();
-        hash.update(token.as_bytes());
-        hash.finish().to_vec().to_hex_string("")
-    };
+    let token_hash = TokenService::hash_token(&token);
+    Ok((token_id, token, token_hash))
+}
 
-    Ok((expiry_date, token_id, token, token_hash))
+/// The model of a token, which replaces the current token of the client once it is stored
+pub fn token_model(
+    token_id: Uuid,
+    token_hash: String,
+    client_id: Uuid,
+    scopes: &Option<Vec<TokenScope>>,
+) -> token::ActiveModel {
+    token::ActiveModel {
+        id: ActiveValue::Set(token_id),
+        token_hash: ActiveValue::Set(token_hash),
+        client_id: ActiveValue::Set(client_id),
+        scopes: ActiveValue::Set(scopes.as_ref().map(|scopes| TokenScope::join(scopes, ","))),
+        ..Default::default()
+    }
 }
 
-async fn create_token_entity(
-    data: &Data<AppState>,
+/// Store a token, which replaces the current token of the client
+pub async fn create_token_entity(
+    data: &AppState,
     token_id: Uuid,
     token_hash: String,
     client_id: Uuid,
+    scopes: &Option<Vec<TokenScope>>,
 ) -> WebResult<token::Model> {
     data.token_service
-        .insert(token::ActiveModel {
-            id: ActiveValue::Set(token_id),
-            token_hash: ActiveValue::Set(token_hash),
-            client_id: ActiveValue::Set(client_id),
-            ..Default::default()
-        })
+        .insert(token_model(token_id, token_hash, client_id, scopes))
         .await
 }
 
+/// Find the token of a client, preferring the active token.
+/// Inactive clients only have inactive tokens left.
+async fn find_token(data: &AppState, client: &client::Model) -> WebResult<token::Model> {
+    match data
+        .token_service
+        .find_by_client_id(&client.id, false)
+        .await?
+    {
+        Some(token) => Ok(token),
+        None => data
+            .token_service
+            .find_by_client_id(&client.id, true)
+            .await?
+            .ok_or(HttpResponseError::not_found(Some("Token not found"))),
+    }
+}
+
+/// Delete a client or, if `delete_in_database` is false, disable it
+async fn remove_client(
+    data: &AppState,
+    client: client::Model,
+    delete_in_database: bool,
+) -> WebResult<()> {
+    if delete_in_database {
+        data.client_service
+            .delete(client.into_active_model())
+            .await?
+            .rows_affected
+            .ge(&1)
+            .then_some(())
+            .ok_or(HttpResponseError::bad_request(Some(
+                "Failed to delete client",
+            )))
+    } else if client.active {
+        let user_id = client.user_id;
+        let event = serde_json::json!({
+            "id": client.id.to_string(),
+            "name": client.name,
+            "userId": client.user_id.to_string(),
+        });
+        data.client_service
+            .disable(client.into_active_model())
+            .await?;
+        data.webhook_service
+            .emit(WebhookEvent::ClientDisabled, Some(&user_id), &event)
+            .await;
+        Ok(())
+    } else {
+        Err(HttpResponseError::bad_request(Some(
+            "Client is already inactive",
+        )))
+    }
+}
+
 #[utoipa::path(
     post,
     tag = "Clients",
@@ -98,17 +246,23 @@ async fn create_token_entity(
         (status = 400, description = "Bad request", body = ErrorDto),
         (status = 401, description = "Unauthorized", body = ErrorDto),
         (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 429, description = "Client quota exceeded, see the Retry-After header", body = ErrorDto),
         (status = 500, description = "Internal server error", body = ErrorDto),
     ),
     security(
         ("oauth2" = [])
     )
 )]
-#[post("/client", wrap = "keycloak_middleware::Keycloak")]
+#[post(
+    "/client",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::ClientCreate)"
+)]
 async fn create(
+    req: HttpRequest,
     client: Json<CreateClientDto>,
     data: Data<AppState>,
-    claims: KeycloakUserClaims<NoRoles>,
+    claims: UserClaims<NoRoles>,
 ) -> WebResult<Json<ClientDto>> {
     debug!("Creating client for user {}", claims.user.id);
 
@@ -128,8 +282,31 @@ async fn create(
             )))
         })
         .unwrap_or(Ok(()))?;
+    data.quota_service
+        .check_client_creation(&data.config, &claims.user.id)
+        .await?;
+
+    let team_id = match client.team_id.as_ref() {
+        Some(id) => {
+            let team = data.team_service.find_by_id_string_unwrap(id).await?;
+            if !claims.has_roles::<AdminRole>()
+                && !data
+                    .team_service
+                    .is_maintainer(&team.id, &claims.user.id)
+                    .await?
+            {
+                return Err(HttpResponseError::unauthorized(Some(
+                    "Only maintainers of the team may create clients for it",
+                )));
+            }
+
+            Some(team.id)
+        }
+        None => None,
+    };
 
     let (expiry_date, token_id, token, token_hash) = create_token(&client, &data).await?;
+    let scopes = client.scopes.clone();
 
     let client = data
         .client_service
@@ -137,12 +314,26 @@ async fn create(
             id: ActiveValue::Set(data.client_service.generate_id().await?),
             name: ActiveValue::Set(client_name.clone()),
             user_id: ActiveValue::Set(claims.user.id),
+            team_id: ActiveValue::Set(team_id),
             valid_until: ActiveValue::Set(expiry_date),
+            // Only admins may disable the approval of a client's requests
+            requires_approval: ActiveValue::Set(
+                data.config.client_requires_approval || client.requires_approval.unwrap_or(false),
+            ),
             ..Default::default()
         })
         .await?;
+    set_audit_target(&req, client.id);
+
+    let token_entity = create_token_entity(&data, token_id, token_hash, client.id, &scopes).await?;
+    data.webhook_service
+        .emit(
+            WebhookEvent::ClientCreated,
+            Some(&client.user_id),
+            &ClientDto::from_model(client.clone(), token_entity.clone()),
+        )
+        .await;
 
-    let token_entity = create_token_entity(&data, token_id, token_hash, client.id).await?;
     Ok(Json(ClientDto::from_model_with_token(
         client,
         token_entity,
@@ -170,19 +361,27 @@ async fn create(
         ("oauth2" = [])
     )
 )]
-#[put("/client/regenerate/{id}", wrap = "keycloak_middleware::Keycloak")]
+#[put(
+    "/client/regenerate/{id}",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::ClientRegenerate)"
+)]
 async fn regenerate_token(
     data: Data<AppState>,
     id: Path<String>,
     client: Json<CreateClientDto>,
-    claims: KeycloakUserClaims<NoRoles>,
+    claims: UserClaims<NoRoles>,
 ) -> WebResult<Json<ClientDto>> {
     let client_entity = data
         .client_service
         .find_by_id_string_unwrap(id.as_ref(), false)
         .await?;
 
-    if client_entity.user_id != claims.user.id {
+    if !data
+        .team_service
+        .can_manage_client(&client_entity, &claims.user.id)
+        .await?
+    {
         return Err(HttpResponseError::bad_request(Some("Client not found")));
     }
 
@@ -190,7 +389,14 @@ async fn regenerate_token(
     data.token_service
         .deactivate_all_by_client_id(&client_entity.id)
         .await?;
-    let token_entity = create_token_entity(&data, token_id, token_hash, client_entity.id).await?;
+    let token_entity = create_token_entity(
+        &data,
+        token_id,
+        token_hash,
+        client_entity.id,
+        &client.scopes,
+    )
+    .await?;
 
     let client_entity = {
         let mut entity = client_entity.into_active_model();
@@ -198,6 +404,14 @@ async fn regenerate_token(
         data.client_service.update(entity).await?
     };
 
+    data.webhook_service
+        .emit(
+            WebhookEvent::ClientTokenRegenerated,
+            Some(&client_entity.user_id),
+            &ClientDto::from_model(client_entity.clone(), token_entity.clone()),
+        )
+        .await;
+
     Ok(Json(ClientDto::from_model_with_token(
         client_entity,
         token_entity,
@@ -225,12 +439,12 @@ async fn regenerate_token(
         ("oauth2" = [])
     )
 )]
-#[get("/client/{id}", wrap = "keycloak_middleware::Keycloak")]
+#[get("/client/{id}", wrap = "identity_middleware::Identity")]
 async fn by_id(
     data: Data<AppState>,
     path: Path<String>,
     query: Query<ClientQuery>,
-    claims: KeycloakUserClaims<NoRoles>,
+    claims: UserClaims<NoRoles>,
 ) -> WebResult<Json<ClientDto>> {
     let include_inactive = query.include_inactive.unwrap_or(false);
     let client_id = Uuid::parse_str(&path).map_bad_request(Some("Invalid client id supplied"))?;
@@ -240,7 +454,11 @@ async fn by_id(
         .await?
         .ok_or(HttpResponseError::not_found(Some("Client not found")))?;
 
-    if client.user_id != claims.user.id {
+    if !data
+        .team_service
+        .can_access_client(&client, &claims.user.id)
+        .await?
+    {
         return Err(HttpResponseError::unauthorized(Some(
             "You are not authorized to access this client",
         )));
@@ -260,9 +478,9 @@ async fn by_id(
     tag = "Clients",
     context_path = "/api/v1",
     operation_id = "listClients",
-    params(ClientQuery),
+    params(ClientListQuery),
     responses(
-        (status = 200, description = "Ok", body = Vec<ClientDto>),
+        (status = 200, description = "Ok", body = ClientPageDto),
         (status = 400, description = "Bad request", body = ErrorDto),
         (status = 401, description = "Unauthorized", body = ErrorDto),
         (status = 424, description = "Failed dependency", body = ErrorDto),
@@ -272,16 +490,17 @@ async fn by_id(
         ("oauth2" = [])
     )
 )]
-#[get("/client/list", wrap = "keycloak_middleware::Keycloak")]
+#[get("/client/list", wrap = "identity_middleware::Identity")]
 async fn list(
     data: Data<AppState>,
-    query: Query<ClientQuery>,
-    claims: KeycloakUserClaims<NoRoles>,
-) -> WebResult<Json<Vec<ClientDto>>> {
+    query: Query<ClientListQuery>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<Json<PageDto<ClientDto>>> {
     let include_inactive = query.include_inactive.unwrap_or(false);
-    let clients = data
+    let pagination = Pagination::new(query.page, query.page_size)?;
+    let (clients, total) = data
         .client_service
-        .find_all_by_user(&claims.user.id, include_inactive)
+        .find_page_by_user(&claims.user.id, include_inactive, &pagination)
         .await?;
 
     let mut res = Vec::with_capacity(clients.len());
@@ -295,7 +514,7 @@ async fn list(
         res.push(ClientDto::from_model(client, token_entity))
     }
 
-    Ok(Json(res))
+    Ok(Json(PageDto::new(res, total, &pagination)))
 }
 
 #[utoipa::path(
@@ -318,43 +537,313 @@ async fn list(
         ("oauth2" = [])
     )
 )]
-#[delete("/client/{id}", wrap = "keycloak_middleware::Keycloak")]
+#[delete(
+    "/client/{id}",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::ClientDelete)"
+)]
 async fn delete(
     data: Data<AppState>,
     path: Path<String>,
     query: Query<DeleteQuery>,
-    claims: KeycloakUserClaims<NoRoles>,
+    claims: UserClaims<NoRoles>,
 ) -> WebResult<impl Responder> {
     let client = data
         .client_service
         .find_by_id_string_unwrap(path.as_ref(), true)
         .await?;
 
-    if client.user_id != claims.user.id {
+    if !data
+        .team_service
+        .can_manage_client(&client, &claims.user.id)
+        .await?
+    {
         return Err(HttpResponseError::bad_request(Some("Client not found")));
     }
 
-    if query.delete_in_database.unwrap_or(false) {
-        data.client_service
-            .delete(client.into_active_model())
+    remove_client(&data, client, query.delete_in_database.unwrap_or(false)).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Transfer a client to another user or to a team.
+/// The client must be managed by the current user, who must also maintain the
+/// target team. Admins may transfer any client.
+#[utoipa::path(
+    put,
+    tag = "Clients",
+    context_path = "/api/v1",
+    request_body = TransferClientDto,
+    operation_id = "transferClient",
+    params(
+        ("id", description = "Id of the client to transfer")
+    ),
+    responses(
+        (status = 200, description = "Ok", body = ClientDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[put(
+    "/client/{id}/owner",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::ClientTransfer)"
+)]
+async fn transfer(
+    req: HttpRequest,
+    data: Data<AppState>,
+    path: Path<String>,
+    body: Json<TransferClientDto>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<Json<ClientDto>> {
+    let is_admin = claims.has_roles::<AdminRole>();
+    let client = data
+        .client_service
+        .find_by_id_string_unwrap(path.as_ref(), false)
+        .await?;
+    if !is_admin
+        && !data
+            .team_service
+            .can_manage_client(&client, &claims.user.id)
             .await?
-            .rows_affected
-            .ge(&1)
-            .then(|| ())
-            .ok_or(HttpResponseError::bad_request(Some(
-                "Failed to delete client",
-            )))?;
-    } else if client.active {
-        data.client_service
-            .disable(client.into_active_model())
-            .await?;
-    } else {
-        return Err(HttpResponseError::bad_request(Some(
-            "Client is already inactive",
-        )));
+    {
+        return Err(HttpResponseError::bad_request(Some("Client not found")));
     }
+    set_audit_target(&req, client.id);
+
+    let (user_id, team_id) = match (body.user_id.as_ref(), body.team_id.as_ref()) {
+        (Some(user_id), None) => {
+            let user = data
+                .user_service
+                .find_by_id_string_unwrap(user_id, false)
+                .await?;
+            if user.id != client.user_id {
+                data.quota_service
+                    .check_client_creation(&data.config, &user.id)
+                    .await?;
+            }
+
+            (user.id, None)
+        }
+        (None, Some(team_id)) => {
+            let team = data.team_service.find_by_id_string_unwrap(team_id).await?;
+            if !is_admin
+                && !data
+                    .team_service
+                    .is_maintainer(&team.id, &claims.user.id)
+                    .await?
+            {
+                return Err(HttpResponseError::unauthorized(Some(
+                    "Clients can only be transferred to teams you maintain",
+                )));
+            }
+
+            // The previous owner stays responsible for the client
+            (client.user_id, Some(team.id))
+        }
+        _ => {
+            return Err(HttpResponseError::bad_request(Some(
+                "Either a user or a team must be supplied",
+            )))
+        }
+    };
 
+    debug!(
+        "Transferring client {} to user {} and team {:?}",
+        client.id, user_id, team_id
+    );
+    let token_entity = find_token(&data, &client).await?;
+    let client = {
+        let mut entity = client.into_active_model();
+        entity.user_id = ActiveValue::Set(user_id);
+        entity.team_id = ActiveValue::Set(team_id);
+        data.client_service.update(entity).await?
+    };
+
+    Ok(Json(ClientDto::from_model(client, token_entity)))
+}
+
+/// List the clients of all users
+#[utoipa::path(
+    get,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "adminListClients",
+    params(AdminClientListQuery),
+    responses(
+        (status = 200, description = "Ok", body = ClientPageDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get("/admin/client", wrap = "identity_middleware::Identity")]
+async fn admin_list(
+    data: Data<AppState>,
+    query: Query<AdminClientListQuery>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<PageDto<ClientDto>>> {
+    let filter = query.to_filter()?;
+    let pagination = Pagination::new(query.page, query.page_size)?;
+    let (clients, total) = data.client_service.search(&filter, &pagination).await?;
+
+    let mut res = Vec::with_capacity(clients.len());
+    for client in clients {
+        let token_entity = find_token(&data, &client).await?;
+        res.push(ClientDto::from_model(client, token_entity))
+    }
+
+    Ok(Json(PageDto::new(res, total, &pagination)))
+}
+
+/// Get any client by its id, including inactive clients
+#[utoipa::path(
+    get,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "adminGetClientById",
+    params(
+        ("id", description = "Client id")
+    ),
+    responses(
+        (status = 200, description = "Ok", body = ClientDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get("/admin/client/{id}", wrap = "identity_middleware::Identity")]
+async fn admin_by_id(
+    data: Data<AppState>,
+    path: Path<String>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<ClientDto>> {
+    let client = data
+        .client_service
+        .find_by_id_string_unwrap(path.as_ref(), true)
+        .await?;
+    let token_entity = find_token(&data, &client).await?;
+
+    Ok(Json(ClientDto::from_model(client, token_entity)))
+}
+
+/// Disable or delete the client of any user
+#[utoipa::path(
+    delete,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "adminDeleteClient",
+    params(
+        ("id", description = "Id of the client to delete"),
+        DeleteQuery
+    ),
+    responses(
+        (status = 204, description = "Client deleted"),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[delete(
+    "/admin/client/{id}",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::ClientDelete)"
+)]
+async fn admin_delete(
+    req: HttpRequest,
+    data: Data<AppState>,
+    path: Path<String>,
+    query: Query<DeleteQuery>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<impl Responder> {
+    let client = data
+        .client_service
+        .find_by_id_string_unwrap(path.as_ref(), true)
+        .await?;
+    set_audit_target(&req, client.id);
+
+    remove_client(&data, client, query.delete_in_database.unwrap_or(false)).await?;
     Ok(HttpResponse::NoContent().finish())
 }
 
-register_module!(create, regenerate_token, list, by_id, delete);
+/// Set whether the signing requests of a client must be approved by an admin
+#[utoipa::path(
+    put,
+    tag = "Clients",
+    context_path = "/api/v1",
+    request_body = ClientPolicyDto,
+    operation_id = "setClientPolicy",
+    params(
+        ("id", description = "Id of the client to update")
+    ),
+    responses(
+        (status = 200, description = "Ok", body = ClientDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[put(
+    "/client/{id}/policy",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::ClientPolicyUpdate)"
+)]
+async fn set_policy(
+    req: HttpRequest,
+    data: Data<AppState>,
+    path: Path<String>,
+    body: Json<ClientPolicyDto>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<ClientDto>> {
+    let client = data
+        .client_service
+        .find_by_id_string_unwrap(path.as_ref(), false)
+        .await?;
+    set_audit_target(&req, client.id);
+
+    let token_entity = data
+        .token_service
+        .find_by_client_id(&client.id, false)
+        .await?
+        .ok_or(HttpResponseError::not_found(Some("Token not found")))?;
+    let client = {
+        let mut entity = client.into_active_model();
+        entity.requires_approval = ActiveValue::Set(body.requires_approval);
+        data.client_service.update(entity).await?
+    };
+
+    Ok(Json(ClientDto::from_model(client, token_entity)))
+}
+
+register_module!(
+    create,
+    regenerate_token,
+    list,
+    by_id,
+    delete,
+    set_policy,
+    transfer,
+    admin_list,
+    admin_by_id,
+    admin_delete
+);
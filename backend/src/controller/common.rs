@@ -2,11 +2,42 @@ use crate::config::app_state::AppState;
 use crate::register_module;
 use crate::util::types::WebResult;
 use actix_web::web::Json;
-use actix_web::{get, web};
+use actix_web::{get, web, HttpResponse};
+use shared::model::component_health_dto::ComponentStatus;
 use shared::model::health_info_dto::HealthInfoDto;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Check all components the API depends on
+async fn check_components(data: &AppState) -> WebResult<HealthInfoDto> {
+    let health = &data.health_service;
+    let warning_days = data.config.health_expiry_warning_days;
+    let identity_provider = data.identity_provider.as_ref();
+    let (identity, keycloak_version) = health.check_identity_provider(identity_provider).await;
+    let components = vec![
+        health.check_database().await,
+        identity,
+        health.check_signing_keys(identity_provider).await,
+        health.check_intermediate(warning_days).await,
+        health.check_root(warning_days).await,
+    ];
+
+    let status = HealthInfoDto::overall_status(&components);
+    Ok(HealthInfoDto {
+        version: VERSION.to_string(),
+        keycloak_version,
+        status: status.as_str().to_string(),
+        ok: status == ComponentStatus::Up,
+        is_initialized: Some(
+            data.root_certificate_service.find_active().await?.is_some()
+                && data.certificate_service.find_active().await?.is_some(),
+        ),
+        components,
+    })
+}
+
+/// Get the status of the API and its components.
+/// Always responds with 200, use `/health/ready` for readiness probes.
 #[utoipa::path(
     get,
     tag = "Common",
@@ -19,18 +50,51 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 )]
 #[get("/health")]
 async fn health_check(data: web::Data<AppState>) -> WebResult<Json<HealthInfoDto>> {
-    let info = data.keycloak_service.get_server_info().await.ok();
+    Ok(Json(check_components(&data).await?))
+}
 
-    Ok(Json(HealthInfoDto {
+/// Check whether the API process is running
+#[utoipa::path(
+    get,
+    tag = "Common",
+    context_path = "/api/v1",
+    operation_id = "livenessCheck",
+    responses(
+        (status = 200, description = "Ok", body = HealthInfoDto),
+    ),
+)]
+#[get("/health/live")]
+async fn liveness_check() -> Json<HealthInfoDto> {
+    Json(HealthInfoDto {
         version: VERSION.to_string(),
-        keycloak_version: info.and_then(|i| i.system_info).and_then(|i| i.version),
-        status: "OK".into(),
+        keycloak_version: None,
+        status: ComponentStatus::Up.as_str().to_string(),
         ok: true,
-        is_initialized: Some(
-            data.root_certificate_service.find_active().await?.is_some()
-                && data.certificate_service.find_active().await?.is_some(),
-        ),
-    }))
+        is_initialized: None,
+        components: vec![],
+    })
+}
+
+/// Check whether the API is able to serve requests
+#[utoipa::path(
+    get,
+    tag = "Common",
+    context_path = "/api/v1",
+    operation_id = "readinessCheck",
+    responses(
+        (status = 200, description = "All components are up", body = HealthInfoDto),
+        (status = 503, description = "At least one component is degraded or down", body = HealthInfoDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+)]
+#[get("/health/ready")]
+async fn readiness_check(data: web::Data<AppState>) -> WebResult<HttpResponse> {
+    let info = check_components(&data).await?;
+    Ok(if info.ok {
+        HttpResponse::Ok().json(info)
+    } else {
+        HttpResponse::ServiceUnavailable().json(info)
+    })
 }
 
-register_module!(health_check);
+register_module!(health_check, liveness_check, readiness_check);
@@ -0,0 +1,190 @@
+use crate::config::app_state::AppState;
+use crate::entity::audit_event::AuditAction;
+use crate::entity::client;
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::middleware::audit_middleware::{set_audit_target, Audit};
+use crate::middleware::extractors::UserClaims;
+use crate::middleware::identity_middleware;
+use crate::middleware::user_roles::NoRoles;
+use crate::model::create_enrollment_token_dto::CreateEnrollmentTokenDto;
+use crate::model::enrollment_token_dto::EnrollmentTokenDto;
+use crate::register_module;
+use crate::util::traits::from_model::FromModel;
+use crate::util::types::WebResult;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, post, HttpRequest, HttpResponse, Responder};
+use chrono::Duration;
+use uuid::Uuid;
+
+/// Find an active client managed by the user
+async fn owned_client(data: &AppState, id: &str, user_id: &Uuid) -> WebResult<client::Model> {
+    let id = Uuid::parse_str(id).map_bad_request(Some("Invalid client id supplied"))?;
+    let client = data
+        .client_service
+        .find_by_id(&id, false)
+        .await?
+        .ok_or(HttpResponseError::not_found(Some("Client not found")))?;
+
+    if !data
+        .team_service
+        .can_manage_client(&client, user_id)
+        .await?
+    {
+        return Err(HttpResponseError::not_found(Some("Client not found")));
+    }
+
+    Ok(client)
+}
+
+/// Create a one-time enrollment token for a client.
+/// The token is only returned once and can be redeemed
+/// exactly once for a certificate, see `enroll`.
+#[utoipa::path(
+    post,
+    tag = "Clients",
+    context_path = "/api/v1",
+    request_body = CreateEnrollmentTokenDto,
+    operation_id = "createEnrollmentToken",
+    params(
+        ("id", description = "Id of the client")
+    ),
+    responses(
+        (status = 200, description = "Ok", body = EnrollmentTokenDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Client not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post(
+    "/client/{id}/enrollment-token",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::EnrollmentTokenCreate)"
+)]
+async fn create(
+    req: HttpRequest,
+    path: Path<String>,
+    body: Json<CreateEnrollmentTokenDto>,
+    data: Data<AppState>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<Json<EnrollmentTokenDto>> {
+    let client = owned_client(&data, &path, &claims.user.id).await?;
+
+    let max_minutes = data.config.enrollment_token_ttl_minutes;
+    let minutes = body.valid_for_minutes.unwrap_or(max_minutes);
+    if minutes < 1 || minutes > max_minutes {
+        return Err(HttpResponseError::bad_request(Some(format!(
+            "The token must be valid for 1 to {} minutes",
+            max_minutes
+        ))));
+    }
+
+    let (model, token) = data
+        .enrollment_token_service
+        .create(client.id, claims.user.id, Duration::minutes(minutes))
+        .await?;
+    set_audit_target(&req, model.id);
+
+    Ok(Json(EnrollmentTokenDto::from_model_with_token(
+        model, token,
+    )))
+}
+
+/// List the enrollment tokens of a client including their redemptions
+#[utoipa::path(
+    get,
+    tag = "Clients",
+    context_path = "/api/v1",
+    operation_id = "listEnrollmentTokens",
+    params(
+        ("id", description = "Id of the client")
+    ),
+    responses(
+        (status = 200, description = "Ok", body = Vec<EnrollmentTokenDto>),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Client not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get(
+    "/client/{id}/enrollment-token",
+    wrap = "identity_middleware::Identity"
+)]
+async fn list(
+    path: Path<String>,
+    data: Data<AppState>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<Json<Vec<EnrollmentTokenDto>>> {
+    let client = owned_client(&data, &path, &claims.user.id).await?;
+
+    Ok(Json(
+        data.enrollment_token_service
+            .find_all_by_client(&client.id)
+            .await?
+            .into_iter()
+            .map(EnrollmentTokenDto::from_model)
+            .collect(),
+    ))
+}
+
+/// Delete an enrollment token so it can't be redeemed anymore
+#[utoipa::path(
+    delete,
+    tag = "Clients",
+    context_path = "/api/v1",
+    operation_id = "deleteEnrollmentToken",
+    params(
+        ("id", description = "Id of the client"),
+        ("token_id", description = "Id of the enrollment token to delete")
+    ),
+    responses(
+        (status = 204, description = "Token deleted"),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[delete(
+    "/client/{id}/enrollment-token/{token_id}",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::EnrollmentTokenDelete)"
+)]
+async fn delete(
+    req: HttpRequest,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<impl Responder> {
+    let (client_id, token_id) = path.into_inner();
+    let client = owned_client(&data, &client_id, &claims.user.id).await?;
+    let token_id = Uuid::parse_str(&token_id).map_bad_request(Some("Invalid token id supplied"))?;
+    set_audit_target(&req, token_id);
+
+    let token = data
+        .enrollment_token_service
+        .find_by_id(&token_id)
+        .await?
+        .filter(|t| t.client_id == client.id)
+        .ok_or(HttpResponseError::not_found(Some(
+            "Enrollment token not found",
+        )))?;
+
+    data.enrollment_token_service.delete(token).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+register_module!(create, list, delete);
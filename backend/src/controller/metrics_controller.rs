@@ -0,0 +1,13 @@
+use crate::config::app_state::AppState;
+use crate::util::types::WebResult;
+use actix_web::web::Data;
+use actix_web::{get, HttpResponse};
+use prometheus::TEXT_FORMAT;
+
+/// Expose the metrics in the prometheus text format
+#[get("/metrics")]
+pub async fn get_metrics(data: Data<AppState>) -> WebResult<HttpResponse> {
+    Ok(HttpResponse::Ok()
+        .content_type(TEXT_FORMAT)
+        .body(data.metrics_service.gather().await?))
+}
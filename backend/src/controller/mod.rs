@@ -1,7 +1,19 @@
 pub mod admin_controller;
+pub mod approval_controller;
+pub mod audit_controller;
+pub mod backup_controller;
 pub mod certificate_controller;
 pub mod client_controller;
 pub mod common;
+pub mod enrollment_token_controller;
+pub mod metrics_controller;
+pub mod quota_controller;
 pub mod signing_request_controller;
 pub mod swagger;
+pub mod team_controller;
+pub mod token_controller;
+pub mod token_key_controller;
+pub mod tools_controller;
+pub mod transparency_controller;
 pub mod user_controller;
+pub mod webhook_controller;
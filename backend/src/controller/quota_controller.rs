@@ -0,0 +1,209 @@
+use crate::config::app_state::AppState;
+use crate::entity::audit_event::AuditAction;
+use crate::entity::quota::QuotaScope;
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::middleware::audit_middleware::{set_audit_target, Audit};
+use crate::middleware::extractors::UserClaims;
+use crate::middleware::identity_middleware;
+use crate::middleware::user_roles::AdminRole;
+use crate::model::quota_dto::QuotaDto;
+use crate::model::set_quota_dto::SetQuotaDto;
+use crate::register_module;
+use crate::util::traits::from_model::FromModel;
+use crate::util::types::WebResult;
+use actix_web::web::{Data, Json, Query};
+use actix_web::{delete, get, put, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct QuotaKeyQuery {
+    /// The subject the quota applies to
+    #[param(inline)]
+    pub scope: QuotaScope,
+    /// The id of the user or client, not set for the global quota
+    #[serde(rename = "subjectId")]
+    pub subject_id: Option<String>,
+}
+
+/// Resolve the subject id of a quota, checking that the user or client exists
+async fn subject_id(
+    data: &Data<AppState>,
+    scope: QuotaScope,
+    subject_id: &Option<String>,
+) -> WebResult<Uuid> {
+    let id = match (scope, subject_id) {
+        (QuotaScope::Global, None) => return Ok(Uuid::nil()),
+        (QuotaScope::Global, Some(_)) => {
+            return Err(HttpResponseError::bad_request(Some(
+                "The global quota must not have a subject id",
+            )))
+        }
+        (_, None) => {
+            return Err(HttpResponseError::bad_request(Some(
+                "A subject id must be supplied",
+            )))
+        }
+        (_, Some(id)) => {
+            Uuid::parse_str(id).map_bad_request(Some("Invalid subject id supplied"))?
+        }
+    };
+
+    let exists = match scope {
+        QuotaScope::User => data.user_service.find_by_id(&id, false).await?.is_some(),
+        _ => data.client_service.find_by_id(&id, false).await?.is_some(),
+    };
+    if exists {
+        Ok(id)
+    } else {
+        Err(HttpResponseError::not_found(Some("Subject not found")))
+    }
+}
+
+/// List all rate limit and quota overrides
+#[utoipa::path(
+    get,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "listQuotas",
+    responses(
+        (status = 200, description = "Ok", body = Vec<QuotaDto>),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get("/admin/quota", wrap = "identity_middleware::Identity")]
+async fn list(
+    data: Data<AppState>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<Vec<QuotaDto>>> {
+    Ok(Json(
+        data.quota_service
+            .find_all()
+            .await?
+            .into_iter()
+            .map(QuotaDto::from_model)
+            .collect(),
+    ))
+}
+
+/// Set the rate limit and quotas of the CA, a user or a client.
+/// Replaces any previously set values.
+#[utoipa::path(
+    put,
+    tag = "Admin",
+    context_path = "/api/v1",
+    request_body = SetQuotaDto,
+    operation_id = "setQuota",
+    responses(
+        (status = 200, description = "Ok", body = QuotaDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[put(
+    "/admin/quota",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::QuotaUpdate)"
+)]
+async fn set(
+    req: HttpRequest,
+    body: Json<SetQuotaDto>,
+    data: Data<AppState>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<QuotaDto>> {
+    if [
+        body.issuance_limit,
+        body.max_clients,
+        body.max_valid_certificates,
+    ]
+    .iter()
+    .flatten()
+    .any(|v| *v < 0)
+    {
+        return Err(HttpResponseError::bad_request(Some(
+            "Limits must not be negative",
+        )));
+    }
+
+    let id = subject_id(&data, body.scope, &body.subject_id).await?;
+    let model = data
+        .quota_service
+        .save(
+            body.scope,
+            id,
+            body.issuance_limit,
+            body.max_clients,
+            body.max_valid_certificates,
+        )
+        .await?;
+    set_audit_target(&req, id);
+
+    Ok(Json(QuotaDto::from_model(model)))
+}
+
+/// Remove a quota override, the configured defaults apply again
+#[utoipa::path(
+    delete,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "deleteQuota",
+    params(QuotaKeyQuery),
+    responses(
+        (status = 204, description = "Quota deleted"),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[delete(
+    "/admin/quota",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::QuotaDelete)"
+)]
+async fn delete(
+    req: HttpRequest,
+    query: Query<QuotaKeyQuery>,
+    data: Data<AppState>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<impl Responder> {
+    let id = match (query.scope, &query.subject_id) {
+        (QuotaScope::Global, _) => Uuid::nil(),
+        (_, Some(id)) => {
+            Uuid::parse_str(id).map_bad_request(Some("Invalid subject id supplied"))?
+        }
+        (_, None) => {
+            return Err(HttpResponseError::bad_request(Some(
+                "A subject id must be supplied",
+            )))
+        }
+    };
+
+    let model = data
+        .quota_service
+        .find(query.scope, &id)
+        .await?
+        .ok_or(HttpResponseError::not_found(Some("Quota not found")))?;
+    set_audit_target(&req, id);
+    data.quota_service.delete(model).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+register_module!(list, set, delete);
@@ -1,18 +1,122 @@
 use crate::config::app_state::AppState;
+use crate::entity::audit_event::AuditAction;
 use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
-use crate::middleware::extractors::KeycloakUserClaims;
-use crate::middleware::keycloak_middleware;
-use crate::middleware::keycloak_roles::NoRoles;
+use crate::middleware::audit_middleware::Audit;
+use crate::middleware::extractors::UserClaims;
+use crate::middleware::identity_middleware;
+use crate::middleware::user_roles::{AdminRole, NoRoles};
+use crate::model::page_dto::PageDto;
+use crate::model::signing_request_filter::{
+    SigningRequestFilter, SigningRequestSortField, SortOrder,
+};
+use crate::model::webhook_event::WebhookEvent;
 use crate::register_module;
+use crate::util::date::parse_date;
+use crate::util::metrics::CERTIFICATES_REVOKED;
+use crate::util::pagination::Pagination;
 use crate::util::traits::from_model::FromModel;
 use crate::util::types::WebResult;
-use actix_web::get;
 use actix_web::web;
-use actix_web::web::Json;
+use actix_web::web::{Json, Query};
+use actix_web::{get, put};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::Deserialize;
+use shared::model::certificate_status::CertificateStatus;
 use shared::model::signing_request_dto::SigningRequestDto;
 use std::str::FromStr;
+use utoipa::IntoParams;
 use uuid::Uuid;
 
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct SigningRequestQuery {
+    /// Only return certificates whose subject common name contains this value
+    #[serde(rename = "subjectName")]
+    pub subject_name: Option<String>,
+    /// Only return certificates with an alternative name containing this value
+    #[serde(rename = "alternativeName")]
+    pub alternative_name: Option<String>,
+    /// Only return the certificate with this serial number (hex)
+    #[serde(rename = "serialNumber")]
+    pub serial_number: Option<String>,
+    /// Only return the certificate with this sha256 fingerprint (hex)
+    pub fingerprint: Option<String>,
+    /// Only return certificates issued to this client
+    #[serde(rename = "clientId")]
+    pub client_id: Option<String>,
+    /// Only return certificates issued at or after this time (RFC 3339)
+    #[serde(rename = "issuedAfter")]
+    pub issued_after: Option<String>,
+    /// Only return certificates issued at or before this time (RFC 3339)
+    #[serde(rename = "issuedBefore")]
+    pub issued_before: Option<String>,
+    /// Only return certificates expiring at or after this time (RFC 3339)
+    #[serde(rename = "expiresAfter")]
+    pub expires_after: Option<String>,
+    /// Only return certificates expiring at or before this time (RFC 3339)
+    #[serde(rename = "expiresBefore")]
+    pub expires_before: Option<String>,
+    /// Only return certificates with this status
+    #[param(inline)]
+    pub status: Option<CertificateStatus>,
+    /// The field to sort by.
+    /// Defaults to issuedAt.
+    #[serde(rename = "sortBy")]
+    #[param(inline)]
+    pub sort_by: Option<SigningRequestSortField>,
+    /// The sort order.
+    /// Defaults to desc.
+    #[serde(rename = "sortOrder")]
+    #[param(inline)]
+    pub sort_order: Option<SortOrder>,
+    /// The zero-based index of the page to return.
+    /// Defaults to 0.
+    pub page: Option<u64>,
+    /// The number of elements per page.
+    /// Defaults to 50.
+    #[serde(rename = "pageSize")]
+    pub page_size: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct SigningRequestOwnerQuery {
+    /// Only return certificates of this user's clients and the user's personal certificates
+    #[serde(rename = "userId")]
+    pub user_id: Option<String>,
+}
+
+impl SigningRequestQuery {
+    fn parse_date(date: &Option<String>) -> WebResult<Option<DateTimeWithTimeZone>> {
+        date.as_deref().map(parse_date).transpose()
+    }
+
+    fn to_filter(&self) -> WebResult<SigningRequestFilter> {
+        Ok(SigningRequestFilter {
+            subject_name: self.subject_name.clone(),
+            alternative_name: self.alternative_name.clone(),
+            serial_number: self
+                .serial_number
+                .as_ref()
+                .map(|s| SigningRequestFilter::normalize_serial_number(s)),
+            fingerprint: self
+                .fingerprint
+                .as_ref()
+                .map(|f| SigningRequestFilter::normalize_fingerprint(f)),
+            client_id: self
+                .client_id
+                .as_ref()
+                .map(|id| Uuid::from_str(id).map_bad_request(Some("Invalid client id supplied")))
+                .transpose()?,
+            issued_after: Self::parse_date(&self.issued_after)?,
+            issued_before: Self::parse_date(&self.issued_before)?,
+            expires_after: Self::parse_date(&self.expires_after)?,
+            expires_before: Self::parse_date(&self.expires_before)?,
+            status: self.status,
+            sort_by: self.sort_by,
+            sort_order: self.sort_order,
+        })
+    }
+}
+
 #[utoipa::path(
     get,
     tag = "Signing requests",
@@ -32,11 +136,11 @@ use uuid::Uuid;
         ("oauth2" = [])
     )
 )]
-#[get("/signing-request/{id}", wrap = "keycloak_middleware::Keycloak")]
+#[get("/signing-request/{id}", wrap = "identity_middleware::Identity")]
 async fn by_client_id(
     data: web::Data<AppState>,
     id: web::Path<String>,
-    claims: KeycloakUserClaims<NoRoles>,
+    claims: UserClaims<NoRoles>,
 ) -> WebResult<Json<Vec<SigningRequestDto>>> {
     let client_id = Uuid::from_str(&id).map_bad_request(Some("Invalid client id supplied"))?;
     let client = data
@@ -45,7 +149,11 @@ async fn by_client_id(
         .await?
         .ok_or(HttpResponseError::not_found(Some("Client not found")))?;
 
-    if client.user_id != claims.user.id {
+    if !data
+        .team_service
+        .can_access_client(&client, &claims.user.id)
+        .await?
+    {
         return Err(HttpResponseError::unauthorized(Some(
             "User is not allowed to access this resource",
         )));
@@ -62,13 +170,16 @@ async fn by_client_id(
     ))
 }
 
+/// Search the signing requests of all clients of the current user,
+/// the clients of the user's teams and the user's personal certificates
 #[utoipa::path(
     get,
     tag = "Signing requests",
     context_path = "/api/v1",
     operation_id = "getSigningRequests",
+    params(SigningRequestQuery),
     responses(
-        (status = 200, description = "Ok", body = Vec<SigningRequestDto>),
+        (status = 200, description = "Ok", body = SigningRequestPageDto),
         (status = 400, description = "Bad request", body = ErrorDto),
         (status = 401, description = "Unauthorized", body = ErrorDto),
         (status = 500, description = "Internal server error", body = ErrorDto),
@@ -77,20 +188,149 @@ async fn by_client_id(
         ("oauth2" = [])
     )
 )]
-#[get("/signing-request", wrap = "keycloak_middleware::Keycloak")]
+#[get("/signing-request", wrap = "identity_middleware::Identity")]
 async fn get_all(
     data: web::Data<AppState>,
-    claims: KeycloakUserClaims<NoRoles>,
-) -> WebResult<Json<Vec<SigningRequestDto>>> {
-    Ok(Json(
-        data.signing_request_service
-            .find_all_by_user_id(&claims.user.id)
-            .await
-            .map_internal_error(Some("Failed to get signing requests"))?
+    query: Query<SigningRequestQuery>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<Json<PageDto<SigningRequestDto>>> {
+    let filter = query.to_filter()?;
+    let pagination = Pagination::new(query.page, query.page_size)?;
+
+    let (items, total) = data
+        .signing_request_service
+        .search_by_user_id(&claims.user.id, &filter, &pagination)
+        .await?;
+
+    Ok(Json(PageDto::new(
+        items
             .into_iter()
             .map(|r| SigningRequestDto::from_model(r))
             .collect(),
-    ))
+        total,
+        &pagination,
+    )))
+}
+
+/// Search the signing requests of all users
+#[utoipa::path(
+    get,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "adminGetSigningRequests",
+    params(SigningRequestQuery, SigningRequestOwnerQuery),
+    responses(
+        (status = 200, description = "Ok", body = SigningRequestPageDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get("/admin/signing-request", wrap = "identity_middleware::Identity")]
+async fn admin_get_all(
+    data: web::Data<AppState>,
+    query: Query<SigningRequestQuery>,
+    owner: Query<SigningRequestOwnerQuery>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<PageDto<SigningRequestDto>>> {
+    let filter = query.to_filter()?;
+    let pagination = Pagination::new(query.page, query.page_size)?;
+    let user_id = owner
+        .user_id
+        .as_ref()
+        .map(|id| Uuid::from_str(id).map_bad_request(Some("Invalid user id supplied")))
+        .transpose()?;
+
+    let (items, total) = data
+        .signing_request_service
+        .search(user_id.as_ref(), &filter, &pagination)
+        .await?;
+
+    Ok(Json(PageDto::new(
+        items
+            .into_iter()
+            .map(SigningRequestDto::from_model)
+            .collect(),
+        total,
+        &pagination,
+    )))
+}
+
+/// Revoke a certificate issued to a client managed by the current user
+/// or a personal certificate of the user
+#[utoipa::path(
+    put,
+    tag = "Signing requests",
+    context_path = "/api/v1",
+    operation_id = "revokeSigningRequest",
+    params(
+        ("id", description = "The id of the signing request")
+    ),
+    responses(
+        (status = 200, description = "Ok", body = SigningRequestDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[put(
+    "/signing-request/{id}/revoke",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::CertificateRevoke)"
+)]
+async fn revoke(
+    data: web::Data<AppState>,
+    id: web::Path<i32>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<Json<SigningRequestDto>> {
+    let request = data
+        .signing_request_service
+        .find_by_id(id.into_inner())
+        .await?
+        .ok_or(HttpResponseError::not_found(Some(
+            "Signing request not found",
+        )))?;
+
+    let is_owner = match &request.client_id {
+        Some(client_id) => match data.client_service.find_by_id(client_id, true).await? {
+            Some(client) => {
+                data.team_service
+                    .can_manage_client(&client, &claims.user.id)
+                    .await?
+            }
+            None => false,
+        },
+        None => request.requested_by == Some(claims.user.id),
+    };
+    if !is_owner {
+        return Err(HttpResponseError::not_found(Some(
+            "Signing request not found",
+        )));
+    }
+
+    if request.revoked_at.is_some() {
+        return Err(HttpResponseError::bad_request(Some(
+            "The certificate is already revoked",
+        )));
+    }
+
+    let dto = SigningRequestDto::from_model(data.signing_request_service.revoke(request).await?);
+    CERTIFICATES_REVOKED.inc();
+    data.webhook_service
+        .emit(
+            WebhookEvent::CertificateRevoked,
+            Some(&claims.user.id),
+            &dto,
+        )
+        .await;
+    Ok(Json(dto))
 }
 
-register_module!(by_client_id, get_all);
+register_module!(get_all, admin_get_all, by_client_id, revoke);
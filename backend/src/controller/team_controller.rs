@@ -0,0 +1,358 @@
+use crate::config::app_state::AppState;
+use crate::entity::audit_event::AuditAction;
+use crate::entity::team;
+use crate::error::http_response_error::HttpResponseError;
+use crate::middleware::audit_middleware::{set_audit_target, Audit};
+use crate::middleware::extractors::UserClaims;
+use crate::middleware::identity_middleware;
+use crate::middleware::user_roles::{AdminRole, NoRoles};
+use crate::model::create_team_dto::CreateTeamDto;
+use crate::model::set_team_member_dto::SetTeamMemberDto;
+use crate::model::team_dto::TeamDto;
+use crate::register_module;
+use crate::service::team_service::SyncedGroup;
+use crate::util::types::WebResult;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, post, put, HttpRequest, HttpResponse, Responder};
+use log::debug;
+
+async fn to_dto(data: &AppState, team: team::Model) -> WebResult<TeamDto> {
+    let members = data.team_service.find_members(&team.id).await?;
+    Ok(TeamDto::from_model(team, members))
+}
+
+/// Create a team whose members are managed locally
+#[utoipa::path(
+    post,
+    tag = "Teams",
+    context_path = "/api/v1",
+    request_body = CreateTeamDto,
+    operation_id = "createTeam",
+    responses(
+        (status = 200, description = "Ok", body = TeamDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post(
+    "/team",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::TeamCreate)"
+)]
+async fn create(
+    req: HttpRequest,
+    body: Json<CreateTeamDto>,
+    data: Data<AppState>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<TeamDto>> {
+    let name = body.name.trim().to_string();
+    if name.len() < 3 {
+        return Err(HttpResponseError::bad_request(Some(
+            "The team name must be at least 3 characters long",
+        )));
+    }
+
+    data.team_service
+        .find_by_name(&name)
+        .await?
+        .map(|_| Err(HttpResponseError::bad_request(Some("Team already exists"))))
+        .unwrap_or(Ok(()))?;
+
+    let team = data.team_service.insert(name).await?;
+    set_audit_target(&req, team.id);
+
+    Ok(Json(TeamDto::from_model(team, vec![])))
+}
+
+/// List the teams of the current user, admins get all teams
+#[utoipa::path(
+    get,
+    tag = "Teams",
+    context_path = "/api/v1",
+    operation_id = "listTeams",
+    responses(
+        (status = 200, description = "Ok", body = Vec<TeamDto>),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get("/team/list", wrap = "identity_middleware::Identity")]
+async fn list(data: Data<AppState>, claims: UserClaims<NoRoles>) -> WebResult<Json<Vec<TeamDto>>> {
+    let teams = if claims.has_roles::<AdminRole>() {
+        data.team_service.find_all().await?
+    } else {
+        data.team_service.find_all_by_user(&claims.user.id).await?
+    };
+
+    let mut res = Vec::with_capacity(teams.len());
+    for team in teams {
+        res.push(to_dto(&data, team).await?);
+    }
+
+    Ok(Json(res))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Teams",
+    context_path = "/api/v1",
+    operation_id = "getTeamById",
+    params(
+        ("id", description = "Id of the team")
+    ),
+    responses(
+        (status = 200, description = "Ok", body = TeamDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get("/team/{id}", wrap = "identity_middleware::Identity")]
+async fn by_id(
+    path: Path<String>,
+    data: Data<AppState>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<Json<TeamDto>> {
+    let team = data.team_service.find_by_id_string_unwrap(&path).await?;
+    if !claims.has_roles::<AdminRole>()
+        && data
+            .team_service
+            .find_member(&team.id, &claims.user.id)
+            .await?
+            .is_none()
+    {
+        return Err(HttpResponseError::not_found(Some("Team not found")));
+    }
+
+    Ok(Json(to_dto(&data, team).await?))
+}
+
+/// Delete a team which doesn't own any clients
+#[utoipa::path(
+    delete,
+    tag = "Teams",
+    context_path = "/api/v1",
+    operation_id = "deleteTeam",
+    params(
+        ("id", description = "Id of the team to delete")
+    ),
+    responses(
+        (status = 204, description = "Team deleted"),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[delete(
+    "/team/{id}",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::TeamDelete)"
+)]
+async fn delete(
+    req: HttpRequest,
+    path: Path<String>,
+    data: Data<AppState>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<impl Responder> {
+    let team = data.team_service.find_by_id_string_unwrap(&path).await?;
+    set_audit_target(&req, team.id);
+
+    data.team_service.delete(team).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Add a user to a team or change the role of a member.
+/// Users can only be added to teams which are not synced from the identity provider.
+#[utoipa::path(
+    put,
+    tag = "Teams",
+    context_path = "/api/v1",
+    request_body = SetTeamMemberDto,
+    operation_id = "setTeamMember",
+    params(
+        ("id", description = "Id of the team"),
+        ("userId", description = "Id of the user")
+    ),
+    responses(
+        (status = 200, description = "Ok", body = TeamDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[put(
+    "/team/{id}/member/{userId}",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::TeamMemberUpdate)"
+)]
+async fn set_member(
+    req: HttpRequest,
+    path: Path<(String, String)>,
+    body: Json<SetTeamMemberDto>,
+    data: Data<AppState>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<TeamDto>> {
+    let (id, user_id) = path.into_inner();
+    let team = data.team_service.find_by_id_string_unwrap(&id).await?;
+    let user = data
+        .user_service
+        .find_by_id_string_unwrap(&user_id, false)
+        .await?;
+    set_audit_target(&req, team.id);
+
+    if team.external_id.is_some()
+        && data
+            .team_service
+            .find_member(&team.id, &user.id)
+            .await?
+            .is_none()
+    {
+        return Err(HttpResponseError::bad_request(Some(
+            "The members of the team are managed in the identity provider",
+        )));
+    }
+
+    debug!("Setting the role of user {} in team {}", user.id, team.id);
+    data.team_service
+        .set_member(&team.id, &user.id, body.role)
+        .await?;
+
+    Ok(Json(to_dto(&data, team).await?))
+}
+
+/// Remove a user from a team which is not synced from the identity provider
+#[utoipa::path(
+    delete,
+    tag = "Teams",
+    context_path = "/api/v1",
+    operation_id = "removeTeamMember",
+    params(
+        ("id", description = "Id of the team"),
+        ("userId", description = "Id of the user")
+    ),
+    responses(
+        (status = 200, description = "Ok", body = TeamDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[delete(
+    "/team/{id}/member/{userId}",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::TeamMemberRemove)"
+)]
+async fn remove_member(
+    req: HttpRequest,
+    path: Path<(String, String)>,
+    data: Data<AppState>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<TeamDto>> {
+    let (id, user_id) = path.into_inner();
+    let team = data.team_service.find_by_id_string_unwrap(&id).await?;
+    set_audit_target(&req, team.id);
+
+    if team.external_id.is_some() {
+        return Err(HttpResponseError::bad_request(Some(
+            "The members of the team are managed in the identity provider",
+        )));
+    }
+
+    let user = data
+        .user_service
+        .find_by_id_string_unwrap(&user_id, true)
+        .await?;
+    let member = data
+        .team_service
+        .find_member(&team.id, &user.id)
+        .await?
+        .ok_or(HttpResponseError::not_found(Some("Team member not found")))?;
+    data.team_service.remove_member(member).await?;
+
+    Ok(Json(to_dto(&data, team).await?))
+}
+
+/// Create or update a team for every top level group of the identity provider and replace
+/// the members of the teams with the members of the groups. New members
+/// join as `member`, existing members keep their role.
+#[utoipa::path(
+    post,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "syncTeams",
+    responses(
+        (status = 200, description = "Ok", body = Vec<TeamDto>),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post(
+    "/admin/team/sync",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::TeamSync)"
+)]
+async fn sync(
+    data: Data<AppState>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<Vec<TeamDto>>> {
+    let mut groups = vec![];
+    for group in data.identity_provider.get_groups().await? {
+        let mut user_ids = vec![];
+        for member in data.identity_provider.get_group_members(&group.id).await? {
+            // Users which have never been created in the CA are skipped
+            if let Some(user) = data
+                .user_service
+                .find_by_external_id(&member.id, false)
+                .await?
+            {
+                user_ids.push(user.id);
+            }
+        }
+
+        groups.push(SyncedGroup {
+            external_id: group.id,
+            name: group.name,
+            user_ids,
+        });
+    }
+
+    debug!("Syncing {} teams from the identity provider", groups.len());
+    let teams = data.team_service.sync(&groups).await?;
+
+    let mut res = Vec::with_capacity(teams.len());
+    for team in teams {
+        res.push(to_dto(&data, team).await?);
+    }
+
+    Ok(Json(res))
+}
+
+register_module!(create, list, by_id, delete, set_member, remove_member, sync);
@@ -0,0 +1,218 @@
+use crate::config::app_state::AppState;
+use crate::entity::audit_event::AuditAction;
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::middleware::audit_middleware::{set_audit_target, Audit};
+use crate::middleware::extractors::UserClaims;
+use crate::middleware::identity_middleware;
+use crate::middleware::user_roles::{AdminRole, NoRoles};
+use crate::model::introspection_dto::IntrospectionDto;
+use crate::model::introspection_request_dto::IntrospectionRequestDto;
+use crate::model::page_dto::PageDto;
+use crate::model::revoke_token_dto::RevokeTokenDto;
+use crate::model::token_dto::TokenDto;
+use crate::model::token_scope::TokenScope;
+use crate::register_module;
+use crate::service::token_service::TokenService;
+use crate::util::date::parse_date;
+use crate::util::pagination::Pagination;
+use crate::util::traits::from_model::FromModel;
+use crate::util::types::WebResult;
+use actix_web::web::{Data, Form, Json, Query};
+use actix_web::{get, post, HttpRequest};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct TokenListQuery {
+    /// Whether to include revoked and replaced tokens.
+    /// Defaults to false.
+    #[serde(rename = "includeInactive")]
+    pub include_inactive: Option<bool>,
+    /// Only return tokens which haven't been used since this time (RFC 3339)
+    #[serde(rename = "unusedSince")]
+    pub unused_since: Option<String>,
+    /// The zero-based index of the page to return.
+    /// Defaults to 0.
+    pub page: Option<u64>,
+    /// The number of elements per page.
+    /// Defaults to 50.
+    #[serde(rename = "pageSize")]
+    pub page_size: Option<u64>,
+}
+
+/// Get the state of a client token as defined in RFC 7662.
+/// Admins may introspect all tokens, other users only the tokens of their clients.
+/// Tokens of other users are reported as inactive.
+#[utoipa::path(
+    post,
+    tag = "Clients",
+    context_path = "/api/v1",
+    operation_id = "introspectToken",
+    request_body(content = IntrospectionRequestDto, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "Ok", body = IntrospectionDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post("/token/introspect", wrap = "identity_middleware::Identity")]
+async fn introspect(
+    data: Data<AppState>,
+    body: Form<IntrospectionRequestDto>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<Json<IntrospectionDto>> {
+    let token_claims = match data
+        .token_key_service
+        .decode(&body.token, data.config.jwt_secret.as_deref())
+        .await
+    {
+        Ok(token_claims) => token_claims,
+        Err(_) => return Ok(Json(IntrospectionDto::inactive())),
+    };
+
+    let token = match Uuid::parse_str(&token_claims.sub) {
+        Ok(id) => data.token_service.find_by_id(&id, false).await?,
+        Err(_) => None,
+    }
+    .filter(|t| t.token_hash == TokenService::hash_token(&body.token));
+    let token = match token {
+        Some(token) => token,
+        None => return Ok(Json(IntrospectionDto::inactive())),
+    };
+
+    let client = match data
+        .client_service
+        .find_by_id(&token.client_id, false)
+        .await?
+    {
+        Some(client) => client,
+        None => return Ok(Json(IntrospectionDto::inactive())),
+    };
+    if !claims.has_roles::<AdminRole>()
+        && !data
+            .team_service
+            .can_access_client(&client, &claims.user.id)
+            .await?
+    {
+        return Ok(Json(IntrospectionDto::inactive()));
+    }
+
+    Ok(Json(IntrospectionDto {
+        active: true,
+        scope: Some(TokenScope::join(&token.scopes(), " ")),
+        client_id: Some(client.id.to_string()),
+        token_type: Some("Bearer".to_string()),
+        exp: Some(token_claims.exp),
+        iat: Some(token_claims.iat),
+        sub: Some(token.id.to_string()),
+    }))
+}
+
+/// List all client tokens, for example to find stale tokens
+/// which haven't been used for a long time
+#[utoipa::path(
+    get,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "listTokens",
+    params(TokenListQuery),
+    responses(
+        (status = 200, description = "Ok", body = TokenPageDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get("/admin/token", wrap = "identity_middleware::Identity")]
+async fn list(
+    data: Data<AppState>,
+    query: Query<TokenListQuery>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<PageDto<TokenDto>>> {
+    let pagination = Pagination::new(query.page, query.page_size)?;
+    let unused_since = query.unused_since.as_deref().map(parse_date).transpose()?;
+
+    let (items, total) = data
+        .token_service
+        .find_page(
+            query.include_inactive.unwrap_or(false),
+            unused_since,
+            &pagination,
+        )
+        .await?;
+
+    Ok(Json(PageDto::new(
+        items.into_iter().map(TokenDto::from_model).collect(),
+        total,
+        &pagination,
+    )))
+}
+
+/// Revoke a client token by its id or hash.
+/// The client has to be issued a new token to authenticate using a token again.
+#[utoipa::path(
+    post,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "revokeToken",
+    request_body = RevokeTokenDto,
+    responses(
+        (status = 200, description = "Ok", body = TokenDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post(
+    "/admin/token/revoke",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::TokenRevoke)"
+)]
+async fn revoke(
+    req: HttpRequest,
+    data: Data<AppState>,
+    body: Json<RevokeTokenDto>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<TokenDto>> {
+    let token = match (&body.id, &body.hash) {
+        (Some(id), None) => {
+            let id = Uuid::parse_str(id).map_bad_request(Some("Invalid token id supplied"))?;
+            data.token_service.find_by_id(&id, true).await?
+        }
+        (None, Some(hash)) => data.token_service.find_by_hash(hash).await?,
+        _ => {
+            return Err(HttpResponseError::bad_request(Some(
+                "Either the id or the hash of the token must be supplied",
+            )))
+        }
+    }
+    .ok_or(HttpResponseError::not_found(Some("Token not found")))?;
+    set_audit_target(&req, token.id);
+
+    if !token.active {
+        return Err(HttpResponseError::bad_request(Some(
+            "The token is not active",
+        )));
+    }
+
+    Ok(Json(TokenDto::from_model(
+        data.token_service.revoke(token).await?,
+    )))
+}
+
+register_module!(introspect, list, revoke);
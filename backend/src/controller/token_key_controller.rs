@@ -0,0 +1,150 @@
+use crate::config::app_state::AppState;
+use crate::entity::audit_event::AuditAction;
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::middleware::audit_middleware::{set_audit_target, Audit};
+use crate::middleware::extractors::UserClaims;
+use crate::middleware::identity_middleware;
+use crate::middleware::user_roles::AdminRole;
+use crate::model::jwk_dto::JwksDto;
+use crate::model::token_key_dto::TokenKeyDto;
+use crate::register_module;
+use crate::util::traits::from_model::FromModel;
+use crate::util::types::WebResult;
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, post, HttpRequest, HttpResponse, Responder};
+use uuid::Uuid;
+
+/// Get the public keys client tokens are signed with
+#[utoipa::path(
+    get,
+    tag = "Common",
+    context_path = "/api/v1",
+    operation_id = "getJwks",
+    responses(
+        (status = 200, description = "Ok", body = JwksDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+)]
+#[get("/.well-known/jwks.json")]
+async fn jwks(data: Data<AppState>) -> WebResult<Json<JwksDto>> {
+    Ok(Json(data.token_key_service.jwks().await?))
+}
+
+/// List all keys client tokens are signed or verified with
+#[utoipa::path(
+    get,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "listTokenKeys",
+    responses(
+        (status = 200, description = "Ok", body = Vec<TokenKeyDto>),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get("/admin/token-key", wrap = "identity_middleware::Identity")]
+async fn list(
+    data: Data<AppState>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<Vec<TokenKeyDto>>> {
+    Ok(Json(
+        data.token_key_service
+            .find_all()
+            .await?
+            .into_iter()
+            .map(TokenKeyDto::from_model)
+            .collect(),
+    ))
+}
+
+/// Generate a new key new client tokens are signed with.
+/// Tokens signed with the previous keys stay valid until their keys are deleted.
+#[utoipa::path(
+    post,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "rotateTokenKey",
+    responses(
+        (status = 200, description = "Ok", body = TokenKeyDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post(
+    "/admin/token-key/rotate",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::TokenKeyRotate)"
+)]
+async fn rotate(
+    req: HttpRequest,
+    data: Data<AppState>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<TokenKeyDto>> {
+    let algorithm = data
+        .config
+        .token_key_algorithm()
+        .map_internal_error(Some("Invalid token key algorithm configured"))?;
+    let key = data.token_key_service.rotate(algorithm).await?;
+    set_audit_target(&req, key.id);
+
+    Ok(Json(TokenKeyDto::from_model(key)))
+}
+
+/// Delete a retired key.
+/// All client tokens signed with the key are rejected afterwards.
+#[utoipa::path(
+    delete,
+    tag = "Admin",
+    context_path = "/api/v1",
+    operation_id = "deleteTokenKey",
+    params(
+        ("kid", description = "The id of the key to delete")
+    ),
+    responses(
+        (status = 204, description = "Key deleted"),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[delete(
+    "/admin/token-key/{kid}",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::TokenKeyDelete)"
+)]
+async fn delete(
+    path: Path<String>,
+    data: Data<AppState>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<impl Responder> {
+    let kid = Uuid::parse_str(&path).map_bad_request(Some("Invalid key id supplied"))?;
+    let key = data
+        .token_key_service
+        .find_by_id(&kid)
+        .await?
+        .ok_or(HttpResponseError::not_found(Some("Token key not found")))?;
+
+    if key.active {
+        return Err(HttpResponseError::bad_request(Some(
+            "The active key can't be deleted, rotate the keys first",
+        )));
+    }
+
+    data.token_key_service.delete(key).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+register_module!(jwks, list, rotate, delete);
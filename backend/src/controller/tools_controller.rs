@@ -0,0 +1,96 @@
+use crate::config::app_state::AppState;
+use crate::error::http_response_error::MapHttpResponseError;
+use crate::middleware::extractors::UserClaims;
+use crate::middleware::identity_middleware;
+use crate::middleware::user_roles::NoRoles;
+use crate::model::inspect_request_dto::InspectRequestDto;
+use crate::model::inspection_result_dto::{InspectedObjectType, InspectionResultDto};
+use crate::register_module;
+use crate::util::ca_store::CAStore;
+use crate::util::certificate_info::{
+    certificate_info, revocation_list_info, signing_request_info, InspectedObject,
+};
+use crate::util::types::WebResult;
+use actix_web::post;
+use actix_web::web::{Data, Json};
+
+/// Decode a certificate, certificate signing request or revocation list.
+/// Also reports whether the object was issued by this CA
+/// and the revocation status of certificates issued by this CA.
+#[utoipa::path(
+    post,
+    tag = "Tools",
+    context_path = "/api/v1",
+    operation_id = "inspect",
+    request_body = InspectRequestDto,
+    responses(
+        (status = 200, description = "Ok", body = InspectionResultDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post("/tools/inspect", wrap = "identity_middleware::Identity")]
+async fn inspect(
+    data: Data<AppState>,
+    body: Json<InspectRequestDto>,
+    _claims: UserClaims<NoRoles>,
+) -> WebResult<Json<InspectionResultDto>> {
+    let object = InspectedObject::parse(&body.data).map_bad_request(Some(
+        "The data is not a valid certificate, signing request or revocation list",
+    ))?;
+
+    Ok(Json(match object {
+        InspectedObject::Certificate(cert) => {
+            let info =
+                certificate_info(&cert).map_bad_request(Some("Failed to decode certificate"))?;
+            let issued_by_ca = CAStore::load(&data)
+                .await?
+                .chains_to_ca(&cert)
+                .map_internal_error(Some("Failed to verify certificate"))?;
+
+            let status = match (issued_by_ca, &info.serial_number) {
+                (true, Some(serial_number)) => data
+                    .signing_request_service
+                    .find_by_serial_number(serial_number)
+                    .await?
+                    .map(|req| req.status()),
+                _ => None,
+            };
+
+            InspectionResultDto {
+                object_type: InspectedObjectType::Certificate,
+                certificate: Some(info),
+                revocation_list: None,
+                issued_by_ca: Some(issued_by_ca),
+                status,
+            }
+        }
+        InspectedObject::SigningRequest(req) => InspectionResultDto {
+            object_type: InspectedObjectType::SigningRequest,
+            certificate: Some(
+                signing_request_info(&req)
+                    .map_bad_request(Some("Failed to decode signing request"))?,
+            ),
+            revocation_list: None,
+            issued_by_ca: None,
+            status: None,
+        },
+        InspectedObject::RevocationList(crl) => InspectionResultDto {
+            object_type: InspectedObjectType::RevocationList,
+            certificate: None,
+            revocation_list: Some(
+                revocation_list_info(&crl)
+                    .map_bad_request(Some("Failed to decode revocation list"))?,
+            ),
+            issued_by_ca: Some(CAStore::load(&data).await?.signed_crl(&crl)),
+            status: None,
+        },
+    }))
+}
+
+register_module!(inspect);
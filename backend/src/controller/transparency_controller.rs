@@ -0,0 +1,125 @@
+use crate::config::app_state::AppState;
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::model::signing_request_filter::SigningRequestFilter;
+use crate::register_module;
+use crate::util::ca_certificate::CACertificate;
+use crate::util::types::WebResult;
+use actix_web::get;
+use actix_web::web::{Data, Json, Query};
+use serde::Deserialize;
+use shared::model::consistency_proof_dto::ConsistencyProofDto;
+use shared::model::inclusion_proof_dto::InclusionProofDto;
+use shared::model::signed_tree_head_dto::SignedTreeHeadDto;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct InclusionProofQuery {
+    /// The serial number (hex) of the certificate
+    #[serde(rename = "serialNumber")]
+    pub serial_number: String,
+    /// The size of the tree to prove the inclusion in.
+    /// Defaults to the current size of the log.
+    #[serde(rename = "treeSize")]
+    pub tree_size: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct ConsistencyProofQuery {
+    /// The size of the older tree
+    pub first: u64,
+    /// The size of the newer tree.
+    /// Defaults to the current size of the log.
+    pub second: Option<u64>,
+}
+
+/// Get the current head of the certificate issuance log,
+/// signed using the key of the active intermediate certificate
+#[utoipa::path(
+    get,
+    context_path = "/api/v1/transparency",
+    tag = "Transparency",
+    operation_id = "getSignedTreeHead",
+    responses(
+        (status = 200, description = "Ok", body = SignedTreeHeadDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+)]
+#[get("/sth")]
+async fn signed_tree_head(data: Data<AppState>) -> WebResult<Json<SignedTreeHeadDto>> {
+    let signer: CACertificate = data
+        .certificate_service
+        .find_active()
+        .await?
+        .ok_or(HttpResponseError::bad_request(Some(
+            "No active CA certificate found",
+        )))?
+        .try_into()
+        .map_internal_error(Some("Failed to map model"))?;
+
+    Ok(Json(
+        data.transparency_log_service.tree_head(&signer).await?,
+    ))
+}
+
+/// Get the proof that a certificate is contained in the issuance log
+#[utoipa::path(
+    get,
+    context_path = "/api/v1/transparency",
+    tag = "Transparency",
+    operation_id = "getInclusionProof",
+    params(InclusionProofQuery),
+    responses(
+        (status = 200, description = "Ok", body = InclusionProofDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+)]
+#[get("/proof/inclusion")]
+async fn inclusion_proof(
+    data: Data<AppState>,
+    query: Query<InclusionProofQuery>,
+) -> WebResult<Json<InclusionProofDto>> {
+    Ok(Json(
+        data.transparency_log_service
+            .inclusion_proof(
+                &SigningRequestFilter::normalize_serial_number(&query.serial_number),
+                query.tree_size,
+            )
+            .await?,
+    ))
+}
+
+/// Get the proof that an older state of the issuance log
+/// is a prefix of a newer state
+#[utoipa::path(
+    get,
+    context_path = "/api/v1/transparency",
+    tag = "Transparency",
+    operation_id = "getConsistencyProof",
+    params(ConsistencyProofQuery),
+    responses(
+        (status = 200, description = "Ok", body = ConsistencyProofDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+)]
+#[get("/proof/consistency")]
+async fn consistency_proof(
+    data: Data<AppState>,
+    query: Query<ConsistencyProofQuery>,
+) -> WebResult<Json<ConsistencyProofDto>> {
+    Ok(Json(
+        data.transparency_log_service
+            .consistency_proof(query.first, query.second)
+            .await?,
+    ))
+}
+
+register_module!(
+    "/transparency",
+    signed_tree_head,
+    inclusion_proof,
+    consistency_proof
+);
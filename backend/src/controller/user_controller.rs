@@ -1,19 +1,25 @@
 use crate::config::app_state::AppState;
+use crate::entity::audit_event::AuditAction;
 use crate::entity::user;
-use crate::error::http_response_error::HttpResponseError;
-use crate::middleware::extractors::KeycloakUserClaims;
-use crate::middleware::keycloak_middleware;
-use crate::middleware::keycloak_roles::AdminRole;
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::identity::identity_provider::{unsupported, IdentityUser, NewIdentityUser};
+use crate::middleware::audit_middleware::{set_audit_target, Audit};
+use crate::middleware::extractors::UserClaims;
+use crate::middleware::identity_middleware;
+use crate::middleware::user_roles::AdminRole;
 use crate::model::create_user_dto::CreateUserDto;
+use crate::model::page_dto::PageDto;
+use crate::model::reset_password_dto::ResetPasswordDto;
+use crate::model::update_user_dto::UpdateUserDto;
 use crate::model::user_dto::UserDto;
 use crate::register_module;
+use crate::util::pagination::Pagination;
 use crate::util::types::WebResult;
 use actix_web::web::{Json, Query};
-use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
 use futures_util::future::join_all;
-use keycloak::types::{CredentialRepresentation, UserRepresentation};
 use log::debug;
-use sea_orm::ActiveValue;
+use sea_orm::{ActiveValue, TryIntoModel};
 use serde::Deserialize;
 use utoipa::IntoParams;
 
@@ -25,6 +31,21 @@ pub struct UserQuery {
     pub include_inactive: Option<bool>,
 }
 
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct UserListQuery {
+    /// Whether to include inactive users in the result.
+    /// Defaults to false.
+    #[serde(rename = "includeInactive")]
+    pub include_inactive: Option<bool>,
+    /// The zero-based index of the page to return.
+    /// Defaults to 0.
+    pub page: Option<u64>,
+    /// The number of elements per page.
+    /// Defaults to 50.
+    #[serde(rename = "pageSize")]
+    pub page_size: Option<u64>,
+}
+
 #[derive(Deserialize, Debug, IntoParams)]
 struct DeleteQuery {
     /// Whether to delete the user rather than just deactivating it.
@@ -33,6 +54,32 @@ struct DeleteQuery {
     pub delete_in_database: Option<bool>,
 }
 
+/// Get the identity provider id of a user. Users disabled by deactivating
+/// them using the delete endpoint no longer exist in the identity provider.
+fn external_id(user: &user::Model) -> WebResult<String> {
+    user.external_id
+        .clone()
+        .ok_or(HttpResponseError::bad_request(Some(
+            "The user no longer exists in the identity provider",
+        )))
+}
+
+/// Get the details of a user from the identity provider,
+/// if the identity provider manages the users of the CA
+async fn identity_user(data: &AppState, model: &user::Model) -> WebResult<Option<IdentityUser>> {
+    match model.external_id.as_ref() {
+        Some(id) if data.identity_provider.manages_users() => {
+            Ok(Some(data.identity_provider.get_user(id).await?))
+        }
+        _ => Ok(None),
+    }
+}
+
+async fn to_dto(data: &AppState, model: user::Model) -> WebResult<UserDto> {
+    let identity_user = identity_user(data, &model).await?;
+    Ok(UserDto::from_model(model, identity_user))
+}
+
 #[utoipa::path(
     post,
     tag = "Users",
@@ -50,11 +97,16 @@ struct DeleteQuery {
         ("oauth2" = [])
     )
 )]
-#[post("/user", wrap = "keycloak_middleware::Keycloak")]
+#[post(
+    "/user",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::UserCreate)"
+)]
 async fn create(
+    req: HttpRequest,
     user: Json<CreateUserDto>,
     data: web::Data<AppState>,
-    _claims: KeycloakUserClaims<AdminRole>,
+    _claims: UserClaims<AdminRole>,
 ) -> WebResult<Json<UserDto>> {
     if user.name.len() < 3 || user.password.len() < 3 {
         return Err(HttpResponseError::bad_request(Some(
@@ -62,76 +114,59 @@ async fn create(
         )));
     }
 
+    if !data.identity_provider.manages_users() {
+        return unsupported(data.identity_provider.as_ref(), "creating users");
+    }
+
     data.user_service
         .find_by_name(user.name.as_str(), true)
         .await?
         .map(|_| Err(HttpResponseError::bad_request(Some("User already exists"))))
         .unwrap_or(Ok(()))?;
 
-    let kc_users = data
-        .keycloak_service
-        .get_users(user.name.clone(), true)
-        .await?;
-
-    let kc_user = kc_users.first();
-    let mut kc_user = if let Some(kc_user) = kc_user {
-        if !kc_user.username.is_some() || kc_user.username.as_ref().unwrap() != user.name.as_str() {
-            return Err(HttpResponseError::bad_request(Some(
-                "The username and name of the user do not match",
-            )));
+    let mut identity_user = match data
+        .identity_provider
+        .find_user_by_name(user.name.as_str())
+        .await?
+    {
+        Some(identity_user) => identity_user,
+        None => {
+            debug!("Creating user in the identity provider");
+            data.identity_provider
+                .create_user(NewIdentityUser {
+                    username: user.name.clone(),
+                    email: user.email.clone(),
+                    email_verified: user.email.is_some()
+                        && data.config.keycloak_default_email_verified,
+                    first_name: user.first_name.clone(),
+                    last_name: user.last_name.clone(),
+                    password: user.password.clone(),
+                    temporary_password: user.is_password_temporary.unwrap_or(false),
+                    roles: user.roles.clone().unwrap_or_default(),
+                })
+                .await?
         }
-
-        kc_user.clone()
-    } else {
-        debug!("Creating user in keycloak");
-        data.keycloak_service
-            .create_user(UserRepresentation {
-                username: Some(user.name.clone()),
-                first_name: user.first_name.clone(),
-                last_name: user.last_name.clone(),
-                enabled: Some(true),
-                email: user.email.clone(),
-                email_verified: Some(
-                    user.email.is_some() && data.config.keycloak_default_email_verified,
-                ),
-                credentials: Some(vec![CredentialRepresentation {
-                    value: Some(user.password.clone()),
-                    temporary: Some(user.is_password_temporary.unwrap_or(false)),
-                    type_: Some("password".to_string()),
-                    ..Default::default()
-                }]),
-                realm_roles: user.roles.clone(),
-                ..Default::default()
-            })
-            .await?;
-
-        data.keycloak_service
-            .get_users(user.name.clone(), true)
-            .await?
-            .first()
-            .map(|kc_user| kc_user.clone())
-            .ok_or_else(|| {
-                HttpResponseError::failed_dependency(Some(
-                    "Failed to get the created user from keycloak",
-                ))
-            })?
     };
 
-    let kc_user_id = kc_user
-        .id
-        .clone()
-        .ok_or(HttpResponseError::internal_error(Some(
-            "Failed to get the external id of the user",
-        )))?;
+    if data
+        .user_service
+        .find_by_external_id(&identity_user.id, true)
+        .await?
+        .is_some()
+    {
+        return Err(HttpResponseError::bad_request(Some(
+            "The user is disabled and must be enabled instead",
+        )));
+    }
 
     if let Some(roles) = user.roles.as_ref() {
         if !roles.is_empty() {
             debug!("Adding roles to user: {:?}", roles);
-            data.keycloak_service
-                .add_roles_to_user(&kc_user_id, roles.clone())
+            data.identity_provider
+                .add_roles_to_user(&identity_user.id, roles.clone())
                 .await?;
 
-            kc_user.realm_roles = Some(roles.clone());
+            identity_user.roles = roles.clone();
         }
     }
 
@@ -139,12 +174,13 @@ async fn create(
         .user_service
         .insert(user::ActiveModel {
             name: ActiveValue::set(user.name.clone()),
-            external_id: ActiveValue::set(Some(kc_user_id)),
+            external_id: ActiveValue::set(Some(identity_user.id.clone())),
             ..Default::default()
         })
         .await?;
+    set_audit_target(&req, model.id);
 
-    Ok(Json(UserDto::from_model(model, Some(kc_user))))
+    Ok(Json(UserDto::from_model(model, Some(identity_user))))
 }
 
 #[utoipa::path(
@@ -152,9 +188,10 @@ async fn create(
     tag = "Users",
     context_path = "/api/v1",
     operation_id = "listUsers",
-    params(UserQuery),
+    params(UserListQuery),
     responses(
-        (status = 200, description = "Ok", body = Vec<UserDto>),
+        (status = 200, description = "Ok", body = UserPageDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
         (status = 401, description = "Unauthorized", body = ErrorDto),
         (status = 424, description = "Failed dependency", body = ErrorDto),
         (status = 500, description = "Internal server error", body = ErrorDto),
@@ -163,40 +200,26 @@ async fn create(
         ("oauth2" = [])
     )
 )]
-#[get("/user/list", wrap = "keycloak_middleware::Keycloak")]
+#[get("/user/list", wrap = "identity_middleware::Identity")]
 async fn list(
     data: web::Data<AppState>,
-    query: Query<UserQuery>,
-    _claims: KeycloakUserClaims<AdminRole>,
-) -> WebResult<Json<Vec<UserDto>>> {
-    async fn map_user(
-        model: user::Model,
-        data: &web::Data<AppState>,
-    ) -> Result<(user::Model, Option<UserRepresentation>), HttpResponseError> {
-        let kc_user = if let Some(id) = model.external_id.as_ref() {
-            Some(data.keycloak_service.get_user_by_id(id).await?)
-        } else {
-            None
-        };
-
-        Ok((model, kc_user))
-    }
+    query: Query<UserListQuery>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<PageDto<UserDto>>> {
+    let pagination = Pagination::new(query.page, query.page_size)?;
+    let (users, total) = data
+        .user_service
+        .find_page(query.include_inactive.unwrap_or(false), &pagination)
+        .await?;
 
-    Ok(Json(
-        join_all(
-            data.user_service
-                .find_all(query.include_inactive.unwrap_or(false))
-                .await?
-                .into_iter()
-                .map(|model| map_user(model, &data)),
-        )
-        .await
-        .into_iter()
-        .collect::<WebResult<Vec<_>>>()?
-        .into_iter()
-        .map(|(model, kc_user)| UserDto::from_model(model, kc_user))
-        .collect::<Vec<_>>(),
-    ))
+    Ok(Json(PageDto::new(
+        join_all(users.into_iter().map(|model| to_dto(&data, model)))
+            .await
+            .into_iter()
+            .collect::<WebResult<Vec<_>>>()?,
+        total,
+        &pagination,
+    )))
 }
 
 #[utoipa::path(
@@ -220,25 +243,19 @@ async fn list(
         ("oauth2" = [])
     )
 )]
-#[get("/user/{id}", wrap = "keycloak_middleware::Keycloak")]
+#[get("/user/{id}", wrap = "identity_middleware::Identity")]
 async fn get(
     id: web::Path<String>,
     data: web::Data<AppState>,
     query: Query<UserQuery>,
-    _claims: KeycloakUserClaims<AdminRole>,
+    _claims: UserClaims<AdminRole>,
 ) -> WebResult<Json<UserDto>> {
     let model = data
         .user_service
         .find_by_id_string_unwrap(&id.into_inner(), query.include_inactive.unwrap_or(false))
         .await?;
 
-    let kc_user = if let Some(id) = model.external_id.as_ref() {
-        Some(data.keycloak_service.get_user_by_id(id).await?)
-    } else {
-        None
-    };
-
-    Ok(Json(UserDto::from_model(model, kc_user)))
+    Ok(Json(to_dto(&data, model).await?))
 }
 
 #[utoipa::path(
@@ -262,12 +279,12 @@ async fn get(
         ("oauth2" = [])
     )
 )]
-#[get("/user/by-name/{name}", wrap = "keycloak_middleware::Keycloak")]
+#[get("/user/by-name/{name}", wrap = "identity_middleware::Identity")]
 async fn by_name(
     name: web::Path<String>,
     data: web::Data<AppState>,
     query: Query<UserQuery>,
-    _claims: KeycloakUserClaims<AdminRole>,
+    _claims: UserClaims<AdminRole>,
 ) -> WebResult<Json<UserDto>> {
     let model = data
         .user_service
@@ -275,13 +292,7 @@ async fn by_name(
         .await?
         .ok_or(HttpResponseError::not_found(Some("User not found")))?;
 
-    let kc_user = if let Some(id) = model.external_id.as_ref() {
-        Some(data.keycloak_service.get_user_by_id(id).await?)
-    } else {
-        None
-    };
-
-    Ok(Json(UserDto::from_model(model, kc_user)))
+    Ok(Json(to_dto(&data, model).await?))
 }
 
 #[utoipa::path(
@@ -305,12 +316,16 @@ async fn by_name(
         ("oauth2" = [])
     )
 )]
-#[delete("/user/{id}", wrap = "keycloak_middleware::Keycloak")]
+#[delete(
+    "/user/{id}",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::UserDelete)"
+)]
 async fn delete(
     id: web::Path<String>,
     data: web::Data<AppState>,
     query: Query<DeleteQuery>,
-    claims: KeycloakUserClaims<AdminRole>,
+    claims: UserClaims<AdminRole>,
 ) -> WebResult<impl Responder> {
     let user = data
         .user_service
@@ -323,23 +338,325 @@ async fn delete(
         )));
     }
 
-    if user.active {
-        data.keycloak_service
-            .delete_user(user.external_id.as_ref().unwrap())
+    let delete_in_database = query.delete_in_database.unwrap_or(true);
+    if !delete_in_database && !user.active {
+        return Err(HttpResponseError::bad_request(Some(
+            "User is already inactive",
+        )));
+    }
+
+    // Clients owned by teams must survive the deletion of the user responsible for them
+    if delete_in_database {
+        data.team_service
+            .reassign_clients(&user.id, &claims.user.id)
             .await?;
     }
 
-    if query.delete_in_database.unwrap_or(true) {
+    // Users managed by other identity providers can't be deleted there, they
+    // keep their external id so they can't log in again while disabled
+    let manages_users = data.identity_provider.manages_users();
+    if let Some(external_id) = user.external_id.as_ref().filter(|_| manages_users) {
+        data.identity_provider.delete_user(external_id).await?;
+    }
+
+    if delete_in_database {
         data.user_service.delete(user).await?;
-    } else if user.active {
-        data.user_service.disable(user.into()).await?;
     } else {
+        let mut model: user::ActiveModel = user.into();
+        if manages_users {
+            model.external_id = ActiveValue::Set(None);
+        }
+        data.user_service.disable(model).await?;
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Update the details of a user or enable or disable it.
+/// Disabled users can't log in and their clients are disabled.
+#[utoipa::path(
+    put,
+    tag = "Users",
+    context_path = "/api/v1",
+    request_body = UpdateUserDto,
+    operation_id = "updateUser",
+    params(
+        ("id", description = "Id of the user to update")
+    ),
+    responses(
+        (status = 200, description = "Ok", body = UserDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[put(
+    "/user/{id}",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::UserUpdate)"
+)]
+async fn update(
+    req: HttpRequest,
+    id: web::Path<String>,
+    body: Json<UpdateUserDto>,
+    data: web::Data<AppState>,
+    claims: UserClaims<AdminRole>,
+) -> WebResult<Json<UserDto>> {
+    let mut model = data
+        .user_service
+        .find_by_id_string_unwrap(&id.into_inner(), true)
+        .await?;
+    set_audit_target(&req, model.id);
+    let external_id = external_id(&model)?;
+
+    match body.enabled {
+        Some(false) if claims.user.id == model.id => {
+            return Err(HttpResponseError::bad_request(Some(
+                "Cannot disable yourself",
+            )));
+        }
+        Some(true) if !model.active => {
+            data.user_service
+                .find_by_name(&model.original_name, true)
+                .await?
+                .map(|_| {
+                    Err(HttpResponseError::bad_request(Some(
+                        "Another user with the name of the user already exists",
+                    )))
+                })
+                .unwrap_or(Ok(()))?;
+        }
+        _ => {}
+    }
+
+    let identity_user = if data.identity_provider.manages_users() {
+        let mut identity_user = data.identity_provider.get_user(&external_id).await?;
+        if let Some(email) = body.email.as_ref() {
+            if identity_user.email.as_ref() != Some(email) {
+                identity_user.email_verified = Some(data.config.keycloak_default_email_verified);
+            }
+            identity_user.email = Some(email.clone());
+        }
+        if let Some(first_name) = body.first_name.as_ref() {
+            identity_user.first_name = Some(first_name.clone());
+        }
+        if let Some(last_name) = body.last_name.as_ref() {
+            identity_user.last_name = Some(last_name.clone());
+        }
+        if let Some(enabled) = body.enabled {
+            identity_user.enabled = enabled;
+        }
+
+        debug!("Updating user {} in the identity provider", model.id);
+        data.identity_provider.update_user(&identity_user).await?;
+        Some(identity_user)
+    } else if body.email.is_some() || body.first_name.is_some() || body.last_name.is_some() {
+        return unsupported(data.identity_provider.as_ref(), "updating users");
+    } else {
+        None
+    };
+
+    let changed = match body.enabled {
+        Some(false) if model.active => Some(data.user_service.disable(model.clone().into()).await?),
+        Some(true) if !model.active => Some(data.user_service.enable(model.clone().into()).await?),
+        _ => None,
+    };
+    if let Some(changed) = changed {
+        model = changed
+            .try_into_model()
+            .map_internal_error(Some("Failed to map model"))?;
+    }
+
+    Ok(Json(UserDto::from_model(model, identity_user)))
+}
+
+/// Assign a realm role to a user
+#[utoipa::path(
+    put,
+    tag = "Users",
+    context_path = "/api/v1",
+    operation_id = "addUserRole",
+    params(
+        ("id", description = "Id of the user"),
+        ("role", description = "Name of the role to assign")
+    ),
+    responses(
+        (status = 200, description = "Ok", body = UserDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[put(
+    "/user/{id}/role/{role}",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::UserRoleAdd)"
+)]
+async fn add_role(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    data: web::Data<AppState>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<Json<UserDto>> {
+    let (id, role) = path.into_inner();
+    let model = data
+        .user_service
+        .find_by_id_string_unwrap(&id, true)
+        .await?;
+    set_audit_target(&req, model.id);
+
+    debug!("Adding role {} to user {}", role, model.id);
+    data.identity_provider
+        .add_roles_to_user(&external_id(&model)?, vec![role])
+        .await?;
+
+    Ok(Json(to_dto(&data, model).await?))
+}
+
+/// Remove a realm role from a user
+#[utoipa::path(
+    delete,
+    tag = "Users",
+    context_path = "/api/v1",
+    operation_id = "removeUserRole",
+    params(
+        ("id", description = "Id of the user"),
+        ("role", description = "Name of the role to remove")
+    ),
+    responses(
+        (status = 200, description = "Ok", body = UserDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[delete(
+    "/user/{id}/role/{role}",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::UserRoleRemove)"
+)]
+async fn remove_role(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    data: web::Data<AppState>,
+    claims: UserClaims<AdminRole>,
+) -> WebResult<Json<UserDto>> {
+    let (id, role) = path.into_inner();
+    let model = data
+        .user_service
+        .find_by_id_string_unwrap(&id, true)
+        .await?;
+    set_audit_target(&req, model.id);
+
+    if claims.user.id == model.id && role == "admin" {
         return Err(HttpResponseError::bad_request(Some(
-            "User is already inactive",
+            "Cannot remove the admin role from yourself",
         )));
     }
 
+    debug!("Removing role {} from user {}", role, model.id);
+    data.identity_provider
+        .remove_roles_from_user(&external_id(&model)?, vec![role])
+        .await?;
+
+    Ok(Json(to_dto(&data, model).await?))
+}
+
+/// Set a new password for a user or, if no password is given,
+/// send the user an email asking them to set a new password
+#[utoipa::path(
+    post,
+    tag = "Users",
+    context_path = "/api/v1",
+    request_body = ResetPasswordDto,
+    operation_id = "resetUserPassword",
+    params(
+        ("id", description = "Id of the user")
+    ),
+    responses(
+        (status = 204, description = "Password reset"),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post(
+    "/user/{id}/password",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::UserPasswordReset)"
+)]
+async fn reset_password(
+    req: HttpRequest,
+    id: web::Path<String>,
+    body: Json<ResetPasswordDto>,
+    data: web::Data<AppState>,
+    _claims: UserClaims<AdminRole>,
+) -> WebResult<impl Responder> {
+    let model = data
+        .user_service
+        .find_by_id_string_unwrap(&id.into_inner(), false)
+        .await?;
+    set_audit_target(&req, model.id);
+    let external_id = external_id(&model)?;
+
+    if let Some(password) = body.password.as_ref() {
+        if password.len() < 3 {
+            return Err(HttpResponseError::bad_request(Some(
+                "The password must be at least 3 characters long",
+            )));
+        }
+
+        data.identity_provider
+            .reset_password(
+                &external_id,
+                password.clone(),
+                body.is_password_temporary.unwrap_or(true),
+            )
+            .await?;
+    } else {
+        let identity_user = data.identity_provider.get_user(&external_id).await?;
+        if identity_user.email.is_none() {
+            return Err(HttpResponseError::bad_request(Some(
+                "The user has no email address, a password must be supplied",
+            )));
+        }
+
+        data.identity_provider
+            .send_update_password_email(&external_id)
+            .await?;
+    }
+
     Ok(HttpResponse::NoContent().finish())
 }
 
-register_module!(create, list, get, delete, by_name);
+register_module!(
+    create,
+    list,
+    get,
+    delete,
+    by_name,
+    update,
+    add_role,
+    remove_role,
+    reset_password
+);
@@ -0,0 +1,249 @@
+use crate::config::app_state::AppState;
+use crate::entity::audit_event::AuditAction;
+use crate::entity::webhook_subscription;
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::middleware::audit_middleware::{set_audit_target, Audit};
+use crate::middleware::extractors::UserClaims;
+use crate::middleware::identity_middleware;
+use crate::middleware::user_roles::{AdminRole, NoRoles};
+use crate::model::create_webhook_subscription_dto::CreateWebhookSubscriptionDto;
+use crate::model::page_dto::PageDto;
+use crate::model::webhook_delivery_dto::WebhookDeliveryDto;
+use crate::model::webhook_subscription_dto::WebhookSubscriptionDto;
+use crate::register_module;
+use crate::util::pagination::Pagination;
+use crate::util::traits::from_model::FromModel;
+use crate::util::types::WebResult;
+use actix_web::web::{Data, Json, Path, Query};
+use actix_web::{delete, get, post, HttpRequest, HttpResponse, Responder};
+use openssl::rand::rand_bytes;
+use sea_orm::ActiveValue;
+use serde::Deserialize;
+use shared::util::traits::u8_vec_to_string::U8VecToString;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+#[derive(Deserialize, Debug, IntoParams)]
+pub struct DeliveryQuery {
+    /// The zero-based index of the page to return.
+    /// Defaults to 0.
+    pub page: Option<u64>,
+    /// The number of elements per page.
+    /// Defaults to 50.
+    #[serde(rename = "pageSize")]
+    pub page_size: Option<u64>,
+}
+
+/// Find a subscription which is owned by the user,
+/// admins may access all subscriptions
+async fn find_subscription(
+    data: &Data<AppState>,
+    id: &str,
+    claims: &UserClaims<NoRoles>,
+) -> WebResult<webhook_subscription::Model> {
+    let id = Uuid::parse_str(id).map_bad_request(Some("Invalid subscription id supplied"))?;
+    data.webhook_service
+        .find_by_id(&id)
+        .await?
+        .filter(|s| s.user_id == claims.user.id || claims.has_roles::<AdminRole>())
+        .ok_or(HttpResponseError::not_found(Some(
+            "Webhook subscription not found",
+        )))
+}
+
+/// Subscribe to CA lifecycle events.
+/// The payloads are signed using HMAC-SHA256 with the returned secret,
+/// see the `X-CA-Signature` and `X-CA-Timestamp` headers.
+#[utoipa::path(
+    post,
+    tag = "Webhooks",
+    context_path = "/api/v1",
+    request_body = CreateWebhookSubscriptionDto,
+    operation_id = "createWebhookSubscription",
+    responses(
+        (status = 200, description = "Ok", body = WebhookSubscriptionDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[post(
+    "/webhook",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::WebhookCreate)"
+)]
+async fn create(
+    req: HttpRequest,
+    data: Data<AppState>,
+    body: Json<CreateWebhookSubscriptionDto>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<Json<WebhookSubscriptionDto>> {
+    let url = reqwest::Url::parse(&body.url).map_bad_request(Some("Invalid url supplied"))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(HttpResponseError::bad_request(Some(
+            "The url must use http or https",
+        )));
+    }
+
+    data.webhook_target_policy
+        .resolve(&url)
+        .await
+        .map_err(|e| HttpResponseError::bad_request(Some(e)))?;
+
+    let global = body.global.unwrap_or(false);
+    if global && !claims.has_roles::<AdminRole>() {
+        return Err(HttpResponseError::unauthorized(Some(
+            "Only admins may create global subscriptions",
+        )));
+    }
+
+    let mut secret = [0u8; 32];
+    rand_bytes(&mut secret).map_internal_error(Some("Failed to generate secret"))?;
+    let secret = secret.to_vec().to_hex_string("");
+
+    let subscription = data
+        .webhook_service
+        .insert(webhook_subscription::ActiveModel {
+            id: ActiveValue::Set(Uuid::new_v4()),
+            user_id: ActiveValue::Set(claims.user.id),
+            url: ActiveValue::Set(url.to_string()),
+            secret: ActiveValue::Set(secret.clone()),
+            events: ActiveValue::Set(
+                body.events
+                    .as_ref()
+                    .filter(|events| !events.is_empty())
+                    .map(|events| {
+                        events
+                            .iter()
+                            .map(|e| e.as_str())
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    }),
+            ),
+            global: ActiveValue::Set(global),
+            ..Default::default()
+        })
+        .await?;
+    set_audit_target(&req, subscription.id);
+
+    let mut dto = WebhookSubscriptionDto::from_model(subscription);
+    dto.secret = Some(secret);
+    Ok(Json(dto))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Webhooks",
+    context_path = "/api/v1",
+    operation_id = "listWebhookSubscriptions",
+    responses(
+        (status = 200, description = "Ok", body = Vec<WebhookSubscriptionDto>),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get("/webhook/list", wrap = "identity_middleware::Identity")]
+async fn list(
+    data: Data<AppState>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<Json<Vec<WebhookSubscriptionDto>>> {
+    Ok(Json(
+        data.webhook_service
+            .find_all_by_user(&claims.user.id)
+            .await?
+            .into_iter()
+            .map(WebhookSubscriptionDto::from_model)
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    tag = "Webhooks",
+    context_path = "/api/v1",
+    operation_id = "listWebhookDeliveries",
+    params(
+        ("id", description = "Id of the subscription"),
+        DeliveryQuery
+    ),
+    responses(
+        (status = 200, description = "Ok", body = WebhookDeliveryPageDto),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[get("/webhook/{id}/deliveries", wrap = "identity_middleware::Identity")]
+async fn deliveries(
+    data: Data<AppState>,
+    path: Path<String>,
+    query: Query<DeliveryQuery>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<Json<PageDto<WebhookDeliveryDto>>> {
+    let pagination = Pagination::new(query.page, query.page_size)?;
+    let subscription = find_subscription(&data, &path, &claims).await?;
+    let (items, total) = data
+        .webhook_service
+        .find_deliveries(&subscription.id, &pagination)
+        .await?;
+
+    Ok(Json(PageDto::new(
+        items
+            .into_iter()
+            .map(WebhookDeliveryDto::from_model)
+            .collect(),
+        total,
+        &pagination,
+    )))
+}
+
+#[utoipa::path(
+    delete,
+    tag = "Webhooks",
+    context_path = "/api/v1",
+    operation_id = "deleteWebhookSubscription",
+    params(
+        ("id", description = "Id of the subscription to delete"),
+    ),
+    responses(
+        (status = 204, description = "Subscription deleted"),
+        (status = 400, description = "Bad request", body = ErrorDto),
+        (status = 401, description = "Unauthorized", body = ErrorDto),
+        (status = 404, description = "Not found", body = ErrorDto),
+        (status = 424, description = "Failed dependency", body = ErrorDto),
+        (status = 500, description = "Internal server error", body = ErrorDto),
+    ),
+    security(
+        ("oauth2" = [])
+    )
+)]
+#[delete(
+    "/webhook/{id}",
+    wrap = "identity_middleware::Identity",
+    wrap = "Audit::new(AuditAction::WebhookDelete)"
+)]
+async fn delete(
+    data: Data<AppState>,
+    path: Path<String>,
+    claims: UserClaims<NoRoles>,
+) -> WebResult<impl Responder> {
+    let subscription = find_subscription(&data, &path, &claims).await?;
+    data.webhook_service.delete(subscription).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+register_module!(create, list, deliveries, delete);
@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "camelCase")]
+pub enum ApprovalStatus {
+    /// The request is waiting for the decision of an admin
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    /// The request has been approved and the certificate has been issued
+    #[sea_orm(string_value = "approved")]
+    Approved,
+    /// The request has been rejected
+    #[sea_orm(string_value = "rejected")]
+    Rejected,
+}
+
+/// A signing request of a client which requires the approval of an admin
+#[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "approval_request")]
+pub struct Model {
+    #[sea_orm(primary_key, unique, generated)]
+    pub id: i32,
+    #[sea_orm(indexed)]
+    pub client_id: Uuid,
+    /// The user who submitted the request on behalf of the client,
+    /// `None` if it was submitted by the client using its token
+    pub requested_by: Option<Uuid>,
+    /// The certificate signing request in PEM format
+    pub request: String,
+    pub subject_name: String,
+    /// The alternative names of the certificate, separated by commas
+    pub alternative_names: Option<String>,
+    #[sea_orm(indexed)]
+    pub status: ApprovalStatus,
+    pub created_at: DateTimeWithTimeZone,
+    /// The admin who approved or rejected the request
+    pub decided_by: Option<Uuid>,
+    pub decided_at: Option<DateTimeWithTimeZone>,
+    /// The reason the request was rejected
+    pub reason: Option<String>,
+    /// The id of the signing request of the issued certificate
+    pub signing_request_id: Option<i32>,
+    /// The issued certificate in PEM format
+    pub certificate: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        has_one = "super::client::Entity",
+        belongs_to = "super::client::Entity",
+        from = "Column::ClientId",
+        to = "super::client::Column::Id"
+    )]
+    Client,
+}
+
+impl Related<super::client::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Client.def()
+    }
+}
+
+impl Model {
+    pub fn alternative_names(&self) -> Option<Vec<String>> {
+        self.alternative_names
+            .as_ref()
+            .map(|names| names.split(',').map(|n| n.to_string()).collect())
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            self.created_at = ActiveValue::Set(Utc::now().into());
+            self.status = ActiveValue::Set(ApprovalStatus::Pending);
+        }
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,202 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A security relevant action which is recorded in the audit log
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(32))")]
+pub enum AuditAction {
+    #[sea_orm(string_value = "root_generate")]
+    #[serde(rename = "root.generate")]
+    RootGenerate,
+    #[sea_orm(string_value = "intermediate_generate")]
+    #[serde(rename = "intermediate.generate")]
+    IntermediateGenerate,
+    #[sea_orm(string_value = "certificate_sign")]
+    #[serde(rename = "certificate.sign")]
+    CertificateSign,
+    #[sea_orm(string_value = "certificate_generate")]
+    #[serde(rename = "certificate.generate")]
+    CertificateGenerate,
+    #[sea_orm(string_value = "certificate_enroll")]
+    #[serde(rename = "certificate.enroll")]
+    CertificateEnroll,
+    #[sea_orm(string_value = "certificate_revoke")]
+    #[serde(rename = "certificate.revoke")]
+    CertificateRevoke,
+    #[sea_orm(string_value = "certificate_approve")]
+    #[serde(rename = "certificate.approve")]
+    CertificateApprove,
+    #[sea_orm(string_value = "certificate_reject")]
+    #[serde(rename = "certificate.reject")]
+    CertificateReject,
+    #[sea_orm(string_value = "client_create")]
+    #[serde(rename = "client.create")]
+    ClientCreate,
+    #[sea_orm(string_value = "client_regenerate")]
+    #[serde(rename = "client.regenerate")]
+    ClientRegenerate,
+    #[sea_orm(string_value = "client_delete")]
+    #[serde(rename = "client.delete")]
+    ClientDelete,
+    #[sea_orm(string_value = "client_policy_update")]
+    #[serde(rename = "client.policy_update")]
+    ClientPolicyUpdate,
+    #[sea_orm(string_value = "client_transfer")]
+    #[serde(rename = "client.transfer")]
+    ClientTransfer,
+    #[sea_orm(string_value = "enrollment_token_create")]
+    #[serde(rename = "enrollment_token.create")]
+    EnrollmentTokenCreate,
+    #[sea_orm(string_value = "enrollment_token_delete")]
+    #[serde(rename = "enrollment_token.delete")]
+    EnrollmentTokenDelete,
+    #[sea_orm(string_value = "user_create")]
+    #[serde(rename = "user.create")]
+    UserCreate,
+    #[sea_orm(string_value = "user_delete")]
+    #[serde(rename = "user.delete")]
+    UserDelete,
+    #[sea_orm(string_value = "user_update")]
+    #[serde(rename = "user.update")]
+    UserUpdate,
+    #[sea_orm(string_value = "user_role_add")]
+    #[serde(rename = "user.role_add")]
+    UserRoleAdd,
+    #[sea_orm(string_value = "user_role_remove")]
+    #[serde(rename = "user.role_remove")]
+    UserRoleRemove,
+    #[sea_orm(string_value = "user_password_reset")]
+    #[serde(rename = "user.password_reset")]
+    UserPasswordReset,
+    #[sea_orm(string_value = "team_create")]
+    #[serde(rename = "team.create")]
+    TeamCreate,
+    #[sea_orm(string_value = "team_delete")]
+    #[serde(rename = "team.delete")]
+    TeamDelete,
+    #[sea_orm(string_value = "team_sync")]
+    #[serde(rename = "team.sync")]
+    TeamSync,
+    #[sea_orm(string_value = "team_member_update")]
+    #[serde(rename = "team.member_update")]
+    TeamMemberUpdate,
+    #[sea_orm(string_value = "team_member_remove")]
+    #[serde(rename = "team.member_remove")]
+    TeamMemberRemove,
+    #[sea_orm(string_value = "token_revoke")]
+    #[serde(rename = "token.revoke")]
+    TokenRevoke,
+    #[sea_orm(string_value = "token_key_rotate")]
+    #[serde(rename = "token_key.rotate")]
+    TokenKeyRotate,
+    #[sea_orm(string_value = "token_key_delete")]
+    #[serde(rename = "token_key.delete")]
+    TokenKeyDelete,
+    #[sea_orm(string_value = "quota_update")]
+    #[serde(rename = "quota.update")]
+    QuotaUpdate,
+    #[sea_orm(string_value = "quota_delete")]
+    #[serde(rename = "quota.delete")]
+    QuotaDelete,
+    #[sea_orm(string_value = "webhook_create")]
+    #[serde(rename = "webhook.create")]
+    WebhookCreate,
+    #[sea_orm(string_value = "webhook_delete")]
+    #[serde(rename = "webhook.delete")]
+    WebhookDelete,
+    #[sea_orm(string_value = "backup_export")]
+    #[serde(rename = "backup.export")]
+    BackupExport,
+    #[sea_orm(string_value = "backup_restore")]
+    #[serde(rename = "backup.restore")]
+    BackupRestore,
+}
+
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "camelCase")]
+pub enum AuditActorType {
+    /// A keycloak user
+    #[sea_orm(string_value = "user")]
+    User,
+    /// A client authenticated using its token
+    #[sea_orm(string_value = "client")]
+    Client,
+    /// The request could not be authenticated
+    #[sea_orm(string_value = "anonymous")]
+    Anonymous,
+}
+
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "camelCase")]
+pub enum AuditOutcome {
+    #[sea_orm(string_value = "success")]
+    Success,
+    #[sea_orm(string_value = "failure")]
+    Failure,
+}
+
+/// An append-only record of a security relevant action
+#[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "audit_event")]
+pub struct Model {
+    #[sea_orm(primary_key, unique, generated)]
+    pub id: i32,
+    #[sea_orm(indexed)]
+    pub occurred_at: DateTimeWithTimeZone,
+    #[sea_orm(indexed)]
+    pub action: AuditAction,
+    pub actor_type: AuditActorType,
+    /// The user id or client id of the actor
+    #[sea_orm(indexed)]
+    pub actor_id: Option<String>,
+    pub actor_name: Option<String>,
+    /// The id of the token used by a client
+    pub token_id: Option<String>,
+    /// The object the action was performed on
+    pub target: Option<String>,
+    pub outcome: AuditOutcome,
+    pub status_code: i32,
+    /// The error message if the action failed
+    pub details: Option<String>,
+    /// The address of the peer which sent the request
+    pub source_ip: Option<String>,
+    /// The client address reported by a proxy, if any
+    pub forwarded_for: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert {
+            return Err(DbErr::Custom("Audit events cannot be modified".to_string()));
+        }
+
+        self.occurred_at = ActiveValue::Set(Utc::now().into());
+        Ok(self)
+    }
+
+    async fn before_delete<C>(self, _db: &C) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Err(DbErr::Custom("Audit events cannot be deleted".to_string()))
+    }
+}
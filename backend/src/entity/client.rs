@@ -1,3 +1,4 @@
+use crate::repository::approval_request_repository::ApprovalRequestRepository;
 use crate::repository::client_repository::ClientRepository;
 use crate::repository::signing_request_repository::SigningRequestRepository;
 use crate::repository::token_repository::TokenRepository;
@@ -13,12 +14,19 @@ use sea_orm::ActiveValue;
 pub struct Model {
     #[sea_orm(primary_key, unique, generated, auto_increment = false)]
     pub id: Uuid,
+    /// The owner of a personal client. For clients owned by a team,
+    /// the user who created the client or last transferred it to the team.
     #[sea_orm(indexed)]
     pub user_id: Uuid,
+    /// The team owning the client, the client is a personal client of the user if not set
+    #[sea_orm(indexed)]
+    pub team_id: Option<Uuid>,
     #[sea_orm(unique)]
     pub name: String,
     pub original_name: String,
     pub active: bool,
+    /// Whether the signing requests of the client must be approved by an admin
+    pub requires_approval: bool,
     pub valid_until: DateTimeWithTimeZone,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
@@ -37,6 +45,8 @@ pub enum Relation {
     SigningRequest,
     #[sea_orm(has_many = "super::token::Entity")]
     Token,
+    #[sea_orm(has_many = "super::approval_request::Entity")]
+    ApprovalRequest,
 }
 
 impl Related<super::signing_request::Entity> for Entity {
@@ -78,6 +88,7 @@ impl ActiveModelBehavior for ActiveModel {
         }
 
         if !self.active.as_ref() {
+            ApprovalRequestRepository::delete_all_by_client(db, self.id.as_ref()).await?;
             join_all(
                 SigningRequestRepository::find_all_by_client(db, self.id.as_ref())
                     .await?
@@ -101,6 +112,7 @@ impl ActiveModelBehavior for ActiveModel {
     where
         C: ConnectionTrait,
     {
+        ApprovalRequestRepository::delete_all_by_client(db, self.id.as_ref()).await?;
         join_all(
             SigningRequestRepository::find_all_by_client(db, self.id.as_ref())
                 .await?
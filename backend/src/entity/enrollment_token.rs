@@ -0,0 +1,66 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue;
+
+/// A short-lived token which can be redeemed exactly once
+/// for the first certificate of a client
+#[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "enrollment_token")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(indexed)]
+    pub client_id: Uuid,
+    /// The SHA-256 hash of the token, the token itself is never stored
+    #[sea_orm(unique)]
+    pub token_hash: String,
+    /// The user who created the token
+    pub created_by: Uuid,
+    pub created_at: DateTimeWithTimeZone,
+    pub expires_at: DateTimeWithTimeZone,
+    /// The time the token was redeemed, `None` if it is still unused
+    pub redeemed_at: Option<DateTimeWithTimeZone>,
+    /// The address the token was redeemed from
+    pub redeemed_from: Option<String>,
+    /// The serial number of the certificate issued for the token
+    pub serial_number: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        has_one = "super::client::Entity",
+        belongs_to = "super::client::Entity",
+        from = "Column::ClientId",
+        to = "super::client::Column::Id"
+    )]
+    Client,
+}
+
+impl Related<super::client::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Client.def()
+    }
+}
+
+impl Model {
+    /// Whether the token has neither been redeemed nor expired
+    pub fn is_redeemable(&self) -> bool {
+        self.redeemed_at.is_none() && self.expires_at > Utc::now()
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            self.created_at = ActiveValue::Set(Utc::now().into());
+        }
+
+        Ok(self)
+    }
+}
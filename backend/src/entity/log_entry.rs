@@ -0,0 +1,46 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue;
+
+/// A leaf of the append-only certificate issuance log.
+/// The leaf data is the SHA-256 fingerprint of the DER encoded certificate.
+#[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "log_entry")]
+pub struct Model {
+    /// The position of the leaf in the merkle tree
+    #[sea_orm(primary_key, unique, auto_increment = false)]
+    pub leaf_index: i64,
+    #[sea_orm(unique)]
+    pub signing_request_id: i32,
+    #[sea_orm(indexed)]
+    pub serial_number: String,
+    /// The hex encoded merkle leaf hash
+    pub leaf_hash: String,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert {
+            return Err(DbErr::Custom("Log entries cannot be modified".to_string()));
+        }
+
+        self.created_at = ActiveValue::Set(Utc::now().into());
+        Ok(self)
+    }
+
+    async fn before_delete<C>(self, _db: &C) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Err(DbErr::Custom("Log entries cannot be deleted".to_string()))
+    }
+}
@@ -0,0 +1,17 @@
+use sea_orm::entity::prelude::*;
+
+/// The single row holding the size of the issuance log.
+/// It is updated in the transaction appending a leaf, which
+/// serializes concurrent appends and keeps the leaf indices free of gaps.
+#[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "log_head")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i32,
+    pub tree_size: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+
+/// The hash of a perfect subtree of the issuance log, stored when the last leaf
+/// of the subtree is appended so proofs don't require reading all leaves
+#[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "log_node")]
+pub struct Model {
+    /// The height of the subtree, level 0 contains the leaves
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub level: i32,
+    /// The position of the subtree within its level
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub node_index: i64,
+    /// The hex encoded merkle tree hash of the subtree
+    pub hash: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,6 +1,20 @@
+pub mod approval_request;
+pub mod audit_event;
 pub mod certificate;
 pub mod client;
+pub mod enrollment_token;
+pub mod log_entry;
+pub mod log_head;
+pub mod log_node;
+pub mod notification;
+pub mod quota;
 pub mod root_certificate;
 pub mod signing_request;
+pub mod team;
+pub mod team_member;
 pub mod token;
+pub mod token_key;
+pub mod used_signature;
 pub mod user;
+pub mod webhook_delivery;
+pub mod webhook_subscription;
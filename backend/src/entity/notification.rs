@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue;
+use serde::Serialize;
+
+/// The kind of item a notification was sent for
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(32))")]
+#[serde(rename_all = "camelCase")]
+pub enum ExpiringItemType {
+    #[sea_orm(string_value = "certificate")]
+    Certificate,
+    #[sea_orm(string_value = "clientToken")]
+    ClientToken,
+    #[sea_orm(string_value = "intermediate")]
+    Intermediate,
+    #[sea_orm(string_value = "root")]
+    Root,
+}
+
+/// The channel a notification is delivered through
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationChannelType {
+    #[sea_orm(string_value = "smtp")]
+    Smtp,
+    #[sea_orm(string_value = "webhook")]
+    Webhook,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+pub enum DeliveryStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "sent")]
+    Sent,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "notification")]
+pub struct Model {
+    #[sea_orm(primary_key, unique, generated)]
+    pub id: i32,
+    /// Identifies the item, expiry date, threshold and channel.
+    /// Used to make sure every notification is only created once.
+    #[sea_orm(unique)]
+    pub dedup_key: String,
+    pub item_type: ExpiringItemType,
+    pub item_id: String,
+    pub item_name: String,
+    pub expires_at: DateTimeWithTimeZone,
+    pub threshold_days: i32,
+    pub channel: NotificationChannelType,
+    #[sea_orm(indexed)]
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub sent_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            self.created_at = ActiveValue::Set(Utc::now().into());
+            self.status = ActiveValue::Set(DeliveryStatus::Pending);
+            self.attempts = ActiveValue::Set(0);
+        }
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,59 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The subject a quota applies to
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+#[serde(rename_all = "camelCase")]
+pub enum QuotaScope {
+    /// The issuance limit applies to all certificates combined,
+    /// the other quotas are the defaults for all users and clients
+    #[sea_orm(string_value = "global")]
+    Global,
+    /// The quotas of a single user
+    #[sea_orm(string_value = "user")]
+    User,
+    /// The quotas of a single client
+    #[sea_orm(string_value = "client")]
+    Client,
+}
+
+/// Overrides the configured rate limits and quotas.
+/// Unset values fall back to the global quota and then to the configuration,
+/// a value of zero disables the limit.
+#[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "quota")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub scope: QuotaScope,
+    /// The id of the user or client, the nil uuid for the global scope
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub subject_id: Uuid,
+    /// The number of certificates which may be issued per rate limit window
+    pub issuance_limit: Option<i32>,
+    /// The number of active clients a user may own
+    pub max_clients: Option<i32>,
+    /// The number of valid certificates a client may have
+    pub max_valid_certificates: Option<i32>,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, _insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        self.updated_at = ActiveValue::Set(Utc::now().into());
+        Ok(self)
+    }
+}
@@ -1,16 +1,28 @@
 use sea_orm::entity::prelude::*;
+use shared::model::certificate_status::CertificateStatus;
 
 #[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
 #[sea_orm(table_name = "signing_request")]
 pub struct Model {
     #[sea_orm(primary_key, unique, generated)]
     pub id: i32,
+    /// The client the certificate was issued to,
+    /// `None` for personal certificates of a user
     #[sea_orm(indexed)]
-    pub client_id: Uuid,
+    pub client_id: Option<Uuid>,
+    /// The user who requested the certificate,
+    /// `None` if it was requested by a client using its token
+    #[sea_orm(indexed)]
+    pub requested_by: Option<Uuid>,
     pub hash: String,
+    #[sea_orm(indexed)]
     pub serial_number: String,
     pub subject_name: String,
+    /// The alternative names of the certificate, separated by commas
+    pub alternative_names: Option<String>,
     pub issued_at: DateTimeWithTimeZone,
+    pub valid_until: DateTimeWithTimeZone,
+    pub revoked_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -30,4 +42,29 @@ impl Related<super::client::Entity> for Entity {
     }
 }
 
+impl Model {
+    pub fn status(&self) -> CertificateStatus {
+        if self.revoked_at.is_some() {
+            CertificateStatus::Revoked
+        } else if self.valid_until < chrono::Utc::now() {
+            CertificateStatus::Expired
+        } else {
+            CertificateStatus::Valid
+        }
+    }
+
+    pub fn alternative_names(&self) -> Vec<String> {
+        self.alternative_names
+            .as_ref()
+            .map(|names| {
+                names
+                    .split(',')
+                    .filter(|n| !n.is_empty())
+                    .map(|n| n.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue;
+
+/// A group of users which can own clients together
+#[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "team")]
+pub struct Model {
+    #[sea_orm(primary_key, unique, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(unique)]
+    pub name: String,
+    /// The id of the identity provider group the team is synced from.
+    /// The members of teams without a group are managed locally.
+    #[sea_orm(unique)]
+    pub external_id: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::team_member::Entity")]
+    Member,
+}
+
+impl Related<super::team_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Member.def()
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            if self.id.is_not_set() {
+                self.id = ActiveValue::Set(Uuid::new_v4());
+            }
+
+            self.created_at = ActiveValue::Set(Utc::now().into());
+        }
+
+        self.updated_at = ActiveValue::Set(Utc::now().into());
+        Ok(self)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::model::token_scope::TokenScope;
 use crate::repository::token_repository::TokenRepository;
 use async_trait::async_trait;
 use sea_orm::entity::prelude::*;
@@ -12,6 +13,16 @@ pub struct Model {
     pub client_id: Uuid,
     pub token_hash: String,
     pub active: bool,
+    /// The scopes of the token, separated by commas.
+    /// `None` if the token was created without specifying scopes.
+    pub scopes: Option<String>,
+    /// The time the token was last used to authenticate a request
+    pub last_used_at: Option<DateTimeWithTimeZone>,
+    /// The address the token was last used from
+    pub last_used_from: Option<String>,
+    /// The time the token was revoked by an admin,
+    /// `None` if it is active or has been replaced by a new token
+    pub revoked_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -31,6 +42,16 @@ impl Related<super::client::Entity> for Entity {
     }
 }
 
+impl Model {
+    /// The scopes of the token, all scopes if none were specified
+    pub fn scopes(&self) -> Vec<TokenScope> {
+        match &self.scopes {
+            Some(scopes) => scopes.split(',').filter_map(TokenScope::parse).collect(),
+            None => TokenScope::ALL.to_vec(),
+        }
+    }
+}
+
 #[async_trait]
 impl ActiveModelBehavior for ActiveModel {
     async fn before_save<C>(mut self, db: &C, insert: bool) -> Result<Self, DbErr>
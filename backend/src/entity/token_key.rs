@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The algorithm a token key signs client tokens with
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(16))")]
+pub enum TokenKeyAlgorithm {
+    /// ECDSA using the P-256 curve and SHA-256
+    #[sea_orm(string_value = "es256")]
+    #[serde(rename = "ES256")]
+    Es256,
+    /// EdDSA using the Ed25519 curve
+    #[sea_orm(string_value = "eddsa")]
+    #[serde(rename = "EdDSA")]
+    EdDsa,
+}
+
+/// A key pair client tokens are signed with.
+/// Tokens reference the key they were signed with in their `kid` header.
+#[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "token_key")]
+pub struct Model {
+    /// The key id used in the `kid` header of tokens
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub algorithm: TokenKeyAlgorithm,
+    /// The PKCS#8 encoded private key in PEM format
+    pub private_key: String,
+    /// The public key in PEM format
+    pub public_key: String,
+    /// Whether new tokens are signed with this key.
+    /// Inactive keys are still used to verify tokens until they are deleted.
+    #[sea_orm(indexed)]
+    pub active: bool,
+    pub created_at: DateTimeWithTimeZone,
+    /// The time a newer key replaced this key
+    pub retired_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            self.created_at = ActiveValue::Set(Utc::now().into());
+        }
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,19 @@
+use sea_orm::entity::prelude::*;
+
+/// A request signature which has been used to authenticate with a certificate.
+/// Kept until its timestamp is too old to be accepted, to reject replayed requests.
+#[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "used_signature")]
+pub struct Model {
+    /// The SHA-256 fingerprint of the certificate, formatted like the certificate hashes
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub certificate_hash: String,
+    /// The unix timestamp the request was signed at
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub signed_at: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
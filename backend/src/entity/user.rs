@@ -59,7 +59,7 @@ impl ActiveModelBehavior for ActiveModel {
 
         if !self.active.as_ref() {
             join_all(
-                ClientRepository::find_all_by_user(db, self.id.as_ref(), false)
+                ClientRepository::find_personal_by_user(db, self.id.as_ref(), false)
                     .await?
                     .into_iter()
                     .map(|c| ClientRepository::disable(db, c.into())),
@@ -80,7 +80,7 @@ impl ActiveModelBehavior for ActiveModel {
         C: ConnectionTrait,
     {
         join_all(
-            ClientRepository::find_all_by_user(db, self.id.as_ref(), true)
+            ClientRepository::find_personal_by_user(db, self.id.as_ref(), true)
                 .await?
                 .into_iter()
                 .map(|c| c.delete(db)),
@@ -0,0 +1,60 @@
+use crate::entity::notification::DeliveryStatus;
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue;
+
+#[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "webhook_delivery")]
+pub struct Model {
+    #[sea_orm(primary_key, unique, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(indexed)]
+    pub subscription_id: Uuid,
+    pub event: String,
+    /// The json payload which is posted to the subscription url
+    #[sea_orm(column_type = "Text")]
+    pub payload: String,
+    #[sea_orm(indexed)]
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: Option<DateTimeWithTimeZone>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub delivered_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::webhook_subscription::Entity",
+        from = "Column::SubscriptionId",
+        to = "super::webhook_subscription::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Subscription,
+}
+
+impl Related<super::webhook_subscription::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Subscription.def()
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            self.created_at = ActiveValue::Set(Utc::now().into());
+            self.status = ActiveValue::Set(DeliveryStatus::Pending);
+            self.attempts = ActiveValue::Set(0);
+            self.next_attempt_at = ActiveValue::Set(Some(Utc::now().into()));
+        }
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::entity::prelude::*;
+use sea_orm::ActiveValue;
+
+#[derive(Debug, Clone, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "webhook_subscription")]
+pub struct Model {
+    #[sea_orm(primary_key, unique, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(indexed)]
+    pub user_id: Uuid,
+    pub url: String,
+    /// The secret used to sign the webhook payloads
+    pub secret: String,
+    /// The events the subscription receives, separated by commas.
+    /// Receives all events if not set.
+    pub events: Option<String>,
+    /// Whether the subscription receives the events of all users.
+    /// Only admins may create global subscriptions.
+    pub global: bool,
+    pub active: bool,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        has_one = "super::user::Entity",
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+    #[sea_orm(has_many = "super::webhook_delivery::Entity")]
+    Delivery,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl Related<super::webhook_delivery::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Delivery.def()
+    }
+}
+
+impl Model {
+    pub fn events(&self) -> Vec<String> {
+        self.events
+            .as_ref()
+            .map(|events| events.split(',').map(|e| e.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn receives(&self, event: &str) -> bool {
+        self.events.is_none() || self.events().iter().any(|e| e == event)
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            self.created_at = ActiveValue::Set(Utc::now().into());
+            self.active = ActiveValue::Set(true);
+        }
+
+        self.updated_at = ActiveValue::Set(Utc::now().into());
+        Ok(self)
+    }
+}
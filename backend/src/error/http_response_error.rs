@@ -1,6 +1,6 @@
 use crate::model::error_dto::ErrorDto;
 use crate::util::types::WebResult;
-use actix_web::http::header::ContentType;
+use actix_web::http::header::{ContentType, RETRY_AFTER};
 use actix_web::http::StatusCode;
 use actix_web::{error, HttpResponse};
 use derive_more::Display;
@@ -21,17 +21,25 @@ pub enum HttpResponseErrorCode {
     Unauthorized,
     #[display(fmt = "FailedDependency")]
     FailedDependency,
+    #[display(fmt = "Too many requests")]
+    TooManyRequests,
 }
 
 #[derive(Debug, Clone)]
 pub struct HttpResponseError {
     pub error: HttpResponseErrorCode,
     pub message: Option<String>,
+    /// The number of seconds after which the request may be retried
+    pub retry_after: Option<i64>,
 }
 
 impl HttpResponseError {
     pub fn new(error: HttpResponseErrorCode, message: Option<String>) -> Self {
-        Self { error, message }
+        Self {
+            error,
+            message,
+            retry_after: None,
+        }
     }
 
     pub fn internal_error<T: Into<String>>(message: Option<T>) -> Self {
@@ -62,6 +70,16 @@ impl HttpResponseError {
             message.map(|m| m.into()),
         )
     }
+
+    pub fn too_many_requests<T: Into<String>>(message: Option<T>, retry_after: i64) -> Self {
+        Self {
+            retry_after: Some(retry_after.max(1)),
+            ..Self::new(
+                HttpResponseErrorCode::TooManyRequests,
+                message.map(|m| m.into()),
+            )
+        }
+    }
 }
 
 impl Display for HttpResponseError {
@@ -154,11 +172,17 @@ impl error::ResponseError for HttpResponseError {
             HttpResponseErrorCode::NotFound => StatusCode::NOT_FOUND,
             HttpResponseErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
             HttpResponseErrorCode::FailedDependency => StatusCode::FAILED_DEPENDENCY,
+            HttpResponseErrorCode::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
         }
     }
 
     fn error_response(&self) -> HttpResponse {
-        HttpResponse::build(self.status_code())
+        let mut response = HttpResponse::build(self.status_code());
+        if let Some(retry_after) = self.retry_after {
+            response.insert_header((RETRY_AFTER, retry_after.to_string()));
+        }
+
+        response
             .insert_header(ContentType::json())
             .body(serde_json::to_string(&ErrorDto::from(self.clone())).unwrap())
     }
@@ -0,0 +1,145 @@
+use crate::config::config::Config;
+use crate::error::http_response_error::HttpResponseError;
+use crate::identity::keycloak_provider::KeycloakProvider;
+use crate::identity::oidc_provider::OidcProvider;
+use crate::service::user_service::UserService;
+use crate::util::types::WebResult;
+use async_trait::async_trait;
+use shared::util::types::BasicResult;
+use std::sync::Arc;
+
+/// The kinds of identity providers users can authenticate with
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IdentityProviderType {
+    Keycloak,
+    /// Any OpenID Connect provider, e.g. Dex, Authentik or Azure AD
+    Oidc,
+}
+
+/// A user as stored by the identity provider
+#[derive(Debug, Clone, Default)]
+pub struct IdentityUser {
+    /// The id of the user in the identity provider
+    pub id: String,
+    pub username: String,
+    pub email: Option<String>,
+    pub email_verified: Option<bool>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub enabled: bool,
+    pub roles: Vec<String>,
+}
+
+/// A user to be created in the identity provider
+#[derive(Debug, Clone)]
+pub struct NewIdentityUser {
+    pub username: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub password: String,
+    /// Whether the user has to change the password on the next login
+    pub temporary_password: bool,
+    pub roles: Vec<String>,
+}
+
+/// A group of users in the identity provider
+#[derive(Debug, Clone)]
+pub struct IdentityGroup {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct IdentityServerInfo {
+    pub version: Option<String>,
+}
+
+/// The claims of a verified access token
+#[derive(Debug, Clone)]
+pub struct IdentityClaims {
+    /// The id of the user in the identity provider
+    pub subject: String,
+    pub username: Option<String>,
+    pub roles: Vec<String>,
+}
+
+/// A service users are authenticated and optionally managed with
+#[async_trait(?Send)]
+pub trait IdentityProvider: Send + Sync {
+    fn provider_type(&self) -> IdentityProviderType;
+
+    /// The name of the provider as reported by the health checks
+    fn name(&self) -> &'static str {
+        match self.provider_type() {
+            IdentityProviderType::Keycloak => "keycloak",
+            IdentityProviderType::Oidc => "oidc",
+        }
+    }
+
+    /// Whether users, roles and groups can be managed through the CA.
+    /// If not, users are created in the CA when they log in for the first time.
+    fn manages_users(&self) -> bool;
+
+    /// Prepare the provider for use and load the keys tokens are signed with
+    async fn init(&self, user_service: &UserService) -> BasicResult<()>;
+
+    async fn get_server_info(&self) -> WebResult<IdentityServerInfo>;
+
+    /// Verify an access token issued by the provider
+    async fn verify_token(&self, token: &str) -> WebResult<IdentityClaims>;
+
+    /// Check whether the keys tokens are signed with can be retrieved,
+    /// returns the number of available keys
+    async fn check_keys(&self) -> BasicResult<usize>;
+
+    /// Find the user with exactly the given name
+    async fn find_user_by_name(&self, username: &str) -> WebResult<Option<IdentityUser>>;
+
+    async fn get_user(&self, id: &str) -> WebResult<IdentityUser>;
+
+    async fn create_user(&self, user: NewIdentityUser) -> WebResult<IdentityUser>;
+
+    async fn update_user(&self, user: &IdentityUser) -> WebResult<()>;
+
+    async fn delete_user(&self, id: &str) -> WebResult<()>;
+
+    async fn get_roles(&self) -> WebResult<Vec<String>>;
+
+    async fn add_roles_to_user(&self, user_id: &str, roles: Vec<String>) -> WebResult<()>;
+
+    async fn remove_roles_from_user(&self, user_id: &str, roles: Vec<String>) -> WebResult<()>;
+
+    async fn reset_password(
+        &self,
+        user_id: &str,
+        password: String,
+        temporary: bool,
+    ) -> WebResult<()>;
+
+    /// Send an email to the user asking them to set a new password
+    async fn send_update_password_email(&self, user_id: &str) -> WebResult<()>;
+
+    /// Get the top level groups teams can be synced from
+    async fn get_groups(&self) -> WebResult<Vec<IdentityGroup>>;
+
+    async fn get_group_members(&self, group_id: &str) -> WebResult<Vec<IdentityUser>>;
+}
+
+/// The error returned for operations the identity provider does not support
+pub fn unsupported<T>(provider: &dyn IdentityProvider, operation: &str) -> WebResult<T> {
+    Err(HttpResponseError::bad_request(Some(format!(
+        "The {} identity provider does not support {}",
+        provider.name(),
+        operation
+    ))))
+}
+
+/// Create the identity provider which is configured
+pub async fn provider_from_config(config: &Config) -> BasicResult<Arc<dyn IdentityProvider>> {
+    Ok(match config.identity_provider()? {
+        IdentityProviderType::Keycloak => Arc::new(KeycloakProvider::new(config)?),
+        IdentityProviderType::Oidc => Arc::new(OidcProvider::new(config).await?),
+    })
+}
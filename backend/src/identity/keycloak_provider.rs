@@ -0,0 +1,621 @@
+use crate::config::config::Config;
+use crate::entity::user;
+use crate::error::http_response_error::{
+    HttpResponseError, MapHttpResponseError, MapKeycloakError, MapToBasicResult,
+};
+use crate::identity::identity_provider::{
+    IdentityClaims, IdentityGroup, IdentityProvider, IdentityProviderType, IdentityServerInfo,
+    IdentityUser, NewIdentityUser,
+};
+use crate::identity::token_verifier::{ClaimMapping, TokenVerifier};
+use crate::service::user_service::UserService;
+use crate::util::metrics::observe_keycloak;
+use crate::util::types::WebResult;
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use keycloak::types::{
+    ClientRepresentation, CredentialRepresentation, GroupRepresentation, RealmRepresentation,
+    RoleRepresentation, ServerInfoRepresentation, UserRepresentation,
+};
+use keycloak::{KeycloakAdmin, KeycloakAdminToken, KeycloakError, KeycloakTokenSupplier};
+use log::{debug, info};
+use reqwest::Client;
+use sea_orm::ActiveValue;
+use shared::util::types::BasicResult;
+use std::sync::Arc;
+
+/// Authenticates users with a keycloak realm and manages
+/// the users, roles and groups of the realm
+pub struct KeycloakProvider {
+    admin: Arc<KeycloakAdmin<KeycloakAdminTokenRetriever>>,
+    realm: String,
+    init_realm: bool,
+    admin_name: String,
+    admin_password: String,
+    verifier: TokenVerifier,
+}
+
+struct KeycloakAdminTokenRetriever {
+    url: String,
+    username: String,
+    password: String,
+    client: Client,
+}
+
+impl KeycloakAdminTokenRetriever {
+    pub fn new(url: String, username: String, password: String) -> Self {
+        Self {
+            url,
+            username,
+            password,
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl KeycloakTokenSupplier for KeycloakAdminTokenRetriever {
+    async fn get(&self, url: &str) -> Result<String, KeycloakError> {
+        KeycloakAdminToken::acquire(&self.url, &self.username, &self.password, &self.client)
+            .await?
+            .get(url)
+            .await
+    }
+}
+
+impl KeycloakProvider {
+    pub fn new(config: &Config) -> BasicResult<Self> {
+        let required = |value: &Option<String>, name: &str| {
+            value.clone().ok_or_else(|| {
+                format!(
+                    "{} must be set when using the keycloak identity provider",
+                    name
+                )
+            })
+        };
+
+        let url = required(&config.keycloak_url, "KEYCLOAK_URL")?;
+        let client = Client::new();
+        let token = KeycloakAdminTokenRetriever::new(
+            url.clone(),
+            required(&config.keycloak_user, "KEYCLOAK_USER")?,
+            required(&config.keycloak_password, "KEYCLOAK_PASSWORD")?,
+        );
+
+        let admin = KeycloakAdmin::new(&url, token, client.clone());
+        let verifier = TokenVerifier::new(
+            client,
+            format!(
+                "{}/realms/{}/protocol/openid-connect/certs",
+                url, config.keycloak_realm
+            ),
+            None,
+            None,
+            ClaimMapping {
+                username: "preferred_username".to_string(),
+                roles: "realm_access.roles".to_string(),
+            },
+        );
+
+        Ok(Self {
+            admin: Arc::new(admin),
+            realm: config.keycloak_realm.clone(),
+            init_realm: config.keycloak_init_realm,
+            admin_name: config.admin_user.clone(),
+            admin_password: config.admin_password.clone(),
+            verifier,
+        })
+    }
+
+    async fn init_realm(&self, user_service: &UserService) -> BasicResult<()> {
+        if self.get_realm(self.realm.as_str()).await.is_err() {
+            debug!("Creating realm {}", self.realm);
+            self.create_realm(RealmRepresentation {
+                realm: Some(self.realm.clone()),
+                enabled: Some(true),
+                ..Default::default()
+            })
+            .await?;
+        }
+
+        if self
+            .get_client_by_name("ca-backend")
+            .await
+            .map_error_to_basic()?
+            .is_empty()
+        {
+            debug!("Creating client ca-backend");
+            self.create_client(ClientRepresentation {
+                client_id: Some("ca-backend".to_string()),
+                enabled: Some(true),
+                client_authenticator_type: Some("client-secret".to_string()),
+                redirect_uris: Some(vec!["http://localhost*".into()]),
+                web_origins: Some(vec!["*".into()]),
+                consent_required: Some(false),
+                standard_flow_enabled: Some(true),
+                implicit_flow_enabled: Some(true),
+                direct_access_grants_enabled: Some(true),
+                service_accounts_enabled: Some(false),
+                public_client: Some(true),
+                frontchannel_logout: Some(true),
+                protocol: Some("openid-connect".to_string()),
+                attributes: Some(
+                    [
+                        ("oidc.ciba.grant.enabled", "false"),
+                        ("post.logout.redirect.uris", "http://localhost*"),
+                        ("oauth2.device.authorization.grant.enabled", "true"),
+                        ("backchannel.logout.session.required", "true"),
+                        ("backchannel.logout.revoke.offline.tokens", "false"),
+                    ]
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string().into()))
+                    .collect(),
+                ),
+                ..Default::default()
+            })
+            .await
+            .map_error_to_basic()?;
+        }
+
+        if self.check_and_create_role("admin").await? {
+            self.create_admin_user(user_service).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn create_admin_user(&self, user_service: &UserService) -> BasicResult<()> {
+        if !self
+            .find_users(self.admin_name.clone(), true)
+            .await
+            .map_err(|e| e.to_string())?
+            .is_empty()
+        {
+            info!("Admin user already exists in keycloak");
+            return Ok(());
+        }
+
+        info!("Creating admin user in keycloak");
+        self.post_user(UserRepresentation {
+            username: Some(self.admin_name.clone()),
+            email: Some("admin@localhost".to_string()),
+            enabled: Some(true),
+            email_verified: Some(true),
+            credentials: Some(vec![keycloak::types::CredentialRepresentation {
+                temporary: Some(false),
+                type_: Some("password".to_string()),
+                value: Some(self.admin_password.clone()),
+                ..Default::default()
+            }]),
+            realm_roles: Some(vec!["admin".to_string()]),
+            ..Default::default()
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let kc_user = self
+            .find_users(self.admin_name.clone(), true)
+            .await
+            .map_err(|e| e.to_string())?
+            .pop()
+            .ok_or("Failed to find admin user in keycloak".to_string())?
+            .id
+            .ok_or("Failed to get keycloak admin user id".to_string())?;
+
+        self.add_roles_to_user(&kc_user, vec!["admin".to_string()])
+            .await
+            .map_err(|e| e.to_string())?;
+
+        info!("Creating admin user in database");
+        user_service
+            .insert(user::ActiveModel {
+                name: ActiveValue::Set(self.admin_name.clone()),
+                external_id: ActiveValue::Set(Some(kc_user)),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    async fn check_and_create_role(&self, name: &str) -> BasicResult<bool> {
+        if self.get_role_by_name(name.to_string()).await.is_err() {
+            debug!("Creating role {}", name);
+            self.create_role(RoleRepresentation {
+                name: Some(name.to_string()),
+                ..Default::default()
+            })
+            .await
+            .map_error_to_basic()?;
+
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    async fn get_realm(&self, realm: &str) -> BasicResult<RealmRepresentation> {
+        self.admin.realm_get(realm).await.map_err(|e| e.into())
+    }
+
+    async fn create_realm(&self, realm: RealmRepresentation) -> BasicResult<()> {
+        self.admin.post(realm).await.map_err(|e| e.into())
+    }
+
+    async fn find_users(
+        &self,
+        username: String,
+        exact: bool,
+    ) -> WebResult<Vec<UserRepresentation>> {
+        observe_keycloak(
+            "get_users",
+            self.admin.realm_users_get(
+                self.realm.as_str(),
+                None,
+                None,
+                None,
+                None,
+                Some(exact),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(username),
+            ),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to find matching users in keycloak"))
+    }
+
+    async fn post_user(&self, user: UserRepresentation) -> WebResult<()> {
+        observe_keycloak(
+            "create_user",
+            self.admin.realm_users_post(self.realm.as_str(), user),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to create the user in keycloak"))
+    }
+
+    async fn get_user_representation(&self, id: &str) -> WebResult<UserRepresentation> {
+        observe_keycloak(
+            "get_user_by_id",
+            self.admin.realm_users_with_id_get(self.realm.as_str(), id),
+        )
+        .await
+        .map_keycloak_error(Some("Keycloak user not found"))
+    }
+
+    async fn get_client_by_name(&self, id: &str) -> WebResult<Vec<ClientRepresentation>> {
+        observe_keycloak(
+            "get_client_by_name",
+            self.admin.realm_clients_get(
+                self.realm.as_str(),
+                Some(id.to_string()),
+                None,
+                None,
+                None,
+                None,
+                None,
+            ),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to find matching client in keycloak"))
+    }
+
+    async fn create_client(&self, client: ClientRepresentation) -> WebResult<()> {
+        observe_keycloak(
+            "create_client",
+            self.admin.realm_clients_post(self.realm.as_str(), client),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to create the client in keycloak"))
+    }
+
+    async fn get_role_by_name(&self, role_name: String) -> WebResult<RoleRepresentation> {
+        observe_keycloak(
+            "get_role_by_name",
+            self.admin
+                .realm_roles_with_role_name_get(self.realm.as_str(), &role_name),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to find matching role in keycloak"))
+    }
+
+    async fn get_roles_by_name(&self, roles: Vec<String>) -> WebResult<Vec<RoleRepresentation>> {
+        join_all(roles.into_iter().map(|role| self.get_role_by_name(role)))
+            .await
+            .into_iter()
+            .collect::<WebResult<Vec<_>>>()
+    }
+
+    async fn create_role(&self, role: RoleRepresentation) -> WebResult<()> {
+        observe_keycloak(
+            "create_role",
+            self.admin.realm_roles_post(self.realm.as_str(), role),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to create the role in keycloak"))
+    }
+
+    async fn get_user_roles(&self, user_id: &str) -> WebResult<Vec<RoleRepresentation>> {
+        observe_keycloak(
+            "get_user_roles",
+            self.admin
+                .realm_users_with_id_role_mappings_realm_get(self.realm.as_str(), user_id),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to get user roles in keycloak"))
+    }
+
+    /// Keycloak assigns some roles to all users, these are not relevant to the CA
+    fn is_default_role(role: &str) -> bool {
+        role.starts_with("default-roles-")
+            || role == "uma_authorization"
+            || role == "offline_access"
+    }
+
+    fn to_identity_user(user: UserRepresentation) -> WebResult<IdentityUser> {
+        Ok(IdentityUser {
+            id: user.id.ok_or(HttpResponseError::failed_dependency(Some(
+                "Failed to get user id",
+            )))?,
+            username: user.username.unwrap_or_default(),
+            email: user.email,
+            email_verified: user.email_verified,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            enabled: user.enabled.unwrap_or(false),
+            roles: user.realm_roles.unwrap_or_default(),
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl IdentityProvider for KeycloakProvider {
+    fn provider_type(&self) -> IdentityProviderType {
+        IdentityProviderType::Keycloak
+    }
+
+    fn manages_users(&self) -> bool {
+        true
+    }
+
+    async fn init(&self, user_service: &UserService) -> BasicResult<()> {
+        if self.init_realm {
+            info!("Initializing keycloak realm");
+            self.init_realm(user_service).await?;
+        }
+
+        self.verifier.refresh().await
+    }
+
+    async fn get_server_info(&self) -> WebResult<IdentityServerInfo> {
+        let info: ServerInfoRepresentation = observe_keycloak("get_server_info", self.admin.get())
+            .await
+            .map_failed_dependency(Some("Failed to get server info"))?;
+
+        Ok(IdentityServerInfo {
+            version: info.system_info.and_then(|i| i.version),
+        })
+    }
+
+    async fn verify_token(&self, token: &str) -> WebResult<IdentityClaims> {
+        self.verifier.verify(token).await
+    }
+
+    async fn check_keys(&self) -> BasicResult<usize> {
+        self.verifier.check().await
+    }
+
+    async fn find_user_by_name(&self, username: &str) -> WebResult<Option<IdentityUser>> {
+        Ok(self
+            .find_users(username.to_string(), true)
+            .await?
+            .into_iter()
+            .map(Self::to_identity_user)
+            .collect::<WebResult<Vec<_>>>()?
+            .into_iter()
+            .find(|u| u.username == username))
+    }
+
+    async fn get_user(&self, id: &str) -> WebResult<IdentityUser> {
+        let mut user = Self::to_identity_user(self.get_user_representation(id).await?)?;
+        if let Ok(roles) = self.get_user_roles(&user.id).await {
+            user.roles = roles
+                .into_iter()
+                .filter_map(|r| r.name)
+                .filter(|n| !Self::is_default_role(n))
+                .collect();
+        }
+
+        Ok(user)
+    }
+
+    async fn create_user(&self, user: NewIdentityUser) -> WebResult<IdentityUser> {
+        let username = user.username.clone();
+        self.post_user(UserRepresentation {
+            username: Some(user.username),
+            first_name: user.first_name,
+            last_name: user.last_name,
+            enabled: Some(true),
+            email: user.email,
+            email_verified: Some(user.email_verified),
+            credentials: Some(vec![CredentialRepresentation {
+                value: Some(user.password),
+                temporary: Some(user.temporary_password),
+                type_: Some("password".to_string()),
+                ..Default::default()
+            }]),
+            realm_roles: Some(user.roles),
+            ..Default::default()
+        })
+        .await?;
+
+        self.find_user_by_name(&username)
+            .await?
+            .ok_or(HttpResponseError::failed_dependency(Some(
+                "Failed to get the created user from keycloak",
+            )))
+    }
+
+    async fn update_user(&self, user: &IdentityUser) -> WebResult<()> {
+        let mut representation = self.get_user_representation(&user.id).await?;
+        representation.email = user.email.clone();
+        representation.email_verified = user.email_verified;
+        representation.first_name = user.first_name.clone();
+        representation.last_name = user.last_name.clone();
+        representation.enabled = Some(user.enabled);
+
+        observe_keycloak(
+            "update_user",
+            self.admin
+                .realm_users_with_id_put(self.realm.as_str(), &user.id, representation),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to update the user in keycloak"))
+    }
+
+    async fn delete_user(&self, id: &str) -> WebResult<()> {
+        observe_keycloak(
+            "delete_user",
+            self.admin
+                .realm_users_with_id_delete(self.realm.as_str(), id),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to delete the user in keycloak"))
+    }
+
+    async fn get_roles(&self) -> WebResult<Vec<String>> {
+        observe_keycloak(
+            "get_roles",
+            self.admin
+                .realm_roles_get(self.realm.as_str(), None, None, None, None),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to get roles in keycloak"))?
+        .into_iter()
+        .map(|r| {
+            r.name.ok_or(HttpResponseError::failed_dependency(Some(
+                "Failed to get role name",
+            )))
+        })
+        .filter(|r| !matches!(r, Ok(name) if Self::is_default_role(name)))
+        .collect::<WebResult<Vec<_>>>()
+    }
+
+    async fn add_roles_to_user(&self, user_id: &str, roles: Vec<String>) -> WebResult<()> {
+        let roles = self.get_roles_by_name(roles).await?;
+        observe_keycloak(
+            "add_roles_to_user",
+            self.admin.realm_users_with_id_role_mappings_realm_post(
+                self.realm.as_str(),
+                user_id,
+                roles,
+            ),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to add role to user in keycloak"))
+    }
+
+    async fn remove_roles_from_user(&self, user_id: &str, roles: Vec<String>) -> WebResult<()> {
+        let roles = self.get_roles_by_name(roles).await?;
+        observe_keycloak(
+            "remove_roles_from_user",
+            self.admin.realm_users_with_id_role_mappings_realm_delete(
+                self.realm.as_str(),
+                user_id,
+                roles,
+            ),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to remove role from user in keycloak"))
+    }
+
+    async fn reset_password(
+        &self,
+        user_id: &str,
+        password: String,
+        temporary: bool,
+    ) -> WebResult<()> {
+        observe_keycloak(
+            "reset_password",
+            self.admin.realm_users_with_id_reset_password_put(
+                self.realm.as_str(),
+                user_id,
+                CredentialRepresentation {
+                    value: Some(password),
+                    temporary: Some(temporary),
+                    type_: Some("password".to_string()),
+                    ..Default::default()
+                },
+            ),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to reset the password in keycloak"))
+    }
+
+    async fn send_update_password_email(&self, user_id: &str) -> WebResult<()> {
+        observe_keycloak(
+            "send_update_password_email",
+            self.admin.realm_users_with_id_execute_actions_email_put(
+                self.realm.as_str(),
+                user_id,
+                None,
+                None,
+                None,
+                vec!["UPDATE_PASSWORD".to_string()],
+            ),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to send the password reset email"))
+    }
+
+    async fn get_groups(&self) -> WebResult<Vec<IdentityGroup>> {
+        let groups: Vec<GroupRepresentation> = observe_keycloak(
+            "get_groups",
+            self.admin.realm_groups_get(
+                self.realm.as_str(),
+                Some(true),
+                None,
+                None,
+                Some(i32::MAX),
+                None,
+                None,
+            ),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to get groups in keycloak"))?;
+
+        Ok(groups
+            .into_iter()
+            .filter_map(|g| match (g.id, g.name) {
+                (Some(id), Some(name)) => Some(IdentityGroup { id, name }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn get_group_members(&self, group_id: &str) -> WebResult<Vec<IdentityUser>> {
+        observe_keycloak(
+            "get_group_members",
+            self.admin.realm_groups_with_id_members_get(
+                self.realm.as_str(),
+                group_id,
+                Some(true),
+                None,
+                Some(i32::MAX),
+            ),
+        )
+        .await
+        .map_failed_dependency(Some("Failed to get group members in keycloak"))?
+        .into_iter()
+        .filter(|u| u.id.is_some())
+        .map(Self::to_identity_user)
+        .collect()
+    }
+}
@@ -0,0 +1,4 @@
+pub mod identity_provider;
+pub mod keycloak_provider;
+pub mod oidc_provider;
+pub mod token_verifier;
@@ -0,0 +1,429 @@
+use crate::config::config::Config;
+use crate::error::http_response_error::MapHttpResponseError;
+use crate::identity::identity_provider::{
+    unsupported, IdentityClaims, IdentityGroup, IdentityProvider, IdentityProviderType,
+    IdentityServerInfo, IdentityUser, NewIdentityUser,
+};
+use crate::identity::token_verifier::{ClaimMapping, TokenVerifier};
+use crate::service::user_service::UserService;
+use crate::util::types::WebResult;
+use async_trait::async_trait;
+use log::info;
+use reqwest::Client;
+use serde::Deserialize;
+use shared::util::types::BasicResult;
+
+/// The parts of the OpenID Connect discovery document used by the CA
+#[derive(Clone, Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    jwks_uri: String,
+}
+
+/// Authenticates users with any OpenID Connect provider, e.g. Dex, Authentik or Azure AD.
+/// The provider is configured using its discovery document and users are managed
+/// in the provider itself, they are created in the CA when they first log in.
+pub struct OidcProvider {
+    client: Client,
+    discovery_url: String,
+    verifier: TokenVerifier,
+}
+
+impl OidcProvider {
+    pub async fn new(config: &Config) -> BasicResult<Self> {
+        let issuer_url = config.oidc_issuer_url.clone().ok_or(
+            "OIDC_ISSUER_URL must be set when using the oidc identity provider".to_string(),
+        )?;
+        let audience = config
+            .oidc_audience
+            .clone()
+            .ok_or("OIDC_AUDIENCE must be set when using the oidc identity provider".to_string())?;
+        let discovery_url = format!(
+            "{}/.well-known/openid-configuration",
+            issuer_url.trim_end_matches('/')
+        );
+
+        let client = Client::new();
+        info!("Discovering the OpenID Connect provider at {}", issuer_url);
+        let metadata = Self::discover(&client, &discovery_url).await?;
+        let verifier = TokenVerifier::new(
+            client.clone(),
+            metadata.jwks_uri,
+            Some(metadata.issuer),
+            Some(audience),
+            ClaimMapping {
+                username: config.oidc_username_claim.clone(),
+                roles: config.oidc_roles_claim.clone(),
+            },
+        );
+
+        Ok(Self {
+            client,
+            discovery_url,
+            verifier,
+        })
+    }
+
+    async fn discover(client: &Client, discovery_url: &str) -> BasicResult<ProviderMetadata> {
+        Ok(client
+            .get(discovery_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ProviderMetadata>()
+            .await?)
+    }
+}
+
+#[async_trait(?Send)]
+impl IdentityProvider for OidcProvider {
+    fn provider_type(&self) -> IdentityProviderType {
+        IdentityProviderType::Oidc
+    }
+
+    fn manages_users(&self) -> bool {
+        false
+    }
+
+    async fn init(&self, _user_service: &UserService) -> BasicResult<()> {
+        self.verifier.refresh().await
+    }
+
+    async fn get_server_info(&self) -> WebResult<IdentityServerInfo> {
+        Self::discover(&self.client, &self.discovery_url)
+            .await
+            .map_failed_dependency(Some("The identity provider is not reachable"))?;
+
+        Ok(IdentityServerInfo::default())
+    }
+
+    async fn verify_token(&self, token: &str) -> WebResult<IdentityClaims> {
+        self.verifier.verify(token).await
+    }
+
+    async fn check_keys(&self) -> BasicResult<usize> {
+        self.verifier.check().await
+    }
+
+    async fn find_user_by_name(&self, _username: &str) -> WebResult<Option<IdentityUser>> {
+        unsupported(self, "searching users")
+    }
+
+    async fn get_user(&self, _id: &str) -> WebResult<IdentityUser> {
+        unsupported(self, "reading user details")
+    }
+
+    async fn create_user(&self, _user: NewIdentityUser) -> WebResult<IdentityUser> {
+        unsupported(self, "creating users")
+    }
+
+    async fn update_user(&self, _user: &IdentityUser) -> WebResult<()> {
+        unsupported(self, "updating users")
+    }
+
+    async fn delete_user(&self, _id: &str) -> WebResult<()> {
+        unsupported(self, "deleting users")
+    }
+
+    async fn get_roles(&self) -> WebResult<Vec<String>> {
+        unsupported(self, "listing roles")
+    }
+
+    async fn add_roles_to_user(&self, _user_id: &str, _roles: Vec<String>) -> WebResult<()> {
+        unsupported(self, "assigning roles")
+    }
+
+    async fn remove_roles_from_user(&self, _user_id: &str, _roles: Vec<String>) -> WebResult<()> {
+        unsupported(self, "removing roles")
+    }
+
+    async fn reset_password(
+        &self,
+        _user_id: &str,
+        _password: String,
+        _temporary: bool,
+    ) -> WebResult<()> {
+        unsupported(self, "resetting passwords")
+    }
+
+    async fn send_update_password_email(&self, _user_id: &str) -> WebResult<()> {
+        unsupported(self, "resetting passwords")
+    }
+
+    async fn get_groups(&self) -> WebResult<Vec<IdentityGroup>> {
+        unsupported(self, "syncing groups")
+    }
+
+    async fn get_group_members(&self, _group_id: &str) -> WebResult<Vec<IdentityUser>> {
+        unsupported(self, "syncing groups")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OidcProvider;
+    use crate::config::config::Config;
+    use crate::identity::identity_provider::IdentityProvider;
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use envconfig::Envconfig;
+    use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+    use openssl::pkey::Private;
+    use openssl::rsa::Rsa;
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    const AUDIENCE: &str = "ca";
+
+    /// The state of a mock OpenID Connect provider
+    struct MockState {
+        issuer: String,
+        keys: Mutex<Vec<Value>>,
+        key_requests: AtomicUsize,
+    }
+
+    struct MockProvider {
+        state: Arc<MockState>,
+    }
+
+    async fn discovery(state: web::Data<MockState>) -> HttpResponse {
+        HttpResponse::Ok().json(json!({
+            "issuer": state.issuer,
+            "jwks_uri": format!("{}/jwks", state.issuer),
+        }))
+    }
+
+    async fn jwks(state: web::Data<MockState>) -> HttpResponse {
+        state.key_requests.fetch_add(1, Ordering::SeqCst);
+        HttpResponse::Ok().json(json!({ "keys": *state.keys.lock().unwrap() }))
+    }
+
+    impl MockProvider {
+        fn start(keys: Vec<Value>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let state = Arc::new(MockState {
+                issuer: format!("http://{}", listener.local_addr().unwrap()),
+                keys: Mutex::new(keys),
+                key_requests: AtomicUsize::new(0),
+            });
+
+            let data = web::Data::from(state.clone());
+            let server = HttpServer::new(move || {
+                App::new()
+                    .app_data(data.clone())
+                    .route(
+                        "/.well-known/openid-configuration",
+                        web::get().to(discovery),
+                    )
+                    .route("/jwks", web::get().to(jwks))
+            })
+            .workers(1)
+            .listen(listener)
+            .unwrap()
+            .run();
+            actix_web::rt::spawn(server);
+
+            Self { state }
+        }
+
+        fn key_requests(&self) -> usize {
+            self.state.key_requests.load(Ordering::SeqCst)
+        }
+
+        fn config(&self, audience: Option<&str>) -> Config {
+            let mut env = HashMap::from([
+                ("JWT_EXPIRES_IN".to_string(), "60m".to_string()),
+                ("JWT_MAX_AGE".to_string(), "60".to_string()),
+                ("IDENTITY_PROVIDER".to_string(), "oidc".to_string()),
+                ("OIDC_ISSUER_URL".to_string(), self.state.issuer.clone()),
+            ]);
+            if let Some(audience) = audience {
+                env.insert("OIDC_AUDIENCE".to_string(), audience.to_string());
+            }
+
+            Config::init_from_hashmap(&env).unwrap()
+        }
+
+        async fn provider(&self) -> OidcProvider {
+            let provider = OidcProvider::new(&self.config(Some(AUDIENCE)))
+                .await
+                .unwrap();
+            provider.verifier.refresh().await.unwrap();
+            provider
+        }
+    }
+
+    fn base64_url(data: &[u8]) -> String {
+        openssl::base64::encode_block(data)
+            .replace('+', "-")
+            .replace('/', "_")
+            .trim_end_matches('=')
+            .to_string()
+    }
+
+    fn rsa_jwk(kid: &str, key: &Rsa<Private>) -> Value {
+        json!({
+            "kty": "RSA",
+            "use": "sig",
+            "kid": kid,
+            "n": base64_url(&key.n().to_vec()),
+            "e": base64_url(&key.e().to_vec()),
+        })
+    }
+
+    fn now() -> i64 {
+        chrono::Utc::now().timestamp()
+    }
+
+    fn claims(issuer: &str) -> Value {
+        json!({
+            "iss": issuer,
+            "aud": AUDIENCE,
+            "sub": "0b8c4a5e-0bd8-4f4e-9d7e-8bd4a7c1f7e2",
+            "preferred_username": "alice",
+            "roles": ["admin"],
+            "iat": now(),
+            "exp": now() + 300,
+        })
+    }
+
+    fn sign(claims: &Value, kid: &str, key: &Rsa<Private>) -> String {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(kid.to_string());
+        encode(
+            &header,
+            claims,
+            &EncodingKey::from_rsa_pem(&key.private_key_to_pem().unwrap()).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[actix_web::test]
+    async fn accepts_valid_token() {
+        let key = Rsa::generate(2048).unwrap();
+        let mock = MockProvider::start(vec![rsa_jwk("key-1", &key)]);
+        let provider = mock.provider().await;
+
+        let claims = provider
+            .verify_token(&sign(&claims(&mock.state.issuer), "key-1", &key))
+            .await
+            .unwrap();
+        assert_eq!(claims.subject, "0b8c4a5e-0bd8-4f4e-9d7e-8bd4a7c1f7e2");
+        assert_eq!(claims.username.as_deref(), Some("alice"));
+        assert_eq!(claims.roles, vec!["admin".to_string()]);
+    }
+
+    #[actix_web::test]
+    async fn requires_audience() {
+        let key = Rsa::generate(2048).unwrap();
+        let mock = MockProvider::start(vec![rsa_jwk("key-1", &key)]);
+
+        assert!(OidcProvider::new(&mock.config(None)).await.is_err());
+    }
+
+    #[actix_web::test]
+    async fn rejects_wrong_issuer() {
+        let key = Rsa::generate(2048).unwrap();
+        let mock = MockProvider::start(vec![rsa_jwk("key-1", &key)]);
+        let provider = mock.provider().await;
+
+        let token = sign(&claims("http://attacker.example"), "key-1", &key);
+        assert!(provider.verify_token(&token).await.is_err());
+    }
+
+    #[actix_web::test]
+    async fn rejects_wrong_audience() {
+        let key = Rsa::generate(2048).unwrap();
+        let mock = MockProvider::start(vec![rsa_jwk("key-1", &key)]);
+        let provider = mock.provider().await;
+
+        let mut claims = claims(&mock.state.issuer);
+        claims["aud"] = json!("another-client");
+        assert!(provider
+            .verify_token(&sign(&claims, "key-1", &key))
+            .await
+            .is_err());
+    }
+
+    #[actix_web::test]
+    async fn rejects_other_authorized_party() {
+        let key = Rsa::generate(2048).unwrap();
+        let mock = MockProvider::start(vec![rsa_jwk("key-1", &key)]);
+        let provider = mock.provider().await;
+
+        let mut claims = claims(&mock.state.issuer);
+        claims["aud"] = json!([AUDIENCE, "another-client"]);
+        claims["azp"] = json!("another-client");
+        assert!(provider
+            .verify_token(&sign(&claims, "key-1", &key))
+            .await
+            .is_err());
+    }
+
+    #[actix_web::test]
+    async fn rejects_expired_token() {
+        let key = Rsa::generate(2048).unwrap();
+        let mock = MockProvider::start(vec![rsa_jwk("key-1", &key)]);
+        let provider = mock.provider().await;
+
+        let mut claims = claims(&mock.state.issuer);
+        claims["iat"] = json!(now() - 7200);
+        claims["exp"] = json!(now() - 3600);
+        assert!(provider
+            .verify_token(&sign(&claims, "key-1", &key))
+            .await
+            .is_err());
+    }
+
+    #[actix_web::test]
+    async fn reloads_keys_for_unknown_key_id() {
+        let key = Rsa::generate(2048).unwrap();
+        let rotated = Rsa::generate(2048).unwrap();
+        let mock = MockProvider::start(vec![rsa_jwk("key-1", &key)]);
+        let provider = mock.provider().await;
+        assert_eq!(mock.key_requests(), 1);
+
+        mock.state
+            .keys
+            .lock()
+            .unwrap()
+            .push(rsa_jwk("key-2", &rotated));
+        let token = sign(&claims(&mock.state.issuer), "key-2", &rotated);
+
+        // The keys were just loaded, unknown keys must not cause another request
+        assert!(provider.verify_token(&token).await.is_err());
+        assert_eq!(mock.key_requests(), 1);
+
+        provider.verifier.expire_keys();
+        assert!(provider.verify_token(&token).await.is_ok());
+        assert_eq!(mock.key_requests(), 2);
+    }
+
+    #[actix_web::test]
+    async fn rejects_algorithm_not_matching_key() {
+        let key = Rsa::generate(2048).unwrap();
+        let mock = MockProvider::start(vec![rsa_jwk("key-1", &key)]);
+        let provider = mock.provider().await;
+
+        // A token signed with the public key as HMAC secret must not be accepted
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some("key-1".to_string());
+        let token = encode(
+            &header,
+            &claims(&mock.state.issuer),
+            &EncodingKey::from_secret(&key.public_key_to_pem().unwrap()),
+        )
+        .unwrap();
+        assert!(provider.verify_token(&token).await.is_err());
+
+        // Neither must a key restricted to another algorithm be used
+        let mut jwk = rsa_jwk("key-2", &key);
+        jwk["alg"] = json!("RS512");
+        mock.state.keys.lock().unwrap().push(jwk);
+        provider.verifier.expire_keys();
+        let token = sign(&claims(&mock.state.issuer), "key-2", &key);
+        assert!(provider.verify_token(&token).await.is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::identity::identity_provider::IdentityClaims;
+use crate::util::types::WebResult;
+use jsonwebtoken::jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use log::{debug, warn};
+use reqwest::Client;
+use serde_json::Value;
+use shared::util::types::BasicResult;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// The minimum time between two reloads of the keys caused by
+/// tokens signed with an unknown key
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How the claims of the user are read from a token
+#[derive(Debug, Clone)]
+pub struct ClaimMapping {
+    /// The claim containing the name of the user
+    pub username: String,
+    /// The path of the claim containing the roles of the user,
+    /// nested claims are separated by dots
+    pub roles: String,
+}
+
+struct CachedKeys {
+    keys: JwkSet,
+    loaded_at: Instant,
+}
+
+/// Verifies access tokens using the keys published in a JSON Web Key Set.
+/// The keys are reloaded if a token signed with an unknown key is received,
+/// so keys rotated by the identity provider are picked up without a restart.
+pub struct TokenVerifier {
+    client: Client,
+    jwks_url: String,
+    issuer: Option<String>,
+    audience: Option<String>,
+    claims: ClaimMapping,
+    keys: RwLock<Option<CachedKeys>>,
+}
+
+impl TokenVerifier {
+    pub fn new(
+        client: Client,
+        jwks_url: String,
+        issuer: Option<String>,
+        audience: Option<String>,
+        claims: ClaimMapping,
+    ) -> Self {
+        Self {
+            client,
+            jwks_url,
+            issuer,
+            audience,
+            claims,
+            keys: RwLock::new(None),
+        }
+    }
+
+    async fn fetch_keys(&self) -> BasicResult<JwkSet> {
+        Ok(self
+            .client
+            .get(&self.jwks_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<JwkSet>()
+            .await?)
+    }
+
+    /// Load the current keys of the identity provider
+    pub async fn refresh(&self) -> BasicResult<()> {
+        debug!("Loading token signing keys from {}", self.jwks_url);
+        let keys = self.fetch_keys().await?;
+        if keys.keys.is_empty() {
+            return Err(format!("No keys found at {}", self.jwks_url).into());
+        }
+
+        self.keys.write().unwrap().replace(CachedKeys {
+            keys,
+            loaded_at: Instant::now(),
+        });
+        Ok(())
+    }
+
+    /// Check whether the keys can be retrieved, returns the number of keys
+    pub async fn check(&self) -> BasicResult<usize> {
+        Ok(self.fetch_keys().await?.keys.len())
+    }
+
+    fn find_key(&self, kid: &str) -> Option<Jwk> {
+        self.keys
+            .read()
+            .unwrap()
+            .as_ref()
+            .and_then(|cached| cached.keys.find(kid).cloned())
+    }
+
+    /// Treat the cached keys as old enough to be reloaded
+    #[cfg(test)]
+    pub fn expire_keys(&self) {
+        if let Some(cached) = self.keys.write().unwrap().as_mut() {
+            cached.loaded_at -= MIN_REFRESH_INTERVAL;
+        }
+    }
+
+    fn may_refresh(&self) -> bool {
+        match self.keys.read().unwrap().as_ref() {
+            Some(cached) => cached.loaded_at.elapsed() >= MIN_REFRESH_INTERVAL,
+            None => true,
+        }
+    }
+
+    /// Get the algorithms tokens signed with the given key may use.
+    /// Only asymmetric algorithms matching the type of the key are allowed,
+    /// if the key is restricted to a single algorithm only that one is allowed.
+    fn allowed_algorithms(jwk: &Jwk) -> WebResult<Vec<Algorithm>> {
+        let algorithms = match &jwk.algorithm {
+            AlgorithmParameters::RSA(_) => {
+                vec![Algorithm::RS256, Algorithm::RS384, Algorithm::RS512]
+            }
+            AlgorithmParameters::EllipticCurve(params) => match params.curve {
+                EllipticCurve::P256 => vec![Algorithm::ES256],
+                EllipticCurve::P384 => vec![Algorithm::ES384],
+                _ => vec![],
+            },
+            _ => vec![],
+        };
+
+        let algorithms = match jwk.common.algorithm {
+            Some(algorithm) => algorithms.into_iter().filter(|a| *a == algorithm).collect(),
+            None => algorithms,
+        };
+
+        if algorithms.is_empty() {
+            Err(HttpResponseError::unauthorized(Some(
+                "Unsupported signing key",
+            )))
+        } else {
+            Ok(algorithms)
+        }
+    }
+
+    /// Get the value of a claim by its dot separated path
+    fn claim<'a>(claims: &'a Value, path: &str) -> Option<&'a Value> {
+        path.split('.')
+            .try_fold(claims, |value, segment| value.get(segment))
+    }
+
+    pub async fn verify(&self, token: &str) -> WebResult<IdentityClaims> {
+        let header = decode_header(token).map_unauthorized(Some("Invalid token"))?;
+        let kid = header.kid.ok_or(HttpResponseError::unauthorized(Some(
+            "The token has no key id",
+        )))?;
+
+        let jwk = match self.find_key(&kid) {
+            Some(jwk) => jwk,
+            None if self.may_refresh() => {
+                if let Err(e) = self.refresh().await {
+                    warn!("Failed to reload the token signing keys: {}", e);
+                }
+
+                self.find_key(&kid)
+                    .ok_or(HttpResponseError::unauthorized(Some("Unknown signing key")))?
+            }
+            None => return Err(HttpResponseError::unauthorized(Some("Unknown signing key"))),
+        };
+
+        let algorithms = Self::allowed_algorithms(&jwk)?;
+        let key = DecodingKey::from_jwk(&jwk).map_unauthorized(Some("Unsupported signing key"))?;
+        let mut validation = Validation::new(algorithms[0]);
+        validation.algorithms = algorithms;
+        if let Some(issuer) = self.issuer.as_ref() {
+            validation.set_issuer(&[issuer]);
+        }
+        if let Some(audience) = self.audience.as_ref() {
+            validation.set_audience(&[audience]);
+        }
+
+        let claims = decode::<Value>(token, &key, &validation)
+            .map_unauthorized(Some("Invalid token"))?
+            .claims;
+
+        // The authorized party is the client the token was issued to,
+        // it has to be this client if the token was issued to multiple audiences
+        if let (Some(audience), Some(azp)) = (self.audience.as_ref(), claims.get("azp")) {
+            if azp.as_str() != Some(audience.as_str()) {
+                return Err(HttpResponseError::unauthorized(Some(
+                    "The token was issued to another client",
+                )));
+            }
+        }
+
+        Ok(IdentityClaims {
+            subject: claims
+                .get("sub")
+                .and_then(Value::as_str)
+                .ok_or(HttpResponseError::unauthorized(Some(
+                    "The token has no subject",
+                )))?
+                .to_string(),
+            username: Self::claim(&claims, &self.claims.username)
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            roles: Self::claim(&claims, &self.claims.roles)
+                .and_then(Value::as_array)
+                .map(|roles| {
+                    roles
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+}
@@ -2,25 +2,45 @@ mod config;
 mod controller;
 mod entity;
 mod error;
+mod identity;
 mod middleware;
+mod migration;
 mod model;
+mod notification;
 mod repository;
 mod service;
 mod util;
 
 use crate::controller::{
-    admin_controller, certificate_controller, client_controller, common,
-    signing_request_controller, swagger, user_controller,
+    admin_controller, approval_controller, audit_controller, backup_controller,
+    certificate_controller, client_controller, common, enrollment_token_controller,
+    metrics_controller, quota_controller, signing_request_controller, swagger, team_controller,
+    token_controller, token_key_controller, tools_controller, transparency_controller,
+    user_controller, webhook_controller,
 };
-use crate::middleware::keycloak_middleware;
+use crate::identity::identity_provider::provider_from_config;
+use crate::migration::Migrator;
+use crate::notification::expiry_scheduler::ExpiryScheduler;
+use crate::notification::webhook_dispatcher::WebhookDispatcher;
+use crate::notification::webhook_target::WebhookTargetPolicy;
 use crate::repository::database;
+use crate::service::approval_service::ApprovalService;
+use crate::service::audit_service::AuditService;
+use crate::service::backup_service::BackupService;
 use crate::service::certificate_service::CertificateService;
 use crate::service::client_service::ClientService;
-use crate::service::keycloak_service::KeycloakService;
+use crate::service::enrollment_token_service::EnrollmentTokenService;
+use crate::service::health_service::HealthService;
+use crate::service::metrics_service::MetricsService;
+use crate::service::quota_service::QuotaService;
 use crate::service::root_certificate_service::RootCertificateService;
 use crate::service::signing_request_service::SigningRequestService;
+use crate::service::team_service::TeamService;
+use crate::service::token_key_service::TokenKeyService;
 use crate::service::token_service::TokenService;
+use crate::service::transparency_log_service::TransparencyLogService;
 use crate::service::user_service::UserService;
+use crate::service::webhook_service::WebhookService;
 use crate::util::api_doc::ApiDoc;
 use crate::util::traits::map_error_to_io_error::MapErrorToIoError;
 use crate::util::traits::register_module::RegisterModule;
@@ -29,6 +49,7 @@ use actix_web::web::scope;
 use actix_web::{middleware as actix_middleware, web, App, HttpServer};
 use config::app_state::AppState;
 use log::info;
+use sea_orm_migration::MigratorTrait;
 use shared::util::logger::init_logger;
 use std::io;
 use std::str::FromStr;
@@ -54,39 +75,75 @@ async fn main() -> io::Result<()> {
 
     info!("Connecting to database");
     let db = database::connect(&config).await.map_to_io_error()?;
-    info!("Creating tables");
-    database::fill(&db).await.map_to_io_error()?;
 
-    info!("Connecting to keycloak");
-    let keycloak_service = KeycloakService::new(&config).await.map_to_io_error()?;
-    let user_service = UserService::new(db.clone());
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.first().map(String::as_str) == Some("migrate") {
+        return migration::run_command(&db, &args[1..])
+            .await
+            .map_to_io_error();
+    }
 
-    if config.keycloak_init_realm {
-        info!("Initializing keycloak realm");
-        keycloak_service
-            .init_realm(&user_service)
+    if config.db_migrate_on_startup {
+        info!("Applying database migrations");
+        Migrator::up(&db, None)
             .await
+            .map_err(|e| e.into())
             .map_to_io_error()?;
     }
 
-    info!("Setting keycloak certificate");
-    keycloak_middleware::set_keycloak_public_key(
-        keycloak_service
-            .get_realm_public_key()
-            .await
-            .map_to_io_error()?,
-    )
-    .map_to_io_error()?;
+    info!("Synchronizing the certificate issuance log");
+    TransparencyLogService::new(db.clone())
+        .sync()
+        .await
+        .map_to_io_error()?;
+
+    info!("Checking the token signing key");
+    TokenKeyService::new(db.clone())
+        .ensure_active(config.token_key_algorithm().map_to_io_error()?)
+        .await
+        .map_err(|e| e.to_string().into())
+        .map_to_io_error()?;
+
+    info!("Connecting to the identity provider");
+    let identity_provider = provider_from_config(&config).await.map_to_io_error()?;
+    let user_service = UserService::new(db.clone());
+    identity_provider
+        .init(&user_service)
+        .await
+        .map_to_io_error()?;
+
+    info!("Starting expiry notification scheduler");
+    ExpiryScheduler::new(db.clone(), &config)
+        .map_to_io_error()?
+        .start();
+
+    info!("Starting webhook dispatcher");
+    let webhook_target_policy =
+        WebhookTargetPolicy::new(config.webhook_allowed_networks().map_to_io_error()?);
+    WebhookDispatcher::new(db.clone(), &config, webhook_target_policy.clone())
+        .map_to_io_error()?
+        .start();
 
     info!("Starting http server");
     let port = config.port;
     HttpServer::new(move || {
         let scope = scope("/api/v1")
             .service(certificate_controller::register())
+            .service(transparency_controller::register())
             .module(user_controller::module)
             .module(client_controller::module)
+            .module(enrollment_token_controller::module)
             .module(signing_request_controller::module)
             .module(admin_controller::module)
+            .module(audit_controller::module)
+            .module(backup_controller::module)
+            .module(team_controller::module)
+            .module(quota_controller::module)
+            .module(approval_controller::module)
+            .module(token_controller::module)
+            .module(token_key_controller::module)
+            .module(tools_controller::module)
+            .module(webhook_controller::module)
             .module(common::module);
 
         let cors = Cors::default()
@@ -97,13 +154,25 @@ async fn main() -> io::Result<()> {
         let mut app = App::new()
             .app_data(web::Data::new(AppState {
                 config: config.clone(),
-                keycloak_service: keycloak_service.clone(),
+                identity_provider: identity_provider.clone(),
+                webhook_target_policy: webhook_target_policy.clone(),
                 client_service: ClientService::new(db.clone()),
                 user_service: user_service.clone(),
                 signing_request_service: SigningRequestService::new(db.clone()),
                 token_service: TokenService::new(db.clone()),
                 certificate_service: CertificateService::new(db.clone()),
                 root_certificate_service: RootCertificateService::new(db.clone()),
+                webhook_service: WebhookService::new(db.clone()),
+                audit_service: AuditService::new(db.clone()),
+                transparency_log_service: TransparencyLogService::new(db.clone()),
+                metrics_service: MetricsService::new(db.clone()),
+                health_service: HealthService::new(db.clone()),
+                quota_service: QuotaService::new(db.clone()),
+                approval_service: ApprovalService::new(db.clone()),
+                token_key_service: TokenKeyService::new(db.clone()),
+                enrollment_token_service: EnrollmentTokenService::new(db.clone()),
+                backup_service: BackupService::new(db.clone()),
+                team_service: TeamService::new(db.clone()),
             }))
             .service(
                 SwaggerUi::new("/swagger-ui/{_:.*}").url("/api-doc/schema.json", ApiDoc::openapi()),
@@ -116,6 +185,10 @@ async fn main() -> io::Result<()> {
             app = app.service(swagger::get_swagger_ui);
         }
 
+        if config.enable_metrics {
+            app = app.service(metrics_controller::get_metrics);
+        }
+
         app
     })
     .bind(("0.0.0.0", port))?
@@ -0,0 +1,177 @@
+use crate::config::app_state::AppState;
+use crate::entity::audit_event::{AuditAction, AuditActorType, AuditOutcome};
+use crate::entity::{audit_event, client, user};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{web, Error, HttpMessage, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use sea_orm::ActiveValue;
+use std::future::{ready, Ready};
+use uuid::Uuid;
+
+/// The authenticated actor of a request.
+/// Inserted into the request extensions by the extractors.
+#[derive(Debug, Clone)]
+pub struct AuditActor {
+    pub actor_type: AuditActorType,
+    pub id: String,
+    pub name: String,
+    pub token_id: Option<String>,
+}
+
+impl AuditActor {
+    pub fn user(user: &user::Model) -> Self {
+        Self {
+            actor_type: AuditActorType::User,
+            id: user.id.to_string(),
+            name: user.name.clone(),
+            token_id: None,
+        }
+    }
+
+    pub fn client(client: &client::Model, token_id: &Uuid) -> Self {
+        Self {
+            actor_type: AuditActorType::Client,
+            id: client.id.to_string(),
+            name: client.name.clone(),
+            token_id: Some(token_id.to_string()),
+        }
+    }
+
+    /// A client which authenticated using one of its certificates
+    pub fn client_certificate(client: &client::Model) -> Self {
+        Self {
+            actor_type: AuditActorType::Client,
+            id: client.id.to_string(),
+            name: client.name.clone(),
+            token_id: None,
+        }
+    }
+}
+
+/// The object an audited action was performed on.
+/// Defaults to the `id` path parameter if not set by the handler.
+#[derive(Debug, Clone)]
+pub struct AuditTarget(pub String);
+
+pub fn set_audit_target<T: ToString>(req: &HttpRequest, target: T) {
+    req.extensions_mut().insert(AuditTarget(target.to_string()));
+}
+
+/// Records the outcome of every request to the wrapped
+/// route in the audit log, including rejected requests
+pub struct Audit(AuditAction);
+
+impl Audit {
+    pub fn new(action: AuditAction) -> Self {
+        Self(action)
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Audit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = Middleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(Middleware {
+            service,
+            action: self.0,
+        }))
+    }
+}
+
+pub struct Middleware<S> {
+    service: S,
+    action: AuditAction,
+}
+
+fn audit_event(
+    req: &HttpRequest,
+    action: AuditAction,
+    status: u16,
+    details: Option<String>,
+) -> audit_event::ActiveModel {
+    let actor = req.extensions().get::<AuditActor>().cloned();
+    let target = req
+        .extensions()
+        .get::<AuditTarget>()
+        .map(|t| t.0.clone())
+        .or_else(|| req.match_info().get("id").map(|id| id.to_string()));
+
+    audit_event::ActiveModel {
+        action: ActiveValue::Set(action),
+        actor_type: ActiveValue::Set(
+            actor
+                .as_ref()
+                .map(|a| a.actor_type)
+                .unwrap_or(AuditActorType::Anonymous),
+        ),
+        actor_id: ActiveValue::Set(actor.as_ref().map(|a| a.id.clone())),
+        actor_name: ActiveValue::Set(actor.as_ref().map(|a| a.name.clone())),
+        token_id: ActiveValue::Set(actor.and_then(|a| a.token_id)),
+        target: ActiveValue::Set(target),
+        outcome: ActiveValue::Set(if status < 400 {
+            AuditOutcome::Success
+        } else {
+            AuditOutcome::Failure
+        }),
+        status_code: ActiveValue::Set(status as i32),
+        details: ActiveValue::Set(details),
+        source_ip: ActiveValue::Set(req.peer_addr().map(|addr| addr.ip().to_string())),
+        forwarded_for: ActiveValue::Set(
+            req.headers()
+                .get("X-Forwarded-For")
+                .and_then(|h| h.to_str().ok())
+                .map(|h| h.to_string()),
+        ),
+        ..Default::default()
+    }
+}
+
+impl<S, B> Service<ServiceRequest> for Middleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let action = self.action;
+        let http_req = req.request().clone();
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let res = fut.await;
+            let (status, details) = match &res {
+                Ok(res) => (
+                    res.status().as_u16(),
+                    res.response().error().map(|e| e.to_string()),
+                ),
+                Err(e) => (
+                    e.as_response_error().status_code().as_u16(),
+                    Some(e.to_string()),
+                ),
+            };
+
+            if let Some(data) = http_req.app_data::<web::Data<AppState>>() {
+                data.audit_service
+                    .record(audit_event(&http_req, action, status, details))
+                    .await;
+            }
+
+            res
+        })
+    }
+}
@@ -1,21 +1,67 @@
 use crate::config::app_state::AppState;
-use crate::entity::{client, user};
+use crate::entity::{client, signing_request, user};
 use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::identity::identity_provider::IdentityClaims;
+use crate::middleware::audit_middleware::AuditActor;
 use crate::middleware::jwt_middleware::JwtMiddleware;
-use crate::middleware::keycloak_roles::KeycloakRoles;
+use crate::middleware::token_scopes::TokenScopes;
+use crate::middleware::user_roles::UserRoles;
+use crate::model::token_scope::TokenScope;
+use crate::util::types::WebResult;
 use actix_web::dev::Payload;
-use actix_web::{web, Error, FromRequest};
-use actix_web_middleware_keycloak_auth::StandardKeycloakClaims;
+use actix_web::{web, Error, FromRequest, HttpMessage, HttpRequest};
 use futures_util::future::LocalBoxFuture;
+use log::{info, warn};
+use openssl::base64;
+use openssl::hash::MessageDigest;
+use openssl::x509::X509;
+use sea_orm::ActiveValue;
+use serde::de::DeserializeOwned;
+use shared::model::certificate_status::CertificateStatus;
+use shared::util::request_signature;
+use shared::util::traits::u8_vec_to_string::U8VecToString;
 
-pub struct KeycloakUserClaims<R: KeycloakRoles> {
+pub struct UserClaims<R: UserRoles> {
     pub user: user::Model,
+    /// The roles of the user as stated in the access token
+    pub roles: Vec<String>,
     _roles: std::marker::PhantomData<R>,
 }
 
-impl<R> FromRequest for KeycloakUserClaims<R>
+impl<R: UserRoles> UserClaims<R> {
+    /// Check whether the user has all roles required by `T`
+    pub fn has_roles<T: UserRoles>(&self) -> bool {
+        T::roles_match(&self.roles)
+    }
+
+    /// Create a user which logs in for the first time
+    async fn provision(data: &AppState, claims: &IdentityClaims) -> WebResult<user::Model> {
+        let name = claims
+            .username
+            .clone()
+            .ok_or(HttpResponseError::unauthorized(Some(
+                "The token does not contain the name of the user",
+            )))?;
+        if data.user_service.find_by_name(&name, true).await?.is_some() {
+            return Err(HttpResponseError::unauthorized(Some(
+                "Another user with the same name already exists",
+            )));
+        }
+
+        info!("Creating user {} on first login", name);
+        data.user_service
+            .insert(user::ActiveModel {
+                name: ActiveValue::Set(name),
+                external_id: ActiveValue::Set(Some(claims.subject.clone())),
+                ..Default::default()
+            })
+            .await
+    }
+}
+
+impl<R> FromRequest for UserClaims<R>
 where
-    R: KeycloakRoles,
+    R: UserRoles,
 {
     type Error = Error;
     type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
@@ -28,40 +74,60 @@ where
                     .ok_or(HttpResponseError::internal_error(Some(
                         "App data not found",
                     )))?;
-            let claims: StandardKeycloakClaims =
-                StandardKeycloakClaims::from_request(&req, &mut Payload::None).await?;
-            let user_id = claims.sub.to_string();
-
-            if !R::roles_match(
-                &claims
-                    .realm_access
-                    .as_ref()
-                    .ok_or(HttpResponseError::internal_error(Some(
-                        "Failed to get user roles",
-                    )))?
-                    .roles,
-            ) {
+            let claims = req
+                .extensions()
+                .get::<IdentityClaims>()
+                .cloned()
+                .ok_or(HttpResponseError::unauthorized(Some("No token provided")))?;
+
+            let user = match data
+                .user_service
+                .find_by_external_id(&claims.subject, true)
+                .await
+                .map_internal_error(Some("Failed to find user"))?
+            {
+                Some(user) if !user.active => {
+                    return Err(HttpResponseError::unauthorized(Some("User is disabled")).into())
+                }
+                Some(user) => user,
+                None if !data.identity_provider.manages_users() => {
+                    Self::provision(data, &claims).await?
+                }
+                None => return Err(HttpResponseError::unauthorized(Some("User not found")).into()),
+            };
+            req.extensions_mut().insert(AuditActor::user(&user));
+
+            let roles = claims.roles;
+            if !R::roles_match(&roles) {
                 return Err(HttpResponseError::unauthorized(Some("User not authorized")).into());
             }
 
-            Ok(KeycloakUserClaims {
-                user: data
-                    .user_service
-                    .find_by_external_id(&user_id, false)
-                    .await
-                    .map_internal_error(Some("Failed to find user"))?
-                    .ok_or(HttpResponseError::unauthorized(Some("User not found")))?,
+            Ok(UserClaims {
+                user,
+                roles,
                 _roles: std::marker::PhantomData,
             })
         })
     }
 }
 
-pub struct JwtClientClaims {
+pub struct JwtClientClaims<S: TokenScopes> {
     pub client: client::Model,
+    /// The scopes of the token the client authenticated with
+    pub scopes: Vec<TokenScope>,
+    _scopes: std::marker::PhantomData<S>,
 }
 
-impl FromRequest for JwtClientClaims {
+impl<S: TokenScopes> JwtClientClaims<S> {
+    pub fn has_scope(&self, scope: TokenScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+
+impl<S> FromRequest for JwtClientClaims<S>
+where
+    S: TokenScopes,
+{
     type Error = Error;
     type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
 
@@ -75,21 +141,176 @@ impl FromRequest for JwtClientClaims {
                     )))?;
             let jwt = JwtMiddleware::from_request(&req, &mut Payload::None).await?;
 
-            let client_id = data
+            let token = data
                 .token_service
                 .find_by_id(&jwt.id, false)
                 .await
                 .map_internal_error(Some("Failed to find token by id"))?
-                .ok_or(HttpResponseError::unauthorized(Some("Token not found")))?
-                .client_id;
+                .ok_or(HttpResponseError::unauthorized(Some("Token not found")))?;
+
+            let client = data
+                .client_service
+                .find_by_id(&token.client_id, false)
+                .await
+                .map_internal_error(Some("Failed to find client"))?
+                .ok_or(HttpResponseError::unauthorized(Some("Client not found")))?;
+            req.extensions_mut()
+                .insert(AuditActor::client(&client, &jwt.id));
+
+            let scopes = token.scopes();
+            if !S::scopes_match(&scopes) {
+                return Err(HttpResponseError::unauthorized(Some(
+                    "The token does not have the required scope",
+                ))
+                .into());
+            }
+
+            // Only calls the token is allowed to make count as a use
+            let used_from = req.peer_addr().map(|addr| addr.ip().to_string());
+            if let Err(e) = data.token_service.touch(&token.id, used_from).await {
+                warn!("Failed to record the use of token {}: {}", token.id, e);
+            }
 
             Ok(JwtClientClaims {
-                client: data
-                    .client_service
-                    .find_by_id(&client_id, false)
-                    .await
-                    .map_internal_error(Some("Failed to find client"))?
-                    .ok_or(HttpResponseError::unauthorized(Some("Client not found")))?,
+                client,
+                scopes,
+                _scopes: std::marker::PhantomData,
+            })
+        })
+    }
+}
+
+/// A client which authenticated by signing the request
+/// with the key of a valid certificate issued to it
+pub struct CertificateClientClaims {
+    pub client: client::Model,
+    /// The certificate the client authenticated with
+    pub certificate: signing_request::Model,
+    /// The parsed certificate the client authenticated with
+    pub x509: X509,
+    /// The signed body of the request.
+    /// The body is consumed by the signature check, use this instead of other extractors.
+    pub body: web::Bytes,
+}
+
+impl CertificateClientClaims {
+    fn header<'a>(req: &'a HttpRequest, name: &str) -> Result<&'a str, HttpResponseError> {
+        req.headers().get(name).and_then(|h| h.to_str().ok()).ok_or(
+            HttpResponseError::unauthorized(Some(format!("Missing {} header", name))),
+        )
+    }
+
+    /// Deserialize the json body of the request
+    pub fn json<T: DeserializeOwned>(&self) -> WebResult<T> {
+        serde_json::from_slice(&self.body).map_bad_request(Some("Invalid request body"))
+    }
+}
+
+impl FromRequest for CertificateClientClaims {
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let req = req.clone();
+        let body = web::Bytes::from_request(&req, payload);
+        Box::pin(async move {
+            let data: &web::Data<AppState> =
+                req.app_data()
+                    .ok_or(HttpResponseError::internal_error(Some(
+                        "App data not found",
+                    )))?;
+
+            let body = body.await?;
+            let x509 =
+                base64::decode_block(Self::header(&req, request_signature::CERTIFICATE_HEADER)?)
+                    .ok()
+                    .and_then(|der| X509::from_der(&der).ok())
+                    .ok_or(HttpResponseError::unauthorized(Some(
+                        "Invalid certificate supplied",
+                    )))?;
+            let timestamp = Self::header(&req, request_signature::TIMESTAMP_HEADER)?
+                .parse::<i64>()
+                .map_unauthorized(Some("Invalid timestamp supplied"))?;
+            let signature =
+                base64::decode_block(Self::header(&req, request_signature::SIGNATURE_HEADER)?)
+                    .map_unauthorized(Some("Invalid signature supplied"))?;
+
+            if (chrono::Utc::now().timestamp() - timestamp).abs()
+                > request_signature::MAX_CLOCK_SKEW_SECS
+            {
+                return Err(HttpResponseError::unauthorized(Some(
+                    "The request signature has expired",
+                ))
+                .into());
+            }
+
+            let public_key = x509
+                .public_key()
+                .map_unauthorized(Some("Invalid certificate supplied"))?;
+            let valid = request_signature::verify(
+                &public_key,
+                req.method().as_str(),
+                req.path(),
+                timestamp,
+                &body,
+                &signature,
+            )
+            .unwrap_or(false);
+            if !valid {
+                return Err(
+                    HttpResponseError::unauthorized(Some("Invalid signature supplied")).into(),
+                );
+            }
+
+            // The certificate must be one of the certificates issued by this CA,
+            // which is checked by comparing it with the issuance records
+            let serial_number = x509
+                .serial_number()
+                .to_bn()
+                .and_then(|bn| bn.to_hex_str().map(|s| s.to_string()))
+                .map_unauthorized(Some("Invalid certificate supplied"))?;
+            let hash = x509
+                .digest(MessageDigest::sha256())
+                .map_internal_error(None)?
+                .to_vec()
+                .to_hex_string(":");
+            let certificate = data
+                .signing_request_service
+                .find_by_serial_number(&serial_number)
+                .await?
+                .filter(|c| c.hash == hash)
+                .ok_or(HttpResponseError::unauthorized(Some("Unknown certificate")))?;
+            if certificate.status() != CertificateStatus::Valid {
+                return Err(HttpResponseError::unauthorized(Some(
+                    "The certificate is not valid anymore",
+                ))
+                .into());
+            }
+
+            if !data
+                .signing_request_service
+                .use_signature(&hash, timestamp)
+                .await?
+            {
+                return Err(HttpResponseError::unauthorized(Some(
+                    "The request signature has already been used",
+                ))
+                .into());
+            }
+
+            let client = match certificate.client_id {
+                Some(id) => data.client_service.find_by_id(&id, false).await?,
+                None => None,
+            }
+            .ok_or(HttpResponseError::unauthorized(Some("Client not found")))?;
+            req.extensions_mut()
+                .insert(AuditActor::client_certificate(&client));
+
+            Ok(CertificateClientClaims {
+                client,
+                certificate,
+                x509,
+                body,
             })
         })
     }
@@ -0,0 +1,89 @@
+use crate::config::app_state::AppState;
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::identity::identity_provider::IdentityClaims;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{http, web, Error, HttpMessage, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+
+/// Verifies the access token of a user using the configured identity provider
+/// and inserts the claims of the token into the request extensions
+pub struct Identity;
+
+impl<S, B> Transform<S, ServiceRequest> for Identity
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = IdentityMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IdentityMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct IdentityMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S> IdentityMiddleware<S> {
+    fn token(req: &HttpRequest) -> Result<String, HttpResponseError> {
+        let header = req
+            .headers()
+            .get(http::header::AUTHORIZATION)
+            .ok_or(HttpResponseError::unauthorized(Some("No token provided")))?
+            .to_str()
+            .map_unauthorized(Some("Invalid token provided"))?;
+
+        header
+            .strip_prefix("Bearer ")
+            .map(str::to_string)
+            .ok_or(HttpResponseError::unauthorized(Some(
+                "Invalid token provided",
+            )))
+    }
+
+    async fn authenticate(req: &HttpRequest) -> Result<(), HttpResponseError> {
+        let data =
+            req.app_data::<web::Data<AppState>>()
+                .ok_or(HttpResponseError::internal_error(Some(
+                    "App data not found",
+                )))?;
+
+        let claims: IdentityClaims = data
+            .identity_provider
+            .verify_token(&Self::token(req)?)
+            .await?;
+        req.extensions_mut().insert(claims);
+        Ok(())
+    }
+}
+
+impl<S, B> Service<ServiceRequest> for IdentityMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        Box::pin(async move {
+            Self::authenticate(req.request()).await?;
+            service.call(req).await
+        })
+    }
+}
@@ -1,18 +1,17 @@
 use crate::config::app_state::AppState;
 use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
-use crate::model::token_claims::TokenClaims;
 use crate::util::types::WebResult;
 use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
 use actix_web::{http, web, Error, FromRequest, HttpMessage, HttpRequest};
 use futures_util::future::LocalBoxFuture;
-use jsonwebtoken::{decode, DecodingKey, Validation};
 use std::future::{ready, Ready};
+use std::rc::Rc;
 
 pub struct Jwt;
 
 impl<S, B> Transform<S, ServiceRequest> for Jwt
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -23,7 +22,9 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ready(Ok(Middleware { service }))
+        ready(Ok(Middleware {
+            service: Rc::new(service),
+        }))
     }
 }
 
@@ -32,9 +33,7 @@ pub struct JwtMiddleware {
 }
 
 impl JwtMiddleware {
-    fn new(req: &HttpRequest) -> Result<Self, Error> {
-        let data = req.app_data::<web::Data<AppState>>().unwrap();
-
+    fn token(req: &HttpRequest) -> Result<String, Error> {
         let token: Option<WebResult<String>> = req
             .cookie("token")
             .map(|c| Ok(c.value().to_string()))
@@ -55,22 +54,23 @@ impl JwtMiddleware {
                 })
             });
 
-        if token.is_none() {
-            return Err(HttpResponseError::unauthorized(Some("No token provided")).into());
+        match token {
+            Some(token) => Ok(token?),
+            None => Err(HttpResponseError::unauthorized(Some("No token provided")).into()),
         }
+    }
 
-        let claims = match decode::<TokenClaims>(
-            &token.unwrap()?,
-            &DecodingKey::from_secret(data.config.jwt_secret.as_ref()),
-            &Validation::default(),
-        ) {
-            Ok(c) => c.claims,
-            Err(e) => {
-                return Err(e).map_unauthorized(Some("Invalid token"))?;
-            }
-        };
-
-        let id = uuid::Uuid::parse_str(claims.sub.as_str()).unwrap();
+    async fn new(req: &HttpRequest) -> Result<Self, Error> {
+        let data = req.app_data::<web::Data<AppState>>().unwrap();
+        let token = Self::token(req)?;
+
+        let claims = data
+            .token_key_service
+            .decode(&token, data.config.jwt_secret.as_deref())
+            .await?;
+
+        let id =
+            uuid::Uuid::parse_str(claims.sub.as_str()).map_unauthorized(Some("Invalid token"))?;
         req.extensions_mut().insert::<uuid::Uuid>(id.to_owned());
 
         Ok(JwtMiddleware { id })
@@ -79,19 +79,20 @@ impl JwtMiddleware {
 
 impl FromRequest for JwtMiddleware {
     type Error = Error;
-    type Future = Ready<Result<Self, Self::Error>>;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
-        ready(Self::new(req))
+        let req = req.clone();
+        Box::pin(async move { Self::new(&req).await })
     }
 }
 
 pub struct Middleware<S> {
-    service: S,
+    service: Rc<S>,
 }
 
 impl<S, B> Service<ServiceRequest> for Middleware<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -102,12 +103,10 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        match JwtMiddleware::new(req.request()) {
-            Ok(_) => {
-                let fut = self.service.call(req);
-                Box::pin(async move { fut.await })
-            }
-            Err(err) => Box::pin(async move { Err(err) }),
-        }
+        let service = self.service.clone();
+        Box::pin(async move {
+            JwtMiddleware::new(req.request()).await?;
+            service.call(req).await
+        })
     }
 }
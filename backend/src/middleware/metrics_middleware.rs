@@ -0,0 +1,82 @@
+use crate::error::http_response_error::HttpResponseError;
+use crate::middleware::audit_middleware::AuditActor;
+use crate::util::metrics::{CERTIFICATES_ISSUED, SIGNING_DURATION, SIGNING_FAILURES};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::StatusCode;
+use actix_web::{Error, HttpMessage};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+
+/// Records the latency and outcome of certificate signing requests
+pub struct SigningMetrics;
+
+impl<S, B> Transform<S, ServiceRequest> for SigningMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = Middleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(Middleware { service }))
+    }
+}
+
+pub struct Middleware<S> {
+    service: S,
+}
+
+fn error_code(error: &Error) -> String {
+    error
+        .as_error::<HttpResponseError>()
+        .map(|e| format!("{:?}", e.error))
+        .unwrap_or_else(|| error.as_response_error().status_code().as_u16().to_string())
+}
+
+impl<S, B> Service<ServiceRequest> for Middleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let timer = SIGNING_DURATION.start_timer();
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let res = fut.await;
+            timer.observe_duration();
+
+            match &res {
+                Ok(res) => match res.response().error() {
+                    Some(e) => SIGNING_FAILURES.with_label_values(&[&error_code(e)]).inc(),
+                    // Requests which require approval are issued later
+                    None if res.status() == StatusCode::ACCEPTED => {}
+                    None => {
+                        let client = res
+                            .request()
+                            .extensions()
+                            .get::<AuditActor>()
+                            .map(|actor| actor.name.clone())
+                            .unwrap_or_default();
+                        CERTIFICATES_ISSUED.with_label_values(&[&client]).inc();
+                    }
+                },
+                Err(e) => SIGNING_FAILURES.with_label_values(&[&error_code(e)]).inc(),
+            }
+
+            res
+        })
+    }
+}
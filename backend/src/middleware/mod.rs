@@ -1,4 +1,7 @@
+pub mod audit_middleware;
 pub mod extractors;
+pub mod identity_middleware;
 pub mod jwt_middleware;
-pub mod keycloak_middleware;
-pub mod keycloak_roles;
+pub mod metrics_middleware;
+pub mod token_scopes;
+pub mod user_roles;
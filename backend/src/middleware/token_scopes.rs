@@ -0,0 +1,39 @@
+use crate::model::token_scope::TokenScope;
+
+/// The scopes a client token must have to access a route.
+/// Unlike user roles, having any of the scopes is sufficient.
+pub trait TokenScopes {
+    fn get_scopes() -> Vec<TokenScope>;
+
+    fn scopes_match(scopes: &[TokenScope]) -> bool {
+        Self::get_scopes().iter().any(|s| scopes.contains(s))
+    }
+}
+
+/// Request certificates for a signing request,
+/// either for any subject or just to renew a certificate
+pub struct SignScope;
+
+impl TokenScopes for SignScope {
+    fn get_scopes() -> Vec<TokenScope> {
+        vec![TokenScope::Sign, TokenScope::RenewOnly]
+    }
+}
+
+pub struct GenerateScope;
+
+impl TokenScopes for GenerateScope {
+    fn get_scopes() -> Vec<TokenScope> {
+        vec![TokenScope::Generate]
+    }
+}
+
+/// Read the status of signing requests,
+/// implied by the scopes which allow submitting them
+pub struct ReadScope;
+
+impl TokenScopes for ReadScope {
+    fn get_scopes() -> Vec<TokenScope> {
+        vec![TokenScope::Sign, TokenScope::RenewOnly, TokenScope::Read]
+    }
+}
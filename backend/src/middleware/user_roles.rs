@@ -1,4 +1,4 @@
-pub trait KeycloakRoles {
+pub trait UserRoles {
     fn get_roles() -> Vec<String>;
 
     fn roles_match(roles: &Vec<String>) -> bool {
@@ -14,7 +14,7 @@ pub trait KeycloakRoles {
 
 pub struct NoRoles;
 
-impl KeycloakRoles for NoRoles {
+impl UserRoles for NoRoles {
     fn get_roles() -> Vec<String> {
         vec![]
     }
@@ -22,7 +22,7 @@ impl KeycloakRoles for NoRoles {
 
 pub struct AdminRole;
 
-impl KeycloakRoles for AdminRole {
+impl UserRoles for AdminRole {
     fn get_roles() -> Vec<String> {
         vec!["admin".to_string()]
     }
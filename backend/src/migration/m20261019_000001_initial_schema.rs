@@ -0,0 +1,317 @@
+use sea_orm_migration::prelude::*;
+
+/// The schema as it was created from the entities before versioned migrations were
+/// introduced. All tables are only created if they don't exist yet, so databases
+/// created by earlier versions are adopted without changes.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum User {
+    Table,
+    Id,
+    Name,
+    OriginalName,
+    ExternalId,
+    Active,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Client {
+    Table,
+    Id,
+    UserId,
+    Name,
+    OriginalName,
+    Active,
+    ValidUntil,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum SigningRequest {
+    Table,
+    Id,
+    ClientId,
+    Hash,
+    SerialNumber,
+    SubjectName,
+    IssuedAt,
+}
+
+#[derive(Iden)]
+enum Token {
+    Table,
+    Id,
+    ClientId,
+    #[iden = "token_hash"]
+    Hash,
+    Active,
+}
+
+#[derive(Iden)]
+enum Certificate {
+    Table,
+    Id,
+    Public,
+    Private,
+    Active,
+    ValidUntil,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum RootCertificate {
+    Table,
+    Id,
+    Public,
+    Active,
+    CreatedBy,
+    ValidUntil,
+    CreatedAt,
+    UpdatedAt,
+}
+
+/// An auto-incrementing integer primary key
+fn serial(col: impl IntoIden) -> ColumnDef {
+    ColumnDef::new(col)
+        .integer()
+        .not_null()
+        .unique_key()
+        .auto_increment()
+        .primary_key()
+        .take()
+}
+
+/// A uuid primary key
+fn uuid(col: impl IntoIden) -> ColumnDef {
+    ColumnDef::new(col)
+        .uuid()
+        .not_null()
+        .unique_key()
+        .primary_key()
+        .take()
+}
+
+/// A foreign key named the way sea-orm names the keys of its relations
+fn foreign_key(
+    from: impl IntoIden,
+    from_col: impl IntoIden,
+    to: impl IntoIden,
+    to_col: impl IntoIden,
+) -> ForeignKeyCreateStatement {
+    let from = from.into_iden();
+    let from_col = from_col.into_iden();
+    let to = to.into_iden();
+    let to_col = to_col.into_iden();
+
+    ForeignKey::create()
+        .name(&format!("fk-{}-{}", from.to_string(), from_col.to_string()))
+        .from(from, from_col)
+        .to(to, to_col)
+        .take()
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(User::Table)
+                    .if_not_exists()
+                    .col(&mut uuid(User::Id))
+                    .col(ColumnDef::new(User::Name).string().not_null().unique_key())
+                    .col(ColumnDef::new(User::OriginalName).string().not_null())
+                    .col(ColumnDef::new(User::ExternalId).string())
+                    .col(ColumnDef::new(User::Active).boolean().not_null())
+                    .col(
+                        ColumnDef::new(User::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(User::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Client::Table)
+                    .if_not_exists()
+                    .col(&mut uuid(Client::Id))
+                    .col(ColumnDef::new(Client::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(Client::Name)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Client::OriginalName).string().not_null())
+                    .col(ColumnDef::new(Client::Active).boolean().not_null())
+                    .col(
+                        ColumnDef::new(Client::ValidUntil)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Client::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Client::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(&mut foreign_key(
+                        Client::Table,
+                        Client::UserId,
+                        User::Table,
+                        User::Id,
+                    ))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SigningRequest::Table)
+                    .if_not_exists()
+                    .col(&mut serial(SigningRequest::Id))
+                    .col(ColumnDef::new(SigningRequest::ClientId).uuid().not_null())
+                    .col(ColumnDef::new(SigningRequest::Hash).string().not_null())
+                    .col(
+                        ColumnDef::new(SigningRequest::SerialNumber)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SigningRequest::SubjectName)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SigningRequest::IssuedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(&mut foreign_key(
+                        SigningRequest::Table,
+                        SigningRequest::ClientId,
+                        Client::Table,
+                        Client::Id,
+                    ))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Token::Table)
+                    .if_not_exists()
+                    .col(&mut uuid(Token::Id))
+                    .col(ColumnDef::new(Token::ClientId).uuid().not_null())
+                    .col(ColumnDef::new(Token::Hash).string().not_null())
+                    .col(ColumnDef::new(Token::Active).boolean().not_null())
+                    .foreign_key(&mut foreign_key(
+                        Token::Table,
+                        Token::ClientId,
+                        Client::Table,
+                        Client::Id,
+                    ))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Certificate::Table)
+                    .if_not_exists()
+                    .col(&mut serial(Certificate::Id))
+                    .col(ColumnDef::new(Certificate::Public).binary().not_null())
+                    .col(ColumnDef::new(Certificate::Private).binary())
+                    .col(ColumnDef::new(Certificate::Active).boolean().not_null())
+                    .col(
+                        ColumnDef::new(Certificate::ValidUntil)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Certificate::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Certificate::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RootCertificate::Table)
+                    .if_not_exists()
+                    .col(&mut serial(RootCertificate::Id))
+                    .col(ColumnDef::new(RootCertificate::Public).binary().not_null())
+                    .col(ColumnDef::new(RootCertificate::Active).boolean().not_null())
+                    .col(ColumnDef::new(RootCertificate::CreatedBy).uuid().not_null())
+                    .col(
+                        ColumnDef::new(RootCertificate::ValidUntil)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RootCertificate::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RootCertificate::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(&mut foreign_key(
+                        RootCertificate::Table,
+                        RootCertificate::CreatedBy,
+                        User::Table,
+                        User::Id,
+                    ))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [
+            RootCertificate::Table.into_iden(),
+            Certificate::Table.into_iden(),
+            Token::Table.into_iden(),
+            SigningRequest::Table.into_iden(),
+            Client::Table.into_iden(),
+            User::Table.into_iden(),
+        ] {
+            manager
+                .drop_table(Table::drop().table(table).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use chrono::Duration;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+/// The default of `CERT_VALIDITY_DAYS`
+const DEFAULT_VALIDITY_DAYS: i64 = 31;
+
+/// Records the alternative names, the validity and the revocation of issued certificates
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum SigningRequest {
+    Table,
+    Id,
+    AlternativeNames,
+    IssuedAt,
+    ValidUntil,
+    RevokedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only supports a single change per statement
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SigningRequest::Table)
+                    .add_column(ColumnDef::new(SigningRequest::AlternativeNames).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        // A constant default is required to add a column which is not nullable
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SigningRequest::Table)
+                    .add_column(
+                        ColumnDef::new(SigningRequest::ValidUntil)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default("1970-01-01T00:00:00+00:00"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SigningRequest::Table)
+                    .add_column(
+                        ColumnDef::new(SigningRequest::RevokedAt).timestamp_with_time_zone(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The validity of certificates issued before it was recorded is unknown,
+        // the configured validity is the only known bound
+        let validity_days = std::env::var("CERT_VALIDITY_DAYS")
+            .ok()
+            .and_then(|days| days.parse::<i64>().ok())
+            .unwrap_or(DEFAULT_VALIDITY_DAYS);
+        let db = manager.get_connection();
+        let builder = manager.get_database_backend();
+        let certificates = db
+            .query_all(
+                builder.build(
+                    Query::select()
+                        .columns([SigningRequest::Id, SigningRequest::IssuedAt])
+                        .from(SigningRequest::Table),
+                ),
+            )
+            .await?;
+
+        for certificate in certificates {
+            let id: i32 = certificate.try_get("", "id")?;
+            let issued_at: DateTimeWithTimeZone = certificate.try_get("", "issued_at")?;
+            manager
+                .exec_stmt(
+                    Query::update()
+                        .table(SigningRequest::Table)
+                        .value(
+                            SigningRequest::ValidUntil,
+                            issued_at + Duration::days(validity_days),
+                        )
+                        .and_where(Expr::col(SigningRequest::Id).eq(id))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            SigningRequest::RevokedAt,
+            SigningRequest::ValidUntil,
+            SigningRequest::AlternativeNames,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(SigningRequest::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+/// Adds the audit log of security relevant actions
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum AuditEvent {
+    Table,
+    Id,
+    OccurredAt,
+    Action,
+    ActorType,
+    ActorId,
+    ActorName,
+    TokenId,
+    Target,
+    Outcome,
+    StatusCode,
+    Details,
+    SourceIp,
+    ForwardedFor,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditEvent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditEvent::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AuditEvent::OccurredAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AuditEvent::Action).string_len(32).not_null())
+                    .col(
+                        ColumnDef::new(AuditEvent::ActorType)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AuditEvent::ActorId).string())
+                    .col(ColumnDef::new(AuditEvent::ActorName).string())
+                    .col(ColumnDef::new(AuditEvent::TokenId).string())
+                    .col(ColumnDef::new(AuditEvent::Target).string())
+                    .col(
+                        ColumnDef::new(AuditEvent::Outcome)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AuditEvent::StatusCode).integer().not_null())
+                    .col(ColumnDef::new(AuditEvent::Details).string())
+                    .col(ColumnDef::new(AuditEvent::SourceIp).string())
+                    .col(ColumnDef::new(AuditEvent::ForwardedFor).string())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditEvent::Table).to_owned())
+            .await
+    }
+}
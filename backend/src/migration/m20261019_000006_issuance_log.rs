@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+/// Adds the leaves of the certificate issuance log
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum LogEntry {
+    Table,
+    LeafIndex,
+    SigningRequestId,
+    SerialNumber,
+    LeafHash,
+    CreatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LogEntry::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LogEntry::LeafIndex)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(LogEntry::SigningRequestId)
+                            .integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(LogEntry::SerialNumber).string().not_null())
+                    .col(ColumnDef::new(LogEntry::LeafHash).string().not_null())
+                    .col(
+                        ColumnDef::new(LogEntry::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LogEntry::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+/// Adds the quotas overriding the configured limits of users and clients
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum Quota {
+    Table,
+    Scope,
+    SubjectId,
+    IssuanceLimit,
+    MaxClients,
+    MaxValidCertificates,
+    UpdatedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Quota::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Quota::Scope).string_len(16).not_null())
+                    .col(ColumnDef::new(Quota::SubjectId).uuid().not_null())
+                    .col(ColumnDef::new(Quota::IssuanceLimit).integer())
+                    .col(ColumnDef::new(Quota::MaxClients).integer())
+                    .col(ColumnDef::new(Quota::MaxValidCertificates).integer())
+                    .col(
+                        ColumnDef::new(Quota::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .primary_key(Index::create().col(Quota::Scope).col(Quota::SubjectId))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Quota::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,108 @@
+use sea_orm_migration::prelude::*;
+
+/// Adds signing requests waiting for approval and the clients requiring it
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum Client {
+    Table,
+    Id,
+    RequiresApproval,
+}
+
+#[derive(Iden)]
+enum ApprovalRequest {
+    Table,
+    Id,
+    ClientId,
+    Request,
+    SubjectName,
+    AlternativeNames,
+    Status,
+    CreatedAt,
+    DecidedBy,
+    DecidedAt,
+    Reason,
+    SigningRequestId,
+    Certificate,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Client::Table)
+                    .add_column(
+                        ColumnDef::new(Client::RequiresApproval)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApprovalRequest::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ApprovalRequest::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ApprovalRequest::ClientId).uuid().not_null())
+                    .col(ColumnDef::new(ApprovalRequest::Request).string().not_null())
+                    .col(
+                        ColumnDef::new(ApprovalRequest::SubjectName)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ApprovalRequest::AlternativeNames).string())
+                    .col(
+                        ColumnDef::new(ApprovalRequest::Status)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ApprovalRequest::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ApprovalRequest::DecidedBy).uuid())
+                    .col(ColumnDef::new(ApprovalRequest::DecidedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(ApprovalRequest::Reason).string())
+                    .col(ColumnDef::new(ApprovalRequest::SigningRequestId).integer())
+                    .col(ColumnDef::new(ApprovalRequest::Certificate).string())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-approval_request-client_id")
+                            .from(ApprovalRequest::Table, ApprovalRequest::ClientId)
+                            .to(Client::Table, Client::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ApprovalRequest::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Client::Table)
+                    .drop_column(Client::RequiresApproval)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,203 @@
+use sea_orm::DbBackend;
+use sea_orm_migration::prelude::*;
+
+/// Records the users who requested certificates on behalf of their clients
+/// and allows certificates which are not issued to a client
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum Client {
+    Table,
+    Id,
+}
+
+#[derive(Iden, Clone, Copy)]
+enum SigningRequest {
+    Table,
+    Id,
+    ClientId,
+    RequestedBy,
+    Hash,
+    SerialNumber,
+    SubjectName,
+    IssuedAt,
+    AlternativeNames,
+    ValidUntil,
+    RevokedAt,
+}
+
+#[derive(Iden)]
+enum ApprovalRequest {
+    Table,
+    RequestedBy,
+}
+
+/// Change whether the client id of signing requests is nullable
+async fn set_client_id_nullable(manager: &SchemaManager<'_>, nullable: bool) -> Result<(), DbErr> {
+    let mut client_id = ColumnDef::new(SigningRequest::ClientId);
+    client_id.uuid();
+    if nullable {
+        client_id.null();
+    } else {
+        client_id.not_null();
+    }
+
+    if manager.get_database_backend() != DbBackend::Sqlite {
+        return manager
+            .alter_table(
+                Table::alter()
+                    .table(SigningRequest::Table)
+                    .modify_column(&mut client_id)
+                    .to_owned(),
+            )
+            .await;
+    }
+
+    // SQLite can't change existing columns, the table is recreated
+    // with the new definition and the rows are copied over
+    let copy = Alias::new("signing_request_new");
+    let columns = [
+        SigningRequest::Id,
+        SigningRequest::ClientId,
+        SigningRequest::Hash,
+        SigningRequest::SerialNumber,
+        SigningRequest::SubjectName,
+        SigningRequest::IssuedAt,
+        SigningRequest::AlternativeNames,
+        SigningRequest::ValidUntil,
+        SigningRequest::RevokedAt,
+    ];
+
+    manager
+        .create_table(
+            Table::create()
+                .table(copy.clone())
+                .col(
+                    ColumnDef::new(SigningRequest::Id)
+                        .integer()
+                        .not_null()
+                        .unique_key()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(&mut client_id)
+                .col(ColumnDef::new(SigningRequest::Hash).string().not_null())
+                .col(
+                    ColumnDef::new(SigningRequest::SerialNumber)
+                        .string()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(SigningRequest::SubjectName)
+                        .string()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(SigningRequest::IssuedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(SigningRequest::AlternativeNames).string())
+                .col(
+                    ColumnDef::new(SigningRequest::ValidUntil)
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default("1970-01-01T00:00:00+00:00"),
+                )
+                .col(ColumnDef::new(SigningRequest::RevokedAt).timestamp_with_time_zone())
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-signing_request-client_id")
+                        .from(copy.clone(), SigningRequest::ClientId)
+                        .to(Client::Table, Client::Id),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .exec_stmt(
+            Query::insert()
+                .into_table(copy.clone())
+                .columns(columns)
+                .select_from(
+                    Query::select()
+                        .columns(columns)
+                        .from(SigningRequest::Table)
+                        .to_owned(),
+                )
+                .map_err(|e| DbErr::Migration(e.to_string()))?
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .drop_table(Table::drop().table(SigningRequest::Table).to_owned())
+        .await?;
+
+    manager
+        .rename_table(
+            Table::rename()
+                .table(copy, SigningRequest::Table)
+                .to_owned(),
+        )
+        .await
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        set_client_id_nullable(manager, true).await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SigningRequest::Table)
+                    .add_column(ColumnDef::new(SigningRequest::RequestedBy).uuid())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ApprovalRequest::Table)
+                    .add_column(ColumnDef::new(ApprovalRequest::RequestedBy).uuid())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ApprovalRequest::Table)
+                    .drop_column(ApprovalRequest::RequestedBy)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SigningRequest::Table)
+                    .drop_column(SigningRequest::RequestedBy)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Certificates which don't belong to a client can't be kept
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(SigningRequest::Table)
+                    .and_where(Expr::col(SigningRequest::ClientId).is_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        set_client_id_nullable(manager, false).await
+    }
+}
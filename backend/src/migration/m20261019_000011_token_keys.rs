@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+/// Adds the keys client tokens are signed with
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum TokenKey {
+    Table,
+    Id,
+    Algorithm,
+    PrivateKey,
+    PublicKey,
+    Active,
+    CreatedAt,
+    RetiredAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TokenKey::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(TokenKey::Id).uuid().not_null().primary_key())
+                    .col(
+                        ColumnDef::new(TokenKey::Algorithm)
+                            .string_len(16)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TokenKey::PrivateKey).string().not_null())
+                    .col(ColumnDef::new(TokenKey::PublicKey).string().not_null())
+                    .col(ColumnDef::new(TokenKey::Active).boolean().not_null())
+                    .col(
+                        ColumnDef::new(TokenKey::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(TokenKey::RetiredAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TokenKey::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+/// Adds the one-time tokens clients are enrolled with
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum Client {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum EnrollmentToken {
+    Table,
+    Id,
+    ClientId,
+    TokenHash,
+    CreatedBy,
+    CreatedAt,
+    ExpiresAt,
+    RedeemedAt,
+    RedeemedFrom,
+    SerialNumber,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(EnrollmentToken::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(EnrollmentToken::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(EnrollmentToken::ClientId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(EnrollmentToken::TokenHash)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(EnrollmentToken::CreatedBy).uuid().not_null())
+                    .col(
+                        ColumnDef::new(EnrollmentToken::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EnrollmentToken::ExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(EnrollmentToken::RedeemedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(EnrollmentToken::RedeemedFrom).string())
+                    .col(ColumnDef::new(EnrollmentToken::SerialNumber).string())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-enrollment_token-client_id")
+                            .from(EnrollmentToken::Table, EnrollmentToken::ClientId)
+                            .to(Client::Table, Client::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EnrollmentToken::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,55 @@
+use sea_orm_migration::prelude::*;
+
+/// Records the last use and the revocation of client tokens
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum Token {
+    Table,
+    LastUsedAt,
+    LastUsedFrom,
+    RevokedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only supports a single change per statement
+        for mut column in [
+            ColumnDef::new(Token::LastUsedAt)
+                .timestamp_with_time_zone()
+                .take(),
+            ColumnDef::new(Token::LastUsedFrom).string().take(),
+            ColumnDef::new(Token::RevokedAt)
+                .timestamp_with_time_zone()
+                .take(),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Token::Table)
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [Token::RevokedAt, Token::LastUsedFrom, Token::LastUsedAt] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Token::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
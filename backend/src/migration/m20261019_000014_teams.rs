@@ -0,0 +1,148 @@
+use sea_orm_migration::prelude::*;
+
+/// Adds teams, their members and the team owning a client
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Client {
+    Table,
+    TeamId,
+}
+
+#[derive(Iden)]
+enum Team {
+    Table,
+    Id,
+    Name,
+    ExternalId,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum TeamMember {
+    Table,
+    TeamId,
+    UserId,
+    Role,
+    CreatedAt,
+}
+
+const CLIENT_TEAM_INDEX: &str = "idx-client-team_id";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Team::Table)
+                    .col(ColumnDef::new(Team::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Team::Name).string().not_null().unique_key())
+                    .col(ColumnDef::new(Team::ExternalId).string().unique_key())
+                    .col(
+                        ColumnDef::new(Team::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Team::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TeamMember::Table)
+                    .col(ColumnDef::new(TeamMember::TeamId).uuid().not_null())
+                    .col(ColumnDef::new(TeamMember::UserId).uuid().not_null())
+                    .col(ColumnDef::new(TeamMember::Role).string_len(16).not_null())
+                    .col(
+                        ColumnDef::new(TeamMember::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(TeamMember::TeamId)
+                            .col(TeamMember::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-team_member-team_id")
+                            .from(TeamMember::Table, TeamMember::TeamId)
+                            .to(Team::Table, Team::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-team_member-user_id")
+                            .from(TeamMember::Table, TeamMember::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // SQLite can't add foreign keys to existing tables,
+        // deleting teams which still own clients is prevented by the service
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Client::Table)
+                    .add_column(ColumnDef::new(Client::TeamId).uuid())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(CLIENT_TEAM_INDEX)
+                    .table(Client::Table)
+                    .col(Client::TeamId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(CLIENT_TEAM_INDEX)
+                    .table(Client::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Client::Table)
+                    .drop_column(Client::TeamId)
+                    .to_owned(),
+            )
+            .await?;
+
+        for table in [TeamMember::Table.into_iden(), Team::Table.into_iden()] {
+            manager
+                .drop_table(Table::drop().table(table).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
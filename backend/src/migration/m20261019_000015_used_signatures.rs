@@ -0,0 +1,46 @@
+use sea_orm_migration::prelude::*;
+
+/// Adds the request signatures which have been used to authenticate with a certificate
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum UsedSignature {
+    Table,
+    CertificateHash,
+    SignedAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UsedSignature::Table)
+                    .col(
+                        ColumnDef::new(UsedSignature::CertificateHash)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UsedSignature::SignedAt)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(UsedSignature::CertificateHash)
+                            .col(UsedSignature::SignedAt),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UsedSignature::Table).to_owned())
+            .await
+    }
+}
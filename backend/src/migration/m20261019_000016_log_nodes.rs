@@ -0,0 +1,88 @@
+use sea_orm_migration::prelude::*;
+
+/// Adds the subtree hashes of the issuance log and the row holding its size.
+/// The hashes of entries appended before are computed when the log is synchronized.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(Iden)]
+enum LogNode {
+    Table,
+    Level,
+    NodeIndex,
+    Hash,
+}
+
+#[derive(Iden)]
+enum LogHead {
+    Table,
+    Id,
+    TreeSize,
+}
+
+#[derive(Iden)]
+enum LogEntry {
+    Table,
+    LeafIndex,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LogNode::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(LogNode::Level).integer().not_null())
+                    .col(ColumnDef::new(LogNode::NodeIndex).big_integer().not_null())
+                    .col(ColumnDef::new(LogNode::Hash).string().not_null())
+                    .primary_key(Index::create().col(LogNode::Level).col(LogNode::NodeIndex))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(LogHead::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LogHead::Id)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(LogHead::TreeSize).big_integer().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(LogHead::Table)
+                    .columns([LogHead::Id, LogHead::TreeSize])
+                    .select_from(
+                        Query::select()
+                            .expr(Expr::val(1))
+                            .expr(Func::count(Expr::col(LogEntry::LeafIndex)))
+                            .from(LogEntry::Table)
+                            .to_owned(),
+                    )
+                    .map_err(|e| DbErr::Migration(e.to_string()))?
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LogHead::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(LogNode::Table).to_owned())
+            .await
+    }
+}
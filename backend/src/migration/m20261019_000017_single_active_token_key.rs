@@ -0,0 +1,74 @@
+use chrono::Utc;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::prelude::*;
+
+/// Allows only a single active token key, so concurrent rotations
+/// can't leave more than one key new tokens are signed with
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const SINGLE_ACTIVE_INDEX: &str = "idx-token_key-single_active";
+
+#[derive(Iden)]
+enum TokenKey {
+    Table,
+    Id,
+    Active,
+    CreatedAt,
+    RetiredAt,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Only the newest key stays active if earlier rotations left more than one
+        let now: DateTimeWithTimeZone = Utc::now().into();
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(TokenKey::Table)
+                    .value(TokenKey::Active, false)
+                    .value(TokenKey::RetiredAt, now)
+                    .and_where(Expr::col(TokenKey::Active).eq(true))
+                    .and_where(
+                        Expr::col(TokenKey::Id).not_in_subquery(
+                            Query::select()
+                                .column(TokenKey::Id)
+                                .from(TokenKey::Table)
+                                .and_where(Expr::col(TokenKey::Active).eq(true))
+                                .order_by(TokenKey::CreatedAt, Order::Desc)
+                                .limit(1)
+                                .to_owned(),
+                        ),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Partial indexes can't be created using the schema builder
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                r#"CREATE UNIQUE INDEX "{}" ON "{}" ("{}") WHERE "{}""#,
+                SINGLE_ACTIVE_INDEX,
+                TokenKey::Table.to_string(),
+                TokenKey::Active.to_string(),
+                TokenKey::Active.to_string()
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(SINGLE_ACTIVE_INDEX)
+                    .table(TokenKey::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
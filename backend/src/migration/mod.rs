@@ -0,0 +1,258 @@
+mod m20261019_000001_initial_schema;
+mod m20261019_000002_certificate_validity;
+mod m20261019_000003_notifications;
+mod m20261019_000004_webhooks;
+mod m20261019_000005_audit_log;
+mod m20261019_000006_issuance_log;
+mod m20261019_000007_quotas;
+mod m20261019_000008_approvals;
+mod m20261019_000009_signing_request_owners;
+mod m20261019_000010_token_scopes;
+mod m20261019_000011_token_keys;
+mod m20261019_000012_enrollment_tokens;
+mod m20261019_000013_token_usage;
+mod m20261019_000014_teams;
+mod m20261019_000015_used_signatures;
+mod m20261019_000016_log_nodes;
+mod m20261019_000017_single_active_token_key;
+
+use log::info;
+use sea_orm::DatabaseConnection;
+use sea_orm_migration::MigratorTrait;
+use std::error::Error;
+
+pub use sea_orm_migration::MigrationTrait;
+
+/// The versioned migrations of the database schema.
+/// New migrations must be appended to the list, existing ones must never be changed.
+pub struct Migrator;
+
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(m20261019_000001_initial_schema::Migration),
+            Box::new(m20261019_000002_certificate_validity::Migration),
+            Box::new(m20261019_000003_notifications::Migration),
+            Box::new(m20261019_000004_webhooks::Migration),
+            Box::new(m20261019_000005_audit_log::Migration),
+            Box::new(m20261019_000006_issuance_log::Migration),
+            Box::new(m20261019_000007_quotas::Migration),
+            Box::new(m20261019_000008_approvals::Migration),
+            Box::new(m20261019_000009_signing_request_owners::Migration),
+            Box::new(m20261019_000010_token_scopes::Migration),
+            Box::new(m20261019_000011_token_keys::Migration),
+            Box::new(m20261019_000012_enrollment_tokens::Migration),
+            Box::new(m20261019_000013_token_usage::Migration),
+            Box::new(m20261019_000014_teams::Migration),
+            Box::new(m20261019_000015_used_signatures::Migration),
+            Box::new(m20261019_000016_log_nodes::Migration),
+            Box::new(m20261019_000017_single_active_token_key::Migration),
+        ]
+    }
+}
+
+/// Run the `migrate` subcommand.
+///
+/// Supported commands are `up [steps]`, `down [steps]` and `status`.
+/// `up` applies all pending migrations and `down` reverts the last one
+/// if no number of steps is given.
+pub async fn run_command(db: &DatabaseConnection, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let steps = args.get(1).map(|s| s.parse::<u32>()).transpose()?;
+
+    match args.first().map(String::as_str).unwrap_or("status") {
+        "up" => {
+            info!("Applying migrations");
+            Migrator::up(db, steps).await?;
+        }
+        "down" => {
+            info!("Reverting migrations");
+            Migrator::down(db, Some(steps.unwrap_or(1))).await?;
+        }
+        "status" => Migrator::status(db).await?,
+        command => {
+            return Err(format!(
+                "Unknown migrate command '{}', expected one of: up, down, status",
+                command
+            )
+            .into())
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Migrator;
+    use crate::entity::{
+        approval_request, audit_event, certificate, client, enrollment_token, log_entry, log_head,
+        log_node, notification, quota, root_certificate, signing_request, team, team_member, token,
+        token_key, used_signature, user, webhook_delivery, webhook_subscription,
+    };
+    use chrono::{Duration, Utc};
+    use sea_orm::{
+        ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbErr, EntityTrait,
+    };
+    use sea_orm_migration::prelude::{Alias, Query};
+    use sea_orm_migration::MigratorTrait;
+    use uuid::Uuid;
+
+    async fn connect() -> DatabaseConnection {
+        // Every connection opens a new in-memory database
+        let mut opts = ConnectOptions::new("sqlite::memory:".to_string());
+        opts.max_connections(1).min_connections(1);
+        Database::connect(opts).await.unwrap()
+    }
+
+    /// Query all entities to make sure the schema contains all of their columns
+    async fn query_entities(db: &DatabaseConnection) -> Result<(), DbErr> {
+        user::Entity::find().all(db).await?;
+        client::Entity::find().all(db).await?;
+        signing_request::Entity::find().all(db).await?;
+        approval_request::Entity::find().all(db).await?;
+        token::Entity::find().all(db).await?;
+        token_key::Entity::find().all(db).await?;
+        enrollment_token::Entity::find().all(db).await?;
+        certificate::Entity::find().all(db).await?;
+        root_certificate::Entity::find().all(db).await?;
+        notification::Entity::find().all(db).await?;
+        webhook_subscription::Entity::find().all(db).await?;
+        webhook_delivery::Entity::find().all(db).await?;
+        audit_event::Entity::find().all(db).await?;
+        log_entry::Entity::find().all(db).await?;
+        log_head::Entity::find().all(db).await?;
+        log_node::Entity::find().all(db).await?;
+        quota::Entity::find().all(db).await?;
+        team::Entity::find().all(db).await?;
+        team_member::Entity::find().all(db).await?;
+        used_signature::Entity::find().all(db).await?;
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn creates_the_schema_of_all_entities() {
+        let db = connect().await;
+        Migrator::up(&db, None).await.unwrap();
+
+        query_entities(&db).await.unwrap();
+        assert_eq!(
+            log_head::Entity::find().all(&db).await.unwrap(),
+            vec![log_head::Model {
+                id: 1,
+                tree_size: 0
+            }]
+        );
+    }
+
+    #[actix_web::test]
+    async fn reverts_all_migrations() {
+        let db = connect().await;
+        Migrator::up(&db, None).await.unwrap();
+        Migrator::down(&db, None).await.unwrap();
+
+        assert!(Migrator::get_applied_migrations(&db)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(query_entities(&db).await.is_err());
+
+        Migrator::up(&db, None).await.unwrap();
+        query_entities(&db).await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn keeps_certificates_issued_before_the_migrations() {
+        let db = connect().await;
+        Migrator::up(&db, Some(1)).await.unwrap();
+
+        let now = Utc::now();
+        let user_id = Uuid::new_v4();
+        let client_id = Uuid::new_v4();
+        let builder = db.get_database_backend();
+        for query in [
+            Query::insert()
+                .into_table(Alias::new("user"))
+                .columns(
+                    [
+                        "id",
+                        "name",
+                        "original_name",
+                        "external_id",
+                        "active",
+                        "created_at",
+                        "updated_at",
+                    ]
+                    .map(Alias::new),
+                )
+                .values_panic([
+                    user_id.into(),
+                    "user".into(),
+                    "user".into(),
+                    "external".into(),
+                    true.into(),
+                    now.into(),
+                    now.into(),
+                ])
+                .to_owned(),
+            Query::insert()
+                .into_table(Alias::new("client"))
+                .columns(
+                    [
+                        "id",
+                        "user_id",
+                        "name",
+                        "original_name",
+                        "active",
+                        "valid_until",
+                        "created_at",
+                        "updated_at",
+                    ]
+                    .map(Alias::new),
+                )
+                .values_panic([
+                    client_id.into(),
+                    user_id.into(),
+                    "client".into(),
+                    "client".into(),
+                    true.into(),
+                    now.into(),
+                    now.into(),
+                    now.into(),
+                ])
+                .to_owned(),
+            Query::insert()
+                .into_table(Alias::new("signing_request"))
+                .columns(
+                    [
+                        "client_id",
+                        "hash",
+                        "serial_number",
+                        "subject_name",
+                        "issued_at",
+                    ]
+                    .map(Alias::new),
+                )
+                .values_panic([
+                    client_id.into(),
+                    "hash".into(),
+                    "01".into(),
+                    "client".into(),
+                    now.into(),
+                ])
+                .to_owned(),
+        ] {
+            db.execute(builder.build(&query)).await.unwrap();
+        }
+
+        Migrator::up(&db, None).await.unwrap();
+
+        let certificates = signing_request::Entity::find().all(&db).await.unwrap();
+        assert_eq!(certificates.len(), 1);
+        assert_eq!(certificates[0].client_id, Some(client_id));
+        assert_eq!(
+            certificates[0].valid_until,
+            certificates[0].issued_at + Duration::days(31)
+        );
+        assert_eq!(certificates[0].revoked_at, None);
+    }
+}
@@ -0,0 +1,60 @@
+use crate::entity::approval_request;
+use crate::entity::approval_request::ApprovalStatus;
+use crate::util::traits::from_model::FromModel;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ApprovalRequestDto {
+    /// The id of the approval request
+    pub id: i32,
+    /// The id of the client which submitted the request
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    /// The id of the user who submitted the request on behalf of the client
+    #[serde(rename = "requestedBy", skip_serializing_if = "Option::is_none")]
+    pub requested_by: Option<String>,
+    /// The certificate signing request in PEM format
+    pub request: String,
+    /// The common name of the requested certificate
+    #[serde(rename = "subjectName")]
+    pub subject_name: String,
+    /// The requested alternative names
+    #[serde(rename = "alternativeNames")]
+    pub alternative_names: Vec<String>,
+    pub status: ApprovalStatus,
+    /// The time the request was submitted
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    /// The id of the admin who approved or rejected the request
+    #[serde(rename = "decidedBy", skip_serializing_if = "Option::is_none")]
+    pub decided_by: Option<String>,
+    /// The time the request was approved or rejected
+    #[serde(rename = "decidedAt", skip_serializing_if = "Option::is_none")]
+    pub decided_at: Option<String>,
+    /// The reason the request was rejected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// The id of the issued certificate
+    #[serde(rename = "signingRequestId", skip_serializing_if = "Option::is_none")]
+    pub signing_request_id: Option<i32>,
+}
+
+impl FromModel<approval_request::Model> for ApprovalRequestDto {
+    fn from_model(model: approval_request::Model) -> Self {
+        Self {
+            id: model.id,
+            client_id: model.client_id.to_string(),
+            requested_by: model.requested_by.map(|id| id.to_string()),
+            alternative_names: model.alternative_names().unwrap_or_default(),
+            request: model.request,
+            subject_name: model.subject_name,
+            status: model.status,
+            created_at: model.created_at.to_rfc3339(),
+            decided_by: model.decided_by.map(|id| id.to_string()),
+            decided_at: model.decided_at.map(|d| d.to_rfc3339()),
+            reason: model.reason,
+            signing_request_id: model.signing_request_id,
+        }
+    }
+}
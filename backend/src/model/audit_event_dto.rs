@@ -0,0 +1,64 @@
+use crate::entity::audit_event;
+use crate::entity::audit_event::{AuditAction, AuditActorType, AuditOutcome};
+use crate::util::traits::from_model::FromModel;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuditEventDto {
+    pub id: i32,
+    /// The time the action was performed
+    #[serde(rename = "occurredAt")]
+    pub occurred_at: String,
+    /// The action which was performed
+    pub action: AuditAction,
+    /// The kind of actor which performed the action
+    #[serde(rename = "actorType")]
+    pub actor_type: AuditActorType,
+    /// The user id or client id of the actor
+    #[serde(rename = "actorId", skip_serializing_if = "Option::is_none")]
+    pub actor_id: Option<String>,
+    /// The user or client name of the actor
+    #[serde(rename = "actorName", skip_serializing_if = "Option::is_none")]
+    pub actor_name: Option<String>,
+    /// The id of the token used by a client
+    #[serde(rename = "tokenId", skip_serializing_if = "Option::is_none")]
+    pub token_id: Option<String>,
+    /// The object the action was performed on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Whether the action succeeded
+    pub outcome: AuditOutcome,
+    /// The http status of the response
+    #[serde(rename = "statusCode")]
+    pub status_code: i32,
+    /// The error message if the action failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// The address of the peer which sent the request
+    #[serde(rename = "sourceIp", skip_serializing_if = "Option::is_none")]
+    pub source_ip: Option<String>,
+    /// The client address reported by a proxy, if any
+    #[serde(rename = "forwardedFor", skip_serializing_if = "Option::is_none")]
+    pub forwarded_for: Option<String>,
+}
+
+impl FromModel<audit_event::Model> for AuditEventDto {
+    fn from_model(model: audit_event::Model) -> Self {
+        Self {
+            id: model.id,
+            occurred_at: model.occurred_at.to_rfc3339(),
+            action: model.action,
+            actor_type: model.actor_type,
+            actor_id: model.actor_id,
+            actor_name: model.actor_name,
+            token_id: model.token_id,
+            target: model.target,
+            outcome: model.outcome,
+            status_code: model.status_code,
+            details: model.details,
+            source_ip: model.source_ip,
+            forwarded_for: model.forwarded_for,
+        }
+    }
+}
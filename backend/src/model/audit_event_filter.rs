@@ -0,0 +1,14 @@
+use crate::entity::audit_event::{AuditAction, AuditOutcome};
+use sea_orm::prelude::DateTimeWithTimeZone;
+
+/// The criteria audit events can be filtered by.
+/// All criteria are optional and combined using a logical and.
+#[derive(Debug, Default, Clone)]
+pub struct AuditEventFilter {
+    pub action: Option<AuditAction>,
+    pub actor_id: Option<String>,
+    pub target: Option<String>,
+    pub outcome: Option<AuditOutcome>,
+    pub from: Option<DateTimeWithTimeZone>,
+    pub to: Option<DateTimeWithTimeZone>,
+}
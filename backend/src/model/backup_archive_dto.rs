@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Identifies a backup archive of this CA
+pub const BACKUP_FORMAT: &str = "ca-backup";
+/// The version of the backup format.
+/// Must be increased whenever the archive or its content changes in an incompatible way.
+pub const BACKUP_VERSION: u32 = 1;
+
+/// An encrypted backup of the entire CA state.
+/// The content is encrypted using AES-256-GCM with a key derived from
+/// the backup passphrase, which also protects it and the header against tampering.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BackupArchiveDto {
+    /// Always `ca-backup`
+    pub format: String,
+    /// The version of the backup format
+    pub version: u32,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    /// The key derivation function, always `pbkdf2-sha256`
+    pub kdf: String,
+    /// The number of key derivation iterations
+    pub iterations: u32,
+    /// The base64 encoded key derivation salt
+    pub salt: String,
+    /// The cipher the content is encrypted with, always `aes-256-gcm`
+    pub cipher: String,
+    /// The base64 encoded nonce
+    pub nonce: String,
+    /// The base64 encoded authentication tag
+    pub tag: String,
+    /// The base64 encoded, encrypted content
+    pub data: String,
+}
+
+impl BackupArchiveDto {
+    /// The header fields which are authenticated along with the content
+    pub fn associated_data(&self) -> Vec<u8> {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            self.format,
+            self.version,
+            self.created_at,
+            self.kdf,
+            self.iterations,
+            self.salt,
+            self.cipher
+        )
+        .into_bytes()
+    }
+}
@@ -0,0 +1,462 @@
+use crate::entity::team_member::TeamRole;
+use crate::entity::token_key::TokenKeyAlgorithm;
+use crate::entity::{
+    certificate, client, log_entry, root_certificate, signing_request, team, team_member, token,
+    token_key, user,
+};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::ActiveValue;
+use serde::{Deserialize, Serialize};
+use shared::util::types::BasicResult;
+use uuid::Uuid;
+
+fn format_date(date: &DateTimeWithTimeZone) -> String {
+    date.to_rfc3339()
+}
+
+fn parse_date(date: &str) -> BasicResult<DateTimeWithTimeZone> {
+    Ok(DateTimeWithTimeZone::parse_from_rfc3339(date)?)
+}
+
+fn parse_optional_date(date: &Option<String>) -> BasicResult<Option<DateTimeWithTimeZone>> {
+    date.as_deref().map(parse_date).transpose()
+}
+
+/// The state of the CA contained in a backup archive.
+/// The records are independent of the database entities
+/// so that the archive format only changes deliberately.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupData {
+    pub users: Vec<UserRecord>,
+    /// Missing in backups created before teams were introduced
+    #[serde(default)]
+    pub teams: Vec<TeamRecord>,
+    #[serde(default)]
+    pub team_members: Vec<TeamMemberRecord>,
+    pub clients: Vec<ClientRecord>,
+    /// The metadata of the client tokens, the tokens themselves are never stored
+    pub tokens: Vec<TokenRecord>,
+    pub token_keys: Vec<TokenKeyRecord>,
+    pub root_certificates: Vec<RootCertificateRecord>,
+    pub intermediate_certificates: Vec<IntermediateCertificateRecord>,
+    /// All issued certificates including their revocation status
+    pub signing_requests: Vec<SigningRequestRecord>,
+    pub log_entries: Vec<LogEntryRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserRecord {
+    pub id: Uuid,
+    pub name: String,
+    pub original_name: String,
+    pub external_id: Option<String>,
+    pub active: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<user::Model> for UserRecord {
+    fn from(model: user::Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            original_name: model.original_name,
+            external_id: model.external_id,
+            active: model.active,
+            created_at: format_date(&model.created_at),
+            updated_at: format_date(&model.updated_at),
+        }
+    }
+}
+
+impl UserRecord {
+    pub fn to_active_model(&self) -> BasicResult<user::ActiveModel> {
+        Ok(user::ActiveModel {
+            id: ActiveValue::Set(self.id),
+            name: ActiveValue::Set(self.name.clone()),
+            original_name: ActiveValue::Set(self.original_name.clone()),
+            external_id: ActiveValue::Set(self.external_id.clone()),
+            active: ActiveValue::Set(self.active),
+            created_at: ActiveValue::Set(parse_date(&self.created_at)?),
+            updated_at: ActiveValue::Set(parse_date(&self.updated_at)?),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamRecord {
+    pub id: Uuid,
+    pub name: String,
+    pub external_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<team::Model> for TeamRecord {
+    fn from(model: team::Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            external_id: model.external_id,
+            created_at: format_date(&model.created_at),
+            updated_at: format_date(&model.updated_at),
+        }
+    }
+}
+
+impl TeamRecord {
+    pub fn to_active_model(&self) -> BasicResult<team::ActiveModel> {
+        Ok(team::ActiveModel {
+            id: ActiveValue::Set(self.id),
+            name: ActiveValue::Set(self.name.clone()),
+            external_id: ActiveValue::Set(self.external_id.clone()),
+            created_at: ActiveValue::Set(parse_date(&self.created_at)?),
+            updated_at: ActiveValue::Set(parse_date(&self.updated_at)?),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamMemberRecord {
+    pub team_id: Uuid,
+    pub user_id: Uuid,
+    pub role: TeamRole,
+    pub created_at: String,
+}
+
+impl From<team_member::Model> for TeamMemberRecord {
+    fn from(model: team_member::Model) -> Self {
+        Self {
+            team_id: model.team_id,
+            user_id: model.user_id,
+            role: model.role,
+            created_at: format_date(&model.created_at),
+        }
+    }
+}
+
+impl TeamMemberRecord {
+    /// Convert the record, the membership is assigned to the given team and user
+    pub fn to_active_model(
+        &self,
+        team_id: Uuid,
+        user_id: Uuid,
+    ) -> BasicResult<team_member::ActiveModel> {
+        Ok(team_member::ActiveModel {
+            team_id: ActiveValue::Set(team_id),
+            user_id: ActiveValue::Set(user_id),
+            role: ActiveValue::Set(self.role),
+            created_at: ActiveValue::Set(parse_date(&self.created_at)?),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientRecord {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[serde(default)]
+    pub team_id: Option<Uuid>,
+    pub name: String,
+    pub original_name: String,
+    pub active: bool,
+    pub requires_approval: bool,
+    pub valid_until: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<client::Model> for ClientRecord {
+    fn from(model: client::Model) -> Self {
+        Self {
+            id: model.id,
+            user_id: model.user_id,
+            team_id: model.team_id,
+            name: model.name,
+            original_name: model.original_name,
+            active: model.active,
+            requires_approval: model.requires_approval,
+            valid_until: format_date(&model.valid_until),
+            created_at: format_date(&model.created_at),
+            updated_at: format_date(&model.updated_at),
+        }
+    }
+}
+
+impl ClientRecord {
+    /// Convert the record, the client is assigned to the given user and team
+    pub fn to_active_model(
+        &self,
+        user_id: Uuid,
+        team_id: Option<Uuid>,
+    ) -> BasicResult<client::ActiveModel> {
+        Ok(client::ActiveModel {
+            id: ActiveValue::Set(self.id),
+            user_id: ActiveValue::Set(user_id),
+            team_id: ActiveValue::Set(team_id),
+            name: ActiveValue::Set(self.name.clone()),
+            original_name: ActiveValue::Set(self.original_name.clone()),
+            active: ActiveValue::Set(self.active),
+            requires_approval: ActiveValue::Set(self.requires_approval),
+            valid_until: ActiveValue::Set(parse_date(&self.valid_until)?),
+            created_at: ActiveValue::Set(parse_date(&self.created_at)?),
+            updated_at: ActiveValue::Set(parse_date(&self.updated_at)?),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenRecord {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub token_hash: String,
+    pub active: bool,
+    pub scopes: Option<String>,
+    pub last_used_at: Option<String>,
+    pub last_used_from: Option<String>,
+    pub revoked_at: Option<String>,
+}
+
+impl From<token::Model> for TokenRecord {
+    fn from(model: token::Model) -> Self {
+        Self {
+            id: model.id,
+            client_id: model.client_id,
+            token_hash: model.token_hash,
+            active: model.active,
+            scopes: model.scopes,
+            last_used_at: model.last_used_at.as_ref().map(format_date),
+            last_used_from: model.last_used_from,
+            revoked_at: model.revoked_at.as_ref().map(format_date),
+        }
+    }
+}
+
+impl TokenRecord {
+    pub fn to_active_model(&self) -> BasicResult<token::ActiveModel> {
+        Ok(token::ActiveModel {
+            id: ActiveValue::Set(self.id),
+            client_id: ActiveValue::Set(self.client_id),
+            token_hash: ActiveValue::Set(self.token_hash.clone()),
+            active: ActiveValue::Set(self.active),
+            scopes: ActiveValue::Set(self.scopes.clone()),
+            last_used_at: ActiveValue::Set(parse_optional_date(&self.last_used_at)?),
+            last_used_from: ActiveValue::Set(self.last_used_from.clone()),
+            revoked_at: ActiveValue::Set(parse_optional_date(&self.revoked_at)?),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenKeyRecord {
+    pub id: Uuid,
+    pub algorithm: TokenKeyAlgorithm,
+    /// The PKCS#8 encoded private key in PEM format
+    pub private_key: String,
+    pub public_key: String,
+    pub active: bool,
+    pub created_at: String,
+    pub retired_at: Option<String>,
+}
+
+impl From<token_key::Model> for TokenKeyRecord {
+    fn from(model: token_key::Model) -> Self {
+        Self {
+            id: model.id,
+            algorithm: model.algorithm,
+            private_key: model.private_key,
+            public_key: model.public_key,
+            active: model.active,
+            created_at: format_date(&model.created_at),
+            retired_at: model.retired_at.as_ref().map(format_date),
+        }
+    }
+}
+
+impl TokenKeyRecord {
+    pub fn to_active_model(&self) -> BasicResult<token_key::ActiveModel> {
+        Ok(token_key::ActiveModel {
+            id: ActiveValue::Set(self.id),
+            algorithm: ActiveValue::Set(self.algorithm),
+            private_key: ActiveValue::Set(self.private_key.clone()),
+            public_key: ActiveValue::Set(self.public_key.clone()),
+            active: ActiveValue::Set(self.active),
+            created_at: ActiveValue::Set(parse_date(&self.created_at)?),
+            retired_at: ActiveValue::Set(parse_optional_date(&self.retired_at)?),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RootCertificateRecord {
+    pub id: i32,
+    /// The certificate in PEM format
+    pub certificate: String,
+    pub active: bool,
+    pub created_by: Uuid,
+    pub valid_until: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl RootCertificateRecord {
+    pub fn from_model(model: root_certificate::Model) -> BasicResult<Self> {
+        Ok(Self {
+            id: model.id,
+            certificate: String::from_utf8(model.public)?,
+            active: model.active,
+            created_by: model.created_by,
+            valid_until: format_date(&model.valid_until),
+            created_at: format_date(&model.created_at),
+            updated_at: format_date(&model.updated_at),
+        })
+    }
+
+    /// Convert the record, the certificate is attributed to the given user
+    pub fn to_active_model(&self, created_by: Uuid) -> BasicResult<root_certificate::ActiveModel> {
+        Ok(root_certificate::ActiveModel {
+            id: ActiveValue::Set(self.id),
+            public: ActiveValue::Set(self.certificate.as_bytes().to_vec()),
+            active: ActiveValue::Set(self.active),
+            created_by: ActiveValue::Set(created_by),
+            valid_until: ActiveValue::Set(parse_date(&self.valid_until)?),
+            created_at: ActiveValue::Set(parse_date(&self.created_at)?),
+            updated_at: ActiveValue::Set(parse_date(&self.updated_at)?),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntermediateCertificateRecord {
+    pub id: i32,
+    /// The certificate in PEM format
+    pub certificate: String,
+    /// The key pair in PEM format, only kept for the active intermediate
+    pub private_key: Option<String>,
+    pub active: bool,
+    pub valid_until: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl IntermediateCertificateRecord {
+    pub fn from_model(model: certificate::Model) -> BasicResult<Self> {
+        Ok(Self {
+            id: model.id,
+            certificate: String::from_utf8(model.public)?,
+            private_key: model.private.map(String::from_utf8).transpose()?,
+            active: model.active,
+            valid_until: format_date(&model.valid_until),
+            created_at: format_date(&model.created_at),
+            updated_at: format_date(&model.updated_at),
+        })
+    }
+
+    pub fn to_active_model(&self) -> BasicResult<certificate::ActiveModel> {
+        Ok(certificate::ActiveModel {
+            id: ActiveValue::Set(self.id),
+            public: ActiveValue::Set(self.certificate.as_bytes().to_vec()),
+            private: ActiveValue::Set(self.private_key.as_ref().map(|k| k.as_bytes().to_vec())),
+            active: ActiveValue::Set(self.active),
+            valid_until: ActiveValue::Set(parse_date(&self.valid_until)?),
+            created_at: ActiveValue::Set(parse_date(&self.created_at)?),
+            updated_at: ActiveValue::Set(parse_date(&self.updated_at)?),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningRequestRecord {
+    pub id: i32,
+    pub client_id: Option<Uuid>,
+    pub requested_by: Option<Uuid>,
+    pub hash: String,
+    pub serial_number: String,
+    pub subject_name: String,
+    pub alternative_names: Option<String>,
+    pub issued_at: String,
+    pub valid_until: String,
+    pub revoked_at: Option<String>,
+}
+
+impl From<signing_request::Model> for SigningRequestRecord {
+    fn from(model: signing_request::Model) -> Self {
+        Self {
+            id: model.id,
+            client_id: model.client_id,
+            requested_by: model.requested_by,
+            hash: model.hash,
+            serial_number: model.serial_number,
+            subject_name: model.subject_name,
+            alternative_names: model.alternative_names,
+            issued_at: format_date(&model.issued_at),
+            valid_until: format_date(&model.valid_until),
+            revoked_at: model.revoked_at.as_ref().map(format_date),
+        }
+    }
+}
+
+impl SigningRequestRecord {
+    /// Convert the record, the request is attributed to the given user
+    pub fn to_active_model(
+        &self,
+        requested_by: Option<Uuid>,
+    ) -> BasicResult<signing_request::ActiveModel> {
+        Ok(signing_request::ActiveModel {
+            id: ActiveValue::Set(self.id),
+            client_id: ActiveValue::Set(self.client_id),
+            requested_by: ActiveValue::Set(requested_by),
+            hash: ActiveValue::Set(self.hash.clone()),
+            serial_number: ActiveValue::Set(self.serial_number.clone()),
+            subject_name: ActiveValue::Set(self.subject_name.clone()),
+            alternative_names: ActiveValue::Set(self.alternative_names.clone()),
+            issued_at: ActiveValue::Set(parse_date(&self.issued_at)?),
+            valid_until: ActiveValue::Set(parse_date(&self.valid_until)?),
+            revoked_at: ActiveValue::Set(parse_optional_date(&self.revoked_at)?),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntryRecord {
+    pub leaf_index: i64,
+    pub signing_request_id: i32,
+    pub serial_number: String,
+    pub leaf_hash: String,
+    pub created_at: String,
+}
+
+impl From<log_entry::Model> for LogEntryRecord {
+    fn from(model: log_entry::Model) -> Self {
+        Self {
+            leaf_index: model.leaf_index,
+            signing_request_id: model.signing_request_id,
+            serial_number: model.serial_number,
+            leaf_hash: model.leaf_hash,
+            created_at: format_date(&model.created_at),
+        }
+    }
+}
+
+impl LogEntryRecord {
+    pub fn to_active_model(&self) -> BasicResult<log_entry::ActiveModel> {
+        Ok(log_entry::ActiveModel {
+            leaf_index: ActiveValue::Set(self.leaf_index),
+            signing_request_id: ActiveValue::Set(self.signing_request_id),
+            serial_number: ActiveValue::Set(self.serial_number.clone()),
+            leaf_hash: ActiveValue::Set(self.leaf_hash.clone()),
+            created_at: ActiveValue::Set(parse_date(&self.created_at)?),
+        })
+    }
+}
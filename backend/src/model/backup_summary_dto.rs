@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The number of records contained in a restored backup
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct BackupSummaryDto {
+    pub users: usize,
+    pub teams: usize,
+    pub clients: usize,
+    pub tokens: usize,
+    #[serde(rename = "tokenKeys")]
+    pub token_keys: usize,
+    #[serde(rename = "rootCertificates")]
+    pub root_certificates: usize,
+    #[serde(rename = "intermediateCertificates")]
+    pub intermediate_certificates: usize,
+    #[serde(rename = "signingRequests")]
+    pub signing_requests: usize,
+    #[serde(rename = "logEntries")]
+    pub log_entries: usize,
+}
@@ -1,5 +1,8 @@
 use crate::entity::{certificate, root_certificate};
+use crate::model::certificate_info_dto::CertificateInfoDto;
+use crate::util::certificate_info::certificate_info;
 use crate::util::traits::from_model::FromModel;
+use openssl::x509::X509;
 use serde::{Deserialize, Serialize};
 use shared::util::traits::u8_vec_to_string::U8VecToString;
 use utoipa::ToSchema;
@@ -21,6 +24,10 @@ pub struct CACertificateDto {
     /// and has just been created.
     #[serde(rename = "privateKey", skip_serializing_if = "Option::is_none")]
     pub private_key: Option<String>,
+    /// The decoded certificate metadata.
+    /// Not set if the certificate could not be decoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<CertificateInfoDto>,
 }
 
 impl FromModel<certificate::Model> for CACertificateDto {
@@ -31,6 +38,7 @@ impl FromModel<certificate::Model> for CACertificateDto {
             created_at: model.created_at.to_rfc3339(),
             root: false,
             private_key: None,
+            details: decode_details(&model.public),
         }
     }
 }
@@ -43,6 +51,13 @@ impl CACertificateDto {
             created_at: model.created_at.to_rfc3339(),
             root: true,
             private_key: private_key.map(|key| key.to_string()),
+            details: decode_details(&model.public),
         }
     }
 }
+
+fn decode_details(pem: &[u8]) -> Option<CertificateInfoDto> {
+    X509::from_pem(pem)
+        .ok()
+        .and_then(|cert| certificate_info(&cert).ok())
+}
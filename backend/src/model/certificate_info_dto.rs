@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FingerprintsDto {
+    /// The sha1 fingerprint of the DER encoded object
+    pub sha1: String,
+    /// The sha256 fingerprint of the DER encoded object
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExtensionDto {
+    /// The object identifier of the extension
+    #[schema(example = "2.5.29.15")]
+    pub oid: String,
+    /// The short name of the extension, if known
+    #[schema(example = "keyUsage")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Whether the extension is marked as critical
+    pub critical: bool,
+    /// A human readable representation of the extension value
+    pub value: String,
+}
+
+/// The decoded contents of a certificate or certificate signing request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CertificateInfoDto {
+    /// The subject distinguished name
+    #[schema(example = "CN=localhost, O=CA")]
+    pub subject: String,
+    /// The issuer distinguished name.
+    /// Not set for signing requests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    /// The serial number in hex.
+    /// Not set for signing requests.
+    #[serde(rename = "serialNumber", skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
+    /// The start of the validity period.
+    /// Not set for signing requests.
+    #[serde(rename = "notBefore", skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<String>,
+    /// The end of the validity period.
+    /// Not set for signing requests.
+    #[serde(rename = "notAfter", skip_serializing_if = "Option::is_none")]
+    pub not_after: Option<String>,
+    /// The algorithm of the public key
+    #[serde(rename = "keyAlgorithm")]
+    #[schema(example = "EC (prime256v1)")]
+    pub key_algorithm: String,
+    /// The size of the public key in bits
+    #[serde(rename = "keySize")]
+    #[schema(example = 256)]
+    pub key_size: u32,
+    /// The algorithm used to sign the object
+    #[serde(rename = "signatureAlgorithm")]
+    #[schema(example = "ecdsa-with-SHA256")]
+    pub signature_algorithm: String,
+    /// The subject alternative names
+    #[serde(rename = "alternativeNames")]
+    pub alternative_names: Vec<String>,
+    /// The allowed key usages
+    #[serde(rename = "keyUsage")]
+    pub key_usage: Vec<String>,
+    /// The allowed extended key usages
+    #[serde(rename = "extendedKeyUsage")]
+    pub extended_key_usage: Vec<String>,
+    /// Whether this is a CA certificate
+    #[serde(rename = "isCa")]
+    pub is_ca: bool,
+    /// All extensions of the object
+    pub extensions: Vec<ExtensionDto>,
+    /// The fingerprints of the object
+    pub fingerprints: FingerprintsDto,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RevokedCertificateDto {
+    /// The serial number of the revoked certificate in hex
+    #[serde(rename = "serialNumber")]
+    pub serial_number: String,
+    /// The time the certificate was revoked
+    #[serde(rename = "revokedAt")]
+    pub revoked_at: String,
+}
+
+/// The decoded contents of a certificate revocation list
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RevocationListInfoDto {
+    /// The issuer distinguished name
+    pub issuer: String,
+    /// The time the list was issued
+    #[serde(rename = "lastUpdate")]
+    pub last_update: String,
+    /// The time the next list will be issued
+    #[serde(rename = "nextUpdate", skip_serializing_if = "Option::is_none")]
+    pub next_update: Option<String>,
+    /// The algorithm used to sign the list
+    #[serde(rename = "signatureAlgorithm")]
+    pub signature_algorithm: String,
+    /// The certificates revoked by this list
+    pub revoked: Vec<RevokedCertificateDto>,
+    /// All extensions of the list
+    pub extensions: Vec<ExtensionDto>,
+    /// The fingerprints of the list
+    pub fingerprints: FingerprintsDto,
+}
@@ -1,4 +1,5 @@
 use crate::entity::{client, token};
+use crate::model::token_scope::TokenScope;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -11,9 +12,13 @@ pub struct ClientDto {
     /// The client display name
     #[serde(rename = "displayName")]
     pub display_name: String,
-    /// The id of the user that owns the client
+    /// The id of the user that owns the client. For clients owned by a team,
+    /// the user who created the client or last transferred it to the team.
     #[serde(rename = "userId")]
     pub user_id: String,
+    /// The id of the team that owns the client
+    #[serde(rename = "teamId", skip_serializing_if = "Option::is_none")]
+    pub team_id: Option<String>,
     /// The client token. Only returned when creating a new client.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub token: Option<String>,
@@ -22,6 +27,11 @@ pub struct ClientDto {
     pub token_hash: String,
     /// Whether the client is active
     pub active: bool,
+    /// Whether the signing requests of the client must be approved by an admin
+    #[serde(rename = "requiresApproval")]
+    pub requires_approval: bool,
+    /// The scopes of the client token
+    pub scopes: Vec<TokenScope>,
     /// The time the client is valid until
     #[serde(rename = "validUntil")]
     pub valid_until: String,
@@ -40,9 +50,12 @@ impl ClientDto {
             name: model.name,
             display_name: model.original_name,
             user_id: model.user_id.to_string(),
+            team_id: model.team_id.map(|id| id.to_string()),
             token: None,
+            scopes: token.scopes(),
             token_hash: token.token_hash,
             active: model.active,
+            requires_approval: model.requires_approval,
             valid_until: model.valid_until.to_rfc3339(),
             created_at: model.created_at.to_rfc3339(),
             updated_at: model.updated_at.to_rfc3339(),
@@ -59,9 +72,12 @@ impl ClientDto {
             name: model.name,
             display_name: model.original_name,
             user_id: model.user_id.to_string(),
+            team_id: model.team_id.map(|id| id.to_string()),
             token: Some(jwt_token),
+            scopes: token.scopes(),
             token_hash: token.token_hash,
             active: model.active,
+            requires_approval: model.requires_approval,
             valid_until: model.valid_until.to_rfc3339(),
             created_at: model.created_at.to_rfc3339(),
             updated_at: model.updated_at.to_rfc3339(),
@@ -0,0 +1,26 @@
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Copy, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ClientStatus {
+    /// The client is active and its token has not expired
+    Active,
+    /// The client is active but its token has expired
+    Expired,
+    /// The client has been disabled
+    Inactive,
+}
+
+/// The parsed filter criteria for searching clients
+#[derive(Debug, Clone, Default)]
+pub struct ClientFilter {
+    pub user_id: Option<Uuid>,
+    pub team_id: Option<Uuid>,
+    pub name: Option<String>,
+    pub status: Option<ClientStatus>,
+    pub expires_after: Option<DateTimeWithTimeZone>,
+    pub expires_before: Option<DateTimeWithTimeZone>,
+}
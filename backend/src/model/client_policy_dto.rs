@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ClientPolicyDto {
+    /// Whether the signing requests of the client must be approved by an admin
+    #[serde(rename = "requiresApproval")]
+    pub requires_approval: bool,
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateBackupDto {
+    /// The passphrase the backup is encrypted with
+    pub passphrase: String,
+}
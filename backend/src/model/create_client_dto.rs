@@ -1,3 +1,4 @@
+use crate::model::token_scope::TokenScope;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -9,4 +10,17 @@ pub struct CreateClientDto {
     #[serde(rename = "validUntil")]
     #[schema(example = "2025-01-01T00:00:00Z")]
     pub valid_until: String,
+    /// Whether the signing requests of the client must be approved by an admin.
+    /// Only used when creating a new client, defaults to false.
+    #[serde(rename = "requiresApproval", skip_serializing_if = "Option::is_none")]
+    pub requires_approval: Option<bool>,
+    /// The scopes of the client token.
+    /// Defaults to all scopes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<TokenScope>>,
+    /// The id of the team which should own the client.
+    /// Only used when creating a new client, the client is
+    /// a personal client of the user if not set.
+    #[serde(rename = "teamId", skip_serializing_if = "Option::is_none")]
+    pub team_id: Option<String>,
 }
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateEnrollmentTokenDto {
+    /// The number of minutes the token can be redeemed for.
+    /// Defaults to and may not exceed the configured maximum.
+    #[serde(rename = "validForMinutes", skip_serializing_if = "Option::is_none")]
+    pub valid_for_minutes: Option<i64>,
+}
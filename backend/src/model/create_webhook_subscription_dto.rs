@@ -0,0 +1,19 @@
+use crate::model::webhook_event::WebhookEvent;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateWebhookSubscriptionDto {
+    /// The url the events are posted to
+    #[schema(example = "https://example.com/webhook")]
+    pub url: String,
+    /// The events to subscribe to.
+    /// Subscribes to all events if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<WebhookEvent>>,
+    /// Whether to receive the events of all users.
+    /// Only admins may create global subscriptions.
+    /// Defaults to false.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub global: Option<bool>,
+}
@@ -0,0 +1,54 @@
+use crate::entity::enrollment_token;
+use crate::util::traits::from_model::FromModel;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EnrollmentTokenDto {
+    pub id: String,
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    /// The token, only returned when it is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// The id of the user who created the token
+    #[serde(rename = "createdBy")]
+    pub created_by: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: String,
+    /// The time the token was redeemed, not set if it is still unused
+    #[serde(rename = "redeemedAt", skip_serializing_if = "Option::is_none")]
+    pub redeemed_at: Option<String>,
+    /// The address the token was redeemed from
+    #[serde(rename = "redeemedFrom", skip_serializing_if = "Option::is_none")]
+    pub redeemed_from: Option<String>,
+    /// The serial number of the certificate issued for the token
+    #[serde(rename = "serialNumber", skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
+}
+
+impl EnrollmentTokenDto {
+    pub fn from_model_with_token(model: enrollment_token::Model, token: String) -> Self {
+        let mut dto = Self::from_model(model);
+        dto.token = Some(token);
+        dto
+    }
+}
+
+impl FromModel<enrollment_token::Model> for EnrollmentTokenDto {
+    fn from_model(model: enrollment_token::Model) -> Self {
+        Self {
+            id: model.id.to_string(),
+            client_id: model.client_id.to_string(),
+            token: None,
+            created_by: model.created_by.to_string(),
+            created_at: model.created_at.to_rfc3339(),
+            expires_at: model.expires_at.to_rfc3339(),
+            redeemed_at: model.redeemed_at.map(|d| d.to_rfc3339()),
+            redeemed_from: model.redeemed_from,
+            serial_number: model.serial_number,
+        }
+    }
+}
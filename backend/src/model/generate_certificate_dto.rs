@@ -0,0 +1,22 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+/// A request to issue a certificate for a key pair generated by the server
+#[derive(Deserialize, ToSchema, Debug)]
+pub struct GenerateCertificateDto {
+    /// The common name of the certificate
+    #[schema(example = "device-01.example.com")]
+    #[serde(rename = "subjectName")]
+    pub subject_name: String,
+    /// Alternative names for the certificate
+    #[serde(rename = "alternativeNames")]
+    pub alternative_names: Option<Vec<String>>,
+    /// The password the returned PKCS#12 archive is encrypted with
+    pub password: String,
+    /// The id of the client to issue the certificate to.
+    /// Only used if requested by a user, a personal certificate of the user
+    /// is issued if not set. The subject name of a personal certificate
+    /// must be the user's name and it can't have alternative names.
+    #[serde(rename = "clientId")]
+    pub client_id: Option<String>,
+}
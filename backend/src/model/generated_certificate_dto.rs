@@ -0,0 +1,14 @@
+use serde::Serialize;
+use shared::model::signing_request_dto::SigningRequestDto;
+use utoipa::ToSchema;
+
+/// A certificate issued for a key pair generated by the server
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GeneratedCertificateDto {
+    /// The issued certificate
+    pub certificate: SigningRequestDto,
+    /// The base64 encoded PKCS#12 archive containing the private key,
+    /// the certificate and the certificate chain.
+    /// The private key is not stored on the server.
+    pub pkcs12: String,
+}
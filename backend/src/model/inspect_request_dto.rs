@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InspectRequestDto {
+    /// A PEM encoded or base64 encoded DER certificate,
+    /// certificate signing request or certificate revocation list
+    pub data: String,
+}
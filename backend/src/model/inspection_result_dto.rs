@@ -0,0 +1,36 @@
+use crate::model::certificate_info_dto::{CertificateInfoDto, RevocationListInfoDto};
+use serde::{Deserialize, Serialize};
+use shared::model::certificate_status::CertificateStatus;
+use utoipa::ToSchema;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum InspectedObjectType {
+    Certificate,
+    SigningRequest,
+    RevocationList,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InspectionResultDto {
+    /// The type of the inspected object
+    #[serde(rename = "type")]
+    pub object_type: InspectedObjectType,
+    /// The decoded certificate or signing request.
+    /// Only set if a certificate or signing request was inspected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<CertificateInfoDto>,
+    /// The decoded revocation list.
+    /// Only set if a revocation list was inspected.
+    #[serde(rename = "revocationList", skip_serializing_if = "Option::is_none")]
+    pub revocation_list: Option<RevocationListInfoDto>,
+    /// Whether the certificate chains to a root certificate of this CA
+    /// or the revocation list was signed by this CA.
+    /// Not set for signing requests.
+    #[serde(rename = "issuedByCa", skip_serializing_if = "Option::is_none")]
+    pub issued_by_ca: Option<bool>,
+    /// The status of the certificate as recorded by this CA.
+    /// Only set if the certificate was issued by this CA.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<CertificateStatus>,
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The state of a token as defined in RFC 7662.
+/// Only `active` is set if the token is not active.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct IntrospectionDto {
+    /// Whether the token is valid and can be used
+    pub active: bool,
+    /// The scopes of the token, separated by spaces
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// The id of the client the token belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    /// The time the token expires at, in seconds since the unix epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<usize>,
+    /// The time the token was issued at, in seconds since the unix epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<usize>,
+    /// The id of the token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+}
+
+impl IntrospectionDto {
+    pub fn inactive() -> Self {
+        Self::default()
+    }
+}
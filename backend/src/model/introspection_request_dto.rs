@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A token introspection request as defined in RFC 7662
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IntrospectionRequestDto {
+    /// The client token to introspect
+    pub token: String,
+    /// A hint about the type of the token, ignored since only client tokens are supported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type_hint: Option<String>,
+}
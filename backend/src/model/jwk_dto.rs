@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A public key in the JSON Web Key format (RFC 7517)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JwkDto {
+    /// The key type, `EC` or `OKP`
+    pub kty: String,
+    /// The key id referenced by the `kid` header of tokens
+    pub kid: String,
+    /// The algorithm the key is used with
+    pub alg: String,
+    /// The intended use of the key, always `sig`
+    #[serde(rename = "use")]
+    pub key_use: String,
+    /// The curve of the key
+    pub crv: String,
+    /// The base64url encoded x coordinate or public key
+    pub x: String,
+    /// The base64url encoded y coordinate, only set for EC keys
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+}
+
+/// The keys client tokens are verified with
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JwksDto {
+    pub keys: Vec<JwkDto>,
+}
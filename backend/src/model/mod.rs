@@ -1,8 +1,50 @@
+pub mod approval_request_dto;
+pub mod audit_event_dto;
+pub mod audit_event_filter;
+pub mod backup_archive_dto;
+pub mod backup_data;
+pub mod backup_summary_dto;
 pub mod ca_certificate_dto;
+pub mod certificate_info_dto;
 pub mod client_dto;
+pub mod client_filter;
+pub mod client_policy_dto;
+pub mod create_backup_dto;
 pub mod create_client_dto;
+pub mod create_enrollment_token_dto;
+pub mod create_team_dto;
 pub mod create_user_dto;
+pub mod create_webhook_subscription_dto;
+pub mod enrollment_token_dto;
 pub mod error_dto;
+pub mod generate_certificate_dto;
 pub mod generate_intermediate_dto;
+pub mod generated_certificate_dto;
+pub mod inspect_request_dto;
+pub mod inspection_result_dto;
+pub mod introspection_dto;
+pub mod introspection_request_dto;
+pub mod jwk_dto;
+pub mod page_dto;
+pub mod quota_dto;
+pub mod reject_approval_dto;
+pub mod reset_password_dto;
+pub mod restore_backup_dto;
+pub mod revoke_token_dto;
+pub mod set_quota_dto;
+pub mod set_team_member_dto;
+pub mod signing_request_filter;
+pub mod team_dto;
 pub mod token_claims;
+pub mod token_dto;
+pub mod token_key_dto;
+pub mod token_scope;
+pub mod transfer_client_dto;
+pub mod update_user_dto;
 pub mod user_dto;
+pub mod user_signing_request_dto;
+pub mod verification_result_dto;
+pub mod verify_certificate_dto;
+pub mod webhook_delivery_dto;
+pub mod webhook_event;
+pub mod webhook_subscription_dto;
@@ -0,0 +1,47 @@
+use crate::model::approval_request_dto::ApprovalRequestDto;
+use crate::model::audit_event_dto::AuditEventDto;
+use crate::model::client_dto::ClientDto;
+use crate::model::token_dto::TokenDto;
+use crate::model::user_dto::UserDto;
+use crate::model::webhook_delivery_dto::WebhookDeliveryDto;
+use crate::util::pagination::Pagination;
+use serde::{Deserialize, Serialize};
+use shared::model::signing_request_dto::SigningRequestDto;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[aliases(
+    SigningRequestPageDto = PageDto<SigningRequestDto>,
+    ClientPageDto = PageDto<ClientDto>,
+    UserPageDto = PageDto<UserDto>,
+    WebhookDeliveryPageDto = PageDto<WebhookDeliveryDto>,
+    AuditEventPageDto = PageDto<AuditEventDto>,
+    ApprovalRequestPageDto = PageDto<ApprovalRequestDto>,
+    TokenPageDto = PageDto<TokenDto>
+)]
+pub struct PageDto<T> {
+    /// The elements on this page
+    pub items: Vec<T>,
+    /// The total number of elements matching the query
+    pub total: u64,
+    /// The zero-based index of this page
+    pub page: u64,
+    /// The maximum number of elements per page
+    #[serde(rename = "pageSize")]
+    pub page_size: u64,
+    /// The total number of pages
+    #[serde(rename = "totalPages")]
+    pub total_pages: u64,
+}
+
+impl<T> PageDto<T> {
+    pub fn new(items: Vec<T>, total: u64, pagination: &Pagination) -> Self {
+        Self {
+            items,
+            total,
+            page: pagination.page,
+            page_size: pagination.page_size,
+            total_pages: total.div_ceil(pagination.page_size),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::entity::quota;
+use crate::entity::quota::QuotaScope;
+use crate::util::traits::from_model::FromModel;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QuotaDto {
+    /// The subject the quota applies to
+    pub scope: QuotaScope,
+    /// The id of the user or client, not set for the global quota
+    #[serde(rename = "subjectId", skip_serializing_if = "Option::is_none")]
+    pub subject_id: Option<String>,
+    /// The number of certificates which may be issued per rate limit window
+    #[serde(rename = "issuanceLimit", skip_serializing_if = "Option::is_none")]
+    pub issuance_limit: Option<i32>,
+    /// The number of active clients a user may own
+    #[serde(rename = "maxClients", skip_serializing_if = "Option::is_none")]
+    pub max_clients: Option<i32>,
+    /// The number of valid certificates a client may have
+    #[serde(
+        rename = "maxValidCertificates",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_valid_certificates: Option<i32>,
+    /// The time the quota was last changed
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+}
+
+impl FromModel<quota::Model> for QuotaDto {
+    fn from_model(model: quota::Model) -> Self {
+        Self {
+            scope: model.scope,
+            subject_id: (model.scope != QuotaScope::Global).then(|| model.subject_id.to_string()),
+            issuance_limit: model.issuance_limit,
+            max_clients: model.max_clients,
+            max_valid_certificates: model.max_valid_certificates,
+            updated_at: model.updated_at.to_rfc3339(),
+        }
+    }
+}
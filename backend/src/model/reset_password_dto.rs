@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ResetPasswordDto {
+    /// The new password of the user. If not set, the user is sent
+    /// an email asking them to set a new password.
+    pub password: Option<String>,
+    /// Whether the user must change the password on the next login.
+    /// Defaults to true.
+    #[serde(rename = "isPasswordTemporary")]
+    pub is_password_temporary: Option<bool>,
+}
@@ -0,0 +1,10 @@
+use crate::model::backup_archive_dto::BackupArchiveDto;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RestoreBackupDto {
+    /// The passphrase the backup was encrypted with
+    pub passphrase: String,
+    pub archive: BackupArchiveDto,
+}
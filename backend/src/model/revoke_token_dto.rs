@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Identifies the token to revoke, either by its id or by its hash
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RevokeTokenDto {
+    /// The id of the token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The SHA-256 hash of the token as returned in `tokenHash`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
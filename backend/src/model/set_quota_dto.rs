@@ -0,0 +1,29 @@
+use crate::entity::quota::QuotaScope;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SetQuotaDto {
+    /// The subject the quota applies to
+    pub scope: QuotaScope,
+    /// The id of the user or client.
+    /// Must not be set for the global quota.
+    #[serde(rename = "subjectId", skip_serializing_if = "Option::is_none")]
+    pub subject_id: Option<String>,
+    /// The number of certificates which may be issued per rate limit window.
+    /// Falls back to the configured limit if not set, 0 disables the limit.
+    #[schema(example = 10)]
+    #[serde(rename = "issuanceLimit", skip_serializing_if = "Option::is_none")]
+    pub issuance_limit: Option<i32>,
+    /// The number of active clients a user may own.
+    /// Falls back to the global quota if not set, 0 disables the limit.
+    #[serde(rename = "maxClients", skip_serializing_if = "Option::is_none")]
+    pub max_clients: Option<i32>,
+    /// The number of valid certificates a client may have.
+    /// Falls back to the global quota if not set, 0 disables the limit.
+    #[serde(
+        rename = "maxValidCertificates",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_valid_certificates: Option<i32>,
+}
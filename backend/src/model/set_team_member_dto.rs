@@ -0,0 +1,9 @@
+use crate::entity::team_member::TeamRole;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SetTeamMemberDto {
+    /// The role of the user in the team
+    pub role: TeamRole,
+}
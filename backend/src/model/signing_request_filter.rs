@@ -0,0 +1,56 @@
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::Deserialize;
+use shared::model::certificate_status::CertificateStatus;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Copy, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SigningRequestSortField {
+    IssuedAt,
+    ValidUntil,
+    SubjectName,
+    SerialNumber,
+}
+
+#[derive(Debug, Copy, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// The parsed filter criteria for searching signing requests
+#[derive(Debug, Clone, Default)]
+pub struct SigningRequestFilter {
+    pub subject_name: Option<String>,
+    pub alternative_name: Option<String>,
+    /// The serial number in upper case hex without separators
+    pub serial_number: Option<String>,
+    /// The sha256 fingerprint in lower case hex separated by colons
+    pub fingerprint: Option<String>,
+    pub client_id: Option<Uuid>,
+    pub issued_after: Option<DateTimeWithTimeZone>,
+    pub issued_before: Option<DateTimeWithTimeZone>,
+    pub expires_after: Option<DateTimeWithTimeZone>,
+    pub expires_before: Option<DateTimeWithTimeZone>,
+    pub status: Option<CertificateStatus>,
+    pub sort_by: Option<SigningRequestSortField>,
+    pub sort_order: Option<SortOrder>,
+}
+
+impl SigningRequestFilter {
+    pub fn normalize_serial_number(serial_number: &str) -> String {
+        serial_number.replace(':', "").to_uppercase()
+    }
+
+    pub fn normalize_fingerprint(fingerprint: &str) -> String {
+        let fingerprint = fingerprint.replace(':', "").to_lowercase();
+        fingerprint
+            .as_bytes()
+            .chunks(2)
+            .map(|c| String::from_utf8_lossy(c).to_string())
+            .collect::<Vec<_>>()
+            .join(":")
+    }
+}
@@ -0,0 +1,55 @@
+use crate::entity::team_member::TeamRole;
+use crate::entity::{team, team_member};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TeamMemberDto {
+    /// The id of the user
+    #[serde(rename = "userId")]
+    pub user_id: String,
+    /// The role of the user in the team
+    pub role: TeamRole,
+    /// The time the user joined the team
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TeamDto {
+    /// The team id
+    pub id: String,
+    /// The team name
+    pub name: String,
+    /// The id of the identity provider group the team is synced from.
+    /// The members of teams without a group are managed locally.
+    #[serde(rename = "externalId", skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    pub members: Vec<TeamMemberDto>,
+    /// The time the team was created
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    /// The time the team was last updated
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+}
+
+impl TeamDto {
+    pub fn from_model(model: team::Model, members: Vec<team_member::Model>) -> Self {
+        Self {
+            id: model.id.to_string(),
+            name: model.name,
+            external_id: model.external_id,
+            members: members
+                .into_iter()
+                .map(|m| TeamMemberDto {
+                    user_id: m.user_id.to_string(),
+                    role: m.role,
+                    created_at: m.created_at.to_rfc3339(),
+                })
+                .collect(),
+            created_at: model.created_at.to_rfc3339(),
+            updated_at: model.updated_at.to_rfc3339(),
+        }
+    }
+}
@@ -5,4 +5,8 @@ pub struct TokenClaims {
     pub sub: String,
     pub iat: usize,
     pub exp: usize,
+    /// The scopes of the token, separated by spaces.
+    /// Not set if the token may use all scopes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
 }
@@ -0,0 +1,44 @@
+use crate::entity::token;
+use crate::model::token_scope::TokenScope;
+use crate::util::traits::from_model::FromModel;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A client token, the token itself is never returned
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TokenDto {
+    pub id: String,
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    /// The SHA-256 hash of the token
+    #[serde(rename = "tokenHash")]
+    pub token_hash: String,
+    /// Whether the token can be used, tokens are deactivated
+    /// when they are revoked or replaced by a new token
+    pub active: bool,
+    pub scopes: Vec<TokenScope>,
+    /// The time the token was last used to authenticate a request
+    #[serde(rename = "lastUsedAt", skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<String>,
+    /// The address the token was last used from
+    #[serde(rename = "lastUsedFrom", skip_serializing_if = "Option::is_none")]
+    pub last_used_from: Option<String>,
+    /// The time the token was revoked by an admin
+    #[serde(rename = "revokedAt", skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<String>,
+}
+
+impl FromModel<token::Model> for TokenDto {
+    fn from_model(model: token::Model) -> Self {
+        Self {
+            id: model.id.to_string(),
+            client_id: model.client_id.to_string(),
+            scopes: model.scopes(),
+            token_hash: model.token_hash,
+            active: model.active,
+            last_used_at: model.last_used_at.map(|d| d.to_rfc3339()),
+            last_used_from: model.last_used_from,
+            revoked_at: model.revoked_at.map(|d| d.to_rfc3339()),
+        }
+    }
+}
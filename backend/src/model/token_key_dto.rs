@@ -0,0 +1,36 @@
+use crate::entity::token_key;
+use crate::entity::token_key::TokenKeyAlgorithm;
+use crate::util::traits::from_model::FromModel;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A key client tokens are signed with, the private key is never returned
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TokenKeyDto {
+    /// The key id referenced by the `kid` header of tokens
+    pub kid: String,
+    pub algorithm: TokenKeyAlgorithm,
+    /// The public key in PEM format
+    #[serde(rename = "publicKey")]
+    pub public_key: String,
+    /// Whether new tokens are signed with this key
+    pub active: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    /// The time a newer key replaced this key
+    #[serde(rename = "retiredAt", skip_serializing_if = "Option::is_none")]
+    pub retired_at: Option<String>,
+}
+
+impl FromModel<token_key::Model> for TokenKeyDto {
+    fn from_model(model: token_key::Model) -> Self {
+        Self {
+            kid: model.id.to_string(),
+            algorithm: model.algorithm,
+            public_key: model.public_key,
+            active: model.active,
+            created_at: model.created_at.to_rfc3339(),
+            retired_at: model.retired_at.map(|d| d.to_rfc3339()),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A permission of a client token.
+/// Scopes restrict the operations a token may be used for. The CA has no
+/// certificate profiles, so there are no profiles a token could be limited to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum TokenScope {
+    /// Sign any certificate signing request
+    #[serde(rename = "sign")]
+    Sign,
+    /// Only sign requests renewing a valid certificate of the client,
+    /// i.e. with the same subject and alternative names
+    #[serde(rename = "renew-only")]
+    RenewOnly,
+    /// Issue certificates for key pairs generated by the server
+    #[serde(rename = "generate")]
+    Generate,
+    /// Read the status of the client's signing requests
+    #[serde(rename = "read")]
+    Read,
+}
+
+impl TokenScope {
+    /// The scopes of tokens which were created without specifying any
+    pub const ALL: [TokenScope; 4] = [
+        TokenScope::Sign,
+        TokenScope::RenewOnly,
+        TokenScope::Generate,
+        TokenScope::Read,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::Sign => "sign",
+            TokenScope::RenewOnly => "renew-only",
+            TokenScope::Generate => "generate",
+            TokenScope::Read => "read",
+        }
+    }
+
+    pub fn parse(scope: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == scope)
+    }
+
+    /// Join scopes using the given separator
+    pub fn join(scopes: &[TokenScope], separator: &str) -> String {
+        scopes
+            .iter()
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+}
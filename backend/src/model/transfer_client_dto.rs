@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The new owner of a client, exactly one of the fields must be set
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TransferClientDto {
+    /// The id of the user who should own the client personally
+    #[serde(rename = "userId", skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// The id of the team which should own the client
+    #[serde(rename = "teamId", skip_serializing_if = "Option::is_none")]
+    pub team_id: Option<String>,
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The changes to apply to a user, fields which are not set are left unchanged
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateUserDto {
+    pub email: Option<String>,
+    #[serde(rename = "firstName")]
+    pub first_name: Option<String>,
+    #[serde(rename = "lastName")]
+    pub last_name: Option<String>,
+    /// Whether the user may log in. Disabling a user disables all of its clients,
+    /// which stay disabled if the user is enabled again.
+    pub enabled: Option<bool>,
+}
@@ -1,5 +1,5 @@
 use crate::entity::user;
-use keycloak::types::UserRepresentation;
+use crate::identity::identity_provider::IdentityUser;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -23,7 +23,7 @@ pub struct UserDto {
 }
 
 impl UserDto {
-    pub fn from_model(model: user::Model, kc_user: Option<UserRepresentation>) -> Self {
+    pub fn from_model(model: user::Model, identity_user: Option<IdentityUser>) -> Self {
         Self {
             id: model.id.to_string(),
             name: model.name.clone(),
@@ -31,13 +31,13 @@ impl UserDto {
             active: model.active,
             created_at: model.created_at.to_rfc3339(),
             updated_at: model.updated_at.to_rfc3339(),
-            email: kc_user.as_ref().and_then(|u| u.email.clone()),
-            roles: kc_user
+            email: identity_user.as_ref().and_then(|u| u.email.clone()),
+            roles: identity_user
                 .as_ref()
-                .and_then(|u| u.realm_roles.clone())
+                .map(|u| u.roles.clone())
                 .unwrap_or_default(),
-            first_name: kc_user.as_ref().and_then(|u| u.first_name.clone()),
-            last_name: kc_user.and_then(|u| u.last_name),
+            first_name: identity_user.as_ref().and_then(|u| u.first_name.clone()),
+            last_name: identity_user.and_then(|u| u.last_name),
         }
     }
 }
@@ -0,0 +1,17 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+/// A certificate signing request submitted by a user
+#[derive(Deserialize, ToSchema, Debug)]
+pub struct UserSigningRequestDto {
+    /// The certificate signing request in PEM format
+    pub request: String,
+    /// Alternative names for the certificate
+    #[serde(rename = "alternativeNames")]
+    pub alternative_names: Option<Vec<String>>,
+    /// The id of the client to issue the certificate to.
+    /// A personal certificate of the user is issued if not set,
+    /// its subject must only contain the user's name as the common name.
+    #[serde(rename = "clientId")]
+    pub client_id: Option<String>,
+}
@@ -0,0 +1,62 @@
+use crate::model::verify_certificate_dto::CertificatePurpose;
+use serde::{Deserialize, Serialize};
+use shared::model::certificate_status::CertificateStatus;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChainEntryDto {
+    /// The subject distinguished name of the certificate
+    pub subject: String,
+    /// The issuer distinguished name of the certificate
+    pub issuer: String,
+    /// The serial number of the certificate
+    #[serde(rename = "serialNumber")]
+    pub serial_number: String,
+    /// The time the certificate is valid from
+    #[serde(rename = "notBefore")]
+    pub not_before: String,
+    /// The time the certificate is valid until
+    #[serde(rename = "notAfter")]
+    pub not_after: String,
+    /// Whether the certificate is expired
+    pub expired: bool,
+    /// Whether the certificate is not yet valid
+    #[serde(rename = "notYetValid")]
+    pub not_yet_valid: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VerificationResultDto {
+    /// Whether the certificate is valid.
+    /// This is only the case if the chain is valid,
+    /// no certificate in the chain is expired, the certificate
+    /// is not revoked and is valid for the requested purpose.
+    pub valid: bool,
+    /// Whether the certificate chains to a root certificate of this CA
+    #[serde(rename = "chainValid")]
+    pub chain_valid: bool,
+    /// The reason the chain could not be verified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The depth in the chain at which the verification failed,
+    /// 0 being the certificate itself
+    #[serde(rename = "errorDepth", skip_serializing_if = "Option::is_none")]
+    pub error_depth: Option<u32>,
+    /// The chain as far as it could be built,
+    /// starting with the certificate itself
+    pub chain: Vec<ChainEntryDto>,
+    /// Whether all certificates in the chain are currently valid
+    #[serde(rename = "timeValid")]
+    pub time_valid: bool,
+    /// The purpose the certificate was checked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<CertificatePurpose>,
+    /// Whether the certificate is valid for the requested purpose.
+    /// Only set if a purpose was requested and the chain is valid.
+    #[serde(rename = "purposeValid", skip_serializing_if = "Option::is_none")]
+    pub purpose_valid: Option<bool>,
+    /// The status of the certificate as recorded by this CA.
+    /// Only set if the certificate was issued by this CA.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<CertificateStatus>,
+}
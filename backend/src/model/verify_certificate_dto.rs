@@ -0,0 +1,33 @@
+use openssl::x509::X509PurposeId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum CertificatePurpose {
+    ServerAuth,
+    ClientAuth,
+}
+
+impl From<CertificatePurpose> for X509PurposeId {
+    fn from(purpose: CertificatePurpose) -> Self {
+        match purpose {
+            CertificatePurpose::ServerAuth => X509PurposeId::SSL_SERVER,
+            CertificatePurpose::ClientAuth => X509PurposeId::SSL_CLIENT,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VerifyCertificateDto {
+    /// The PEM encoded certificate to verify
+    pub certificate: String,
+    /// PEM encoded intermediate certificates which may be used
+    /// to build the chain. These are never trusted on their own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain: Option<Vec<String>>,
+    /// The purpose the certificate is intended to be used for.
+    /// The purpose is not checked if this is not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<CertificatePurpose>,
+}
@@ -0,0 +1,57 @@
+use crate::entity::notification::DeliveryStatus;
+use crate::entity::webhook_delivery;
+use crate::util::traits::from_model::FromModel;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookDeliveryDto {
+    /// The delivery id, also sent in the `X-CA-Delivery` header
+    pub id: String,
+    /// The id of the subscription the event was delivered to
+    #[serde(rename = "subscriptionId")]
+    pub subscription_id: String,
+    /// The event name
+    pub event: String,
+    /// The delivery status, either pending, sent or failed
+    pub status: String,
+    /// The number of delivery attempts
+    pub attempts: i32,
+    /// The http status of the last delivery attempt
+    #[serde(rename = "responseStatus", skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<i32>,
+    /// The error of the last failed delivery attempt
+    #[serde(rename = "lastError", skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// The time of the next delivery attempt
+    #[serde(rename = "nextAttemptAt", skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<String>,
+    /// The time the event was created
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    /// The time the event was delivered
+    #[serde(rename = "deliveredAt", skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<String>,
+}
+
+impl FromModel<webhook_delivery::Model> for WebhookDeliveryDto {
+    fn from_model(model: webhook_delivery::Model) -> Self {
+        Self {
+            id: model.id.to_string(),
+            subscription_id: model.subscription_id.to_string(),
+            event: model.event,
+            status: match model.status {
+                DeliveryStatus::Pending => "pending",
+                DeliveryStatus::Sent => "sent",
+                DeliveryStatus::Failed => "failed",
+            }
+            .to_string(),
+            attempts: model.attempts,
+            response_status: model.response_status,
+            last_error: model.last_error,
+            next_attempt_at: model.next_attempt_at.map(|t| t.to_rfc3339()),
+            created_at: model.created_at.to_rfc3339(),
+            delivered_at: model.delivered_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
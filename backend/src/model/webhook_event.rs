@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A CA lifecycle event webhooks can subscribe to
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum WebhookEvent {
+    #[serde(rename = "certificate.issued")]
+    CertificateIssued,
+    #[serde(rename = "certificate.revoked")]
+    CertificateRevoked,
+    #[serde(rename = "certificate.pending")]
+    CertificatePending,
+    #[serde(rename = "certificate.rejected")]
+    CertificateRejected,
+    #[serde(rename = "client.created")]
+    ClientCreated,
+    #[serde(rename = "client.token_regenerated")]
+    ClientTokenRegenerated,
+    #[serde(rename = "client.disabled")]
+    ClientDisabled,
+    #[serde(rename = "intermediate.rotated")]
+    IntermediateRotated,
+    #[serde(rename = "root.generated")]
+    RootGenerated,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::CertificateIssued => "certificate.issued",
+            WebhookEvent::CertificateRevoked => "certificate.revoked",
+            WebhookEvent::CertificatePending => "certificate.pending",
+            WebhookEvent::CertificateRejected => "certificate.rejected",
+            WebhookEvent::ClientCreated => "client.created",
+            WebhookEvent::ClientTokenRegenerated => "client.token_regenerated",
+            WebhookEvent::ClientDisabled => "client.disabled",
+            WebhookEvent::IntermediateRotated => "intermediate.rotated",
+            WebhookEvent::RootGenerated => "root.generated",
+        }
+    }
+}
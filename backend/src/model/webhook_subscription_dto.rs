@@ -0,0 +1,44 @@
+use crate::entity::webhook_subscription;
+use crate::util::traits::from_model::FromModel;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookSubscriptionDto {
+    /// The subscription id
+    pub id: String,
+    /// The id of the user that owns the subscription
+    #[serde(rename = "userId")]
+    pub user_id: String,
+    /// The url the events are posted to
+    pub url: String,
+    /// The events the subscription receives.
+    /// Empty if the subscription receives all events.
+    pub events: Vec<String>,
+    /// Whether the subscription receives the events of all users
+    pub global: bool,
+    /// Whether the subscription is active
+    pub active: bool,
+    /// The secret used to sign the payloads.
+    /// Only returned when creating a new subscription.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// The time the subscription was created
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+}
+
+impl FromModel<webhook_subscription::Model> for WebhookSubscriptionDto {
+    fn from_model(model: webhook_subscription::Model) -> Self {
+        Self {
+            id: model.id.to_string(),
+            user_id: model.user_id.to_string(),
+            events: model.events(),
+            url: model.url,
+            global: model.global,
+            active: model.active,
+            secret: None,
+            created_at: model.created_at.to_rfc3339(),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::config::config::Config;
+use crate::entity::notification;
+use crate::entity::notification::{ExpiringItemType, NotificationChannelType};
+use crate::notification::smtp_channel::SmtpChannel;
+use crate::notification::webhook_channel::WebhookChannel;
+use crate::util::traits::from_model::FromModel;
+use async_trait::async_trait;
+use serde::Serialize;
+use shared::util::types::BasicResult;
+
+/// The contents of an expiry notification
+#[derive(Debug, Serialize)]
+pub struct ExpiryNotification {
+    #[serde(rename = "itemType")]
+    pub item_type: ExpiringItemType,
+    #[serde(rename = "itemId")]
+    pub item_id: String,
+    #[serde(rename = "itemName")]
+    pub item_name: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: String,
+    #[serde(rename = "daysLeft")]
+    pub days_left: i64,
+}
+
+impl ExpiryNotification {
+    fn item_description(&self) -> &'static str {
+        match self.item_type {
+            ExpiringItemType::Certificate => "Certificate",
+            ExpiringItemType::ClientToken => "Client token",
+            ExpiringItemType::Intermediate => "Intermediate certificate",
+            ExpiringItemType::Root => "Root certificate",
+        }
+    }
+
+    pub fn subject(&self) -> String {
+        format!(
+            "{} '{}' expires in {} days",
+            self.item_description(),
+            self.item_name,
+            self.days_left
+        )
+    }
+
+    pub fn message(&self) -> String {
+        format!(
+            "{} '{}' (id: {}) expires at {}.\nPlease renew it before it expires.",
+            self.item_description(),
+            self.item_name,
+            self.item_id,
+            self.expires_at
+        )
+    }
+}
+
+impl FromModel<notification::Model> for ExpiryNotification {
+    fn from_model(model: notification::Model) -> Self {
+        Self {
+            item_type: model.item_type,
+            item_id: model.item_id,
+            item_name: model.item_name,
+            expires_at: model.expires_at.to_rfc3339(),
+            days_left: (model.expires_at.with_timezone(&chrono::Utc) - chrono::Utc::now())
+                .num_days()
+                .max(0),
+        }
+    }
+}
+
+/// A channel expiry notifications can be delivered through
+#[async_trait(?Send)]
+pub trait NotificationChannel {
+    fn channel_type(&self) -> NotificationChannelType;
+
+    async fn send(&self, notification: &ExpiryNotification) -> BasicResult<()>;
+}
+
+/// Create all notification channels which are configured
+pub fn channels_from_config(config: &Config) -> BasicResult<Vec<Box<dyn NotificationChannel>>> {
+    let mut channels: Vec<Box<dyn NotificationChannel>> = vec![];
+    if let Some(host) = &config.smtp_host {
+        channels.push(Box::new(SmtpChannel::new(config, host)?));
+    }
+
+    if let Some(url) = &config.notification_webhook_url {
+        channels.push(Box::new(WebhookChannel::new(url)));
+    }
+
+    Ok(channels)
+}
@@ -0,0 +1,360 @@
+use crate::config::config::Config;
+use crate::entity::notification;
+use crate::entity::notification::{DeliveryStatus, ExpiringItemType};
+use crate::notification::channel::{channels_from_config, ExpiryNotification, NotificationChannel};
+use crate::repository::certificate_repository::CertificateRepository;
+use crate::repository::client_repository::ClientRepository;
+use crate::repository::notification_repository::NotificationRepository;
+use crate::repository::root_certificate_repository::RootCertificateRepository;
+use crate::repository::signing_request_repository::SigningRequestRepository;
+use crate::util::traits::from_model::FromModel;
+use chrono::{Duration, Utc};
+use log::{debug, error, info, warn};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ActiveEnum, ActiveValue, DatabaseConnection, IntoActiveModel};
+use shared::util::types::BasicResult;
+
+struct ExpiringItem {
+    item_type: ExpiringItemType,
+    id: String,
+    name: String,
+    expires_at: DateTimeWithTimeZone,
+}
+
+/// Periodically checks for certificates and client tokens
+/// which are about to expire and notifies about them
+/// through all configured notification channels
+pub struct ExpiryScheduler {
+    db: DatabaseConnection,
+    channels: Vec<Box<dyn NotificationChannel>>,
+    thresholds: Vec<i64>,
+    max_attempts: i32,
+    interval: std::time::Duration,
+}
+
+impl ExpiryScheduler {
+    pub fn new(db: DatabaseConnection, config: &Config) -> BasicResult<Self> {
+        Ok(Self {
+            db,
+            channels: channels_from_config(config)?,
+            thresholds: config.notification_thresholds()?,
+            max_attempts: config.notification_max_attempts,
+            interval: std::time::Duration::from_secs(config.notification_interval_seconds),
+        })
+    }
+
+    /// Run the expiry checks in the background
+    pub fn start(self) {
+        if self.channels.is_empty() || self.thresholds.is_empty() {
+            info!("No notification channels configured, expiry notifications are disabled");
+            return;
+        }
+
+        actix_web::rt::spawn(async move {
+            let mut interval = actix_web::rt::time::interval(self.interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run().await {
+                    error!("Failed to send expiry notifications: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn run(&self) -> BasicResult<()> {
+        debug!("Checking for expiring items");
+        self.create_notifications().await?;
+        self.deliver_notifications().await
+    }
+
+    async fn find_expiring_items(
+        &self,
+        before: DateTimeWithTimeZone,
+    ) -> BasicResult<Vec<ExpiringItem>> {
+        let now = Utc::now();
+        let mut items = vec![];
+
+        for req in SigningRequestRepository::find_expiring(&self.db, before).await? {
+            items.push(ExpiringItem {
+                item_type: ExpiringItemType::Certificate,
+                id: req.serial_number,
+                name: req.subject_name,
+                expires_at: req.valid_until,
+            });
+        }
+
+        for client in ClientRepository::find_expiring(&self.db, before).await? {
+            items.push(ExpiringItem {
+                item_type: ExpiringItemType::ClientToken,
+                id: client.id.to_string(),
+                name: client.name,
+                expires_at: client.valid_until,
+            });
+        }
+
+        if let Some(cert) = CertificateRepository::find_active(&self.db).await? {
+            items.push(ExpiringItem {
+                item_type: ExpiringItemType::Intermediate,
+                id: cert.id.to_string(),
+                name: "Intermediate certificate".to_string(),
+                expires_at: cert.valid_until,
+            });
+        }
+
+        if let Some(cert) = RootCertificateRepository::find_active(&self.db).await? {
+            items.push(ExpiringItem {
+                item_type: ExpiringItemType::Root,
+                id: cert.id.to_string(),
+                name: "Root certificate".to_string(),
+                expires_at: cert.valid_until,
+            });
+        }
+
+        Ok(items
+            .into_iter()
+            .filter(|item| item.expires_at > now && item.expires_at <= before)
+            .collect())
+    }
+
+    /// Create a notification for every item and channel which crossed a threshold.
+    /// Only the smallest crossed threshold is notified about.
+    async fn create_notifications(&self) -> BasicResult<()> {
+        let now = Utc::now();
+        let max_threshold = *self.thresholds.iter().max().unwrap_or(&0);
+        let items = self
+            .find_expiring_items((now + Duration::days(max_threshold)).into())
+            .await?;
+
+        for item in items {
+            let threshold = match self
+                .thresholds
+                .iter()
+                .find(|t| item.expires_at <= now + Duration::days(**t))
+            {
+                Some(threshold) => *threshold,
+                None => continue,
+            };
+
+            for channel in self.channels.iter() {
+                let dedup_key = format!(
+                    "{}:{}:{}:{}:{}",
+                    item.item_type.to_value(),
+                    item.id,
+                    item.expires_at.timestamp(),
+                    threshold,
+                    channel.channel_type().to_value()
+                );
+
+                if NotificationRepository::find_by_dedup_key(&self.db, &dedup_key)
+                    .await?
+                    .is_some()
+                {
+                    continue;
+                }
+
+                debug!("Creating notification {}", dedup_key);
+                if let Err(e) = NotificationRepository::insert(
+                    &self.db,
+                    notification::ActiveModel {
+                        dedup_key: ActiveValue::Set(dedup_key),
+                        item_type: ActiveValue::Set(item.item_type),
+                        item_id: ActiveValue::Set(item.id.clone()),
+                        item_name: ActiveValue::Set(item.name.clone()),
+                        expires_at: ActiveValue::Set(item.expires_at),
+                        threshold_days: ActiveValue::Set(threshold as i32),
+                        channel: ActiveValue::Set(channel.channel_type()),
+                        ..Default::default()
+                    },
+                )
+                .await
+                {
+                    // Another instance may have created the same notification
+                    warn!("Failed to create notification: {}", e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn deliver_notifications(&self) -> BasicResult<()> {
+        for model in NotificationRepository::find_undelivered(&self.db, self.max_attempts).await? {
+            let channel = match self
+                .channels
+                .iter()
+                .find(|c| c.channel_type() == model.channel)
+            {
+                Some(channel) => channel,
+                None => continue,
+            };
+
+            let res = channel
+                .send(&ExpiryNotification::from_model(model.clone()))
+                .await;
+            let mut active = model.clone().into_active_model();
+            active.attempts = ActiveValue::Set(model.attempts + 1);
+            match res {
+                Ok(_) => {
+                    info!("Sent notification {}", model.dedup_key);
+                    active.status = ActiveValue::Set(DeliveryStatus::Sent);
+                    active.sent_at = ActiveValue::Set(Some(Utc::now().into()));
+                    active.last_error = ActiveValue::Set(None);
+                }
+                Err(e) => {
+                    warn!("Failed to send notification {}: {}", model.dedup_key, e);
+                    active.status = ActiveValue::Set(DeliveryStatus::Failed);
+                    active.last_error = ActiveValue::Set(Some(e.to_string()));
+                }
+            }
+
+            NotificationRepository::update(&self.db, active).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExpiryScheduler;
+    use crate::config::config::Config;
+    use crate::repository::signing_request_repository::SigningRequestRepository;
+    use crate::util::testing::{connect, insert_client, issued};
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use chrono::{Duration, Utc};
+    use envconfig::Envconfig;
+    use sea_orm::ActiveValue;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    type Received = Arc<Mutex<Vec<String>>>;
+
+    /// Start an smtp server which accepts every message and records its contents
+    fn start_smtp_sink() -> (u16, Received) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let received = Received::default();
+
+        let messages = received.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                stream.write_all(b"220 localhost ESMTP\r\n").unwrap();
+
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 0 {
+                    let command = line.trim_end().to_uppercase();
+                    if command == "DATA" {
+                        stream
+                            .write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n")
+                            .unwrap();
+                        let mut message = String::new();
+                        line.clear();
+                        while reader.read_line(&mut line).unwrap() > 0 && line != ".\r\n" {
+                            message.push_str(&line);
+                            line.clear();
+                        }
+
+                        messages.lock().unwrap().push(message);
+                        stream.write_all(b"250 OK\r\n").unwrap();
+                    } else if command == "QUIT" {
+                        stream.write_all(b"221 Bye\r\n").unwrap();
+                        break;
+                    } else if command.starts_with("EHLO") {
+                        stream.write_all(b"250 localhost\r\n").unwrap();
+                    } else {
+                        stream.write_all(b"250 OK\r\n").unwrap();
+                    }
+
+                    line.clear();
+                }
+            }
+        });
+
+        (port, received)
+    }
+
+    async fn receive(received: web::Data<Mutex<Vec<String>>>, body: String) -> HttpResponse {
+        received.lock().unwrap().push(body);
+        HttpResponse::Ok().finish()
+    }
+
+    /// Start an http server which records the bodies of all requests to `/expiry`
+    fn start_http_receiver() -> (String, Received) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/expiry", listener.local_addr().unwrap());
+        let received = Received::default();
+
+        let data = web::Data::from(received.clone());
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(data.clone())
+                .route("/expiry", web::post().to(receive))
+        })
+        .workers(1)
+        .listen(listener)
+        .unwrap()
+        .run();
+        actix_web::rt::spawn(server);
+
+        (url, received)
+    }
+
+    #[actix_web::test]
+    async fn notifies_only_about_the_newest_certificates() {
+        let (smtp_port, mails) = start_smtp_sink();
+        let (webhook_url, requests) = start_http_receiver();
+        let config = Config::init_from_hashmap(&HashMap::from([
+            ("JWT_EXPIRES_IN".to_string(), "60m".to_string()),
+            ("JWT_MAX_AGE".to_string(), "60".to_string()),
+            ("SMTP_HOST".to_string(), "127.0.0.1".to_string()),
+            ("SMTP_PORT".to_string(), smtp_port.to_string()),
+            ("SMTP_TO".to_string(), "admin@localhost".to_string()),
+            ("NOTIFICATION_WEBHOOK_URL".to_string(), webhook_url),
+        ]))
+        .unwrap();
+
+        let db = connect().await;
+        let (user, client) = insert_client(&db, false).await;
+        let expiring = (Utc::now() + Duration::days(5)).into();
+        let renewed = (Utc::now() + Duration::days(60)).into();
+
+        // The client and the user renewed their certificates, but not the other one
+        for (client_id, serial, subject, valid_until) in [
+            (Some(client.id), 1, "client", expiring),
+            (Some(client.id), 2, "client", renewed),
+            (Some(client.id), 3, "other", expiring),
+            (None, 4, "user", expiring),
+            (None, 5, "user", renewed),
+        ] {
+            let mut model = issued(client_id, serial);
+            model.subject_name = ActiveValue::Set(subject.to_string());
+            model.valid_until = ActiveValue::Set(valid_until);
+            if client_id.is_none() {
+                model.requested_by = ActiveValue::Set(Some(user.id));
+            }
+
+            SigningRequestRepository::insert(&db, model).await.unwrap();
+        }
+
+        let scheduler = ExpiryScheduler::new(db, &config).unwrap();
+        scheduler.run().await.unwrap();
+        // Notifications are only created once
+        scheduler.run().await.unwrap();
+
+        let mails = mails.lock().unwrap();
+        assert_eq!(mails.len(), 1);
+        assert!(mails[0].contains("Certificate 'other' expires in"));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        let body: serde_json::Value = serde_json::from_str(&requests[0]).unwrap();
+        assert_eq!(body["itemType"], "certificate");
+        assert_eq!(body["itemId"], "03");
+        assert_eq!(body["itemName"], "other");
+    }
+}
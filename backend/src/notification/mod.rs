@@ -0,0 +1,6 @@
+pub mod channel;
+pub mod expiry_scheduler;
+pub mod smtp_channel;
+pub mod webhook_channel;
+pub mod webhook_dispatcher;
+pub mod webhook_target;
@@ -0,0 +1,68 @@
+use crate::config::config::Config;
+use crate::entity::notification::NotificationChannelType;
+use crate::notification::channel::{ExpiryNotification, NotificationChannel};
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use shared::util::types::BasicResult;
+
+/// Sends notifications as plain text emails
+pub struct SmtpChannel {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl SmtpChannel {
+    pub fn new(config: &Config, host: &str) -> BasicResult<Self> {
+        let mut builder = if config.smtp_starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+        }
+        .port(config.smtp_port);
+
+        if let (Some(user), Some(password)) = (&config.smtp_user, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(user.clone(), password.clone()));
+        }
+
+        let to = config
+            .smtp_to
+            .split(',')
+            .map(|to| to.trim())
+            .filter(|to| !to.is_empty())
+            .map(|to| to.parse())
+            .collect::<Result<Vec<Mailbox>, _>>()?;
+        if to.is_empty() {
+            return Err("SMTP_TO must contain at least one recipient".into());
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: config.smtp_from.parse()?,
+            to,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl NotificationChannel for SmtpChannel {
+    fn channel_type(&self) -> NotificationChannelType {
+        NotificationChannelType::Smtp
+    }
+
+    async fn send(&self, notification: &ExpiryNotification) -> BasicResult<()> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(notification.subject());
+        for to in self.to.iter() {
+            builder = builder.to(to.clone());
+        }
+
+        self.transport
+            .send(builder.body(notification.message())?)
+            .await?;
+        Ok(())
+    }
+}
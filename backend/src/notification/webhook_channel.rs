@@ -0,0 +1,38 @@
+use crate::entity::notification::NotificationChannelType;
+use crate::notification::channel::{ExpiryNotification, NotificationChannel};
+use async_trait::async_trait;
+use reqwest::header::CONTENT_TYPE;
+use shared::util::types::BasicResult;
+
+/// Posts notifications as json to a configured url
+pub struct WebhookChannel {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookChannel {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl NotificationChannel for WebhookChannel {
+    fn channel_type(&self) -> NotificationChannelType {
+        NotificationChannelType::Webhook
+    }
+
+    async fn send(&self, notification: &ExpiryNotification) -> BasicResult<()> {
+        self.client
+            .post(&self.url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_string(notification)?)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
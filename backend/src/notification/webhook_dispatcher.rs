@@ -0,0 +1,176 @@
+use crate::config::config::Config;
+use crate::entity::notification::DeliveryStatus;
+use crate::entity::{webhook_delivery, webhook_subscription};
+use crate::notification::webhook_target::WebhookTargetPolicy;
+use crate::repository::webhook_delivery_repository::WebhookDeliveryRepository;
+use crate::repository::webhook_subscription_repository::WebhookSubscriptionRepository;
+use chrono::{Duration, Utc};
+use log::{debug, error, warn};
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::redirect::Policy;
+use reqwest::Url;
+use sea_orm::{ActiveValue, DatabaseConnection, IntoActiveModel};
+use shared::util::traits::u8_vec_to_string::U8VecToString;
+use shared::util::types::BasicResult;
+
+pub const EVENT_HEADER: &str = "X-CA-Event";
+pub const DELIVERY_HEADER: &str = "X-CA-Delivery";
+pub const TIMESTAMP_HEADER: &str = "X-CA-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-CA-Signature";
+
+/// Sign a webhook payload.
+/// The signature is the hex encoded HMAC-SHA256 of `<timestamp>.<payload>`
+/// using the subscription secret as the key.
+pub fn sign_payload(secret: &str, timestamp: i64, payload: &str) -> BasicResult<String> {
+    let key = PKey::hmac(secret.as_bytes())?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+    signer.update(format!("{}.{}", timestamp, payload).as_bytes())?;
+    Ok(format!(
+        "sha256={}",
+        signer.sign_to_vec()?.to_hex_string("")
+    ))
+}
+
+/// Delivers queued webhook events in the background,
+/// retrying failed deliveries with an exponential backoff
+pub struct WebhookDispatcher {
+    db: DatabaseConnection,
+    policy: WebhookTargetPolicy,
+    timeout: std::time::Duration,
+    max_attempts: i32,
+    retry_base_seconds: i64,
+    interval: std::time::Duration,
+}
+
+impl WebhookDispatcher {
+    pub fn new(
+        db: DatabaseConnection,
+        config: &Config,
+        policy: WebhookTargetPolicy,
+    ) -> BasicResult<Self> {
+        Ok(Self {
+            db,
+            policy,
+            timeout: std::time::Duration::from_secs(config.webhook_timeout_seconds),
+            max_attempts: config.webhook_max_attempts,
+            retry_base_seconds: config.webhook_retry_base_seconds,
+            interval: std::time::Duration::from_secs(config.webhook_dispatch_interval_seconds),
+        })
+    }
+
+    pub fn start(self) {
+        actix_web::rt::spawn(async move {
+            let mut interval = actix_web::rt::time::interval(self.interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run().await {
+                    error!("Failed to deliver webhooks: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn run(&self) -> BasicResult<()> {
+        let due =
+            WebhookDeliveryRepository::find_due(&self.db, Utc::now().into(), self.max_attempts)
+                .await?;
+
+        for delivery in due {
+            let subscription =
+                WebhookSubscriptionRepository::find_by_id(&self.db, &delivery.subscription_id)
+                    .await?
+                    .filter(|s| s.active);
+
+            let mut active = delivery.clone().into_active_model();
+            match subscription {
+                Some(subscription) => {
+                    let attempts = delivery.attempts + 1;
+                    active.attempts = ActiveValue::Set(attempts);
+
+                    match self.send(&subscription, &delivery).await {
+                        Ok(status) => {
+                            debug!("Delivered webhook {}", delivery.id);
+                            active.status = ActiveValue::Set(DeliveryStatus::Sent);
+                            active.response_status = ActiveValue::Set(Some(status));
+                            active.last_error = ActiveValue::Set(None);
+                            active.next_attempt_at = ActiveValue::Set(None);
+                            active.delivered_at = ActiveValue::Set(Some(Utc::now().into()));
+                        }
+                        Err((status, e)) => {
+                            warn!("Failed to deliver webhook {}: {}", delivery.id, e);
+                            active.status = ActiveValue::Set(DeliveryStatus::Failed);
+                            active.response_status = ActiveValue::Set(status);
+                            active.last_error = ActiveValue::Set(Some(e));
+                            active.next_attempt_at =
+                                ActiveValue::Set((attempts < self.max_attempts).then(|| {
+                                    (Utc::now()
+                                        + Duration::seconds(
+                                            self.retry_base_seconds << (attempts - 1).min(16),
+                                        ))
+                                    .into()
+                                }));
+                        }
+                    }
+                }
+                None => {
+                    active.status = ActiveValue::Set(DeliveryStatus::Failed);
+                    active.last_error =
+                        ActiveValue::Set(Some("The subscription is no longer active".into()));
+                    active.next_attempt_at = ActiveValue::Set(None);
+                }
+            }
+
+            WebhookDeliveryRepository::update(&self.db, active).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Post the payload of a delivery to the subscription url.
+    /// The request is only sent to the address the url was checked against
+    /// and redirects are not followed.
+    /// Returns the http status on success, the status (if any) and the error otherwise.
+    async fn send(
+        &self,
+        subscription: &webhook_subscription::Model,
+        delivery: &webhook_delivery::Model,
+    ) -> Result<i32, (Option<i32>, String)> {
+        let timestamp = Utc::now().timestamp();
+        let signature = sign_payload(&subscription.secret, timestamp, &delivery.payload)
+            .map_err(|e| (None, e.to_string()))?;
+
+        let url = Url::parse(&subscription.url).map_err(|e| (None, e.to_string()))?;
+        let (host, addr) = self.policy.resolve(&url).await.map_err(|e| (None, e))?;
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .redirect(Policy::none())
+            .resolve(&host, addr)
+            .build()
+            .map_err(|e| (None, e.to_string()))?;
+
+        let res = client
+            .post(url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, &delivery.event)
+            .header(DELIVERY_HEADER, delivery.id.to_string())
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .body(delivery.payload.clone())
+            .send()
+            .await
+            .map_err(|e| (None, e.to_string()))?;
+
+        let status = res.status();
+        if status.is_success() {
+            Ok(status.as_u16() as i32)
+        } else {
+            Err((
+                Some(status.as_u16() as i32),
+                format!("The server responded with status {}", status),
+            ))
+        }
+    }
+}
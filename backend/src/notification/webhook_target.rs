@@ -0,0 +1,236 @@
+use actix_web::web;
+use reqwest::Url;
+use std::error::Error;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::str::FromStr;
+
+/// A network in CIDR notation, e.g. `10.0.0.0/8`.
+/// A single address is parsed as a network containing only that address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNetwork {
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, normalize(*ip)) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => prefix_matches(
+                u32::from(net) as u128,
+                u32::from(ip) as u128,
+                32,
+                self.prefix,
+            ),
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_matches(u128::from(net), u128::from(ip), 128, self.prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+
+        let addr = normalize(addr.trim().parse::<IpAddr>()?);
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.trim().parse::<u8>()?,
+            None => max,
+        };
+
+        if prefix > max {
+            return Err(format!("Invalid prefix length in network '{}'", s).into());
+        }
+
+        Ok(Self { addr, prefix })
+    }
+}
+
+fn prefix_matches(net: u128, ip: u128, bits: u8, prefix: u8) -> bool {
+    let shift = bits - prefix;
+    shift >= bits || (net >> shift) == (ip >> shift)
+}
+
+/// Map ipv4-mapped ipv6 addresses to the ipv4 address they represent
+fn normalize(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6
+            .to_ipv4_mapped()
+            .map(IpAddr::V4)
+            .unwrap_or(IpAddr::V6(v6)),
+        ip => ip,
+    }
+}
+
+fn is_public_v4(ip: &Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network" (0.0.0.0/8)
+        || a == 0
+        // Shared address space (100.64.0.0/10)
+        || (a == 100 && (b & 0xc0) == 64)
+        // Benchmarking (198.18.0.0/15)
+        || (a == 198 && (b & 0xfe) == 18)
+        // Reserved (240.0.0.0/4)
+        || a >= 240)
+}
+
+fn is_public_v6(ip: &Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local (fc00::/7)
+        || (first & 0xfe00) == 0xfc00
+        // Link local (fe80::/10)
+        || (first & 0xffc0) == 0xfe80
+        // Documentation (2001:db8::/32)
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+/// Whether an address is publicly routable.
+/// Loopback, private, link-local and other special purpose addresses are not.
+pub fn is_public(ip: &IpAddr) -> bool {
+    match normalize(*ip) {
+        IpAddr::V4(v4) => is_public_v4(&v4),
+        IpAddr::V6(v6) => is_public_v6(&v6),
+    }
+}
+
+/// Decides which addresses webhook events may be delivered to.
+/// Only publicly routable addresses are permitted
+/// unless an admin explicitly allows a network.
+#[derive(Debug, Clone, Default)]
+pub struct WebhookTargetPolicy {
+    allowed_networks: Vec<IpNetwork>,
+}
+
+impl WebhookTargetPolicy {
+    pub fn new(allowed_networks: Vec<IpNetwork>) -> Self {
+        Self { allowed_networks }
+    }
+
+    pub fn permits(&self, ip: &IpAddr) -> bool {
+        is_public(ip) || self.allowed_networks.iter().any(|n| n.contains(ip))
+    }
+
+    /// Resolve the host of a webhook url and check every address it resolves to.
+    /// Returns the host and the address the request must be sent to,
+    /// so the request can't be redirected to another address by a second lookup.
+    pub async fn resolve(&self, url: &Url) -> Result<(String, SocketAddr), String> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| "The url does not contain a host".to_string())?;
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| "The url does not contain a port".to_string())?;
+
+        // Ipv6 literals are enclosed in brackets in urls
+        let name = host.trim_start_matches('[').trim_end_matches(']');
+        let target = (name.to_string(), port);
+        let addrs = web::block(move || target.to_socket_addrs().map(|a| a.collect::<Vec<_>>()))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("Failed to resolve '{}': {}", host, e))?;
+
+        if let Some(addr) = addrs.iter().find(|a| !self.permits(&a.ip())) {
+            return Err(format!(
+                "The host '{}' resolves to the address {} which is not permitted",
+                host,
+                addr.ip()
+            ));
+        }
+
+        addrs
+            .first()
+            .map(|addr| (host.to_string(), *addr))
+            .ok_or_else(|| format!("The host '{}' did not resolve to any address", host))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn rejects_internal_addresses() {
+        for addr in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:169.254.169.254",
+        ] {
+            assert!(!is_public(&ip(addr)), "{} is public", addr);
+        }
+
+        for addr in ["1.1.1.1", "93.184.216.34", "2606:4700:4700::1111"] {
+            assert!(is_public(&ip(addr)), "{} is not public", addr);
+        }
+    }
+
+    #[test]
+    fn permits_allowed_networks() {
+        let policy = WebhookTargetPolicy::new(vec![
+            "10.0.0.0/8".parse().unwrap(),
+            "fd00::1".parse().unwrap(),
+        ]);
+
+        assert!(policy.permits(&ip("10.20.30.40")));
+        assert!(policy.permits(&ip("::ffff:10.0.0.1")));
+        assert!(policy.permits(&ip("fd00::1")));
+        assert!(policy.permits(&ip("1.1.1.1")));
+        assert!(!policy.permits(&ip("fd00::2")));
+        assert!(!policy.permits(&ip("127.0.0.1")));
+        assert!(!policy.permits(&ip("192.168.0.1")));
+
+        assert!("10.0.0.0/33".parse::<IpNetwork>().is_err());
+        assert!("localhost".parse::<IpNetwork>().is_err());
+        assert!("0.0.0.0/0"
+            .parse::<IpNetwork>()
+            .unwrap()
+            .contains(&ip("127.0.0.1")));
+    }
+
+    #[actix_web::test]
+    async fn resolves_to_permitted_addresses() {
+        let policy = WebhookTargetPolicy::default();
+        let url = Url::parse("http://127.0.0.1:8080/hook").unwrap();
+        assert!(policy.resolve(&url).await.is_err());
+
+        let url = Url::parse("http://[::1]/hook").unwrap();
+        assert!(policy.resolve(&url).await.is_err());
+
+        let policy = WebhookTargetPolicy::new(vec!["127.0.0.0/8".parse().unwrap()]);
+        let url = Url::parse("http://127.0.0.1:8080/hook").unwrap();
+        let (host, addr) = policy.resolve(&url).await.unwrap();
+        assert_eq!(host, "127.0.0.1");
+        assert_eq!(addr, "127.0.0.1:8080".parse().unwrap());
+    }
+}
@@ -0,0 +1,104 @@
+use crate::entity::approval_request;
+use crate::entity::approval_request::ApprovalStatus;
+use crate::util::pagination::Pagination;
+use crate::util::types::DbResult;
+use chrono::Utc;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DeleteResult, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder,
+};
+use uuid::Uuid;
+
+pub struct ApprovalRequestRepository;
+
+impl ApprovalRequestRepository {
+    pub async fn insert<C: ConnectionTrait>(
+        db: &C,
+        model: approval_request::ActiveModel,
+    ) -> DbResult<approval_request::Model> {
+        model.insert(db).await
+    }
+
+    pub async fn update<C: ConnectionTrait>(
+        db: &C,
+        model: approval_request::ActiveModel,
+    ) -> DbResult<approval_request::Model> {
+        model.update(db).await
+    }
+
+    /// Record the decision on a request if it is still pending.
+    /// Returns whether the request has been decided by this call.
+    pub async fn decide<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        status: ApprovalStatus,
+        decided_by: &Uuid,
+        reason: Option<String>,
+    ) -> DbResult<bool> {
+        let decided_at: DateTimeWithTimeZone = Utc::now().into();
+        let res = approval_request::Entity::update_many()
+            .col_expr(approval_request::Column::Status, Expr::value(status))
+            .col_expr(
+                approval_request::Column::DecidedBy,
+                Expr::value(*decided_by),
+            )
+            .col_expr(approval_request::Column::DecidedAt, Expr::value(decided_at))
+            .col_expr(approval_request::Column::Reason, Expr::value(reason))
+            .filter(approval_request::Column::Id.eq(id))
+            .filter(approval_request::Column::Status.eq(ApprovalStatus::Pending))
+            .exec(db)
+            .await?;
+
+        Ok(res.rows_affected == 1)
+    }
+
+    pub async fn find_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+    ) -> DbResult<Option<approval_request::Model>> {
+        approval_request::Entity::find_by_id(id).one(db).await
+    }
+
+    pub async fn find_pending_by_client<C: ConnectionTrait>(
+        db: &C,
+        client_id: &Uuid,
+    ) -> DbResult<Vec<approval_request::Model>> {
+        approval_request::Entity::find()
+            .filter(approval_request::Column::ClientId.eq(*client_id))
+            .filter(approval_request::Column::Status.eq(ApprovalStatus::Pending))
+            .all(db)
+            .await
+    }
+
+    /// Find the requests with the given status, oldest first.
+    /// Returns the requested page and the total number of matching elements.
+    pub async fn find_page<C: ConnectionTrait>(
+        db: &C,
+        status: Option<ApprovalStatus>,
+        pagination: &Pagination,
+    ) -> DbResult<(Vec<approval_request::Model>, u64)> {
+        let mut q = approval_request::Entity::find();
+        if let Some(status) = status {
+            q = q.filter(approval_request::Column::Status.eq(status));
+        }
+
+        let paginator = q
+            .order_by_asc(approval_request::Column::CreatedAt)
+            .order_by_asc(approval_request::Column::Id)
+            .paginate(db, pagination.page_size);
+        let total = paginator.num_items().await?;
+        Ok((paginator.fetch_page(pagination.page).await?, total))
+    }
+
+    pub async fn delete_all_by_client<C: ConnectionTrait>(
+        db: &C,
+        client_id: &Uuid,
+    ) -> DbResult<DeleteResult> {
+        approval_request::Entity::delete_many()
+            .filter(approval_request::Column::ClientId.eq(*client_id))
+            .exec(db)
+            .await
+    }
+}
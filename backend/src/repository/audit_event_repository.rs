@@ -0,0 +1,77 @@
+use crate::entity::audit_event;
+use crate::model::audit_event_filter::AuditEventFilter;
+use crate::util::pagination::Pagination;
+use crate::util::types::DbResult;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Select,
+};
+
+pub struct AuditEventRepository;
+
+impl AuditEventRepository {
+    pub async fn insert<C: ConnectionTrait>(
+        db: &C,
+        model: audit_event::ActiveModel,
+    ) -> DbResult<audit_event::Model> {
+        model.insert(db).await
+    }
+
+    fn filtered(filter: &AuditEventFilter) -> Select<audit_event::Entity> {
+        let mut condition = Condition::all();
+        if let Some(action) = filter.action {
+            condition = condition.add(audit_event::Column::Action.eq(action));
+        }
+        if let Some(actor_id) = &filter.actor_id {
+            condition = condition.add(audit_event::Column::ActorId.eq(actor_id.as_str()));
+        }
+        if let Some(target) = &filter.target {
+            condition = condition.add(audit_event::Column::Target.eq(target.as_str()));
+        }
+        if let Some(outcome) = filter.outcome {
+            condition = condition.add(audit_event::Column::Outcome.eq(outcome));
+        }
+        if let Some(from) = filter.from {
+            condition = condition.add(audit_event::Column::OccurredAt.gte(from));
+        }
+        if let Some(to) = filter.to {
+            condition = condition.add(audit_event::Column::OccurredAt.lte(to));
+        }
+
+        audit_event::Entity::find().filter(condition)
+    }
+
+    /// Find the audit events matching the filter, newest first.
+    /// Returns the requested page and the total number of matching events.
+    pub async fn find_page<C: ConnectionTrait>(
+        db: &C,
+        filter: &AuditEventFilter,
+        pagination: &Pagination,
+    ) -> DbResult<(Vec<audit_event::Model>, u64)> {
+        let paginator = Self::filtered(filter)
+            .order_by_desc(audit_event::Column::OccurredAt)
+            .order_by_desc(audit_event::Column::Id)
+            .paginate(db, pagination.page_size);
+        let total = paginator.num_items().await?;
+        Ok((paginator.fetch_page(pagination.page).await?, total))
+    }
+
+    /// Find up to `limit` audit events matching the filter in the order they were recorded.
+    /// Only events recorded after the event with the id `after` are returned if it is set.
+    pub async fn find_batch<C: ConnectionTrait>(
+        db: &C,
+        filter: &AuditEventFilter,
+        after: Option<i32>,
+        limit: u64,
+    ) -> DbResult<Vec<audit_event::Model>> {
+        let mut q = Self::filtered(filter);
+        if let Some(after) = after {
+            q = q.filter(audit_event::Column::Id.gt(after));
+        }
+
+        q.order_by_asc(audit_event::Column::Id)
+            .limit(limit)
+            .all(db)
+            .await
+    }
+}
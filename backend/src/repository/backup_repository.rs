@@ -0,0 +1,73 @@
+use crate::util::types::DbResult;
+use sea_orm::{
+    ActiveModelTrait, ConnectionTrait, DbBackend, EntityTrait, IntoActiveModel, Statement,
+};
+
+/// The number of rows inserted with a single statement
+const INSERT_BATCH_SIZE: usize = 500;
+
+/// Reads and writes whole tables when exporting and restoring backups
+pub struct BackupRepository;
+
+impl BackupRepository {
+    pub async fn find_all<E, C>(db: &C) -> DbResult<Vec<E::Model>>
+    where
+        E: EntityTrait,
+        C: ConnectionTrait,
+    {
+        E::find().all(db).await
+    }
+
+    pub async fn is_empty<E, C>(db: &C) -> DbResult<bool>
+    where
+        E: EntityTrait,
+        C: ConnectionTrait,
+    {
+        Ok(E::find().one(db).await?.is_none())
+    }
+
+    /// Insert the rows exactly as they are.
+    /// Unlike saving an active model this skips the entity hooks,
+    /// which would otherwise overwrite e.g. the creation dates.
+    pub async fn insert_all<A, C>(db: &C, models: Vec<A>) -> DbResult<()>
+    where
+        A: ActiveModelTrait + Send,
+        <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
+        C: ConnectionTrait,
+    {
+        let mut models = models.into_iter().peekable();
+        while models.peek().is_some() {
+            let batch = models.by_ref().take(INSERT_BATCH_SIZE).collect::<Vec<_>>();
+            A::Entity::insert_many(batch)
+                .exec_without_returning(db)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Continue the id sequence of a table after its highest id.
+    /// Required on postgres after rows were inserted with explicit ids.
+    pub async fn reset_id_sequence<E, C>(db: &C) -> DbResult<()>
+    where
+        E: EntityTrait,
+        C: ConnectionTrait,
+    {
+        if db.get_database_backend() != DbBackend::Postgres {
+            return Ok(());
+        }
+
+        let table = E::default().table_name().to_string();
+        db.execute(Statement::from_string(
+            DbBackend::Postgres,
+            format!(
+                "SELECT setval(pg_get_serial_sequence('\"{0}\"', 'id'), \
+                 COALESCE((SELECT MAX(id) FROM \"{0}\"), 0) + 1, false)",
+                table
+            ),
+        ))
+        .await?;
+
+        Ok(())
+    }
+}
@@ -23,6 +23,13 @@ impl CertificateRepository {
         Ok(res.pop())
     }
 
+    pub async fn find_all<C>(db: &C) -> DbResult<Vec<certificate::Model>>
+    where
+        C: ConnectionTrait,
+    {
+        certificate::Entity::find().all(db).await
+    }
+
     pub async fn insert<C>(db: &C, model: certificate::ActiveModel) -> DbResult<certificate::Model>
     where
         C: ConnectionTrait,
@@ -1,8 +1,12 @@
 use crate::entity::client;
+use crate::model::client_filter::{ClientFilter, ClientStatus};
+use crate::repository::team_member_repository::TeamMemberRepository;
+use crate::util::pagination::Pagination;
 use crate::util::types::DbResult;
+use sea_orm::prelude::DateTimeWithTimeZone;
 use sea_orm::{
-    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DeleteResult, EntityTrait,
-    QueryFilter,
+    ActiveModelTrait, ActiveValue, ColumnTrait, Condition, ConnectionTrait, DeleteResult,
+    EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
 };
 use uuid::Uuid;
 
@@ -16,6 +20,13 @@ impl ClientRepository {
         model.insert(db).await.map_err(|e| e.into())
     }
 
+    pub async fn update<C: ConnectionTrait>(
+        db: &C,
+        model: client::ActiveModel,
+    ) -> DbResult<client::Model> {
+        model.update(db).await
+    }
+
     pub async fn find_by_name<C: ConnectionTrait>(
         db: &C,
         name: &str,
@@ -39,12 +50,41 @@ impl ClientRepository {
         q.one(db).await
     }
 
-    pub async fn find_all_by_user<C: ConnectionTrait>(
+    /// Find all active clients whose token expires between now and the given date
+    pub async fn find_expiring<C: ConnectionTrait>(
+        db: &C,
+        before: DateTimeWithTimeZone,
+    ) -> DbResult<Vec<client::Model>> {
+        client::Entity::find()
+            .filter(client::Column::Active.eq(true))
+            .filter(client::Column::ValidUntil.gt(chrono::Utc::now()))
+            .filter(client::Column::ValidUntil.lte(before))
+            .all(db)
+            .await
+    }
+
+    /// The personal clients of a user and the clients of the teams the user is a member of
+    fn accessible_by_user(user_id: &Uuid) -> Condition {
+        Condition::any()
+            .add(
+                Condition::all()
+                    .add(client::Column::TeamId.is_null())
+                    .add(client::Column::UserId.eq(*user_id)),
+            )
+            .add(
+                client::Column::TeamId.in_subquery(TeamMemberRepository::team_ids_of_user(user_id)),
+            )
+    }
+
+    /// Find the personal clients of a user, clients owned by teams are not included
+    pub async fn find_personal_by_user<C: ConnectionTrait>(
         db: &C,
         user_id: &Uuid,
         include_inactive: bool,
     ) -> DbResult<Vec<client::Model>> {
-        let mut q = client::Entity::find().filter(client::Column::UserId.eq(user_id.clone()));
+        let mut q = client::Entity::find()
+            .filter(client::Column::UserId.eq(*user_id))
+            .filter(client::Column::TeamId.is_null());
         if !include_inactive {
             q = q.filter(client::Column::Active.eq(true));
         }
@@ -52,6 +92,114 @@ impl ClientRepository {
         q.all(db).await
     }
 
+    /// Find the clients owned by teams the user is responsible for
+    pub async fn find_team_clients_by_user<C: ConnectionTrait>(
+        db: &C,
+        user_id: &Uuid,
+    ) -> DbResult<Vec<client::Model>> {
+        client::Entity::find()
+            .filter(client::Column::UserId.eq(*user_id))
+            .filter(client::Column::TeamId.is_not_null())
+            .all(db)
+            .await
+    }
+
+    pub async fn count_by_team<C: ConnectionTrait>(db: &C, team_id: &Uuid) -> DbResult<u64> {
+        client::Entity::find()
+            .filter(client::Column::TeamId.eq(*team_id))
+            .count(db)
+            .await
+    }
+
+    /// Find the clients accessible by a user, see `accessible_by_user`
+    pub async fn find_page_by_user<C: ConnectionTrait>(
+        db: &C,
+        user_id: &Uuid,
+        include_inactive: bool,
+        pagination: &Pagination,
+    ) -> DbResult<(Vec<client::Model>, u64)> {
+        let mut q = client::Entity::find().filter(Self::accessible_by_user(user_id));
+        if !include_inactive {
+            q = q.filter(client::Column::Active.eq(true));
+        }
+
+        let paginator = q
+            .order_by_asc(client::Column::CreatedAt)
+            .order_by_asc(client::Column::Id)
+            .paginate(db, pagination.page_size);
+        let total = paginator.num_items().await?;
+        Ok((paginator.fetch_page(pagination.page).await?, total))
+    }
+
+    /// Find all clients of all users matching the given filter.
+    /// Returns the requested page and the total number of matching elements.
+    pub async fn search<C: ConnectionTrait>(
+        db: &C,
+        filter: &ClientFilter,
+        pagination: &Pagination,
+    ) -> DbResult<(Vec<client::Model>, u64)> {
+        let now = chrono::Utc::now();
+        let mut condition = Condition::all();
+
+        if let Some(user_id) = filter.user_id {
+            condition = condition.add(client::Column::UserId.eq(user_id));
+        }
+        if let Some(team_id) = filter.team_id {
+            condition = condition.add(client::Column::TeamId.eq(team_id));
+        }
+        if let Some(name) = &filter.name {
+            condition = condition.add(client::Column::Name.contains(name));
+        }
+        if let Some(expires_after) = filter.expires_after {
+            condition = condition.add(client::Column::ValidUntil.gte(expires_after));
+        }
+        if let Some(expires_before) = filter.expires_before {
+            condition = condition.add(client::Column::ValidUntil.lte(expires_before));
+        }
+
+        condition = match filter.status {
+            Some(ClientStatus::Active) => condition
+                .add(client::Column::Active.eq(true))
+                .add(client::Column::ValidUntil.gte(now)),
+            Some(ClientStatus::Expired) => condition
+                .add(client::Column::Active.eq(true))
+                .add(client::Column::ValidUntil.lt(now)),
+            Some(ClientStatus::Inactive) => condition.add(client::Column::Active.eq(false)),
+            None => condition,
+        };
+
+        let paginator = client::Entity::find()
+            .filter(condition)
+            .order_by_asc(client::Column::CreatedAt)
+            .order_by_asc(client::Column::Id)
+            .paginate(db, pagination.page_size);
+        let total = paginator.num_items().await?;
+        Ok((paginator.fetch_page(pagination.page).await?, total))
+    }
+
+    pub async fn count_active_by_user<C: ConnectionTrait>(db: &C, user_id: &Uuid) -> DbResult<u64> {
+        client::Entity::find()
+            .filter(client::Column::UserId.eq(*user_id))
+            .filter(client::Column::Active.eq(true))
+            .count(db)
+            .await
+    }
+
+    /// Find the active client of a user whose token expires `n`-th (zero-based)
+    pub async fn find_nth_active_by_user<C: ConnectionTrait>(
+        db: &C,
+        user_id: &Uuid,
+        n: u64,
+    ) -> DbResult<Option<client::Model>> {
+        client::Entity::find()
+            .filter(client::Column::UserId.eq(*user_id))
+            .filter(client::Column::Active.eq(true))
+            .order_by_asc(client::Column::ValidUntil)
+            .offset(n)
+            .one(db)
+            .await
+    }
+
     pub async fn delete<C: ConnectionTrait>(
         db: &C,
         model: client::ActiveModel,
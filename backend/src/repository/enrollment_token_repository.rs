@@ -0,0 +1,97 @@
+use crate::entity::enrollment_token;
+use crate::util::types::DbResult;
+use chrono::Utc;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DeleteResult, EntityTrait, ModelTrait,
+    QueryFilter, QueryOrder,
+};
+use uuid::Uuid;
+
+pub struct EnrollmentTokenRepository;
+
+impl EnrollmentTokenRepository {
+    pub async fn insert<C: ConnectionTrait>(
+        db: &C,
+        model: enrollment_token::ActiveModel,
+    ) -> DbResult<enrollment_token::Model> {
+        model.insert(db).await
+    }
+
+    pub async fn find_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: &Uuid,
+    ) -> DbResult<Option<enrollment_token::Model>> {
+        enrollment_token::Entity::find_by_id(*id).one(db).await
+    }
+
+    pub async fn find_by_hash<C: ConnectionTrait>(
+        db: &C,
+        token_hash: &str,
+    ) -> DbResult<Option<enrollment_token::Model>> {
+        enrollment_token::Entity::find()
+            .filter(enrollment_token::Column::TokenHash.eq(token_hash))
+            .one(db)
+            .await
+    }
+
+    /// Find all enrollment tokens of a client, newest first
+    pub async fn find_all_by_client<C: ConnectionTrait>(
+        db: &C,
+        client_id: &Uuid,
+    ) -> DbResult<Vec<enrollment_token::Model>> {
+        enrollment_token::Entity::find()
+            .filter(enrollment_token::Column::ClientId.eq(*client_id))
+            .order_by_desc(enrollment_token::Column::CreatedAt)
+            .all(db)
+            .await
+    }
+
+    /// Mark a token as redeemed if it hasn't been redeemed yet.
+    /// Returns whether the token was redeemed by this call, which is decided
+    /// by the database so concurrent redemptions of the same token can't both succeed.
+    pub async fn redeem<C: ConnectionTrait>(
+        db: &C,
+        id: &Uuid,
+        redeemed_from: Option<String>,
+    ) -> DbResult<bool> {
+        let now: DateTimeWithTimeZone = Utc::now().into();
+        let res = enrollment_token::Entity::update_many()
+            .col_expr(enrollment_token::Column::RedeemedAt, Expr::value(Some(now)))
+            .col_expr(
+                enrollment_token::Column::RedeemedFrom,
+                Expr::value(redeemed_from),
+            )
+            .filter(enrollment_token::Column::Id.eq(*id))
+            .filter(enrollment_token::Column::RedeemedAt.is_null())
+            .exec(db)
+            .await?;
+
+        Ok(res.rows_affected == 1)
+    }
+
+    pub async fn set_serial_number<C: ConnectionTrait>(
+        db: &C,
+        id: &Uuid,
+        serial_number: &str,
+    ) -> DbResult<()> {
+        enrollment_token::Entity::update_many()
+            .col_expr(
+                enrollment_token::Column::SerialNumber,
+                Expr::value(Some(serial_number.to_string())),
+            )
+            .filter(enrollment_token::Column::Id.eq(*id))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete<C: ConnectionTrait>(
+        db: &C,
+        model: enrollment_token::Model,
+    ) -> DbResult<DeleteResult> {
+        model.delete(db).await
+    }
+}
@@ -0,0 +1,44 @@
+use crate::entity::log_entry;
+use crate::util::types::DbResult;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+
+pub struct LogEntryRepository;
+
+impl LogEntryRepository {
+    pub async fn insert<C: ConnectionTrait>(
+        db: &C,
+        model: log_entry::ActiveModel,
+    ) -> DbResult<log_entry::Model> {
+        model.insert(db).await
+    }
+
+    pub async fn count<C: ConnectionTrait>(db: &C) -> DbResult<u64> {
+        log_entry::Entity::find().count(db).await
+    }
+
+    /// Find the first `size` entries of the log, ordered by their index
+    pub async fn find_first<C: ConnectionTrait>(
+        db: &C,
+        size: u64,
+    ) -> DbResult<Vec<log_entry::Model>> {
+        log_entry::Entity::find()
+            .order_by_asc(log_entry::Column::LeafIndex)
+            .limit(size)
+            .all(db)
+            .await
+    }
+
+    pub async fn find_by_serial_number<C: ConnectionTrait>(
+        db: &C,
+        serial_number: &str,
+    ) -> DbResult<Option<log_entry::Model>> {
+        log_entry::Entity::find()
+            .filter(log_entry::Column::SerialNumber.eq(serial_number))
+            .order_by_asc(log_entry::Column::LeafIndex)
+            .one(db)
+            .await
+    }
+}
@@ -0,0 +1,47 @@
+use crate::entity::log_head;
+use crate::util::types::DbResult;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveValue, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+
+/// The id of the single row holding the size of the issuance log
+const HEAD_ID: i32 = 1;
+
+pub struct LogHeadRepository;
+
+impl LogHeadRepository {
+    pub async fn find_tree_size<C: ConnectionTrait>(db: &C) -> DbResult<u64> {
+        log_head::Entity::find_by_id(HEAD_ID)
+            .one(db)
+            .await?
+            .map(|head| head.tree_size as u64)
+            .ok_or(DbErr::RecordNotFound("The issuance log head".to_string()))
+    }
+
+    /// Increment the size of the log and return the new size.
+    /// The row stays locked until the transaction ends.
+    pub async fn increment<C: ConnectionTrait>(db: &C) -> DbResult<u64> {
+        let res = log_head::Entity::update_many()
+            .col_expr(
+                log_head::Column::TreeSize,
+                Expr::col(log_head::Column::TreeSize).add(1),
+            )
+            .filter(log_head::Column::Id.eq(HEAD_ID))
+            .exec(db)
+            .await?;
+        if res.rows_affected != 1 {
+            return Err(DbErr::RecordNotFound("The issuance log head".to_string()));
+        }
+
+        Self::find_tree_size(db).await
+    }
+
+    pub async fn set_tree_size<C: ConnectionTrait>(db: &C, tree_size: u64) -> DbResult<()> {
+        log_head::Entity::update(log_head::ActiveModel {
+            id: ActiveValue::Unchanged(HEAD_ID),
+            tree_size: ActiveValue::Set(tree_size as i64),
+        })
+        .exec(db)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::entity::log_node;
+use crate::util::types::DbResult;
+use sea_orm::{ColumnTrait, Condition, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use shared::util::merkle::Node;
+
+/// The number of rows inserted with a single statement
+const INSERT_BATCH_SIZE: usize = 500;
+
+pub struct LogNodeRepository;
+
+impl LogNodeRepository {
+    pub async fn insert_all<C: ConnectionTrait>(
+        db: &C,
+        models: Vec<log_node::ActiveModel>,
+    ) -> DbResult<()> {
+        let mut models = models.into_iter().peekable();
+        while models.peek().is_some() {
+            let batch = models.by_ref().take(INSERT_BATCH_SIZE).collect::<Vec<_>>();
+            log_node::Entity::insert_many(batch)
+                .exec_without_returning(db)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Find the stored subtrees among the given ones
+    pub async fn find_all<C: ConnectionTrait>(
+        db: &C,
+        nodes: &[Node],
+    ) -> DbResult<Vec<log_node::Model>> {
+        if nodes.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let condition = nodes.iter().fold(Condition::any(), |condition, node| {
+            condition.add(
+                Condition::all()
+                    .add(log_node::Column::Level.eq(node.level as i32))
+                    .add(log_node::Column::NodeIndex.eq(node.index as i64)),
+            )
+        });
+
+        log_node::Entity::find().filter(condition).all(db).await
+    }
+
+    pub async fn count<C: ConnectionTrait>(db: &C) -> DbResult<u64> {
+        log_node::Entity::find().count(db).await
+    }
+
+    pub async fn delete_all<C: ConnectionTrait>(db: &C) -> DbResult<u64> {
+        Ok(log_node::Entity::delete_many()
+            .exec(db)
+            .await?
+            .rows_affected)
+    }
+}
@@ -1,7 +1,22 @@
+pub mod approval_request_repository;
+pub mod audit_event_repository;
+pub mod backup_repository;
 pub mod certificate_repository;
 pub mod client_repository;
 pub mod database;
+pub mod enrollment_token_repository;
+pub mod log_entry_repository;
+pub mod log_head_repository;
+pub mod log_node_repository;
+pub mod notification_repository;
+pub mod quota_repository;
 pub mod root_certificate_repository;
 pub mod signing_request_repository;
+pub mod team_member_repository;
+pub mod team_repository;
+pub mod token_key_repository;
 pub mod token_repository;
+pub mod used_signature_repository;
 pub mod user_repository;
+pub mod webhook_delivery_repository;
+pub mod webhook_subscription_repository;
@@ -0,0 +1,48 @@
+use crate::entity::notification;
+use crate::entity::notification::DeliveryStatus;
+use crate::util::types::DbResult;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder,
+};
+
+pub struct NotificationRepository;
+
+impl NotificationRepository {
+    pub async fn insert<C: ConnectionTrait>(
+        db: &C,
+        model: notification::ActiveModel,
+    ) -> DbResult<notification::Model> {
+        model.insert(db).await
+    }
+
+    pub async fn update<C: ConnectionTrait>(
+        db: &C,
+        model: notification::ActiveModel,
+    ) -> DbResult<notification::Model> {
+        model.update(db).await
+    }
+
+    pub async fn find_by_dedup_key<C: ConnectionTrait>(
+        db: &C,
+        dedup_key: &str,
+    ) -> DbResult<Option<notification::Model>> {
+        notification::Entity::find()
+            .filter(notification::Column::DedupKey.eq(dedup_key))
+            .one(db)
+            .await
+    }
+
+    /// Find all notifications which have not been delivered yet
+    /// and have been attempted less than `max_attempts` times
+    pub async fn find_undelivered<C: ConnectionTrait>(
+        db: &C,
+        max_attempts: i32,
+    ) -> DbResult<Vec<notification::Model>> {
+        notification::Entity::find()
+            .filter(notification::Column::Status.ne(DeliveryStatus::Sent))
+            .filter(notification::Column::Attempts.lt(max_attempts))
+            .order_by_asc(notification::Column::CreatedAt)
+            .all(db)
+            .await
+    }
+}
@@ -0,0 +1,47 @@
+use crate::entity::quota;
+use crate::entity::quota::QuotaScope;
+use crate::util::types::DbResult;
+use sea_orm::{
+    ActiveModelTrait, ConnectionTrait, DeleteResult, EntityTrait, ModelTrait, QueryOrder,
+};
+use uuid::Uuid;
+
+pub struct QuotaRepository;
+
+impl QuotaRepository {
+    pub async fn insert<C: ConnectionTrait>(
+        db: &C,
+        model: quota::ActiveModel,
+    ) -> DbResult<quota::Model> {
+        model.insert(db).await
+    }
+
+    pub async fn update<C: ConnectionTrait>(
+        db: &C,
+        model: quota::ActiveModel,
+    ) -> DbResult<quota::Model> {
+        model.update(db).await
+    }
+
+    pub async fn find<C: ConnectionTrait>(
+        db: &C,
+        scope: QuotaScope,
+        subject_id: &Uuid,
+    ) -> DbResult<Option<quota::Model>> {
+        quota::Entity::find_by_id((scope, *subject_id))
+            .one(db)
+            .await
+    }
+
+    pub async fn find_all<C: ConnectionTrait>(db: &C) -> DbResult<Vec<quota::Model>> {
+        quota::Entity::find()
+            .order_by_asc(quota::Column::Scope)
+            .order_by_asc(quota::Column::SubjectId)
+            .all(db)
+            .await
+    }
+
+    pub async fn delete<C: ConnectionTrait>(db: &C, model: quota::Model) -> DbResult<DeleteResult> {
+        model.delete(db).await
+    }
+}
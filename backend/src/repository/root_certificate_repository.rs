@@ -20,4 +20,8 @@ impl RootCertificateRepository {
             .one(db)
             .await
     }
+
+    pub async fn find_all<C: ConnectionTrait>(db: &C) -> DbResult<Vec<root_certificate::Model>> {
+        root_certificate::Entity::find().all(db).await
+    }
 }
@@ -1,11 +1,30 @@
-use crate::entity::{client, signing_request};
+use crate::entity::quota::QuotaScope;
+use crate::entity::{client, log_entry, signing_request};
+use crate::model::signing_request_filter::{
+    SigningRequestFilter, SigningRequestSortField, SortOrder,
+};
+use crate::repository::team_member_repository::TeamMemberRepository;
+use crate::util::pagination::Pagination;
 use crate::util::types::DbResult;
-use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::{Alias, Expr, Query, SelectStatement};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, Condition, ConnectionTrait, EntityTrait,
+    IntoActiveModel, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select,
+};
+use shared::model::certificate_status::CertificateStatus;
 use uuid::Uuid;
 
 pub struct SigningRequestRepository;
 
 impl SigningRequestRepository {
+    pub async fn insert<C: ConnectionTrait>(
+        db: &C,
+        model: signing_request::ActiveModel,
+    ) -> DbResult<signing_request::Model> {
+        model.insert(db).await
+    }
+
     pub async fn find_all_by_client<C: ConnectionTrait>(
         db: &C,
         client_id: &Uuid,
@@ -17,16 +36,297 @@ impl SigningRequestRepository {
             .map_err(|e| e.into())
     }
 
-    pub async fn find_all_by_user<C: ConnectionTrait>(
+    pub async fn find_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+    ) -> DbResult<Option<signing_request::Model>> {
+        signing_request::Entity::find_by_id(id).one(db).await
+    }
+
+    pub async fn find_by_serial_number<C: ConnectionTrait>(
+        db: &C,
+        serial_number: &str,
+    ) -> DbResult<Option<signing_request::Model>> {
+        signing_request::Entity::find()
+            .filter(signing_request::Column::SerialNumber.eq(serial_number))
+            .one(db)
+            .await
+    }
+
+    /// Find all signing requests which have not been appended
+    /// to the issuance log yet, in the order they were issued
+    pub async fn find_unlogged<C: ConnectionTrait>(
         db: &C,
-        user_id: &Uuid,
     ) -> DbResult<Vec<signing_request::Model>> {
         signing_request::Entity::find()
-            .inner_join(client::Entity)
-            .filter(client::Column::UserId.eq(user_id.clone()))
+            .filter(
+                signing_request::Column::Id.not_in_subquery(
+                    Query::select()
+                        .column(log_entry::Column::SigningRequestId)
+                        .from(log_entry::Entity)
+                        .to_owned(),
+                ),
+            )
+            .order_by_asc(signing_request::Column::Id)
             .all(db)
             .await
-            .map_err(|e| e.into())
+    }
+
+    /// Find all certificates which are not revoked and expire between now and the given date.
+    /// Certificates which were renewed, i.e. a newer certificate which is not revoked
+    /// was issued for the same client and subject, are skipped.
+    pub async fn find_expiring<C: ConnectionTrait>(
+        db: &C,
+        before: DateTimeWithTimeZone,
+    ) -> DbResult<Vec<signing_request::Model>> {
+        signing_request::Entity::find()
+            .filter(signing_request::Column::RevokedAt.is_null())
+            .filter(signing_request::Column::ValidUntil.gt(chrono::Utc::now()))
+            .filter(signing_request::Column::ValidUntil.lte(before))
+            .filter(Expr::exists(Self::newer_certificates()).not())
+            .all(db)
+            .await
+    }
+
+    /// The certificates which are not revoked and were issued after the certificate of the
+    /// outer query for the same client and subject. Personal certificates are only
+    /// superseded by the personal certificates of the same user.
+    fn newer_certificates() -> SelectStatement {
+        let newer = Alias::new("newer");
+        let outer = signing_request::Entity;
+
+        Query::select()
+            .expr(Expr::val(1))
+            .from_as(signing_request::Entity, newer.clone())
+            .and_where(
+                Expr::col((newer.clone(), signing_request::Column::SubjectName))
+                    .equals((outer, signing_request::Column::SubjectName)),
+            )
+            .cond_where(
+                Condition::any()
+                    .add(
+                        Expr::col((newer.clone(), signing_request::Column::ClientId))
+                            .equals((outer, signing_request::Column::ClientId)),
+                    )
+                    .add(
+                        Condition::all()
+                            .add(
+                                Expr::col((newer.clone(), signing_request::Column::ClientId))
+                                    .is_null(),
+                            )
+                            .add(Expr::col((outer, signing_request::Column::ClientId)).is_null())
+                            .add(
+                                Expr::col((newer.clone(), signing_request::Column::RequestedBy))
+                                    .equals((outer, signing_request::Column::RequestedBy)),
+                            ),
+                    ),
+            )
+            .and_where(Expr::col((newer.clone(), signing_request::Column::RevokedAt)).is_null())
+            .and_where(
+                Expr::col((newer, signing_request::Column::Id))
+                    .gt(Expr::col((outer, signing_request::Column::Id))),
+            )
+            .to_owned()
+    }
+
+    /// The certificates issued to the clients of a user, the clients of the user's teams
+    /// and the user's personal certificates
+    fn owned_by_user(user_id: &Uuid) -> Condition {
+        Condition::any()
+            .add(
+                Condition::all()
+                    .add(client::Column::TeamId.is_null())
+                    .add(client::Column::UserId.eq(*user_id)),
+            )
+            .add(
+                client::Column::TeamId.in_subquery(TeamMemberRepository::team_ids_of_user(user_id)),
+            )
+            .add(
+                Condition::all()
+                    .add(signing_request::Column::ClientId.is_null())
+                    .add(signing_request::Column::RequestedBy.eq(*user_id)),
+            )
+    }
+
+    /// Find all signing requests matching the given filter. If a user is given,
+    /// only the requests of the user's clients and the user's personal
+    /// certificates are searched.
+    /// Returns the requested page and the total number of matching elements.
+    pub async fn search<C: ConnectionTrait>(
+        db: &C,
+        user_id: Option<&Uuid>,
+        filter: &SigningRequestFilter,
+        pagination: &Pagination,
+    ) -> DbResult<(Vec<signing_request::Model>, u64)> {
+        let now = chrono::Utc::now();
+        let mut condition = Condition::all();
+
+        if let Some(user_id) = user_id {
+            condition = condition.add(Self::owned_by_user(user_id));
+        }
+
+        if let Some(subject_name) = &filter.subject_name {
+            condition = condition.add(signing_request::Column::SubjectName.contains(subject_name));
+        }
+        if let Some(alternative_name) = &filter.alternative_name {
+            condition =
+                condition.add(signing_request::Column::AlternativeNames.contains(alternative_name));
+        }
+        if let Some(serial_number) = &filter.serial_number {
+            condition = condition.add(signing_request::Column::SerialNumber.eq(serial_number));
+        }
+        if let Some(fingerprint) = &filter.fingerprint {
+            condition = condition.add(signing_request::Column::Hash.eq(fingerprint));
+        }
+        if let Some(client_id) = filter.client_id {
+            condition = condition.add(signing_request::Column::ClientId.eq(client_id));
+        }
+        if let Some(issued_after) = filter.issued_after {
+            condition = condition.add(signing_request::Column::IssuedAt.gte(issued_after));
+        }
+        if let Some(issued_before) = filter.issued_before {
+            condition = condition.add(signing_request::Column::IssuedAt.lte(issued_before));
+        }
+        if let Some(expires_after) = filter.expires_after {
+            condition = condition.add(signing_request::Column::ValidUntil.gte(expires_after));
+        }
+        if let Some(expires_before) = filter.expires_before {
+            condition = condition.add(signing_request::Column::ValidUntil.lte(expires_before));
+        }
+        match filter.status {
+            Some(CertificateStatus::Valid) => {
+                condition = condition
+                    .add(signing_request::Column::RevokedAt.is_null())
+                    .add(signing_request::Column::ValidUntil.gte(now));
+            }
+            Some(CertificateStatus::Expired) => {
+                condition = condition
+                    .add(signing_request::Column::RevokedAt.is_null())
+                    .add(signing_request::Column::ValidUntil.lt(now));
+            }
+            Some(CertificateStatus::Revoked) => {
+                condition = condition.add(signing_request::Column::RevokedAt.is_not_null());
+            }
+            // Issued certificates are never pending or rejected, these are approval requests
+            Some(CertificateStatus::Pending) | Some(CertificateStatus::Rejected) => {
+                condition = condition.add(signing_request::Column::Id.is_null());
+            }
+            None => {}
+        }
+
+        let sort_column = match filter.sort_by.unwrap_or(SigningRequestSortField::IssuedAt) {
+            SigningRequestSortField::IssuedAt => signing_request::Column::IssuedAt,
+            SigningRequestSortField::ValidUntil => signing_request::Column::ValidUntil,
+            SigningRequestSortField::SubjectName => signing_request::Column::SubjectName,
+            SigningRequestSortField::SerialNumber => signing_request::Column::SerialNumber,
+        };
+        let order = match filter.sort_order.unwrap_or(SortOrder::Desc) {
+            SortOrder::Asc => Order::Asc,
+            SortOrder::Desc => Order::Desc,
+        };
+
+        let paginator = signing_request::Entity::find()
+            .left_join(client::Entity)
+            .filter(condition)
+            .order_by(sort_column, order)
+            .order_by(signing_request::Column::Id, Order::Asc)
+            .paginate(db, pagination.page_size);
+
+        let total = paginator.num_items().await?;
+        let items = paginator.fetch_page(pagination.page).await?;
+
+        Ok((items, total))
+    }
+
+    /// Select the certificates issued since the given date, either to anyone,
+    /// to a user and the user's clients or to a single client
+    fn issued_since(
+        scope: QuotaScope,
+        subject_id: &Uuid,
+        since: DateTimeWithTimeZone,
+    ) -> Select<signing_request::Entity> {
+        let q =
+            signing_request::Entity::find().filter(signing_request::Column::IssuedAt.gte(since));
+        match scope {
+            QuotaScope::Global => q,
+            QuotaScope::User => q
+                .left_join(client::Entity)
+                .filter(Self::owned_by_user(subject_id)),
+            QuotaScope::Client => q.filter(signing_request::Column::ClientId.eq(*subject_id)),
+        }
+    }
+
+    pub async fn count_issued_since<C: ConnectionTrait>(
+        db: &C,
+        scope: QuotaScope,
+        subject_id: &Uuid,
+        since: DateTimeWithTimeZone,
+    ) -> DbResult<u64> {
+        Self::issued_since(scope, subject_id, since).count(db).await
+    }
+
+    /// Find the `n`-th (zero-based) oldest certificate issued since the given date
+    pub async fn find_nth_issued_since<C: ConnectionTrait>(
+        db: &C,
+        scope: QuotaScope,
+        subject_id: &Uuid,
+        since: DateTimeWithTimeZone,
+        n: u64,
+    ) -> DbResult<Option<signing_request::Model>> {
+        Self::issued_since(scope, subject_id, since)
+            .order_by_asc(signing_request::Column::IssuedAt)
+            .offset(n)
+            .one(db)
+            .await
+    }
+
+    /// Select the certificates of a client which are neither revoked nor expired
+    fn valid_by_client(client_id: &Uuid) -> Select<signing_request::Entity> {
+        signing_request::Entity::find()
+            .filter(signing_request::Column::ClientId.eq(*client_id))
+            .filter(signing_request::Column::RevokedAt.is_null())
+            .filter(signing_request::Column::ValidUntil.gt(chrono::Utc::now()))
+    }
+
+    pub async fn count_valid_by_client<C: ConnectionTrait>(
+        db: &C,
+        client_id: &Uuid,
+    ) -> DbResult<u64> {
+        Self::valid_by_client(client_id).count(db).await
+    }
+
+    /// Find the valid certificate of a client which expires `n`-th (zero-based)
+    pub async fn find_nth_valid_by_client<C: ConnectionTrait>(
+        db: &C,
+        client_id: &Uuid,
+        n: u64,
+    ) -> DbResult<Option<signing_request::Model>> {
+        Self::valid_by_client(client_id)
+            .order_by_asc(signing_request::Column::ValidUntil)
+            .offset(n)
+            .one(db)
+            .await
+    }
+
+    /// Find the valid certificates of a client with the given subject common name
+    pub async fn find_valid_by_client_and_subject<C: ConnectionTrait>(
+        db: &C,
+        client_id: &Uuid,
+        subject_name: &str,
+    ) -> DbResult<Vec<signing_request::Model>> {
+        Self::valid_by_client(client_id)
+            .filter(signing_request::Column::SubjectName.eq(subject_name))
+            .all(db)
+            .await
+    }
+
+    pub async fn revoke<C: ConnectionTrait>(
+        db: &C,
+        model: signing_request::Model,
+    ) -> DbResult<signing_request::Model> {
+        let mut model = model.into_active_model();
+        model.revoked_at = ActiveValue::Set(Some(chrono::Utc::now().into()));
+        model.update(db).await
     }
 
     /*pub async fn delete<C: ConnectionTrait>(
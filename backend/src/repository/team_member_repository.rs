@@ -0,0 +1,63 @@
+use crate::entity::team_member;
+use crate::util::types::DbResult;
+use sea_orm::sea_query::{Query, SelectStatement};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DeleteResult, EntityTrait, ModelTrait,
+    QueryFilter, QueryOrder,
+};
+use uuid::Uuid;
+
+pub struct TeamMemberRepository;
+
+impl TeamMemberRepository {
+    pub async fn insert<C: ConnectionTrait>(
+        db: &C,
+        model: team_member::ActiveModel,
+    ) -> DbResult<team_member::Model> {
+        model.insert(db).await
+    }
+
+    pub async fn update<C: ConnectionTrait>(
+        db: &C,
+        model: team_member::ActiveModel,
+    ) -> DbResult<team_member::Model> {
+        model.update(db).await
+    }
+
+    pub async fn find<C: ConnectionTrait>(
+        db: &C,
+        team_id: &Uuid,
+        user_id: &Uuid,
+    ) -> DbResult<Option<team_member::Model>> {
+        team_member::Entity::find_by_id((*team_id, *user_id))
+            .one(db)
+            .await
+    }
+
+    pub async fn find_all_by_team<C: ConnectionTrait>(
+        db: &C,
+        team_id: &Uuid,
+    ) -> DbResult<Vec<team_member::Model>> {
+        team_member::Entity::find()
+            .filter(team_member::Column::TeamId.eq(*team_id))
+            .order_by_asc(team_member::Column::CreatedAt)
+            .all(db)
+            .await
+    }
+
+    pub async fn delete<C: ConnectionTrait>(
+        db: &C,
+        model: team_member::Model,
+    ) -> DbResult<DeleteResult> {
+        model.delete(db).await
+    }
+
+    /// A query selecting the ids of all teams the user is a member of
+    pub fn team_ids_of_user(user_id: &Uuid) -> SelectStatement {
+        Query::select()
+            .column(team_member::Column::TeamId)
+            .from(team_member::Entity)
+            .and_where(team_member::Column::UserId.eq(*user_id))
+            .to_owned()
+    }
+}
@@ -0,0 +1,84 @@
+use crate::entity::team;
+use crate::repository::team_member_repository::TeamMemberRepository;
+use crate::util::types::DbResult;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DeleteResult, EntityTrait, ModelTrait,
+    QueryFilter, QueryOrder,
+};
+use uuid::Uuid;
+
+pub struct TeamRepository;
+
+impl TeamRepository {
+    pub async fn insert<C: ConnectionTrait>(
+        db: &C,
+        model: team::ActiveModel,
+    ) -> DbResult<team::Model> {
+        model.insert(db).await
+    }
+
+    pub async fn update<C: ConnectionTrait>(
+        db: &C,
+        model: team::ActiveModel,
+    ) -> DbResult<team::Model> {
+        model.update(db).await
+    }
+
+    pub async fn find_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: &Uuid,
+    ) -> DbResult<Option<team::Model>> {
+        team::Entity::find_by_id(*id).one(db).await
+    }
+
+    pub async fn find_by_name<C: ConnectionTrait>(
+        db: &C,
+        name: &str,
+    ) -> DbResult<Option<team::Model>> {
+        team::Entity::find()
+            .filter(team::Column::Name.eq(name))
+            .one(db)
+            .await
+    }
+
+    pub async fn find_by_external_id<C: ConnectionTrait>(
+        db: &C,
+        external_id: &str,
+    ) -> DbResult<Option<team::Model>> {
+        team::Entity::find()
+            .filter(team::Column::ExternalId.eq(external_id))
+            .one(db)
+            .await
+    }
+
+    pub async fn find_all<C: ConnectionTrait>(db: &C) -> DbResult<Vec<team::Model>> {
+        team::Entity::find()
+            .order_by_asc(team::Column::Name)
+            .all(db)
+            .await
+    }
+
+    /// Find all teams synced from identity provider groups
+    pub async fn find_all_synced<C: ConnectionTrait>(db: &C) -> DbResult<Vec<team::Model>> {
+        team::Entity::find()
+            .filter(team::Column::ExternalId.is_not_null())
+            .all(db)
+            .await
+    }
+
+    /// Find all teams the user is a member of
+    pub async fn find_all_by_user<C: ConnectionTrait>(
+        db: &C,
+        user_id: &Uuid,
+    ) -> DbResult<Vec<team::Model>> {
+        team::Entity::find()
+            .filter(team::Column::Id.in_subquery(TeamMemberRepository::team_ids_of_user(user_id)))
+            .order_by_asc(team::Column::Name)
+            .all(db)
+            .await
+    }
+
+    pub async fn delete<C: ConnectionTrait>(db: &C, model: team::Model) -> DbResult<DeleteResult> {
+        model.delete(db).await
+    }
+}
@@ -0,0 +1,66 @@
+use crate::entity::token_key;
+use crate::util::types::DbResult;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DeleteResult, EntityTrait,
+    IntoActiveModel, ModelTrait, QueryFilter, QueryOrder,
+};
+use uuid::Uuid;
+
+pub struct TokenKeyRepository;
+
+impl TokenKeyRepository {
+    pub async fn insert<C: ConnectionTrait>(
+        db: &C,
+        model: token_key::ActiveModel,
+    ) -> DbResult<token_key::Model> {
+        model.insert(db).await
+    }
+
+    pub async fn find_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: &Uuid,
+    ) -> DbResult<Option<token_key::Model>> {
+        token_key::Entity::find_by_id(*id).one(db).await
+    }
+
+    pub async fn find_active<C: ConnectionTrait>(db: &C) -> DbResult<Option<token_key::Model>> {
+        token_key::Entity::find()
+            .filter(token_key::Column::Active.eq(true))
+            .order_by_desc(token_key::Column::CreatedAt)
+            .one(db)
+            .await
+    }
+
+    /// Find all keys, the newest first
+    pub async fn find_all<C: ConnectionTrait>(db: &C) -> DbResult<Vec<token_key::Model>> {
+        token_key::Entity::find()
+            .order_by_desc(token_key::Column::CreatedAt)
+            .all(db)
+            .await
+    }
+
+    /// Stop signing new tokens with the currently active keys
+    pub async fn retire_all<C: ConnectionTrait>(db: &C) -> DbResult<Vec<token_key::Model>> {
+        let keys = token_key::Entity::find()
+            .filter(token_key::Column::Active.eq(true))
+            .all(db)
+            .await?;
+
+        let mut retired = Vec::with_capacity(keys.len());
+        for key in keys {
+            let mut key = key.into_active_model();
+            key.active = ActiveValue::Set(false);
+            key.retired_at = ActiveValue::Set(Some(chrono::Utc::now().into()));
+            retired.push(key.update(db).await?);
+        }
+
+        Ok(retired)
+    }
+
+    pub async fn delete<C: ConnectionTrait>(
+        db: &C,
+        model: token_key::Model,
+    ) -> DbResult<DeleteResult> {
+        model.delete(db).await
+    }
+}
@@ -1,9 +1,12 @@
 use crate::entity::token;
+use crate::util::pagination::Pagination;
 use crate::util::types::DbResult;
 use futures_util::future::join_all;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::Expr;
 use sea_orm::{
-    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DeleteResult, EntityTrait,
-    IntoActiveModel, ModelTrait, QueryFilter,
+    ActiveModelTrait, ActiveValue, ColumnTrait, Condition, ConnectionTrait, DeleteResult,
+    EntityTrait, IntoActiveModel, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder,
 };
 use uuid::Uuid;
 
@@ -26,6 +29,68 @@ impl TokenRepository {
         q.one(db).await
     }
 
+    pub async fn find_by_hash<C: ConnectionTrait>(
+        db: &C,
+        token_hash: &str,
+    ) -> DbResult<Option<token::Model>> {
+        token::Entity::find()
+            .filter(token::Column::TokenHash.eq(token_hash))
+            .one(db)
+            .await
+    }
+
+    /// Find a page of all tokens, optionally only those
+    /// which haven't been used since the given time
+    pub async fn find_page<C: ConnectionTrait>(
+        db: &C,
+        include_inactive: bool,
+        unused_since: Option<DateTimeWithTimeZone>,
+        pagination: &Pagination,
+    ) -> DbResult<(Vec<token::Model>, u64)> {
+        let mut q = token::Entity::find();
+        if !include_inactive {
+            q = q.filter(token::Column::Active.eq(true));
+        }
+        if let Some(since) = unused_since {
+            q = q.filter(
+                Condition::any()
+                    .add(token::Column::LastUsedAt.is_null())
+                    .add(token::Column::LastUsedAt.lt(since)),
+            );
+        }
+
+        let paginator = q
+            .order_by_asc(token::Column::LastUsedAt)
+            .order_by_asc(token::Column::Id)
+            .paginate(db, pagination.page_size);
+        let total = paginator.num_items().await?;
+        Ok((paginator.fetch_page(pagination.page).await?, total))
+    }
+
+    /// Record that a token has been used to authenticate a request
+    pub async fn touch<C: ConnectionTrait>(
+        db: &C,
+        id: &Uuid,
+        used_from: Option<String>,
+    ) -> DbResult<()> {
+        let now: DateTimeWithTimeZone = chrono::Utc::now().into();
+        token::Entity::update_many()
+            .col_expr(token::Column::LastUsedAt, Expr::value(Some(now)))
+            .col_expr(token::Column::LastUsedFrom, Expr::value(used_from))
+            .filter(token::Column::Id.eq(*id))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn revoke<C: ConnectionTrait>(db: &C, model: token::Model) -> DbResult<token::Model> {
+        let mut model = model.into_active_model();
+        model.active = ActiveValue::Set(false);
+        model.revoked_at = ActiveValue::Set(Some(chrono::Utc::now().into()));
+        model.update(db).await
+    }
+
     pub async fn find_all_by_client<C>(
         db: &C,
         client_id: &Uuid,
@@ -0,0 +1,37 @@
+use crate::entity::used_signature;
+use crate::util::types::DbResult;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+
+pub struct UsedSignatureRepository;
+
+impl UsedSignatureRepository {
+    /// Insert the signature if it hasn't been used before.
+    /// Returns whether it has been inserted.
+    pub async fn insert<C: ConnectionTrait>(
+        db: &C,
+        model: used_signature::ActiveModel,
+    ) -> DbResult<bool> {
+        let inserted = used_signature::Entity::insert(model)
+            .on_conflict(
+                OnConflict::columns([
+                    used_signature::Column::CertificateHash,
+                    used_signature::Column::SignedAt,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await?;
+
+        Ok(inserted > 0)
+    }
+
+    pub async fn delete_signed_before<C: ConnectionTrait>(db: &C, timestamp: i64) -> DbResult<u64> {
+        Ok(used_signature::Entity::delete_many()
+            .filter(used_signature::Column::SignedAt.lt(timestamp))
+            .exec(db)
+            .await?
+            .rows_affected)
+    }
+}
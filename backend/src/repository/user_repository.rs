@@ -1,8 +1,9 @@
 use crate::entity::user;
+use crate::util::pagination::Pagination;
 use crate::util::types::DbResult;
 use sea_orm::{
     ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DeleteResult, EntityTrait,
-    ModelTrait, QueryFilter,
+    ModelTrait, PaginatorTrait, QueryFilter, QueryOrder,
 };
 use uuid::Uuid;
 
@@ -34,16 +35,21 @@ impl UserRepository {
         q.one(db).await
     }
 
-    pub async fn find_all<C: ConnectionTrait>(
+    pub async fn find_page<C: ConnectionTrait>(
         db: &C,
         include_inactive: bool,
-    ) -> DbResult<Vec<user::Model>> {
+        pagination: &Pagination,
+    ) -> DbResult<(Vec<user::Model>, u64)> {
         let mut q = user::Entity::find();
         if !include_inactive {
             q = q.filter(user::Column::Active.eq(true));
         }
 
-        q.all(db).await
+        let paginator = q
+            .order_by_asc(user::Column::Name)
+            .paginate(db, pagination.page_size);
+        let total = paginator.num_items().await?;
+        Ok((paginator.fetch_page(pagination.page).await?, total))
     }
 
     pub async fn find_by_external_id<C: ConnectionTrait>(
@@ -69,7 +75,18 @@ impl UserRepository {
     ) -> DbResult<user::ActiveModel> {
         model.active = ActiveValue::Set(false);
         model.name = ActiveValue::Set(format!("{}-{}", model.name.as_ref(), Uuid::new_v4()));
-        model.external_id = ActiveValue::Set(None);
+
+        model.save(db).await
+    }
+
+    /// Reactivate a disabled user under its original name.
+    /// The clients disabled with the user stay disabled.
+    pub async fn enable<C: ConnectionTrait>(
+        db: &C,
+        mut model: user::ActiveModel,
+    ) -> DbResult<user::ActiveModel> {
+        model.active = ActiveValue::Set(true);
+        model.name = ActiveValue::Set(model.original_name.as_ref().clone());
 
         model.save(db).await
     }
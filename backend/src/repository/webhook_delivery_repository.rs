@@ -0,0 +1,60 @@
+use crate::entity::notification::DeliveryStatus;
+use crate::entity::webhook_delivery;
+use crate::util::pagination::Pagination;
+use crate::util::types::DbResult;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder,
+};
+use uuid::Uuid;
+
+pub struct WebhookDeliveryRepository;
+
+impl WebhookDeliveryRepository {
+    pub async fn insert<C: ConnectionTrait>(
+        db: &C,
+        model: webhook_delivery::ActiveModel,
+    ) -> DbResult<webhook_delivery::Model> {
+        model.insert(db).await
+    }
+
+    pub async fn update<C: ConnectionTrait>(
+        db: &C,
+        model: webhook_delivery::ActiveModel,
+    ) -> DbResult<webhook_delivery::Model> {
+        model.update(db).await
+    }
+
+    /// Find all deliveries which have not succeeded yet,
+    /// are due for another attempt and have been attempted
+    /// less than `max_attempts` times
+    pub async fn find_due<C: ConnectionTrait>(
+        db: &C,
+        now: DateTimeWithTimeZone,
+        max_attempts: i32,
+    ) -> DbResult<Vec<webhook_delivery::Model>> {
+        webhook_delivery::Entity::find()
+            .filter(webhook_delivery::Column::Status.ne(DeliveryStatus::Sent))
+            .filter(webhook_delivery::Column::Attempts.lt(max_attempts))
+            .filter(webhook_delivery::Column::NextAttemptAt.lte(now))
+            .order_by_asc(webhook_delivery::Column::CreatedAt)
+            .all(db)
+            .await
+    }
+
+    pub async fn find_page_by_subscription<C: ConnectionTrait>(
+        db: &C,
+        subscription_id: &Uuid,
+        pagination: &Pagination,
+    ) -> DbResult<(Vec<webhook_delivery::Model>, u64)> {
+        let paginator = webhook_delivery::Entity::find()
+            .filter(webhook_delivery::Column::SubscriptionId.eq(*subscription_id))
+            .order_by_desc(webhook_delivery::Column::CreatedAt)
+            .paginate(db, pagination.page_size);
+
+        let total = paginator.num_items().await?;
+        let items = paginator.fetch_page(pagination.page).await?;
+        Ok((items, total))
+    }
+}
@@ -0,0 +1,62 @@
+use crate::entity::webhook_subscription;
+use crate::util::types::DbResult;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DeleteResult, EntityTrait,
+    ModelTrait, QueryFilter, QueryOrder,
+};
+use uuid::Uuid;
+
+pub struct WebhookSubscriptionRepository;
+
+impl WebhookSubscriptionRepository {
+    pub async fn insert<C: ConnectionTrait>(
+        db: &C,
+        model: webhook_subscription::ActiveModel,
+    ) -> DbResult<webhook_subscription::Model> {
+        model.insert(db).await
+    }
+
+    pub async fn find_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: &Uuid,
+    ) -> DbResult<Option<webhook_subscription::Model>> {
+        webhook_subscription::Entity::find_by_id(*id).one(db).await
+    }
+
+    pub async fn find_all_by_user<C: ConnectionTrait>(
+        db: &C,
+        user_id: &Uuid,
+    ) -> DbResult<Vec<webhook_subscription::Model>> {
+        webhook_subscription::Entity::find()
+            .filter(webhook_subscription::Column::UserId.eq(*user_id))
+            .order_by_asc(webhook_subscription::Column::CreatedAt)
+            .all(db)
+            .await
+    }
+
+    /// Find all active subscriptions which receive the events
+    /// of the given user. Global subscriptions receive all events,
+    /// events without an owner are only delivered to global subscriptions.
+    pub async fn find_active_by_owner<C: ConnectionTrait>(
+        db: &C,
+        owner: Option<&Uuid>,
+    ) -> DbResult<Vec<webhook_subscription::Model>> {
+        let mut condition = Condition::any().add(webhook_subscription::Column::Global.eq(true));
+        if let Some(owner) = owner {
+            condition = condition.add(webhook_subscription::Column::UserId.eq(*owner));
+        }
+
+        webhook_subscription::Entity::find()
+            .filter(webhook_subscription::Column::Active.eq(true))
+            .filter(condition)
+            .all(db)
+            .await
+    }
+
+    pub async fn delete<C: ConnectionTrait>(
+        db: &C,
+        model: webhook_subscription::Model,
+    ) -> DbResult<DeleteResult> {
+        model.delete(db).await
+    }
+}
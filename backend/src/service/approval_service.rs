@@ -0,0 +1,259 @@
+use crate::entity::approval_request::ApprovalStatus;
+use crate::entity::{approval_request, client, signing_request};
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::repository::approval_request_repository::ApprovalRequestRepository;
+use crate::service::signing_request_service::SigningRequestService;
+use crate::util::pagination::Pagination;
+use crate::util::types::WebResult;
+use chrono::Utc;
+use openssl::x509::X509Req;
+use sea_orm::{
+    ActiveValue, ConnectionTrait, DatabaseConnection, IntoActiveModel, TransactionTrait,
+};
+use shared::util::traits::u8_vec_to_string::U8VecToString;
+use uuid::Uuid;
+
+/// Whether two signing requests are for the same key and alternative names
+fn is_same_request(
+    pending: &approval_request::Model,
+    req: &X509Req,
+    alternative_names: &Option<String>,
+) -> bool {
+    let pending_key = X509Req::from_pem(pending.request.as_bytes())
+        .and_then(|r| r.public_key())
+        .ok();
+    match (pending_key, req.public_key().ok()) {
+        (Some(a), Some(b)) => a.public_eq(&b) && &pending.alternative_names == alternative_names,
+        _ => false,
+    }
+}
+
+/// Manages the signing requests of clients which require the approval of an admin
+pub struct ApprovalService(DatabaseConnection);
+
+impl ApprovalService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self(db)
+    }
+
+    pub async fn find_by_id(&self, id: i32) -> WebResult<Option<approval_request::Model>> {
+        ApprovalRequestRepository::find_by_id(&self.0, id)
+            .await
+            .map_internal_error(Some("Failed to find approval request"))
+    }
+
+    pub async fn find_page(
+        &self,
+        status: Option<ApprovalStatus>,
+        pagination: &Pagination,
+    ) -> WebResult<(Vec<approval_request::Model>, u64)> {
+        ApprovalRequestRepository::find_page(&self.0, status, pagination)
+            .await
+            .map_internal_error(Some("Failed to find approval requests"))
+    }
+
+    /// Store a signing request until it is approved.
+    /// A client only has a single pending request: if the client submits the same
+    /// request again the pending one is returned, other pending requests are superseded.
+    pub async fn submit(
+        &self,
+        client: &client::Model,
+        requested_by: Option<Uuid>,
+        req: &X509Req,
+        subject_name: String,
+        alternative_names: &Option<Vec<String>>,
+    ) -> WebResult<approval_request::Model> {
+        Self::submit_in(
+            &self.0,
+            client,
+            requested_by,
+            req,
+            subject_name,
+            alternative_names,
+        )
+        .await
+    }
+
+    /// Store a signing request until it is approved using the given connection,
+    /// e.g. in the transaction redeeming an enrollment token. See [`Self::submit`].
+    pub async fn submit_in<C: ConnectionTrait>(
+        db: &C,
+        client: &client::Model,
+        requested_by: Option<Uuid>,
+        req: &X509Req,
+        subject_name: String,
+        alternative_names: &Option<Vec<String>>,
+    ) -> WebResult<approval_request::Model> {
+        let alternative_names = alternative_names
+            .as_ref()
+            .filter(|names| !names.is_empty())
+            .map(|names| names.join(","));
+
+        let pending = ApprovalRequestRepository::find_pending_by_client(db, &client.id)
+            .await
+            .map_internal_error(Some("Failed to find approval requests"))?;
+        for model in pending {
+            if is_same_request(&model, req, &alternative_names) {
+                return Ok(model);
+            }
+
+            let mut model = model.into_active_model();
+            model.status = ActiveValue::Set(ApprovalStatus::Rejected);
+            model.decided_at = ActiveValue::Set(Some(Utc::now().into()));
+            model.reason = ActiveValue::Set(Some("Superseded by a newer request".to_string()));
+            ApprovalRequestRepository::update(db, model)
+                .await
+                .map_internal_error(Some("Failed to update approval request"))?;
+        }
+
+        ApprovalRequestRepository::insert(
+            db,
+            approval_request::ActiveModel {
+                client_id: ActiveValue::Set(client.id),
+                requested_by: ActiveValue::Set(requested_by),
+                request: ActiveValue::Set(
+                    req.to_pem()
+                        .map_internal_error(Some("Failed to encode signing request"))?
+                        .to_string(),
+                ),
+                subject_name: ActiveValue::Set(subject_name),
+                alternative_names: ActiveValue::Set(alternative_names),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_internal_error(Some("Failed to store approval request"))
+    }
+
+    /// Record the decision on a pending request,
+    /// fails if the request has already been decided
+    async fn decide<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        status: ApprovalStatus,
+        admin_id: &Uuid,
+        reason: Option<String>,
+    ) -> WebResult<()> {
+        if ApprovalRequestRepository::decide(db, id, status, admin_id, reason)
+            .await
+            .map_internal_error(Some("Failed to update approval request"))?
+        {
+            Ok(())
+        } else {
+            Err(HttpResponseError::bad_request(Some(
+                "The request has already been decided",
+            )))
+        }
+    }
+
+    /// Approve a pending request and store the certificate issued for it in a single
+    /// transaction. Nothing is stored if the request has been decided in the meantime.
+    /// Returns the approved request and the stored certificate.
+    pub async fn approve(
+        &self,
+        id: i32,
+        admin_id: &Uuid,
+        issued: signing_request::ActiveModel,
+        certificate: String,
+    ) -> WebResult<(approval_request::Model, signing_request::Model)> {
+        let txn = self
+            .0
+            .begin()
+            .await
+            .map_internal_error(Some("Failed to start transaction"))?;
+        Self::decide(&txn, id, ApprovalStatus::Approved, admin_id, None).await?;
+
+        let issued = SigningRequestService::insert_logged(&txn, issued)
+            .await
+            .map_internal_error(Some("Failed to save the issued certificate"))?;
+        let mut model = ApprovalRequestRepository::find_by_id(&txn, id)
+            .await
+            .map_internal_error(Some("Failed to find approval request"))?
+            .ok_or(HttpResponseError::not_found(Some(
+                "Approval request not found",
+            )))?
+            .into_active_model();
+        model.signing_request_id = ActiveValue::Set(Some(issued.id));
+        model.certificate = ActiveValue::Set(Some(certificate));
+        let model = ApprovalRequestRepository::update(&txn, model)
+            .await
+            .map_internal_error(Some("Failed to update approval request"))?;
+
+        txn.commit()
+            .await
+            .map_internal_error(Some("Failed to approve the request"))?;
+        Ok((model, issued))
+    }
+
+    pub async fn reject(
+        &self,
+        id: i32,
+        admin_id: &Uuid,
+        reason: Option<String>,
+    ) -> WebResult<approval_request::Model> {
+        Self::decide(&self.0, id, ApprovalStatus::Rejected, admin_id, reason).await?;
+        self.find_by_id(id)
+            .await?
+            .ok_or(HttpResponseError::not_found(Some(
+                "Approval request not found",
+            )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::log_head_repository::LogHeadRepository;
+    use crate::util::testing::{connect, insert_client, issued};
+    use sea_orm::{EntityTrait, PaginatorTrait};
+
+    #[actix_web::test]
+    async fn decides_a_request_only_once() {
+        let db = connect().await;
+        let (_, client) = insert_client(&db, true).await;
+        let request = ApprovalRequestRepository::insert(
+            &db,
+            approval_request::ActiveModel {
+                client_id: ActiveValue::Set(client.id),
+                request: ActiveValue::Set("request".to_string()),
+                subject_name: ActiveValue::Set("client".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let service = ApprovalService::new(db.clone());
+        let admin_id = Uuid::new_v4();
+        let (approved, certificate) = service
+            .approve(
+                request.id,
+                &admin_id,
+                issued(Some(client.id), 1),
+                "pem".to_string(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(approved.status, ApprovalStatus::Approved);
+        assert_eq!(approved.decided_by, Some(admin_id));
+        assert_eq!(approved.signing_request_id, Some(certificate.id));
+
+        assert!(service
+            .approve(
+                request.id,
+                &admin_id,
+                issued(Some(client.id), 2),
+                "pem".to_string()
+            )
+            .await
+            .is_err());
+        assert!(service.reject(request.id, &admin_id, None).await.is_err());
+
+        assert_eq!(signing_request::Entity::find().count(&db).await.unwrap(), 1);
+        assert_eq!(LogHeadRepository::find_tree_size(&db).await.unwrap(), 1);
+        assert_eq!(
+            service.find_by_id(request.id).await.unwrap(),
+            Some(approved)
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use crate::entity::audit_event;
+use crate::error::http_response_error::MapHttpResponseError;
+use crate::model::audit_event_filter::AuditEventFilter;
+use crate::repository::audit_event_repository::AuditEventRepository;
+use crate::util::pagination::Pagination;
+use crate::util::types::WebResult;
+use log::error;
+use sea_orm::DatabaseConnection;
+
+pub struct AuditService(DatabaseConnection);
+
+impl AuditService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self(db)
+    }
+
+    /// Append an event to the audit log.
+    /// Failures are logged as the request has already been processed.
+    pub async fn record(&self, model: audit_event::ActiveModel) {
+        if let Err(e) = AuditEventRepository::insert(&self.0, model).await {
+            error!("Failed to record audit event: {}", e);
+        }
+    }
+
+    pub async fn find_page(
+        &self,
+        filter: &AuditEventFilter,
+        pagination: &Pagination,
+    ) -> WebResult<(Vec<audit_event::Model>, u64)> {
+        AuditEventRepository::find_page(&self.0, filter, pagination)
+            .await
+            .map_internal_error(Some("Failed to find audit events"))
+    }
+
+    pub async fn find_batch(
+        &self,
+        filter: &AuditEventFilter,
+        after: Option<i32>,
+        limit: u64,
+    ) -> WebResult<Vec<audit_event::Model>> {
+        AuditEventRepository::find_batch(&self.0, filter, after, limit)
+            .await
+            .map_internal_error(Some("Failed to find audit events"))
+    }
+}
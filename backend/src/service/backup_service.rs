@@ -0,0 +1,511 @@
+use crate::entity::{
+    certificate, client, log_entry, root_certificate, signing_request, team, team_member, token,
+    token_key, user,
+};
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::model::backup_archive_dto::BackupArchiveDto;
+use crate::model::backup_data::{
+    BackupData, IntermediateCertificateRecord, LogEntryRecord, RootCertificateRecord,
+};
+use crate::model::backup_summary_dto::BackupSummaryDto;
+use crate::repository::backup_repository::BackupRepository;
+use crate::repository::team_repository::TeamRepository;
+use crate::repository::token_key_repository::TokenKeyRepository;
+use crate::repository::user_repository::UserRepository;
+use crate::service::transparency_log_service;
+use crate::service::transparency_log_service::leaf_hash;
+use crate::util::backup_cipher;
+use crate::util::ca_store::CAStore;
+use crate::util::types::WebResult;
+use openssl::pkey::PKey;
+use openssl::x509::X509;
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use shared::util::traits::u8_vec_to_string::U8VecToString;
+use shared::util::types::BasicResult;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// The minimum length of the passphrase a backup is encrypted with
+const MIN_BACKUP_PASSPHRASE_LENGTH: usize = 12;
+
+/// The active-model form of the backup content, ready to be inserted
+struct RestoreModels {
+    users: Vec<user::ActiveModel>,
+    teams: Vec<team::ActiveModel>,
+    team_members: Vec<team_member::ActiveModel>,
+    clients: Vec<client::ActiveModel>,
+    tokens: Vec<token::ActiveModel>,
+    token_keys: Vec<token_key::ActiveModel>,
+    root_certificates: Vec<root_certificate::ActiveModel>,
+    intermediate_certificates: Vec<certificate::ActiveModel>,
+    signing_requests: Vec<signing_request::ActiveModel>,
+    log_entries: Vec<log_entry::ActiveModel>,
+}
+
+fn verify_root_certificates(roots: &[RootCertificateRecord]) -> BasicResult<()> {
+    for root in roots {
+        let cert = X509::from_pem(root.certificate.as_bytes())?;
+        let public_key = cert.public_key()?;
+        if !cert.verify(&public_key)? {
+            return Err(format!("Root certificate {} is not self-signed", root.id).into());
+        }
+    }
+
+    if roots.iter().filter(|r| r.active).count() > 1 {
+        return Err("The backup contains more than one active root certificate".into());
+    }
+
+    Ok(())
+}
+
+fn verify_intermediate_certificates(
+    roots: &[RootCertificateRecord],
+    intermediates: &[IntermediateCertificateRecord],
+) -> BasicResult<()> {
+    let store = CAStore::from_pems(roots.iter().map(|r| r.certificate.as_bytes()))?;
+    let active_root = CAStore::from_pems(
+        roots
+            .iter()
+            .filter(|r| r.active)
+            .map(|r| r.certificate.as_bytes()),
+    )?;
+
+    for intermediate in intermediates {
+        let cert = X509::from_pem(intermediate.certificate.as_bytes())?;
+        let verification = if intermediate.active {
+            active_root.verify(&cert, &[], None)?
+        } else {
+            store.verify(&cert, &[], None)?
+        };
+
+        if !verification.valid {
+            return Err(format!(
+                "Intermediate certificate {} does not chain to {} root certificate: {}",
+                intermediate.id,
+                if intermediate.active {
+                    "the active"
+                } else {
+                    "a"
+                },
+                verification.error.unwrap_or_default()
+            )
+            .into());
+        }
+
+        match &intermediate.private_key {
+            Some(key) => {
+                let key = PKey::private_key_from_pem(key.as_bytes())?;
+                let public_key = cert.public_key()?;
+                if !key.public_eq(&public_key) {
+                    return Err(format!(
+                        "The key of intermediate certificate {} does not match the certificate",
+                        intermediate.id
+                    )
+                    .into());
+                }
+            }
+            None if intermediate.active => {
+                return Err("The active intermediate certificate has no private key".into())
+            }
+            None => {}
+        }
+    }
+
+    if intermediates.iter().filter(|i| i.active).count() > 1 {
+        return Err("The backup contains more than one active intermediate certificate".into());
+    }
+
+    Ok(())
+}
+
+fn verify_token_keys(data: &BackupData) -> BasicResult<()> {
+    for key in &data.token_keys {
+        let private_key = PKey::private_key_from_pem(key.private_key.as_bytes())?;
+        let public_key = PKey::public_key_from_pem(key.public_key.as_bytes())?;
+        if !private_key.public_eq(&public_key) {
+            return Err(format!("The key pair of token key {} does not match", key.id).into());
+        }
+    }
+
+    if data.token_keys.iter().filter(|k| k.active).count() > 1 {
+        return Err("The backup contains more than one active token key".into());
+    }
+
+    Ok(())
+}
+
+fn verify_references(data: &BackupData) -> BasicResult<()> {
+    let users = data.users.iter().map(|u| u.id).collect::<HashSet<_>>();
+    let clients = data.clients.iter().map(|c| c.id).collect::<HashSet<_>>();
+
+    let teams = data.teams.iter().map(|t| t.id).collect::<HashSet<_>>();
+
+    if let Some(client) = data.clients.iter().find(|c| !users.contains(&c.user_id)) {
+        return Err(format!("The owner of client {} is missing", client.id).into());
+    }
+
+    if let Some(client) = data
+        .clients
+        .iter()
+        .find(|c| matches!(c.team_id, Some(id) if !teams.contains(&id)))
+    {
+        return Err(format!("The team of client {} is missing", client.id).into());
+    }
+
+    if let Some(member) = data
+        .team_members
+        .iter()
+        .find(|m| !teams.contains(&m.team_id) || !users.contains(&m.user_id))
+    {
+        return Err(format!(
+            "The team or user of the membership of user {} in team {} is missing",
+            member.user_id, member.team_id
+        )
+        .into());
+    }
+
+    if let Some(root) = data
+        .root_certificates
+        .iter()
+        .find(|r| !users.contains(&r.created_by))
+    {
+        return Err(format!("The creator of root certificate {} is missing", root.id).into());
+    }
+
+    if let Some(token) = data.tokens.iter().find(|t| !clients.contains(&t.client_id)) {
+        return Err(format!("The client of token {} is missing", token.id).into());
+    }
+
+    if let Some(req) = data
+        .signing_requests
+        .iter()
+        .find(|r| matches!(r.client_id, Some(id) if !clients.contains(&id)))
+    {
+        return Err(format!("The client of signing request {} is missing", req.id).into());
+    }
+
+    Ok(())
+}
+
+/// Verify that the issuance log is complete and matches the issued certificates
+fn verify_log(data: &BackupData) -> BasicResult<()> {
+    let requests = data
+        .signing_requests
+        .iter()
+        .map(|r| (r.id, r))
+        .collect::<HashMap<_, _>>();
+
+    let mut entries = data.log_entries.iter().collect::<Vec<&LogEntryRecord>>();
+    entries.sort_by_key(|e| e.leaf_index);
+
+    for (index, entry) in entries.into_iter().enumerate() {
+        if entry.leaf_index != index as i64 {
+            return Err(format!("The issuance log is missing the entry {}", index).into());
+        }
+
+        let req = requests.get(&entry.signing_request_id).ok_or_else(|| {
+            format!(
+                "The certificate of log entry {} is missing",
+                entry.leaf_index
+            )
+        })?;
+
+        if req.serial_number != entry.serial_number
+            || leaf_hash(&req.hash)?.to_vec().to_hex_string("") != entry.leaf_hash
+        {
+            return Err(format!(
+                "Log entry {} does not match its certificate",
+                entry.leaf_index
+            )
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Verify the consistency of a backup before anything is restored
+fn verify_backup(data: &BackupData) -> BasicResult<()> {
+    verify_root_certificates(&data.root_certificates)?;
+    verify_intermediate_certificates(&data.root_certificates, &data.intermediate_certificates)?;
+    verify_token_keys(data)?;
+    verify_references(data)?;
+    verify_log(data)
+}
+
+/// Exports and restores the entire state of the CA
+pub struct BackupService(DatabaseConnection);
+
+impl BackupService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self(db)
+    }
+
+    async fn read_data(&self) -> BasicResult<BackupData> {
+        let db = &self.0;
+        Ok(BackupData {
+            users: BackupRepository::find_all::<user::Entity, _>(db)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            teams: BackupRepository::find_all::<team::Entity, _>(db)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            team_members: BackupRepository::find_all::<team_member::Entity, _>(db)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            clients: BackupRepository::find_all::<client::Entity, _>(db)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            tokens: BackupRepository::find_all::<token::Entity, _>(db)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            token_keys: BackupRepository::find_all::<token_key::Entity, _>(db)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            root_certificates: BackupRepository::find_all::<root_certificate::Entity, _>(db)
+                .await?
+                .into_iter()
+                .map(RootCertificateRecord::from_model)
+                .collect::<BasicResult<_>>()?,
+            intermediate_certificates: BackupRepository::find_all::<certificate::Entity, _>(db)
+                .await?
+                .into_iter()
+                .map(IntermediateCertificateRecord::from_model)
+                .collect::<BasicResult<_>>()?,
+            signing_requests: BackupRepository::find_all::<signing_request::Entity, _>(db)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            log_entries: BackupRepository::find_all::<log_entry::Entity, _>(db)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        })
+    }
+
+    /// Export the entire state of the CA into an archive encrypted with the passphrase
+    pub async fn export(&self, passphrase: &str) -> WebResult<BackupArchiveDto> {
+        if passphrase.chars().count() < MIN_BACKUP_PASSPHRASE_LENGTH {
+            return Err(HttpResponseError::bad_request(Some(format!(
+                "The passphrase must be at least {} characters long",
+                MIN_BACKUP_PASSPHRASE_LENGTH
+            ))));
+        }
+
+        let data = self
+            .read_data()
+            .await
+            .map_internal_error(Some("Failed to read the CA state"))?;
+        let content =
+            serde_json::to_vec(&data).map_internal_error(Some("Failed to encode the backup"))?;
+
+        backup_cipher::encrypt(passphrase, &content)
+            .map_internal_error(Some("Failed to encrypt the backup"))
+    }
+
+    async fn is_empty(&self) -> BasicResult<bool> {
+        let db = &self.0;
+        Ok(
+            BackupRepository::is_empty::<root_certificate::Entity, _>(db).await?
+                && BackupRepository::is_empty::<certificate::Entity, _>(db).await?
+                && BackupRepository::is_empty::<client::Entity, _>(db).await?
+                && BackupRepository::is_empty::<signing_request::Entity, _>(db).await?
+                && BackupRepository::is_empty::<log_entry::Entity, _>(db).await?,
+        )
+    }
+
+    /// Map the ids of the users in the backup to the ids of existing users with
+    /// the same name. Users which don't exist yet keep their id and are restored.
+    async fn map_users(&self, data: &BackupData) -> BasicResult<(HashMap<Uuid, Uuid>, Vec<Uuid>)> {
+        let mut mapping = HashMap::new();
+        let mut restored = vec![];
+        for record in &data.users {
+            match UserRepository::find_by_name(&self.0, &record.name, true).await? {
+                Some(existing) => {
+                    mapping.insert(record.id, existing.id);
+                }
+                None => restored.push(record.id),
+            }
+        }
+
+        Ok((mapping, restored))
+    }
+
+    /// Map the ids of the teams in the backup to the ids of existing teams with the
+    /// same name. Teams which don't exist yet are restored including their members.
+    async fn map_teams(&self, data: &BackupData) -> BasicResult<(HashMap<Uuid, Uuid>, Vec<Uuid>)> {
+        let mut mapping = HashMap::new();
+        let mut restored = vec![];
+        for record in &data.teams {
+            match TeamRepository::find_by_name(&self.0, &record.name).await? {
+                Some(existing) => {
+                    mapping.insert(record.id, existing.id);
+                }
+                None => restored.push(record.id),
+            }
+        }
+
+        Ok((mapping, restored))
+    }
+
+    fn to_models(
+        data: &BackupData,
+        (user_ids, restored_users): &(HashMap<Uuid, Uuid>, Vec<Uuid>),
+        (team_ids, restored_teams): &(HashMap<Uuid, Uuid>, Vec<Uuid>),
+    ) -> BasicResult<RestoreModels> {
+        let user_id = |id: &Uuid| *user_ids.get(id).unwrap_or(id);
+        let team_id = |id: &Uuid| *team_ids.get(id).unwrap_or(id);
+
+        Ok(RestoreModels {
+            users: data
+                .users
+                .iter()
+                .filter(|u| restored_users.contains(&u.id))
+                .map(|u| u.to_active_model())
+                .collect::<BasicResult<_>>()?,
+            teams: data
+                .teams
+                .iter()
+                .filter(|t| restored_teams.contains(&t.id))
+                .map(|t| t.to_active_model())
+                .collect::<BasicResult<_>>()?,
+            team_members: data
+                .team_members
+                .iter()
+                .filter(|m| restored_teams.contains(&m.team_id))
+                .map(|m| m.to_active_model(m.team_id, user_id(&m.user_id)))
+                .collect::<BasicResult<_>>()?,
+            clients: data
+                .clients
+                .iter()
+                .map(|c| c.to_active_model(user_id(&c.user_id), c.team_id.as_ref().map(team_id)))
+                .collect::<BasicResult<_>>()?,
+            tokens: data
+                .tokens
+                .iter()
+                .map(|t| t.to_active_model())
+                .collect::<BasicResult<_>>()?,
+            token_keys: data
+                .token_keys
+                .iter()
+                .map(|k| k.to_active_model())
+                .collect::<BasicResult<_>>()?,
+            root_certificates: data
+                .root_certificates
+                .iter()
+                .map(|r| r.to_active_model(user_id(&r.created_by)))
+                .collect::<BasicResult<_>>()?,
+            intermediate_certificates: data
+                .intermediate_certificates
+                .iter()
+                .map(|i| i.to_active_model())
+                .collect::<BasicResult<_>>()?,
+            signing_requests: data
+                .signing_requests
+                .iter()
+                .map(|r| r.to_active_model(r.requested_by.as_ref().map(user_id)))
+                .collect::<BasicResult<_>>()?,
+            log_entries: data
+                .log_entries
+                .iter()
+                .map(|e| e.to_active_model())
+                .collect::<BasicResult<_>>()?,
+        })
+    }
+
+    async fn write(&self, models: RestoreModels) -> BasicResult<()> {
+        let txn = self.0.begin().await?;
+
+        if models.token_keys.iter().any(|k| *k.active.as_ref()) {
+            TokenKeyRepository::retire_all(&txn).await?;
+        }
+
+        BackupRepository::insert_all(&txn, models.users).await?;
+        BackupRepository::insert_all(&txn, models.teams).await?;
+        BackupRepository::insert_all(&txn, models.team_members).await?;
+        BackupRepository::insert_all(&txn, models.token_keys).await?;
+        BackupRepository::insert_all(&txn, models.root_certificates).await?;
+        BackupRepository::reset_id_sequence::<root_certificate::Entity, _>(&txn).await?;
+        BackupRepository::insert_all(&txn, models.intermediate_certificates).await?;
+        BackupRepository::reset_id_sequence::<certificate::Entity, _>(&txn).await?;
+        BackupRepository::insert_all(&txn, models.clients).await?;
+        BackupRepository::insert_all(&txn, models.tokens).await?;
+        BackupRepository::insert_all(&txn, models.signing_requests).await?;
+        BackupRepository::reset_id_sequence::<signing_request::Entity, _>(&txn).await?;
+        BackupRepository::insert_all(&txn, models.log_entries).await?;
+        transparency_log_service::rebuild(&txn).await?;
+
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Restore a backup into a CA which has no certificates and clients yet.
+    /// The backup is fully verified before anything is written and is
+    /// restored in a single transaction.
+    pub async fn restore(
+        &self,
+        passphrase: &str,
+        archive: &BackupArchiveDto,
+    ) -> WebResult<BackupSummaryDto> {
+        let content = backup_cipher::decrypt(passphrase, archive)
+            .map_err(|e| HttpResponseError::bad_request(Some(e.to_string())))?;
+        let data = serde_json::from_slice::<BackupData>(&content)
+            .map_bad_request(Some("The backup content is invalid"))?;
+
+        verify_backup(&data).map_err(|e| {
+            HttpResponseError::bad_request(Some(format!("The backup is inconsistent: {}", e)))
+        })?;
+
+        if !self
+            .is_empty()
+            .await
+            .map_internal_error(Some("Failed to check the CA state"))?
+        {
+            return Err(HttpResponseError::bad_request(Some(
+                "Backups can only be restored into a CA without certificates and clients",
+            )));
+        }
+
+        let users = self
+            .map_users(&data)
+            .await
+            .map_internal_error(Some("Failed to find users"))?;
+        let teams = self
+            .map_teams(&data)
+            .await
+            .map_internal_error(Some("Failed to find teams"))?;
+        let models = Self::to_models(&data, &users, &teams).map_err(|e| {
+            HttpResponseError::bad_request(Some(format!("The backup is invalid: {}", e)))
+        })?;
+
+        self.write(models)
+            .await
+            .map_internal_error(Some("Failed to restore the backup"))?;
+
+        Ok(BackupSummaryDto {
+            users: data.users.len(),
+            teams: data.teams.len(),
+            clients: data.clients.len(),
+            tokens: data.tokens.len(),
+            token_keys: data.token_keys.len(),
+            root_certificates: data.root_certificates.len(),
+            intermediate_certificates: data.intermediate_certificates.len(),
+            signing_requests: data.signing_requests.len(),
+            log_entries: data.log_entries.len(),
+        })
+    }
+}
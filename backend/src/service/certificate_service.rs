@@ -23,6 +23,12 @@ impl CertificateService {
             .map_internal_error(Some("Failed to find active certificate"))
     }
 
+    pub async fn find_all(&self) -> WebResult<Vec<certificate::Model>> {
+        CertificateRepository::find_all(&self.0)
+            .await
+            .map_internal_error(Some("Failed to find certificates"))
+    }
+
     /*pub async fn get_certificate(&self, config: &Config) -> WebResult<certificate::Model> {
         if let Some(cert) = self.find_active().await? {
             return Ok(cert);
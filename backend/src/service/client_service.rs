@@ -1,6 +1,8 @@
 use crate::entity::client;
 use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::model::client_filter::ClientFilter;
 use crate::repository::client_repository::ClientRepository;
+use crate::util::pagination::Pagination;
 use crate::util::types::WebResult;
 use sea_orm::{ActiveModelTrait, DatabaseConnection, DeleteResult};
 use uuid::Uuid;
@@ -65,14 +67,26 @@ impl ClientService {
         .ok_or_else(|| HttpResponseError::not_found(Some("Client not found")))
     }
 
-    pub async fn find_all_by_user(
+    pub async fn find_page_by_user(
         &self,
         user_id: &Uuid,
         include_inactive: bool,
-    ) -> WebResult<Vec<client::Model>> {
-        ClientRepository::find_all_by_user(&self.0, user_id, include_inactive)
+        pagination: &Pagination,
+    ) -> WebResult<(Vec<client::Model>, u64)> {
+        ClientRepository::find_page_by_user(&self.0, user_id, include_inactive, pagination)
             .await
-            .map_internal_error(Some("Failed to find all clients by user"))
+            .map_internal_error(Some("Failed to find clients by user"))
+    }
+
+    /// Search the clients of all users
+    pub async fn search(
+        &self,
+        filter: &ClientFilter,
+        pagination: &Pagination,
+    ) -> WebResult<(Vec<client::Model>, u64)> {
+        ClientRepository::search(&self.0, filter, pagination)
+            .await
+            .map_internal_error(Some("Failed to search clients"))
     }
 
     pub async fn disable(&self, model: client::ActiveModel) -> WebResult<client::ActiveModel> {
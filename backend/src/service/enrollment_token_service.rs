@@ -0,0 +1,229 @@
+use crate::entity::{approval_request, client, enrollment_token, signing_request, token};
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::repository::enrollment_token_repository::EnrollmentTokenRepository;
+use crate::service::approval_service::ApprovalService;
+use crate::service::signing_request_service::SigningRequestService;
+use crate::util::types::WebResult;
+use chrono::{Duration, Utc};
+use openssl::rand::rand_bytes;
+use openssl::sha::Sha256;
+use openssl::x509::X509Req;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ConnectionTrait, DatabaseConnection, DeleteResult,
+    TransactionTrait,
+};
+use shared::util::traits::u8_vec_to_string::U8VecToString;
+use uuid::Uuid;
+
+/// The SHA-256 hash enrollment tokens are stored as
+fn hash_token(token: &str) -> String {
+    let mut hash = Sha256::new();
+    hash.update(token.as_bytes());
+    hash.finish().to_vec().to_hex_string("")
+}
+
+/// The redemption of an enrollment token
+pub struct Redemption {
+    pub id: Uuid,
+    /// The address the token was redeemed from
+    pub redeemed_from: Option<String>,
+    /// The renew-only token issued to the client, if requested
+    pub renewal_token: Option<token::ActiveModel>,
+}
+
+pub struct EnrollmentTokenService(DatabaseConnection);
+
+impl EnrollmentTokenService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self(db)
+    }
+
+    /// Create a new enrollment token for a client.
+    /// Returns the stored token along with the secret token value,
+    /// which is only available at this point.
+    pub async fn create(
+        &self,
+        client_id: Uuid,
+        created_by: Uuid,
+        valid_for: Duration,
+    ) -> WebResult<(enrollment_token::Model, String)> {
+        let mut token = [0u8; 32];
+        rand_bytes(&mut token).map_internal_error(Some("Failed to generate token"))?;
+        let token = token.to_vec().to_hex_string("");
+
+        let model = EnrollmentTokenRepository::insert(
+            &self.0,
+            enrollment_token::ActiveModel {
+                id: ActiveValue::Set(Uuid::new_v4()),
+                client_id: ActiveValue::Set(client_id),
+                token_hash: ActiveValue::Set(hash_token(&token)),
+                created_by: ActiveValue::Set(created_by),
+                expires_at: ActiveValue::Set((Utc::now() + valid_for).into()),
+                redeemed_at: ActiveValue::Set(None),
+                redeemed_from: ActiveValue::Set(None),
+                serial_number: ActiveValue::Set(None),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_internal_error(Some("Failed to store enrollment token"))?;
+
+        Ok((model, token))
+    }
+
+    pub async fn find_by_id(&self, id: &Uuid) -> WebResult<Option<enrollment_token::Model>> {
+        EnrollmentTokenRepository::find_by_id(&self.0, id)
+            .await
+            .map_internal_error(Some("Failed to find enrollment token"))
+    }
+
+    pub async fn find_by_token(&self, token: &str) -> WebResult<Option<enrollment_token::Model>> {
+        EnrollmentTokenRepository::find_by_hash(&self.0, &hash_token(token))
+            .await
+            .map_internal_error(Some("Failed to find enrollment token"))
+    }
+
+    pub async fn find_all_by_client(
+        &self,
+        client_id: &Uuid,
+    ) -> WebResult<Vec<enrollment_token::Model>> {
+        EnrollmentTokenRepository::find_all_by_client(&self.0, client_id)
+            .await
+            .map_internal_error(Some("Failed to find enrollment tokens"))
+    }
+
+    /// Mark a token as redeemed and store the renewal token of the client, if any.
+    /// Fails if the token has already been redeemed.
+    async fn redeem_in<C: ConnectionTrait>(db: &C, redemption: Redemption) -> WebResult<()> {
+        if !EnrollmentTokenRepository::redeem(db, &redemption.id, redemption.redeemed_from)
+            .await
+            .map_internal_error(Some("Failed to redeem enrollment token"))?
+        {
+            return Err(HttpResponseError::unauthorized(Some(
+                "The enrollment token has already been redeemed",
+            )));
+        }
+
+        if let Some(renewal_token) = redemption.renewal_token {
+            renewal_token
+                .insert(db)
+                .await
+                .map_internal_error(Some("Failed to create token"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Redeem a token and store the certificate issued for it in a single transaction,
+    /// so the token stays redeemable if the certificate can't be stored
+    pub async fn redeem_and_issue(
+        &self,
+        redemption: Redemption,
+        issued: signing_request::ActiveModel,
+    ) -> WebResult<signing_request::Model> {
+        let txn = self
+            .0
+            .begin()
+            .await
+            .map_internal_error(Some("Failed to start transaction"))?;
+        let id = redemption.id;
+        Self::redeem_in(&txn, redemption).await?;
+
+        let issued = SigningRequestService::insert_logged(&txn, issued)
+            .await
+            .map_internal_error(Some("Failed to save the issued certificate"))?;
+        EnrollmentTokenRepository::set_serial_number(&txn, &id, &issued.serial_number)
+            .await
+            .map_internal_error(Some("Failed to update enrollment token"))?;
+
+        txn.commit()
+            .await
+            .map_internal_error(Some("Failed to redeem enrollment token"))?;
+        Ok(issued)
+    }
+
+    /// Redeem a token and store the signing request until it is approved
+    /// in a single transaction, see [`ApprovalService::submit`]
+    pub async fn redeem_and_submit(
+        &self,
+        redemption: Redemption,
+        client: &client::Model,
+        req: &X509Req,
+        subject_name: String,
+        alternative_names: &Option<Vec<String>>,
+    ) -> WebResult<approval_request::Model> {
+        let txn = self
+            .0
+            .begin()
+            .await
+            .map_internal_error(Some("Failed to start transaction"))?;
+        Self::redeem_in(&txn, redemption).await?;
+
+        let approval =
+            ApprovalService::submit_in(&txn, client, None, req, subject_name, alternative_names)
+                .await?;
+
+        txn.commit()
+            .await
+            .map_internal_error(Some("Failed to redeem enrollment token"))?;
+        Ok(approval)
+    }
+
+    pub async fn delete(&self, model: enrollment_token::Model) -> WebResult<DeleteResult> {
+        EnrollmentTokenRepository::delete(&self.0, model)
+            .await
+            .map_internal_error(Some("Failed to delete enrollment token"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::testing::{connect, insert_client, issued};
+    use sea_orm::{EntityTrait, PaginatorTrait};
+
+    fn redemption(id: Uuid) -> Redemption {
+        Redemption {
+            id,
+            redeemed_from: None,
+            renewal_token: None,
+        }
+    }
+
+    #[actix_web::test]
+    async fn keeps_the_token_redeemable_if_issuing_fails() {
+        let db = connect().await;
+        let (user, client) = insert_client(&db, false).await;
+
+        let service = EnrollmentTokenService::new(db.clone());
+        let (token, _) = service
+            .create(client.id, user.id, Duration::hours(1))
+            .await
+            .unwrap();
+
+        // The hash can't be appended to the issuance log
+        let mut invalid = issued(Some(client.id), 1);
+        invalid.hash = ActiveValue::Set("zz".to_string());
+        assert!(service
+            .redeem_and_issue(redemption(token.id), invalid)
+            .await
+            .is_err());
+        let token = service.find_by_id(&token.id).await.unwrap().unwrap();
+        assert!(token.is_redeemable());
+        assert_eq!(signing_request::Entity::find().count(&db).await.unwrap(), 0);
+
+        let certificate = service
+            .redeem_and_issue(redemption(token.id), issued(Some(client.id), 1))
+            .await
+            .unwrap();
+        let token = service.find_by_id(&token.id).await.unwrap().unwrap();
+        assert!(!token.is_redeemable());
+        assert_eq!(token.serial_number, Some(certificate.serial_number));
+
+        assert!(service
+            .redeem_and_issue(redemption(token.id), issued(Some(client.id), 1))
+            .await
+            .is_err());
+        assert_eq!(signing_request::Entity::find().count(&db).await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::identity::identity_provider::IdentityProvider;
+use crate::repository::certificate_repository::CertificateRepository;
+use crate::repository::root_certificate_repository::RootCertificateRepository;
+use crate::util::ca_certificate::CACertificate;
+use chrono::Utc;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ConnectionTrait, DatabaseConnection, Statement};
+use shared::model::component_health_dto::{ComponentHealthDto, ComponentStatus};
+
+/// Checks the components the API depends on
+pub struct HealthService(DatabaseConnection);
+
+impl HealthService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self(db)
+    }
+
+    pub async fn check_database(&self) -> ComponentHealthDto {
+        let backend = self.0.get_database_backend();
+        match self
+            .0
+            .execute(Statement::from_string(backend, "SELECT 1".to_string()))
+            .await
+        {
+            Ok(_) => ComponentHealthDto::up("database"),
+            Err(e) => ComponentHealthDto::down("database", e),
+        }
+    }
+
+    /// Check whether the identity provider is reachable, returns its version if it is known
+    pub async fn check_identity_provider(
+        &self,
+        identity_provider: &dyn IdentityProvider,
+    ) -> (ComponentHealthDto, Option<String>) {
+        let name = identity_provider.name();
+        match identity_provider.get_server_info().await {
+            Ok(info) => (ComponentHealthDto::up(name), info.version),
+            Err(e) => (
+                ComponentHealthDto::down(
+                    name,
+                    e.message
+                        .unwrap_or("The identity provider is not reachable".into()),
+                ),
+                None,
+            ),
+        }
+    }
+
+    /// Check whether the keys tokens are signed with can be retrieved.
+    /// Tokens signed with new keys are verified without a restart.
+    pub async fn check_signing_keys(
+        &self,
+        identity_provider: &dyn IdentityProvider,
+    ) -> ComponentHealthDto {
+        const NAME: &str = "tokenSigningKeys";
+        match identity_provider.check_keys().await {
+            Ok(0) => ComponentHealthDto::down(NAME, "The identity provider published no keys"),
+            Ok(_) => ComponentHealthDto::up(NAME),
+            Err(e) => ComponentHealthDto::down(NAME, e),
+        }
+    }
+
+    /// Check the expiry of a certificate and set the number of days until it expires.
+    /// Certificates which are already down for another reason keep their status.
+    fn check_expiry(
+        mut health: ComponentHealthDto,
+        valid_until: DateTimeWithTimeZone,
+        warning_days: i64,
+    ) -> ComponentHealthDto {
+        let days = (valid_until.with_timezone(&Utc) - Utc::now()).num_days();
+        if health.status == ComponentStatus::Up {
+            if valid_until.with_timezone(&Utc) <= Utc::now() {
+                health = ComponentHealthDto::down(health.name, "The certificate has expired");
+            } else if days < warning_days {
+                health = ComponentHealthDto::degraded(
+                    health.name,
+                    format!("The certificate expires in {} days", days),
+                );
+            }
+        }
+
+        health.expires_in_days = Some(days);
+        health
+    }
+
+    /// Check whether an active intermediate certificate with a usable private key exists
+    pub async fn check_intermediate(&self, warning_days: i64) -> ComponentHealthDto {
+        const NAME: &str = "intermediateCertificate";
+        let model = match CertificateRepository::find_active(&self.0).await {
+            Ok(Some(model)) => model,
+            Ok(None) => {
+                return ComponentHealthDto::down(NAME, "No active intermediate certificate found")
+            }
+            Err(e) => return ComponentHealthDto::down(NAME, e),
+        };
+
+        let valid_until = model.valid_until;
+        let health = match CACertificate::try_from(model).and_then(|c| c.key_matches()) {
+            Ok(true) => ComponentHealthDto::up(NAME),
+            Ok(false) => {
+                ComponentHealthDto::down(NAME, "The private key does not match the certificate")
+            }
+            Err(e) => {
+                ComponentHealthDto::down(NAME, format!("The private key is not usable: {}", e))
+            }
+        };
+
+        Self::check_expiry(health, valid_until, warning_days)
+    }
+
+    /// Check whether an active root certificate exists
+    pub async fn check_root(&self, warning_days: i64) -> ComponentHealthDto {
+        const NAME: &str = "rootCertificate";
+        match RootCertificateRepository::find_active(&self.0).await {
+            Ok(Some(model)) => Self::check_expiry(
+                ComponentHealthDto::up(NAME),
+                model.valid_until,
+                warning_days,
+            ),
+            Ok(None) => ComponentHealthDto::down(NAME, "No active root certificate found"),
+            Err(e) => ComponentHealthDto::down(NAME, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HealthService;
+    use chrono::{Duration, Utc};
+    use shared::model::component_health_dto::{ComponentHealthDto, ComponentStatus};
+
+    #[test]
+    fn keeps_the_status_of_unusable_certificates() {
+        let expiring = (Utc::now() + Duration::days(3)).into();
+
+        let health = HealthService::check_expiry(ComponentHealthDto::up("ca"), expiring, 14);
+        assert_eq!(health.status, ComponentStatus::Degraded);
+        assert_eq!(health.expires_in_days, Some(2));
+
+        let health = HealthService::check_expiry(
+            ComponentHealthDto::down("ca", "The private key is not usable"),
+            expiring,
+            14,
+        );
+        assert_eq!(health.status, ComponentStatus::Down);
+        assert_eq!(
+            health.message.as_deref(),
+            Some("The private key is not usable")
+        );
+        assert_eq!(health.expires_in_days, Some(2));
+    }
+}
@@ -0,0 +1,58 @@
+use crate::error::http_response_error::MapHttpResponseError;
+use crate::repository::certificate_repository::CertificateRepository;
+use crate::repository::root_certificate_repository::RootCertificateRepository;
+use crate::util::metrics::{
+    DB_POOL_CONNECTIONS, DB_POOL_IDLE_CONNECTIONS, INTERMEDIATE_EXPIRY, ROOT_EXPIRY,
+};
+use crate::util::types::WebResult;
+use chrono::Utc;
+use prometheus::{Encoder, TextEncoder};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend};
+
+pub struct MetricsService(DatabaseConnection);
+
+impl MetricsService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self(db)
+    }
+
+    /// Update the gauges which are derived from the current state
+    async fn update_gauges(&self) -> WebResult<()> {
+        if self.0.get_database_backend() == DbBackend::Postgres {
+            let pool = self.0.get_postgres_connection_pool();
+            DB_POOL_CONNECTIONS.set(pool.size() as i64);
+            DB_POOL_IDLE_CONNECTIONS.set(pool.num_idle() as i64);
+        }
+
+        let now = Utc::now();
+        let root = RootCertificateRepository::find_active(&self.0)
+            .await
+            .map_internal_error(Some("Failed to find the root certificate"))?;
+        ROOT_EXPIRY.set(
+            root.map(|r| (r.valid_until.with_timezone(&Utc) - now).num_seconds())
+                .unwrap_or(0),
+        );
+
+        let intermediate = CertificateRepository::find_active(&self.0)
+            .await
+            .map_internal_error(Some("Failed to find the intermediate certificate"))?;
+        INTERMEDIATE_EXPIRY.set(
+            intermediate
+                .map(|c| (c.valid_until.with_timezone(&Utc) - now).num_seconds())
+                .unwrap_or(0),
+        );
+
+        Ok(())
+    }
+
+    /// Encode all metrics in the prometheus text format
+    pub async fn gather(&self) -> WebResult<String> {
+        self.update_gauges().await?;
+
+        let mut buffer = vec![];
+        TextEncoder::new()
+            .encode(&prometheus::gather(), &mut buffer)
+            .map_internal_error(Some("Failed to encode metrics"))?;
+        String::from_utf8(buffer).map_internal_error(Some("Failed to encode metrics"))
+    }
+}
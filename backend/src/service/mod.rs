@@ -1,7 +1,17 @@
+pub mod approval_service;
+pub mod audit_service;
+pub mod backup_service;
 pub mod certificate_service;
 pub mod client_service;
-pub mod keycloak_service;
+pub mod enrollment_token_service;
+pub mod health_service;
+pub mod metrics_service;
+pub mod quota_service;
 pub mod root_certificate_service;
 pub mod signing_request_service;
+pub mod team_service;
+pub mod token_key_service;
 pub mod token_service;
+pub mod transparency_log_service;
 pub mod user_service;
+pub mod webhook_service;
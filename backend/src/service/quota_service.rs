@@ -0,0 +1,239 @@
+use crate::config::config::Config;
+use crate::entity::quota::QuotaScope;
+use crate::entity::{client, quota};
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::repository::client_repository::ClientRepository;
+use crate::repository::quota_repository::QuotaRepository;
+use crate::repository::signing_request_repository::SigningRequestRepository;
+use crate::util::types::WebResult;
+use chrono::{Duration, Utc};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ActiveEnum, ActiveValue, DatabaseConnection, DeleteResult, IntoActiveModel};
+use uuid::Uuid;
+
+/// The number of seconds until the given date, at least one second
+fn seconds_until(date: DateTimeWithTimeZone) -> i64 {
+    (date.with_timezone(&Utc) - Utc::now()).num_seconds().max(1)
+}
+
+/// Enforces the issuance rate limits and the quotas on clients and certificates
+pub struct QuotaService(DatabaseConnection);
+
+impl QuotaService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self(db)
+    }
+
+    pub async fn find(
+        &self,
+        scope: QuotaScope,
+        subject_id: &Uuid,
+    ) -> WebResult<Option<quota::Model>> {
+        QuotaRepository::find(&self.0, scope, subject_id)
+            .await
+            .map_internal_error(Some("Failed to find quota"))
+    }
+
+    pub async fn find_all(&self) -> WebResult<Vec<quota::Model>> {
+        QuotaRepository::find_all(&self.0)
+            .await
+            .map_internal_error(Some("Failed to find quotas"))
+    }
+
+    /// Create or replace the quota of a subject
+    pub async fn save(
+        &self,
+        scope: QuotaScope,
+        subject_id: Uuid,
+        issuance_limit: Option<i32>,
+        max_clients: Option<i32>,
+        max_valid_certificates: Option<i32>,
+    ) -> WebResult<quota::Model> {
+        let res = match self.find(scope, &subject_id).await? {
+            Some(existing) => {
+                let mut model = existing.into_active_model();
+                model.issuance_limit = ActiveValue::Set(issuance_limit);
+                model.max_clients = ActiveValue::Set(max_clients);
+                model.max_valid_certificates = ActiveValue::Set(max_valid_certificates);
+                QuotaRepository::update(&self.0, model).await
+            }
+            None => {
+                QuotaRepository::insert(
+                    &self.0,
+                    quota::ActiveModel {
+                        scope: ActiveValue::Set(scope),
+                        subject_id: ActiveValue::Set(subject_id),
+                        issuance_limit: ActiveValue::Set(issuance_limit),
+                        max_clients: ActiveValue::Set(max_clients),
+                        max_valid_certificates: ActiveValue::Set(max_valid_certificates),
+                        ..Default::default()
+                    },
+                )
+                .await
+            }
+        };
+
+        res.map_internal_error(Some("Failed to save quota"))
+    }
+
+    pub async fn delete(&self, model: quota::Model) -> WebResult<DeleteResult> {
+        QuotaRepository::delete(&self.0, model)
+            .await
+            .map_internal_error(Some("Failed to delete quota"))
+    }
+
+    /// Reject the request if `limit` certificates have already
+    /// been issued to the subject within the current window
+    async fn check_rate(
+        &self,
+        scope: QuotaScope,
+        subject_id: &Uuid,
+        limit: i32,
+        window_seconds: i64,
+    ) -> WebResult<()> {
+        if limit <= 0 {
+            return Ok(());
+        }
+
+        let since = Utc::now() - Duration::seconds(window_seconds);
+        let count =
+            SigningRequestRepository::count_issued_since(&self.0, scope, subject_id, since.into())
+                .await
+                .map_internal_error(Some("Failed to count issued certificates"))?;
+        if count < limit as u64 {
+            return Ok(());
+        }
+
+        // The next certificate may be issued once enough issuances have left the window
+        let retry_after = SigningRequestRepository::find_nth_issued_since(
+            &self.0,
+            scope,
+            subject_id,
+            since.into(),
+            count - limit as u64,
+        )
+        .await
+        .map_internal_error(Some("Failed to find issued certificates"))?
+        .map(|req| seconds_until(req.issued_at + Duration::seconds(window_seconds)))
+        .unwrap_or(window_seconds);
+
+        Err(HttpResponseError::too_many_requests(
+            Some(format!(
+                "The {} rate limit of {} certificates per {} seconds has been exceeded",
+                scope.to_value(),
+                limit,
+                window_seconds
+            )),
+            retry_after,
+        ))
+    }
+
+    /// Check whether the user or one of the user's clients may request another certificate
+    pub async fn check_user_issuance(&self, config: &Config, user_id: &Uuid) -> WebResult<()> {
+        let global = self.find(QuotaScope::Global, &Uuid::nil()).await?;
+        let user = self.find(QuotaScope::User, user_id).await?;
+        let window = config.rate_limit_window_seconds;
+
+        self.check_rate(
+            QuotaScope::User,
+            user_id,
+            user.and_then(|q| q.issuance_limit)
+                .unwrap_or(config.rate_limit_user),
+            window,
+        )
+        .await?;
+        self.check_rate(
+            QuotaScope::Global,
+            &Uuid::nil(),
+            global
+                .and_then(|q| q.issuance_limit)
+                .unwrap_or(config.rate_limit_global),
+            window,
+        )
+        .await
+    }
+
+    /// Check whether the client may request another certificate
+    pub async fn check_issuance(&self, config: &Config, client: &client::Model) -> WebResult<()> {
+        let global = self.find(QuotaScope::Global, &Uuid::nil()).await?;
+        let own = self.find(QuotaScope::Client, &client.id).await?;
+        let window = config.rate_limit_window_seconds;
+
+        self.check_rate(
+            QuotaScope::Client,
+            &client.id,
+            own.as_ref()
+                .and_then(|q| q.issuance_limit)
+                .unwrap_or(config.rate_limit_client),
+            window,
+        )
+        .await?;
+        self.check_user_issuance(config, &client.user_id).await?;
+
+        let max_valid = own
+            .and_then(|q| q.max_valid_certificates)
+            .or(global.and_then(|q| q.max_valid_certificates))
+            .unwrap_or(config.quota_max_valid_certificates_per_client);
+        if max_valid <= 0 {
+            return Ok(());
+        }
+
+        let count = SigningRequestRepository::count_valid_by_client(&self.0, &client.id)
+            .await
+            .map_internal_error(Some("Failed to count valid certificates"))?;
+        if count < max_valid as u64 {
+            return Ok(());
+        }
+
+        let retry_after = SigningRequestRepository::find_nth_valid_by_client(
+            &self.0,
+            &client.id,
+            count - max_valid as u64,
+        )
+        .await
+        .map_internal_error(Some("Failed to find valid certificates"))?
+        .map(|req| seconds_until(req.valid_until))
+        .unwrap_or(window);
+
+        Err(HttpResponseError::too_many_requests(
+            Some(format!(
+                "The client already has {} valid certificates",
+                count
+            )),
+            retry_after,
+        ))
+    }
+
+    /// Check whether the user may create another client
+    pub async fn check_client_creation(&self, config: &Config, user_id: &Uuid) -> WebResult<()> {
+        let global = self.find(QuotaScope::Global, &Uuid::nil()).await?;
+        let user = self.find(QuotaScope::User, user_id).await?;
+
+        let max_clients = user
+            .and_then(|q| q.max_clients)
+            .or(global.and_then(|q| q.max_clients))
+            .unwrap_or(config.quota_max_clients_per_user);
+        if max_clients <= 0 {
+            return Ok(());
+        }
+
+        let count = ClientRepository::count_active_by_user(&self.0, user_id)
+            .await
+            .map_internal_error(Some("Failed to count clients"))?;
+        if count < max_clients as u64 {
+            return Ok(());
+        }
+
+        let retry_after =
+            ClientRepository::find_nth_active_by_user(&self.0, user_id, count - max_clients as u64)
+                .await
+                .map_internal_error(Some("Failed to find clients"))?
+                .map(|client| seconds_until(client.valid_until))
+                .unwrap_or(config.rate_limit_window_seconds);
+
+        Err(HttpResponseError::too_many_requests(
+            Some(format!("The user already has {} active clients", count)),
+            retry_after,
+        ))
+    }
+}
@@ -25,4 +25,10 @@ impl RootCertificateService {
             .await
             .map_internal_error(Some("Failed to find active root certificate"))
     }
+
+    pub async fn find_all(&self) -> WebResult<Vec<root_certificate::Model>> {
+        RootCertificateRepository::find_all(&self.0)
+            .await
+            .map_internal_error(Some("Failed to find root certificates"))
+    }
 }
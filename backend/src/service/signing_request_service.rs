@@ -1,8 +1,15 @@
-use crate::entity::signing_request;
+use crate::entity::{signing_request, used_signature};
 use crate::error::http_response_error::MapHttpResponseError;
+use crate::model::signing_request_filter::SigningRequestFilter;
 use crate::repository::signing_request_repository::SigningRequestRepository;
+use crate::repository::used_signature_repository::UsedSignatureRepository;
+use crate::service::transparency_log_service::TransparencyLogService;
+use crate::util::pagination::Pagination;
 use crate::util::types::WebResult;
-use sea_orm::{ActiveModelTrait, DatabaseConnection};
+use chrono::Utc;
+use sea_orm::{ActiveValue, ConnectionTrait, DatabaseConnection, TransactionTrait};
+use shared::util::request_signature;
+use shared::util::types::BasicResult;
 use uuid::Uuid;
 
 pub struct SigningRequestService(DatabaseConnection);
@@ -12,14 +19,57 @@ impl SigningRequestService {
         Self(db)
     }
 
-    pub async fn save(
+    /// Store an issued certificate and append it to the issuance log in one transaction
+    pub async fn issue(
         &self,
         model: signing_request::ActiveModel,
-    ) -> WebResult<signing_request::ActiveModel> {
-        model
-            .save(&self.0)
+    ) -> WebResult<signing_request::Model> {
+        let txn = self
+            .0
+            .begin()
             .await
-            .map_internal_error(Some("Failed to save signing request"))
+            .map_internal_error(Some("Failed to start transaction"))?;
+        let req = Self::insert_logged(&txn, model)
+            .await
+            .map_internal_error(Some("Failed to save the issued certificate"))?;
+        txn.commit()
+            .await
+            .map_internal_error(Some("Failed to save the issued certificate"))?;
+
+        Ok(req)
+    }
+
+    /// Store an issued certificate and append it to the issuance log.
+    /// Must be called in a transaction so the certificate is only stored if it is logged.
+    pub async fn insert_logged<C: ConnectionTrait>(
+        db: &C,
+        model: signing_request::ActiveModel,
+    ) -> BasicResult<signing_request::Model> {
+        let req = SigningRequestRepository::insert(db, model).await?;
+        TransparencyLogService::append(db, &req).await?;
+        Ok(req)
+    }
+
+    /// Record that a certificate has been used to sign a request at `signed_at`.
+    /// Returns false if the certificate has already been used with the same timestamp.
+    pub async fn use_signature(&self, certificate_hash: &str, signed_at: i64) -> WebResult<bool> {
+        // Signatures this old are rejected anyway, there's no need to remember them
+        UsedSignatureRepository::delete_signed_before(
+            &self.0,
+            Utc::now().timestamp() - request_signature::MAX_CLOCK_SKEW_SECS,
+        )
+        .await
+        .map_internal_error(Some("Failed to delete used signatures"))?;
+
+        UsedSignatureRepository::insert(
+            &self.0,
+            used_signature::ActiveModel {
+                certificate_hash: ActiveValue::Set(certificate_hash.to_string()),
+                signed_at: ActiveValue::Set(signed_at),
+            },
+        )
+        .await
+        .map_internal_error(Some("Failed to save the used signature"))
     }
 
     pub async fn find_all_by_client_id(
@@ -31,12 +81,75 @@ impl SigningRequestService {
             .map_internal_error(Some("Failed to find signing requests"))
     }
 
-    pub async fn find_all_by_user_id(
+    pub async fn find_by_id(&self, id: i32) -> WebResult<Option<signing_request::Model>> {
+        SigningRequestRepository::find_by_id(&self.0, id)
+            .await
+            .map_internal_error(Some("Failed to find signing request"))
+    }
+
+    /// Whether a request renews a valid certificate of the client,
+    /// i.e. has the same subject common name and alternative names
+    pub async fn is_renewal(
+        &self,
+        client_id: &Uuid,
+        subject_name: &str,
+        alternative_names: &Option<Vec<String>>,
+    ) -> WebResult<bool> {
+        let mut requested = alternative_names.clone().unwrap_or_default();
+        requested.sort_unstable();
+        requested.dedup();
+
+        Ok(SigningRequestRepository::find_valid_by_client_and_subject(
+            &self.0,
+            client_id,
+            subject_name,
+        )
+        .await
+        .map_internal_error(Some("Failed to find signing requests"))?
+        .into_iter()
+        .any(|cert| {
+            let mut names = cert.alternative_names();
+            names.sort_unstable();
+            names.dedup();
+            names == requested
+        }))
+    }
+
+    pub async fn find_by_serial_number(
+        &self,
+        serial_number: &str,
+    ) -> WebResult<Option<signing_request::Model>> {
+        SigningRequestRepository::find_by_serial_number(&self.0, serial_number)
+            .await
+            .map_internal_error(Some("Failed to find signing request"))
+    }
+
+    pub async fn search_by_user_id(
         &self,
         user_id: &Uuid,
-    ) -> WebResult<Vec<signing_request::Model>> {
-        SigningRequestRepository::find_all_by_user(&self.0, user_id)
+        filter: &SigningRequestFilter,
+        pagination: &Pagination,
+    ) -> WebResult<(Vec<signing_request::Model>, u64)> {
+        SigningRequestRepository::search(&self.0, Some(user_id), filter, pagination)
             .await
-            .map_internal_error(Some("Failed to find signing requests"))
+            .map_internal_error(Some("Failed to search signing requests"))
+    }
+
+    /// Search the signing requests of all users or, if given, of a single user
+    pub async fn search(
+        &self,
+        user_id: Option<&Uuid>,
+        filter: &SigningRequestFilter,
+        pagination: &Pagination,
+    ) -> WebResult<(Vec<signing_request::Model>, u64)> {
+        SigningRequestRepository::search(&self.0, user_id, filter, pagination)
+            .await
+            .map_internal_error(Some("Failed to search signing requests"))
+    }
+
+    pub async fn revoke(&self, model: signing_request::Model) -> WebResult<signing_request::Model> {
+        SigningRequestRepository::revoke(&self.0, model)
+            .await
+            .map_internal_error(Some("Failed to revoke signing request"))
     }
 }
@@ -0,0 +1,313 @@
+use crate::entity::team_member::TeamRole;
+use crate::entity::{client, team, team_member};
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::repository::client_repository::ClientRepository;
+use crate::repository::team_member_repository::TeamMemberRepository;
+use crate::repository::team_repository::TeamRepository;
+use crate::util::types::{DbResult, WebResult};
+use sea_orm::{
+    ActiveValue, ConnectionTrait, DatabaseConnection, DbErr, DeleteResult, IntoActiveModel,
+    TransactionTrait,
+};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// A group of the identity provider and the ids of the local users which are members of it
+pub struct SyncedGroup {
+    pub external_id: String,
+    pub name: String,
+    pub user_ids: Vec<Uuid>,
+}
+
+pub struct TeamService(DatabaseConnection);
+
+impl TeamService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self(db)
+    }
+
+    pub async fn insert(&self, name: String) -> WebResult<team::Model> {
+        TeamRepository::insert(
+            &self.0,
+            team::ActiveModel {
+                name: ActiveValue::Set(name),
+                external_id: ActiveValue::Set(None),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_internal_error(Some("Failed to create team"))
+    }
+
+    pub async fn find_by_name(&self, name: &str) -> WebResult<Option<team::Model>> {
+        TeamRepository::find_by_name(&self.0, name)
+            .await
+            .map_internal_error(Some("Failed to find team by name"))
+    }
+
+    pub async fn find_by_id_string_unwrap(&self, id: &str) -> WebResult<team::Model> {
+        TeamRepository::find_by_id(
+            &self.0,
+            &Uuid::parse_str(id).map_bad_request(Some("Invalid team id supplied"))?,
+        )
+        .await
+        .map_internal_error(Some("Failed to find team by id"))?
+        .ok_or_else(|| HttpResponseError::not_found(Some("Team not found")))
+    }
+
+    pub async fn find_all(&self) -> WebResult<Vec<team::Model>> {
+        TeamRepository::find_all(&self.0)
+            .await
+            .map_internal_error(Some("Failed to find teams"))
+    }
+
+    pub async fn find_all_by_user(&self, user_id: &Uuid) -> WebResult<Vec<team::Model>> {
+        TeamRepository::find_all_by_user(&self.0, user_id)
+            .await
+            .map_internal_error(Some("Failed to find teams by user"))
+    }
+
+    pub async fn find_members(&self, team_id: &Uuid) -> WebResult<Vec<team_member::Model>> {
+        TeamMemberRepository::find_all_by_team(&self.0, team_id)
+            .await
+            .map_internal_error(Some("Failed to find team members"))
+    }
+
+    pub async fn find_member(
+        &self,
+        team_id: &Uuid,
+        user_id: &Uuid,
+    ) -> WebResult<Option<team_member::Model>> {
+        TeamMemberRepository::find(&self.0, team_id, user_id)
+            .await
+            .map_internal_error(Some("Failed to find team member"))
+    }
+
+    /// Add a user to a team or change the role of an existing member
+    pub async fn set_member(
+        &self,
+        team_id: &Uuid,
+        user_id: &Uuid,
+        role: TeamRole,
+    ) -> WebResult<team_member::Model> {
+        match self.find_member(team_id, user_id).await? {
+            Some(member) => {
+                let mut member = member.into_active_model();
+                member.role = ActiveValue::Set(role);
+                TeamMemberRepository::update(&self.0, member).await
+            }
+            None => {
+                TeamMemberRepository::insert(
+                    &self.0,
+                    team_member::ActiveModel {
+                        team_id: ActiveValue::Set(*team_id),
+                        user_id: ActiveValue::Set(*user_id),
+                        role: ActiveValue::Set(role),
+                        ..Default::default()
+                    },
+                )
+                .await
+            }
+        }
+        .map_internal_error(Some("Failed to update team member"))
+    }
+
+    pub async fn remove_member(&self, member: team_member::Model) -> WebResult<DeleteResult> {
+        TeamMemberRepository::delete(&self.0, member)
+            .await
+            .map_internal_error(Some("Failed to remove team member"))
+    }
+
+    /// Delete a team, teams which still own clients can't be deleted
+    pub async fn delete(&self, team: team::Model) -> WebResult<DeleteResult> {
+        if ClientRepository::count_by_team(&self.0, &team.id)
+            .await
+            .map_internal_error(Some("Failed to count the clients of the team"))?
+            > 0
+        {
+            return Err(HttpResponseError::bad_request(Some(
+                "The team still owns clients, transfer or delete them first",
+            )));
+        }
+
+        TeamRepository::delete(&self.0, team)
+            .await
+            .map_internal_error(Some("Failed to delete team"))
+    }
+
+    /// Whether the user is a maintainer of the team
+    pub async fn is_maintainer(&self, team_id: &Uuid, user_id: &Uuid) -> WebResult<bool> {
+        Ok(self
+            .find_member(team_id, user_id)
+            .await?
+            .filter(|m| m.role == TeamRole::Maintainer)
+            .is_some())
+    }
+
+    /// Whether the user may view the client and its certificates
+    pub async fn can_access_client(
+        &self,
+        client: &client::Model,
+        user_id: &Uuid,
+    ) -> WebResult<bool> {
+        match client.team_id {
+            Some(team_id) => Ok(self.find_member(&team_id, user_id).await?.is_some()),
+            None => Ok(&client.user_id == user_id),
+        }
+    }
+
+    /// Whether the user may update, delete and transfer the client
+    pub async fn can_manage_client(
+        &self,
+        client: &client::Model,
+        user_id: &Uuid,
+    ) -> WebResult<bool> {
+        match client.team_id {
+            Some(team_id) => self.is_maintainer(&team_id, user_id).await,
+            None => Ok(&client.user_id == user_id),
+        }
+    }
+
+    /// Make other users responsible for the team clients of a user who is about to be deleted.
+    /// The clients are assigned to the longest standing other maintainer of their team,
+    /// or to `fallback` if the team has no other maintainer.
+    pub async fn reassign_clients(&self, user_id: &Uuid, fallback: &Uuid) -> WebResult<()> {
+        let txn = self
+            .0
+            .begin()
+            .await
+            .map_internal_error(Some("Failed to start transaction"))?;
+        Self::reassign_clients_of(&txn, user_id, fallback)
+            .await
+            .map_internal_error(Some("Failed to reassign the clients of the user"))?;
+        txn.commit()
+            .await
+            .map_internal_error(Some("Failed to reassign the clients of the user"))
+    }
+
+    async fn reassign_clients_of<C: ConnectionTrait>(
+        db: &C,
+        user_id: &Uuid,
+        fallback: &Uuid,
+    ) -> DbResult<()> {
+        for client in ClientRepository::find_team_clients_by_user(db, user_id).await? {
+            let team_id = client.team_id.unwrap_or_default();
+            let owner = TeamMemberRepository::find_all_by_team(db, &team_id)
+                .await?
+                .into_iter()
+                .find(|m| m.role == TeamRole::Maintainer && &m.user_id != user_id)
+                .map(|m| m.user_id)
+                .unwrap_or(*fallback);
+
+            let mut model = client.into_active_model();
+            model.user_id = ActiveValue::Set(owner);
+            ClientRepository::update(db, model).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Create or update the teams of the identity provider groups and replace their members.
+    /// Teams whose group no longer exists become locally managed
+    /// so the clients they own stay accessible.
+    pub async fn sync(&self, groups: &[SyncedGroup]) -> WebResult<Vec<team::Model>> {
+        let txn = self
+            .0
+            .begin()
+            .await
+            .map_internal_error(Some("Failed to start transaction"))?;
+        let teams = Self::sync_groups(&txn, groups)
+            .await
+            .map_internal_error(Some("Failed to sync teams"))?;
+        txn.commit()
+            .await
+            .map_internal_error(Some("Failed to sync teams"))?;
+
+        Ok(teams)
+    }
+
+    async fn sync_groups<C: ConnectionTrait>(
+        db: &C,
+        groups: &[SyncedGroup],
+    ) -> DbResult<Vec<team::Model>> {
+        let mut teams = Vec::with_capacity(groups.len());
+        for group in groups {
+            let team = Self::sync_team(db, group).await?;
+
+            let members = TeamMemberRepository::find_all_by_team(db, &team.id).await?;
+            let existing = members.iter().map(|m| m.user_id).collect::<HashSet<_>>();
+            for member in members {
+                if !group.user_ids.contains(&member.user_id) {
+                    TeamMemberRepository::delete(db, member).await?;
+                }
+            }
+
+            // Members keep their role, new members start without any permissions to manage
+            for user_id in group.user_ids.iter().filter(|id| !existing.contains(id)) {
+                TeamMemberRepository::insert(
+                    db,
+                    team_member::ActiveModel {
+                        team_id: ActiveValue::Set(team.id),
+                        user_id: ActiveValue::Set(*user_id),
+                        role: ActiveValue::Set(TeamRole::Member),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+            }
+
+            teams.push(team);
+        }
+
+        let synced = groups
+            .iter()
+            .map(|g| g.external_id.as_str())
+            .collect::<HashSet<_>>();
+        for team in TeamRepository::find_all_synced(db).await? {
+            if !synced.contains(team.external_id.as_deref().unwrap_or_default()) {
+                let mut team = team.into_active_model();
+                team.external_id = ActiveValue::Set(None);
+                TeamRepository::update(db, team).await?;
+            }
+        }
+
+        Ok(teams)
+    }
+
+    /// Find or create the team of a group. A locally managed team
+    /// with the name of the group is linked to the group.
+    async fn sync_team<C: ConnectionTrait>(db: &C, group: &SyncedGroup) -> DbResult<team::Model> {
+        if let Some(team) = TeamRepository::find_by_external_id(db, &group.external_id).await? {
+            if team.name == group.name {
+                return Ok(team);
+            }
+
+            let mut team = team.into_active_model();
+            team.name = ActiveValue::Set(group.name.clone());
+            return TeamRepository::update(db, team).await;
+        }
+
+        match TeamRepository::find_by_name(db, &group.name).await? {
+            Some(team) if team.external_id.is_none() => {
+                let mut team = team.into_active_model();
+                team.external_id = ActiveValue::Set(Some(group.external_id.clone()));
+                TeamRepository::update(db, team).await
+            }
+            Some(_) => Err(DbErr::Custom(format!(
+                "The team {} is already synced from another group",
+                group.name
+            ))),
+            None => {
+                TeamRepository::insert(
+                    db,
+                    team::ActiveModel {
+                        name: ActiveValue::Set(group.name.clone()),
+                        external_id: ActiveValue::Set(Some(group.external_id.clone())),
+                        ..Default::default()
+                    },
+                )
+                .await
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use crate::entity::token_key;
+use crate::entity::token_key::TokenKeyAlgorithm;
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::model::jwk_dto::JwksDto;
+use crate::model::token_claims::TokenClaims;
+use crate::repository::token_key_repository::TokenKeyRepository;
+use crate::util::jwk;
+use crate::util::types::WebResult;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use log::info;
+use sea_orm::{ActiveValue, DatabaseConnection, DeleteResult, TransactionTrait};
+use shared::util::traits::u8_vec_to_string::U8VecToString;
+use uuid::Uuid;
+
+fn jwt_algorithm(algorithm: TokenKeyAlgorithm) -> Algorithm {
+    match algorithm {
+        TokenKeyAlgorithm::Es256 => Algorithm::ES256,
+        TokenKeyAlgorithm::EdDsa => Algorithm::EdDSA,
+    }
+}
+
+/// Signs client tokens and verifies them using the keys stored in the database
+pub struct TokenKeyService(DatabaseConnection);
+
+impl TokenKeyService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self(db)
+    }
+
+    pub async fn find_all(&self) -> WebResult<Vec<token_key::Model>> {
+        TokenKeyRepository::find_all(&self.0)
+            .await
+            .map_internal_error(Some("Failed to find token keys"))
+    }
+
+    pub async fn find_by_id(&self, id: &Uuid) -> WebResult<Option<token_key::Model>> {
+        TokenKeyRepository::find_by_id(&self.0, id)
+            .await
+            .map_internal_error(Some("Failed to find token key"))
+    }
+
+    /// Generate a new key new tokens are signed with.
+    /// The previous keys keep verifying the tokens signed with them until they are deleted.
+    /// Only one key can be active, a concurrent rotation fails instead of activating a second key.
+    pub async fn rotate(&self, algorithm: TokenKeyAlgorithm) -> WebResult<token_key::Model> {
+        let key_pair = jwk::generate_key_pair(algorithm)
+            .map_internal_error(Some("Failed to generate token key"))?;
+        let model = token_key::ActiveModel {
+            id: ActiveValue::Set(Uuid::new_v4()),
+            algorithm: ActiveValue::Set(algorithm),
+            private_key: ActiveValue::Set(
+                key_pair
+                    .private_key_to_pem_pkcs8()
+                    .map_internal_error(Some("Failed to encode token key"))?
+                    .to_string(),
+            ),
+            public_key: ActiveValue::Set(
+                key_pair
+                    .public_key_to_pem()
+                    .map_internal_error(Some("Failed to encode token key"))?
+                    .to_string(),
+            ),
+            active: ActiveValue::Set(true),
+            retired_at: ActiveValue::Set(None),
+            ..Default::default()
+        };
+
+        let txn = self
+            .0
+            .begin()
+            .await
+            .map_internal_error(Some("Failed to start transaction"))?;
+        TokenKeyRepository::retire_all(&txn)
+            .await
+            .map_internal_error(Some("Failed to retire token keys"))?;
+        let key = TokenKeyRepository::insert(&txn, model)
+            .await
+            .map_internal_error(Some("Failed to store token key"))?;
+        txn.commit()
+            .await
+            .map_internal_error(Some("Failed to rotate token keys"))?;
+
+        Ok(key)
+    }
+
+    /// Generate the first token key if there is no active key yet
+    pub async fn ensure_active(&self, algorithm: TokenKeyAlgorithm) -> WebResult<()> {
+        let active = TokenKeyRepository::find_active(&self.0)
+            .await
+            .map_internal_error(Some("Failed to find active token key"))?;
+        if active.is_none() {
+            info!("No active token key found, generating a new one");
+            self.rotate(algorithm).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, model: token_key::Model) -> WebResult<DeleteResult> {
+        TokenKeyRepository::delete(&self.0, model)
+            .await
+            .map_internal_error(Some("Failed to delete token key"))
+    }
+
+    /// Sign a client token using the active key
+    pub async fn encode(&self, claims: &TokenClaims) -> WebResult<String> {
+        let key = TokenKeyRepository::find_active(&self.0)
+            .await
+            .map_internal_error(Some("Failed to find active token key"))?
+            .ok_or(HttpResponseError::internal_error(Some(
+                "No active token key found",
+            )))?;
+
+        let encoding_key = match key.algorithm {
+            TokenKeyAlgorithm::Es256 => EncodingKey::from_ec_pem(key.private_key.as_bytes()),
+            TokenKeyAlgorithm::EdDsa => EncodingKey::from_ed_pem(key.private_key.as_bytes()),
+        }
+        .map_internal_error(Some("Failed to parse token key"))?;
+
+        let mut header = Header::new(jwt_algorithm(key.algorithm));
+        header.kid = Some(key.id.to_string());
+        jsonwebtoken::encode(&header, claims, &encoding_key)
+            .map_internal_error(Some("Failed to encode jwt"))
+    }
+
+    /// Verify a client token using the key referenced by its `kid` header.
+    /// Tokens without a key id were signed using the shared secret
+    /// and are only accepted if the secret is still configured.
+    pub async fn decode(&self, token: &str, legacy_secret: Option<&str>) -> WebResult<TokenClaims> {
+        let header = jsonwebtoken::decode_header(token).map_unauthorized(Some("Invalid token"))?;
+
+        let (decoding_key, validation) = match header.kid {
+            Some(kid) => {
+                let kid = Uuid::parse_str(&kid).map_unauthorized(Some("Invalid token"))?;
+                let key = self
+                    .find_by_id(&kid)
+                    .await?
+                    .ok_or(HttpResponseError::unauthorized(Some("Unknown token key")))?;
+
+                let decoding_key = match key.algorithm {
+                    TokenKeyAlgorithm::Es256 => DecodingKey::from_ec_pem(key.public_key.as_bytes()),
+                    TokenKeyAlgorithm::EdDsa => DecodingKey::from_ed_pem(key.public_key.as_bytes()),
+                }
+                .map_internal_error(Some("Failed to parse token key"))?;
+                (decoding_key, Validation::new(jwt_algorithm(key.algorithm)))
+            }
+            None => {
+                let secret =
+                    legacy_secret.ok_or(HttpResponseError::unauthorized(Some("Invalid token")))?;
+                (
+                    DecodingKey::from_secret(secret.as_bytes()),
+                    Validation::new(Algorithm::HS256),
+                )
+            }
+        };
+
+        jsonwebtoken::decode::<TokenClaims>(token, &decoding_key, &validation)
+            .map(|data| data.claims)
+            .map_unauthorized(Some("Invalid token"))
+    }
+
+    /// The public keys of all keys which verify tokens
+    pub async fn jwks(&self) -> WebResult<JwksDto> {
+        Ok(JwksDto {
+            keys: self
+                .find_all()
+                .await?
+                .iter()
+                .map(jwk::public_jwk)
+                .collect::<Result<Vec<_>, _>>()
+                .map_internal_error(Some("Failed to encode token key"))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::testing::connect;
+
+    #[actix_web::test]
+    async fn keeps_a_single_active_key() {
+        let db = connect().await;
+        let service = TokenKeyService::new(db.clone());
+        let first = service.rotate(TokenKeyAlgorithm::Es256).await.unwrap();
+        let second = service.rotate(TokenKeyAlgorithm::EdDsa).await.unwrap();
+
+        let keys = service.find_all().await.unwrap();
+        assert_eq!(
+            keys.iter()
+                .filter(|k| k.active)
+                .map(|k| k.id)
+                .collect::<Vec<_>>(),
+            vec![second.id]
+        );
+        assert!(keys
+            .iter()
+            .any(|k| k.id == first.id && k.retired_at.is_some()));
+
+        // A key activated by a concurrent rotation is rejected
+        let concurrent = token_key::ActiveModel {
+            id: ActiveValue::Set(Uuid::new_v4()),
+            algorithm: ActiveValue::Set(second.algorithm),
+            private_key: ActiveValue::Set(second.private_key.clone()),
+            public_key: ActiveValue::Set(second.public_key.clone()),
+            active: ActiveValue::Set(true),
+            retired_at: ActiveValue::Set(None),
+            ..Default::default()
+        };
+        assert!(TokenKeyRepository::insert(&db, concurrent).await.is_err());
+    }
+}
@@ -1,8 +1,12 @@
 use crate::entity::token;
 use crate::error::http_response_error::MapHttpResponseError;
 use crate::repository::token_repository::TokenRepository;
+use crate::util::pagination::Pagination;
 use crate::util::types::WebResult;
+use openssl::sha::Sha256;
+use sea_orm::prelude::DateTimeWithTimeZone;
 use sea_orm::{ActiveModelTrait, DatabaseConnection};
+use shared::util::traits::u8_vec_to_string::U8VecToString;
 use uuid::Uuid;
 
 pub struct TokenService(DatabaseConnection);
@@ -12,6 +16,13 @@ impl TokenService {
         Self(db)
     }
 
+    /// The SHA-256 hash tokens are identified by
+    pub fn hash_token(token: &str) -> String {
+        let mut hash = Sha256::new();
+        hash.update(token.as_bytes());
+        hash.finish().to_vec().to_hex_string("")
+    }
+
     pub async fn generate_id(&self) -> WebResult<Uuid> {
         let mut id = Uuid::new_v4();
         while let Some(_) = self.find_by_id(&id, true).await? {
@@ -60,4 +71,33 @@ impl TokenService {
             .await
             .map_internal_error(Some("Failed to deactivate tokens by client id"))
     }
+
+    pub async fn find_by_hash(&self, token_hash: &str) -> WebResult<Option<token::Model>> {
+        TokenRepository::find_by_hash(&self.0, token_hash)
+            .await
+            .map_internal_error(Some("Failed to find token by hash"))
+    }
+
+    pub async fn find_page(
+        &self,
+        include_inactive: bool,
+        unused_since: Option<DateTimeWithTimeZone>,
+        pagination: &Pagination,
+    ) -> WebResult<(Vec<token::Model>, u64)> {
+        TokenRepository::find_page(&self.0, include_inactive, unused_since, pagination)
+            .await
+            .map_internal_error(Some("Failed to find tokens"))
+    }
+
+    pub async fn touch(&self, id: &Uuid, used_from: Option<String>) -> WebResult<()> {
+        TokenRepository::touch(&self.0, id, used_from)
+            .await
+            .map_internal_error(Some("Failed to update token"))
+    }
+
+    pub async fn revoke(&self, model: token::Model) -> WebResult<token::Model> {
+        TokenRepository::revoke(&self.0, model)
+            .await
+            .map_internal_error(Some("Failed to revoke token"))
+    }
 }
@@ -0,0 +1,372 @@
+use crate::entity::{log_entry, log_node, signing_request};
+use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
+use crate::repository::log_entry_repository::LogEntryRepository;
+use crate::repository::log_head_repository::LogHeadRepository;
+use crate::repository::log_node_repository::LogNodeRepository;
+use crate::repository::signing_request_repository::SigningRequestRepository;
+use crate::util::ca_certificate::CACertificate;
+use crate::util::types::WebResult;
+use chrono::Utc;
+use log::info;
+use sea_orm::{ActiveValue, ConnectionTrait, DatabaseConnection, TransactionTrait};
+use shared::model::consistency_proof_dto::ConsistencyProofDto;
+use shared::model::inclusion_proof_dto::InclusionProofDto;
+use shared::model::signed_tree_head_dto::SignedTreeHeadDto;
+use shared::util::merkle;
+use shared::util::merkle::{Hash, Node};
+use shared::util::traits::u8_vec_to_string::U8VecToString;
+use shared::util::types::BasicResult;
+use std::collections::HashMap;
+
+/// The merkle leaf hash of an issued certificate,
+/// given the hash stored in its signing request
+pub fn leaf_hash(certificate_hash: &str) -> BasicResult<Hash> {
+    Ok(merkle::leaf_hash(&merkle::decode_hash(
+        &certificate_hash.replace(':', ""),
+    )?))
+}
+
+fn node_model(node: Node, hash: &Hash) -> log_node::ActiveModel {
+    log_node::ActiveModel {
+        level: ActiveValue::Set(node.level as i32),
+        node_index: ActiveValue::Set(node.index as i64),
+        hash: ActiveValue::Set(hash.to_vec().to_hex_string("")),
+    }
+}
+
+/// Find the hashes of the given subtrees, all of them must be stored
+async fn node_hashes<C: ConnectionTrait>(
+    db: &C,
+    nodes: &[Node],
+) -> BasicResult<HashMap<Node, Hash>> {
+    let hashes = LogNodeRepository::find_all(db, nodes)
+        .await?
+        .into_iter()
+        .map(|n| {
+            Ok((
+                Node::new(n.level as u32, n.node_index as u64),
+                merkle::decode_hash(&n.hash)?,
+            ))
+        })
+        .collect::<BasicResult<HashMap<_, _>>>()?;
+    if nodes.iter().any(|n| !hashes.contains_key(n)) {
+        return Err("A subtree of the issuance log is missing".into());
+    }
+
+    Ok(hashes)
+}
+
+/// Get the root hashes of the subtrees of the given leaf ranges
+async fn subtree_hashes<C: ConnectionTrait>(
+    db: &C,
+    ranges: &[(u64, u64)],
+) -> BasicResult<Vec<Hash>> {
+    let subtrees = ranges
+        .iter()
+        .map(|(start, end)| merkle::subtrees(*start, *end))
+        .collect::<Vec<_>>();
+    let hashes = node_hashes(db, &subtrees.concat()).await?;
+
+    Ok(subtrees
+        .iter()
+        .map(|nodes| merkle::fold_subtrees(&nodes.iter().map(|n| hashes[n]).collect::<Vec<_>>()))
+        .collect())
+}
+
+/// Recompute the subtree hashes and the size of the log from all of its leaves,
+/// e.g. after the leaves have been restored from a backup
+pub async fn rebuild<C: ConnectionTrait>(db: &C) -> BasicResult<()> {
+    let tree_size = LogEntryRepository::count(db).await?;
+    let mut level = LogEntryRepository::find_first(db, tree_size)
+        .await?
+        .iter()
+        .enumerate()
+        .map(|(i, e)| match e.leaf_index as usize == i {
+            true => merkle::decode_hash(&e.leaf_hash),
+            false => Err(format!("The issuance log has no leaf at index {}", i).into()),
+        })
+        .collect::<BasicResult<Vec<_>>>()?;
+
+    let mut nodes = vec![];
+    let mut height = 0;
+    while !level.is_empty() {
+        nodes.extend(
+            level
+                .iter()
+                .enumerate()
+                .map(|(i, hash)| node_model(Node::new(height, i as u64), hash)),
+        );
+        level = level
+            .chunks_exact(2)
+            .map(|pair| merkle::node_hash(&pair[0], &pair[1]))
+            .collect();
+        height += 1;
+    }
+
+    LogNodeRepository::delete_all(db).await?;
+    LogNodeRepository::insert_all(db, nodes).await?;
+    LogHeadRepository::set_tree_size(db, tree_size).await?;
+    Ok(())
+}
+
+/// Manages the append-only, merkle tree backed log of all issued certificates
+pub struct TransparencyLogService(DatabaseConnection);
+
+impl TransparencyLogService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self(db)
+    }
+
+    /// Append an issued certificate to the log, storing the hashes of the subtrees
+    /// its leaf completes. Must be called in the transaction storing the certificate,
+    /// the leaf index is allocated by incrementing the size of the log.
+    pub async fn append<C: ConnectionTrait>(
+        db: &C,
+        req: &signing_request::Model,
+    ) -> BasicResult<log_entry::Model> {
+        let hash = leaf_hash(&req.hash)?;
+        let index = LogHeadRepository::increment(db).await? - 1;
+        let entry = LogEntryRepository::insert(
+            db,
+            log_entry::ActiveModel {
+                leaf_index: ActiveValue::Set(index as i64),
+                signing_request_id: ActiveValue::Set(req.id),
+                serial_number: ActiveValue::Set(req.serial_number.clone()),
+                leaf_hash: ActiveValue::Set(hash.to_vec().to_hex_string("")),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        // The leaf completes a subtree on every level it is the right child on
+        let mut node = Node::new(0, index);
+        let siblings = (0..)
+            .map(|level| Node::new(level, index >> level))
+            .take_while(|n| n.index & 1 == 1)
+            .map(|n| Node::new(n.level, n.index - 1))
+            .collect::<Vec<_>>();
+        let sibling_hashes = node_hashes(db, &siblings).await?;
+
+        let mut hash = hash;
+        let mut nodes = vec![node_model(node, &hash)];
+        for sibling in siblings {
+            hash = merkle::node_hash(&sibling_hashes[&sibling], &hash);
+            node = Node::new(node.level + 1, node.index >> 1);
+            nodes.push(node_model(node, &hash));
+        }
+
+        LogNodeRepository::insert_all(db, nodes).await?;
+        Ok(entry)
+    }
+
+    /// Rebuild the subtree hashes if they don't match the leaves of the log and append
+    /// all certificates which have been issued but are not logged yet,
+    /// e.g. the ones issued before the log was introduced
+    pub async fn sync(&self) -> BasicResult<()> {
+        let txn = self.0.begin().await?;
+        let tree_size = LogEntryRepository::count(&txn).await?;
+        if LogHeadRepository::find_tree_size(&txn).await? != tree_size
+            || LogNodeRepository::count(&txn).await?
+                != 2 * tree_size - tree_size.count_ones() as u64
+        {
+            info!("Rebuilding the issuance log of {} certificates", tree_size);
+            rebuild(&txn).await?;
+        }
+        txn.commit().await?;
+
+        let unlogged = SigningRequestRepository::find_unlogged(&self.0).await?;
+        if !unlogged.is_empty() {
+            info!(
+                "Appending {} certificates to the issuance log",
+                unlogged.len()
+            );
+        }
+
+        for req in unlogged {
+            let txn = self.0.begin().await?;
+            Self::append(&txn, &req).await?;
+            txn.commit().await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn tree_size(&self) -> WebResult<u64> {
+        LogHeadRepository::find_tree_size(&self.0)
+            .await
+            .map_internal_error(Some("Failed to get the size of the issuance log"))
+    }
+
+    /// Get the root hashes of the subtrees of the given leaf ranges
+    async fn subtree_hashes(&self, ranges: &[(u64, u64)]) -> WebResult<Vec<String>> {
+        Ok(subtree_hashes(&self.0, ranges)
+            .await
+            .map_internal_error(Some("Failed to read the issuance log"))?
+            .into_iter()
+            .map(|h| h.to_vec().to_hex_string(""))
+            .collect())
+    }
+
+    /// Resolve an optional tree size, defaulting to the current size of the log
+    async fn resolve_size(&self, tree_size: Option<u64>) -> WebResult<u64> {
+        let current = self.tree_size().await?;
+        match tree_size {
+            Some(size) if size > current => Err(HttpResponseError::bad_request(Some(
+                "The tree size exceeds the size of the issuance log",
+            ))),
+            Some(size) => Ok(size),
+            None => Ok(current),
+        }
+    }
+
+    /// Get the current tree head, signed using the key of the given CA certificate
+    pub async fn tree_head(&self, signer: &CACertificate) -> WebResult<SignedTreeHeadDto> {
+        let tree_size = self.tree_size().await?;
+        let root_hash = self.subtree_hashes(&[(0, tree_size)]).await?.remove(0);
+        let timestamp = Utc::now().timestamp();
+
+        let signature = signer
+            .sign_data(
+                SignedTreeHeadDto::signature_input(tree_size, timestamp, &root_hash).as_bytes(),
+            )
+            .map_internal_error(Some("Failed to sign the tree head"))?;
+
+        Ok(SignedTreeHeadDto {
+            tree_size,
+            root_hash,
+            timestamp,
+            signature: signature.to_hex_string(""),
+            certificate: signer
+                .cert_as_pem()
+                .map_internal_error(Some("Failed to get the signing certificate"))?
+                .to_string(),
+        })
+    }
+
+    /// Get the proof that the certificate with the given
+    /// serial number is included in the tree of the given size
+    pub async fn inclusion_proof(
+        &self,
+        serial_number: &str,
+        tree_size: Option<u64>,
+    ) -> WebResult<InclusionProofDto> {
+        let entry = LogEntryRepository::find_by_serial_number(&self.0, serial_number)
+            .await
+            .map_internal_error(Some("Failed to find log entry"))?
+            .ok_or(HttpResponseError::not_found(Some(
+                "The certificate is not contained in the issuance log",
+            )))?;
+
+        let tree_size = self.resolve_size(tree_size).await?;
+        if entry.leaf_index as u64 >= tree_size {
+            return Err(HttpResponseError::not_found(Some(
+                "The certificate is not contained in a tree of this size",
+            )));
+        }
+
+        Ok(InclusionProofDto {
+            leaf_index: entry.leaf_index as u64,
+            tree_size,
+            leaf_hash: entry.leaf_hash,
+            audit_path: self
+                .subtree_hashes(&merkle::inclusion_path(entry.leaf_index as u64, tree_size))
+                .await?,
+        })
+    }
+
+    /// Get the proof that the tree of size `first` is a prefix of the tree of size `second`
+    pub async fn consistency_proof(
+        &self,
+        first: u64,
+        second: Option<u64>,
+    ) -> WebResult<ConsistencyProofDto> {
+        let second = self.resolve_size(second).await?;
+        if first > second {
+            return Err(HttpResponseError::bad_request(Some(
+                "The first tree size must not exceed the second one",
+            )));
+        }
+
+        Ok(ConsistencyProofDto {
+            first,
+            second,
+            proof: self
+                .subtree_hashes(&merkle::consistency_path(first, second))
+                .await?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::testing::{connect, issued};
+    use sea_orm::{EntityTrait, QueryOrder};
+
+    fn to_hex(hashes: Vec<Hash>) -> Vec<String> {
+        hashes
+            .into_iter()
+            .map(|h| h.to_vec().to_hex_string(""))
+            .collect()
+    }
+
+    async fn find_nodes(db: &DatabaseConnection) -> Vec<log_node::Model> {
+        log_node::Entity::find()
+            .order_by_asc(log_node::Column::Level)
+            .order_by_asc(log_node::Column::NodeIndex)
+            .all(db)
+            .await
+            .unwrap()
+    }
+
+    #[actix_web::test]
+    async fn proves_the_appended_certificates() {
+        let db = connect().await;
+        let service = TransparencyLogService::new(db.clone());
+
+        let mut requests = vec![];
+        let mut leaves = vec![];
+        for serial in 1..=13 {
+            let req = SigningRequestRepository::insert(&db, issued(None, serial))
+                .await
+                .unwrap();
+            TransparencyLogService::append(&db, &req).await.unwrap();
+            leaves.push(leaf_hash(&req.hash).unwrap());
+            requests.push(req);
+
+            let size = leaves.len() as u64;
+            assert_eq!(service.tree_size().await.unwrap(), size);
+            assert_eq!(
+                subtree_hashes(&db, &[(0, size)]).await.unwrap(),
+                vec![merkle::root_hash(&leaves)]
+            );
+
+            for (index, req) in requests.iter().enumerate() {
+                let proof = service
+                    .inclusion_proof(&req.serial_number, None)
+                    .await
+                    .unwrap();
+                assert_eq!(proof.leaf_index, index as u64);
+                assert_eq!(
+                    proof.audit_path,
+                    to_hex(merkle::inclusion_proof(&leaves, index))
+                );
+            }
+
+            for first in 0..=size {
+                let proof = service.consistency_proof(first, None).await.unwrap();
+                assert_eq!(
+                    proof.proof,
+                    to_hex(merkle::consistency_proof(&leaves, first as usize))
+                );
+            }
+        }
+
+        let nodes = find_nodes(&db).await;
+        assert_eq!(nodes.len(), 2 * 13 - 3);
+
+        rebuild(&db).await.unwrap();
+        assert_eq!(find_nodes(&db).await, nodes);
+        assert_eq!(service.tree_size().await.unwrap(), 13);
+    }
+}
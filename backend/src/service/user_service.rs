@@ -1,6 +1,7 @@
 use crate::entity::user;
 use crate::error::http_response_error::{HttpResponseError, MapHttpResponseError};
 use crate::repository::user_repository::UserRepository;
+use crate::util::pagination::Pagination;
 use crate::util::types::WebResult;
 use sea_orm::{ActiveModelTrait, DatabaseConnection, DeleteResult};
 use uuid::Uuid;
@@ -63,10 +64,14 @@ impl UserService {
             .map_internal_error(Some("Failed to find user by external id"))
     }
 
-    pub async fn find_all(&self, include_inactive: bool) -> WebResult<Vec<user::Model>> {
-        UserRepository::find_all(&self.0, include_inactive)
+    pub async fn find_page(
+        &self,
+        include_inactive: bool,
+        pagination: &Pagination,
+    ) -> WebResult<(Vec<user::Model>, u64)> {
+        UserRepository::find_page(&self.0, include_inactive, pagination)
             .await
-            .map_internal_error(Some("Failed to find all users"))
+            .map_internal_error(Some("Failed to find users"))
     }
 
     pub async fn delete(&self, model: user::Model) -> WebResult<DeleteResult> {
@@ -80,4 +85,10 @@ impl UserService {
             .await
             .map_internal_error(Some("Failed to disable user"))
     }
+
+    pub async fn enable(&self, model: user::ActiveModel) -> WebResult<user::ActiveModel> {
+        UserRepository::enable(&self.0, model)
+            .await
+            .map_internal_error(Some("Failed to enable user"))
+    }
 }
@@ -0,0 +1,121 @@
+use crate::entity::{webhook_delivery, webhook_subscription};
+use crate::error::http_response_error::MapHttpResponseError;
+use crate::model::webhook_event::WebhookEvent;
+use crate::repository::webhook_delivery_repository::WebhookDeliveryRepository;
+use crate::repository::webhook_subscription_repository::WebhookSubscriptionRepository;
+use crate::util::pagination::Pagination;
+use crate::util::types::WebResult;
+use log::error;
+use sea_orm::{ActiveValue, DatabaseConnection, DeleteResult};
+use serde::Serialize;
+use shared::util::types::BasicResult;
+use uuid::Uuid;
+
+/// The body of every webhook request
+#[derive(Serialize)]
+struct WebhookPayload<'a, T: Serialize> {
+    /// The delivery id
+    id: String,
+    event: WebhookEvent,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+    data: &'a T,
+}
+
+pub struct WebhookService(DatabaseConnection);
+
+impl WebhookService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self(db)
+    }
+
+    pub async fn insert(
+        &self,
+        model: webhook_subscription::ActiveModel,
+    ) -> WebResult<webhook_subscription::Model> {
+        WebhookSubscriptionRepository::insert(&self.0, model)
+            .await
+            .map_internal_error(Some("Failed to create webhook subscription"))
+    }
+
+    pub async fn find_by_id(&self, id: &Uuid) -> WebResult<Option<webhook_subscription::Model>> {
+        WebhookSubscriptionRepository::find_by_id(&self.0, id)
+            .await
+            .map_internal_error(Some("Failed to find webhook subscription"))
+    }
+
+    pub async fn find_all_by_user(
+        &self,
+        user_id: &Uuid,
+    ) -> WebResult<Vec<webhook_subscription::Model>> {
+        WebhookSubscriptionRepository::find_all_by_user(&self.0, user_id)
+            .await
+            .map_internal_error(Some("Failed to find webhook subscriptions"))
+    }
+
+    pub async fn delete(&self, model: webhook_subscription::Model) -> WebResult<DeleteResult> {
+        WebhookSubscriptionRepository::delete(&self.0, model)
+            .await
+            .map_internal_error(Some("Failed to delete webhook subscription"))
+    }
+
+    pub async fn find_deliveries(
+        &self,
+        subscription_id: &Uuid,
+        pagination: &Pagination,
+    ) -> WebResult<(Vec<webhook_delivery::Model>, u64)> {
+        WebhookDeliveryRepository::find_page_by_subscription(&self.0, subscription_id, pagination)
+            .await
+            .map_internal_error(Some("Failed to find webhook deliveries"))
+    }
+
+    /// Queue the delivery of an event to all subscriptions receiving it.
+    /// Events without an owner are only delivered to global subscriptions.
+    /// Failures are logged rather than returned as emitting an event
+    /// should never fail the request which caused it.
+    pub async fn emit<T: Serialize>(&self, event: WebhookEvent, owner: Option<&Uuid>, data: &T) {
+        if let Err(e) = self.create_deliveries(event, owner, data).await {
+            error!(
+                "Failed to create webhook deliveries for event {}: {}",
+                event.as_str(),
+                e
+            );
+        }
+    }
+
+    async fn create_deliveries<T: Serialize>(
+        &self,
+        event: WebhookEvent,
+        owner: Option<&Uuid>,
+        data: &T,
+    ) -> BasicResult<()> {
+        let created_at = chrono::Utc::now().to_rfc3339();
+        for subscription in WebhookSubscriptionRepository::find_active_by_owner(&self.0, owner)
+            .await?
+            .into_iter()
+            .filter(|s| s.receives(event.as_str()))
+        {
+            let id = Uuid::new_v4();
+            let payload = serde_json::to_string(&WebhookPayload {
+                id: id.to_string(),
+                event,
+                created_at: created_at.clone(),
+                data,
+            })?;
+
+            WebhookDeliveryRepository::insert(
+                &self.0,
+                webhook_delivery::ActiveModel {
+                    id: ActiveValue::Set(id),
+                    subscription_id: ActiveValue::Set(subscription.id),
+                    event: ActiveValue::Set(event.as_str().to_string()),
+                    payload: ActiveValue::Set(payload),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
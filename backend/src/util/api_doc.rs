@@ -9,38 +9,191 @@ use utoipa::{Modify, OpenApi};
         crate::controller::certificate_controller::get_intermediate,
         crate::controller::certificate_controller::generate_intermediate,
         crate::controller::certificate_controller::sign,
+        crate::controller::certificate_controller::get_signing_status,
+        crate::controller::certificate_controller::renew,
+        crate::controller::certificate_controller::get_renewal_status,
+        crate::controller::certificate_controller::enroll,
+        crate::controller::certificate_controller::sign_as_user,
+        crate::controller::certificate_controller::get_user_signing_status,
+        crate::controller::certificate_controller::generate_certificate,
+        crate::controller::certificate_controller::generate_certificate_as_user,
         crate::controller::certificate_controller::generate_root_certificate,
         crate::controller::certificate_controller::get_root_certificate,
+        crate::controller::certificate_controller::verify,
         crate::controller::common::health_check,
+        crate::controller::common::liveness_check,
+        crate::controller::common::readiness_check,
         crate::controller::user_controller::create,
         crate::controller::user_controller::list,
         crate::controller::user_controller::get,
         crate::controller::user_controller::delete,
         crate::controller::user_controller::by_name,
+        crate::controller::user_controller::update,
+        crate::controller::user_controller::add_role,
+        crate::controller::user_controller::remove_role,
+        crate::controller::user_controller::reset_password,
+        crate::controller::team_controller::create,
+        crate::controller::team_controller::list,
+        crate::controller::team_controller::by_id,
+        crate::controller::team_controller::delete,
+        crate::controller::team_controller::set_member,
+        crate::controller::team_controller::remove_member,
+        crate::controller::team_controller::sync,
         crate::controller::client_controller::create,
         crate::controller::client_controller::regenerate_token,
         crate::controller::client_controller::list,
         crate::controller::client_controller::by_id,
         crate::controller::client_controller::delete,
+        crate::controller::client_controller::set_policy,
+        crate::controller::client_controller::transfer,
+        crate::controller::client_controller::admin_list,
+        crate::controller::client_controller::admin_by_id,
+        crate::controller::client_controller::admin_delete,
+        crate::controller::enrollment_token_controller::create,
+        crate::controller::enrollment_token_controller::list,
+        crate::controller::enrollment_token_controller::delete,
         crate::controller::signing_request_controller::by_client_id,
         crate::controller::signing_request_controller::get_all,
+        crate::controller::signing_request_controller::admin_get_all,
+        crate::controller::signing_request_controller::revoke,
         crate::controller::admin_controller::list_roles,
+        crate::controller::audit_controller::list,
+        crate::controller::audit_controller::export,
+        crate::controller::backup_controller::export,
+        crate::controller::backup_controller::restore,
+        crate::controller::quota_controller::list,
+        crate::controller::quota_controller::set,
+        crate::controller::quota_controller::delete,
+        crate::controller::approval_controller::list,
+        crate::controller::approval_controller::approve,
+        crate::controller::approval_controller::reject,
+        crate::controller::token_controller::introspect,
+        crate::controller::token_controller::list,
+        crate::controller::token_controller::revoke,
+        crate::controller::token_key_controller::jwks,
+        crate::controller::token_key_controller::list,
+        crate::controller::token_key_controller::rotate,
+        crate::controller::token_key_controller::delete,
+        crate::controller::tools_controller::inspect,
+        crate::controller::transparency_controller::signed_tree_head,
+        crate::controller::transparency_controller::inclusion_proof,
+        crate::controller::transparency_controller::consistency_proof,
+        crate::controller::webhook_controller::create,
+        crate::controller::webhook_controller::list,
+        crate::controller::webhook_controller::deliveries,
+        crate::controller::webhook_controller::delete,
     ),
     components(
         schemas(shared::model::new_signing_request_dto::NewSigningRequestDto),
+        schemas(crate::model::user_signing_request_dto::UserSigningRequestDto),
+        schemas(shared::model::enrollment_request_dto::EnrollmentRequestDto),
+        schemas(shared::model::enrollment_result_dto::EnrollmentResultDto),
+        schemas(crate::model::create_enrollment_token_dto::CreateEnrollmentTokenDto),
+        schemas(crate::model::enrollment_token_dto::EnrollmentTokenDto),
+        schemas(crate::model::generate_certificate_dto::GenerateCertificateDto),
+        schemas(crate::model::generated_certificate_dto::GeneratedCertificateDto),
         schemas(crate::model::error_dto::ErrorDto),
-        schemas(shared::model::health_info_dto::HealthInfoDto),
+        schemas(
+            shared::model::health_info_dto::HealthInfoDto,
+            shared::model::component_health_dto::ComponentHealthDto,
+            shared::model::component_health_dto::ComponentStatus
+        ),
         schemas(
             crate::model::user_dto::UserDto,
-            crate::model::create_user_dto::CreateUserDto
+            crate::model::create_user_dto::CreateUserDto,
+            crate::model::update_user_dto::UpdateUserDto,
+            crate::model::reset_password_dto::ResetPasswordDto
         ),
         schemas(
             crate::model::client_dto::ClientDto,
-            crate::model::create_client_dto::CreateClientDto
+            crate::model::create_client_dto::CreateClientDto,
+            crate::model::token_scope::TokenScope,
+            crate::model::client_policy_dto::ClientPolicyDto,
+            crate::model::transfer_client_dto::TransferClientDto
+        ),
+        schemas(
+            crate::model::team_dto::TeamDto,
+            crate::model::team_dto::TeamMemberDto,
+            crate::model::create_team_dto::CreateTeamDto,
+            crate::model::set_team_member_dto::SetTeamMemberDto,
+            crate::entity::team_member::TeamRole
         ),
         schemas(crate::model::ca_certificate_dto::CACertificateDto),
         schemas(crate::model::generate_intermediate_dto::GenerateIntermediateDto),
-        schemas(shared::model::signing_request_dto::SigningRequestDto),
+        schemas(
+            shared::model::signing_request_dto::SigningRequestDto,
+            shared::model::certificate_status::CertificateStatus
+        ),
+        schemas(
+            crate::model::page_dto::SigningRequestPageDto,
+            crate::model::page_dto::ClientPageDto,
+            crate::model::page_dto::UserPageDto,
+            crate::model::page_dto::WebhookDeliveryPageDto,
+            crate::model::page_dto::AuditEventPageDto,
+            crate::model::page_dto::ApprovalRequestPageDto
+        ),
+        schemas(
+            crate::model::inspect_request_dto::InspectRequestDto,
+            crate::model::inspection_result_dto::InspectionResultDto,
+            crate::model::inspection_result_dto::InspectedObjectType,
+            crate::model::certificate_info_dto::CertificateInfoDto,
+            crate::model::certificate_info_dto::ExtensionDto,
+            crate::model::certificate_info_dto::FingerprintsDto,
+            crate::model::certificate_info_dto::RevocationListInfoDto,
+            crate::model::certificate_info_dto::RevokedCertificateDto
+        ),
+        schemas(
+            crate::model::verify_certificate_dto::VerifyCertificateDto,
+            crate::model::verify_certificate_dto::CertificatePurpose,
+            crate::model::verification_result_dto::VerificationResultDto,
+            crate::model::verification_result_dto::ChainEntryDto
+        ),
+        schemas(
+            crate::model::webhook_event::WebhookEvent,
+            crate::model::create_webhook_subscription_dto::CreateWebhookSubscriptionDto,
+            crate::model::webhook_subscription_dto::WebhookSubscriptionDto,
+            crate::model::webhook_delivery_dto::WebhookDeliveryDto
+        ),
+        schemas(
+            crate::model::audit_event_dto::AuditEventDto,
+            crate::entity::audit_event::AuditAction,
+            crate::entity::audit_event::AuditActorType,
+            crate::entity::audit_event::AuditOutcome
+        ),
+        schemas(
+            crate::model::create_backup_dto::CreateBackupDto,
+            crate::model::restore_backup_dto::RestoreBackupDto,
+            crate::model::backup_archive_dto::BackupArchiveDto,
+            crate::model::backup_summary_dto::BackupSummaryDto
+        ),
+        schemas(
+            crate::model::quota_dto::QuotaDto,
+            crate::model::set_quota_dto::SetQuotaDto,
+            crate::entity::quota::QuotaScope
+        ),
+        schemas(
+            crate::model::token_dto::TokenDto,
+            crate::model::page_dto::TokenPageDto,
+            crate::model::revoke_token_dto::RevokeTokenDto,
+            crate::model::introspection_request_dto::IntrospectionRequestDto,
+            crate::model::introspection_dto::IntrospectionDto
+        ),
+        schemas(
+            crate::model::jwk_dto::JwkDto,
+            crate::model::jwk_dto::JwksDto,
+            crate::model::token_key_dto::TokenKeyDto,
+            crate::entity::token_key::TokenKeyAlgorithm
+        ),
+        schemas(
+            crate::model::approval_request_dto::ApprovalRequestDto,
+            crate::model::reject_approval_dto::RejectApprovalDto,
+            crate::entity::approval_request::ApprovalStatus
+        ),
+        schemas(
+            shared::model::signed_tree_head_dto::SignedTreeHeadDto,
+            shared::model::inclusion_proof_dto::InclusionProofDto,
+            shared::model::consistency_proof_dto::ConsistencyProofDto
+        ),
     ),
     tags(
         (name = "Certificates", description = "Certificate endpoints"),
@@ -49,6 +202,9 @@ use utoipa::{Modify, OpenApi};
         (name = "Clients", description = "Client endpoints"),
         (name = "Signing requests", description = "Signing Request endpoints"),
         (name = "Admin", description = "Admin endpoints"),
+        (name = "Tools", description = "Tool endpoints"),
+        (name = "Webhooks", description = "Webhook endpoints"),
+        (name = "Transparency", description = "Certificate issuance log endpoints"),
     ),
     info(
         title = "Certificate Authority API",
@@ -79,5 +235,11 @@ impl Modify for SecurityAddon {
             "jwt",
             SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("Authorization"))),
         );
+        components.add_security_scheme(
+            "certificate",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(
+                shared::util::request_signature::SIGNATURE_HEADER,
+            ))),
+        );
     }
 }
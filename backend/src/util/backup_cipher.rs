@@ -0,0 +1,108 @@
+use crate::model::backup_archive_dto::{BackupArchiveDto, BACKUP_FORMAT, BACKUP_VERSION};
+use chrono::Utc;
+use openssl::base64;
+use openssl::hash::MessageDigest;
+use openssl::pkcs5::pbkdf2_hmac;
+use openssl::rand::rand_bytes;
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use shared::util::types::BasicResult;
+
+const KDF: &str = "pbkdf2-sha256";
+const CIPHER: &str = "aes-256-gcm";
+/// The number of PBKDF2 iterations used for new archives
+const KDF_ITERATIONS: u32 = 600_000;
+/// The maximum number of PBKDF2 iterations accepted when decrypting
+const MAX_KDF_ITERATIONS: u32 = 10_000_000;
+const SALT_LENGTH: usize = 16;
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> BasicResult<Vec<u8>> {
+    let mut key = vec![0; KEY_LENGTH];
+    pbkdf2_hmac(
+        passphrase.as_bytes(),
+        salt,
+        iterations as usize,
+        MessageDigest::sha256(),
+        &mut key,
+    )?;
+
+    Ok(key)
+}
+
+fn random_bytes(len: usize) -> BasicResult<Vec<u8>> {
+    let mut bytes = vec![0; len];
+    rand_bytes(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Encrypt the content of a backup with the given passphrase
+pub fn encrypt(passphrase: &str, content: &[u8]) -> BasicResult<BackupArchiveDto> {
+    let salt = random_bytes(SALT_LENGTH)?;
+    let nonce = random_bytes(NONCE_LENGTH)?;
+    let key = derive_key(passphrase, &salt, KDF_ITERATIONS)?;
+
+    let mut archive = BackupArchiveDto {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        created_at: Utc::now().to_rfc3339(),
+        kdf: KDF.to_string(),
+        iterations: KDF_ITERATIONS,
+        salt: base64::encode_block(&salt),
+        cipher: CIPHER.to_string(),
+        nonce: base64::encode_block(&nonce),
+        tag: String::new(),
+        data: String::new(),
+    };
+
+    let mut tag = vec![0; TAG_LENGTH];
+    let data = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        &key,
+        Some(&nonce),
+        &archive.associated_data(),
+        content,
+        &mut tag,
+    )?;
+
+    archive.tag = base64::encode_block(&tag);
+    archive.data = base64::encode_block(&data);
+    Ok(archive)
+}
+
+/// Decrypt the content of a backup.
+/// Fails if the passphrase is wrong or the archive has been modified.
+pub fn decrypt(passphrase: &str, archive: &BackupArchiveDto) -> BasicResult<Vec<u8>> {
+    if archive.format != BACKUP_FORMAT {
+        return Err("The file is not a backup archive".into());
+    }
+
+    if archive.version != BACKUP_VERSION {
+        return Err(format!("Unsupported backup version {}", archive.version).into());
+    }
+
+    if archive.kdf != KDF
+        || archive.cipher != CIPHER
+        || archive.iterations == 0
+        || archive.iterations > MAX_KDF_ITERATIONS
+    {
+        return Err("Unsupported backup encryption".into());
+    }
+
+    let key = derive_key(
+        passphrase,
+        &base64::decode_block(&archive.salt)?,
+        archive.iterations,
+    )?;
+
+    decrypt_aead(
+        Cipher::aes_256_gcm(),
+        &key,
+        Some(&base64::decode_block(&archive.nonce)?),
+        &archive.associated_data(),
+        &base64::decode_block(&archive.data)?,
+        &base64::decode_block(&archive.tag)?,
+    )
+    .map_err(|_| "Invalid passphrase or the archive has been modified".into())
+}
@@ -2,12 +2,13 @@
 
 use crate::config::config::Config;
 use crate::entity::certificate;
-use openssl::asn1::Asn1Time;
+use openssl::asn1::{Asn1Time, Asn1TimeRef};
 use openssl::bn::{BigNum, MsbOption};
 use openssl::ec::{EcGroup, EcKey};
 use openssl::hash::MessageDigest;
 use openssl::nid::Nid;
 use openssl::pkey::{PKey, Private};
+use openssl::sign::Signer;
 use openssl::x509::extension::{
     AuthorityKeyIdentifier, BasicConstraints, KeyUsage, SubjectAlternativeName,
     SubjectKeyIdentifier,
@@ -204,14 +205,29 @@ impl CACertificate {
             .map_err(|e| e.into())
     }
 
+    /// Sign arbitrary data using SHA-256 and the key of this certificate
+    pub fn sign_data(&self, data: &[u8]) -> BasicResult<Vec<u8>> {
+        let mut signer = Signer::new(MessageDigest::sha256(), &self.key_pair)?;
+        signer.update(data)?;
+        signer.sign_to_vec().map_err(|e| e.into())
+    }
+
+    /// Whether the private key belongs to the certificate
+    pub fn key_matches(&self) -> BasicResult<bool> {
+        Ok(self.cert.public_key()?.public_eq(&self.key_pair))
+    }
+
     pub fn valid_until(&self) -> BasicResult<DateTimeWithTimeZone> {
-        let not_after = self.cert.not_after();
-        let not_after = not_after.to_string() + " +0000";
-        DateTimeWithTimeZone::parse_from_str(&not_after, "%b %d %H:%M:%S %Y GMT %z")
-            .map_err(|e| e.into())
+        asn1_time_to_date_time(self.cert.not_after())
     }
 }
 
+/// Convert an openssl time to a date time in UTC
+pub fn asn1_time_to_date_time(time: &Asn1TimeRef) -> BasicResult<DateTimeWithTimeZone> {
+    let time = time.to_string() + " +0000";
+    DateTimeWithTimeZone::parse_from_str(&time, "%b %d %H:%M:%S %Y GMT %z").map_err(|e| e.into())
+}
+
 impl TryFrom<certificate::Model> for CACertificate {
     type Error = Box<dyn Error>;
 
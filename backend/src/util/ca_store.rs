@@ -0,0 +1,114 @@
+use crate::config::app_state::AppState;
+use crate::error::http_response_error::MapHttpResponseError;
+use crate::util::types::WebResult;
+use openssl::stack::Stack;
+use openssl::x509::store::{X509Store, X509StoreBuilder};
+use openssl::x509::verify::X509VerifyFlags;
+use openssl::x509::{X509CrlRef, X509PurposeId, X509Ref, X509StoreContext, X509};
+use shared::util::types::BasicResult;
+
+/// The result of verifying a certificate against the CA certificates
+pub struct ChainVerification {
+    /// Whether a chain to a trusted root could be built
+    pub valid: bool,
+    /// The openssl error, if the verification failed
+    pub error: Option<String>,
+    /// The depth in the chain at which the error occurred
+    pub error_depth: Option<u32>,
+    /// The verified chain, starting with the leaf certificate
+    pub chain: Vec<X509>,
+}
+
+/// A trust store containing all root and intermediate
+/// certificates ever issued by this CA
+pub struct CAStore {
+    certificates: Vec<X509>,
+}
+
+impl CAStore {
+    /// Load all root and intermediate certificates from the database
+    pub async fn load(data: &AppState) -> WebResult<Self> {
+        let roots = data.root_certificate_service.find_all().await?;
+        let intermediates = data.certificate_service.find_all().await?;
+
+        Self::from_pems(
+            roots
+                .iter()
+                .map(|cert| cert.public.as_slice())
+                .chain(intermediates.iter().map(|cert| cert.public.as_slice())),
+        )
+        .map_internal_error(Some("Failed to load the CA certificates"))
+    }
+
+    pub fn from_pems<'a, I>(pems: I) -> BasicResult<Self>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        Ok(Self {
+            certificates: pems
+                .into_iter()
+                .map(X509::from_pem)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn build_store(&self, purpose: Option<X509PurposeId>) -> BasicResult<X509Store> {
+        let mut builder = X509StoreBuilder::new()?;
+        for cert in self.certificates.iter() {
+            builder.add_cert(cert.clone())?;
+        }
+
+        // Expired certificates are reported separately,
+        // so the chain itself is checked regardless of the current time
+        builder.set_flags(X509VerifyFlags::NO_CHECK_TIME)?;
+        if let Some(purpose) = purpose {
+            builder.set_purpose(purpose)?;
+        }
+
+        Ok(builder.build())
+    }
+
+    /// Verify a certificate against the CA certificates.
+    /// The untrusted certificates may be used to build the chain
+    /// but are never trusted on their own.
+    pub fn verify(
+        &self,
+        cert: &X509Ref,
+        untrusted: &[X509],
+        purpose: Option<X509PurposeId>,
+    ) -> BasicResult<ChainVerification> {
+        let store = self.build_store(purpose)?;
+        let mut chain = Stack::new()?;
+        for cert in untrusted {
+            chain.push(cert.clone())?;
+        }
+
+        let mut context = X509StoreContext::new()?;
+        Ok(context.init(&store, cert, &chain, |ctx| {
+            let valid = ctx.verify_cert()?;
+            Ok(ChainVerification {
+                valid,
+                error: (!valid).then(|| ctx.error().error_string().to_string()),
+                error_depth: (!valid).then(|| ctx.error_depth()),
+                chain: ctx
+                    .chain()
+                    .map(|chain| chain.iter().map(|c| c.to_owned()).collect())
+                    .unwrap_or_default(),
+            })
+        })?)
+    }
+
+    /// Check whether the given certificate chains to a root certificate of this CA
+    pub fn chains_to_ca(&self, cert: &X509Ref) -> BasicResult<bool> {
+        Ok(self.verify(cert, &[], None)?.valid)
+    }
+
+    /// Check whether the given revocation list was signed by a certificate of this CA
+    pub fn signed_crl(&self, crl: &X509CrlRef) -> bool {
+        self.certificates.iter().any(|cert| {
+            cert.public_key()
+                .and_then(|key| crl.verify(&key))
+                .unwrap_or(false)
+        })
+    }
+}
@@ -0,0 +1,300 @@
+use crate::model::certificate_info_dto::{
+    CertificateInfoDto, ExtensionDto, FingerprintsDto, RevocationListInfoDto, RevokedCertificateDto,
+};
+use crate::model::verification_result_dto::ChainEntryDto;
+use crate::util::ca_certificate::asn1_time_to_date_time;
+use openssl::asn1::Asn1Time;
+use openssl::base64;
+use openssl::hash::{hash, MessageDigest};
+use openssl::pkey::{Id, PKey, Public};
+use openssl::x509::{X509Crl, X509CrlRef, X509Ref, X509Req, X509ReqRef, X509};
+use shared::util::traits::u8_vec_to_string::U8VecToString;
+use shared::util::types::BasicResult;
+use std::net::IpAddr;
+use x509_parser::certificate::X509Certificate;
+use x509_parser::certification_request::X509CertificationRequest;
+use x509_parser::cri_attributes::ParsedCriAttribute;
+use x509_parser::der_parser::oid::Oid;
+use x509_parser::extensions::{ExtendedKeyUsage, GeneralName, ParsedExtension, X509Extension};
+use x509_parser::objects::{oid2sn, oid_registry};
+use x509_parser::prelude::FromDer;
+use x509_parser::revocation_list::CertificateRevocationList;
+
+/// An object which can be decoded by the inspection endpoint
+pub enum InspectedObject {
+    Certificate(X509),
+    SigningRequest(X509Req),
+    RevocationList(X509Crl),
+}
+
+impl InspectedObject {
+    /// Parse a PEM encoded or base64 encoded DER object
+    pub fn parse(data: &str) -> BasicResult<Self> {
+        let data = data.trim();
+        if data.starts_with("-----BEGIN") {
+            let pem = data.as_bytes();
+            if let Ok(cert) = X509::from_pem(pem) {
+                return Ok(Self::Certificate(cert));
+            } else if let Ok(req) = X509Req::from_pem(pem) {
+                return Ok(Self::SigningRequest(req));
+            } else if let Ok(crl) = X509Crl::from_pem(pem) {
+                return Ok(Self::RevocationList(crl));
+            }
+        } else {
+            let der = base64::decode_block(&data.split_whitespace().collect::<String>())?;
+            if let Ok(cert) = X509::from_der(&der) {
+                return Ok(Self::Certificate(cert));
+            } else if let Ok(req) = X509Req::from_der(&der) {
+                return Ok(Self::SigningRequest(req));
+            } else if let Ok(crl) = X509Crl::from_der(&der) {
+                return Ok(Self::RevocationList(crl));
+            }
+        }
+
+        Err("The data is not a certificate, signing request or revocation list".into())
+    }
+}
+
+pub fn certificate_info(cert: &X509Ref) -> BasicResult<CertificateInfoDto> {
+    let der = cert.to_der()?;
+    let (_, parsed) = X509Certificate::from_der(&der).map_err(|e| e.to_string())?;
+    let (key_algorithm, key_size) = describe_key(&cert.public_key()?);
+
+    Ok(CertificateInfoDto {
+        subject: parsed.subject().to_string(),
+        issuer: Some(parsed.issuer().to_string()),
+        serial_number: Some(cert.serial_number().to_bn()?.to_hex_str()?.to_string()),
+        not_before: Some(asn1_time_to_date_time(cert.not_before())?.to_rfc3339()),
+        not_after: Some(asn1_time_to_date_time(cert.not_after())?.to_rfc3339()),
+        key_algorithm,
+        key_size,
+        signature_algorithm: cert.signature_algorithm().object().to_string(),
+        alternative_names: parsed
+            .subject_alternative_name()
+            .map_err(|e| e.to_string())?
+            .map(|san| describe_general_names(&san.value.general_names))
+            .unwrap_or_default(),
+        key_usage: parsed
+            .key_usage()
+            .map_err(|e| e.to_string())?
+            .map(|ku| split_list(&ku.value.to_string()))
+            .unwrap_or_default(),
+        extended_key_usage: parsed
+            .extended_key_usage()
+            .map_err(|e| e.to_string())?
+            .map(|eku| describe_extended_key_usage(eku.value))
+            .unwrap_or_default(),
+        is_ca: parsed.is_ca(),
+        extensions: parsed.extensions().iter().map(extension_dto).collect(),
+        fingerprints: fingerprints(&der)?,
+    })
+}
+
+pub fn chain_entry(cert: &X509Ref) -> BasicResult<ChainEntryDto> {
+    let der = cert.to_der()?;
+    let (_, parsed) = X509Certificate::from_der(&der).map_err(|e| e.to_string())?;
+    let now = Asn1Time::days_from_now(0)?;
+
+    Ok(ChainEntryDto {
+        subject: parsed.subject().to_string(),
+        issuer: parsed.issuer().to_string(),
+        serial_number: cert.serial_number().to_bn()?.to_hex_str()?.to_string(),
+        not_before: asn1_time_to_date_time(cert.not_before())?.to_rfc3339(),
+        not_after: asn1_time_to_date_time(cert.not_after())?.to_rfc3339(),
+        expired: cert.not_after() < now,
+        not_yet_valid: cert.not_before() > now,
+    })
+}
+
+pub fn signing_request_info(req: &X509ReqRef) -> BasicResult<CertificateInfoDto> {
+    let der = req.to_der()?;
+    let (_, parsed) = X509CertificationRequest::from_der(&der).map_err(|e| e.to_string())?;
+    let (key_algorithm, key_size) = describe_key(&req.public_key()?);
+
+    let extensions = parsed
+        .certification_request_info
+        .iter_attributes()
+        .filter_map(|attr| match attr.parsed_attribute() {
+            ParsedCriAttribute::ExtensionRequest(req) => Some(req.extensions.iter()),
+            _ => None,
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let mut alternative_names = vec![];
+    let mut key_usage = vec![];
+    let mut extended_key_usage = vec![];
+    let mut is_ca = false;
+    for ext in extensions.iter() {
+        match ext.parsed_extension() {
+            ParsedExtension::SubjectAlternativeName(san) => {
+                alternative_names.extend(describe_general_names(&san.general_names))
+            }
+            ParsedExtension::KeyUsage(ku) => key_usage.extend(split_list(&ku.to_string())),
+            ParsedExtension::ExtendedKeyUsage(eku) => {
+                extended_key_usage.extend(describe_extended_key_usage(eku))
+            }
+            ParsedExtension::BasicConstraints(bc) => is_ca = bc.ca,
+            _ => {}
+        }
+    }
+
+    Ok(CertificateInfoDto {
+        subject: parsed.certification_request_info.subject.to_string(),
+        issuer: None,
+        serial_number: None,
+        not_before: None,
+        not_after: None,
+        key_algorithm,
+        key_size,
+        signature_algorithm: describe_oid(&parsed.signature_algorithm.algorithm),
+        alternative_names,
+        key_usage,
+        extended_key_usage,
+        is_ca,
+        extensions: extensions.into_iter().map(extension_dto).collect(),
+        fingerprints: fingerprints(&der)?,
+    })
+}
+
+pub fn revocation_list_info(crl: &X509CrlRef) -> BasicResult<RevocationListInfoDto> {
+    let der = crl.to_der()?;
+    let (_, parsed) = CertificateRevocationList::from_der(&der).map_err(|e| e.to_string())?;
+
+    Ok(RevocationListInfoDto {
+        issuer: parsed.issuer().to_string(),
+        last_update: asn1_time_to_date_time(crl.last_update())?.to_rfc3339(),
+        next_update: crl
+            .next_update()
+            .map(asn1_time_to_date_time)
+            .transpose()?
+            .map(|t| t.to_rfc3339()),
+        signature_algorithm: describe_oid(&parsed.signature_algorithm.algorithm),
+        revoked: crl
+            .get_revoked()
+            .map(|revoked| {
+                revoked
+                    .iter()
+                    .map(|r| {
+                        Ok(RevokedCertificateDto {
+                            serial_number: r.serial_number().to_bn()?.to_hex_str()?.to_string(),
+                            revoked_at: asn1_time_to_date_time(r.revocation_date())?.to_rfc3339(),
+                        })
+                    })
+                    .collect::<BasicResult<Vec<_>>>()
+            })
+            .transpose()?
+            .unwrap_or_default(),
+        extensions: parsed.extensions().iter().map(extension_dto).collect(),
+        fingerprints: fingerprints(&der)?,
+    })
+}
+
+fn fingerprints(der: &[u8]) -> BasicResult<FingerprintsDto> {
+    Ok(FingerprintsDto {
+        sha1: hash(MessageDigest::sha1(), der)?
+            .to_vec()
+            .to_hex_string(":"),
+        sha256: hash(MessageDigest::sha256(), der)?
+            .to_vec()
+            .to_hex_string(":"),
+    })
+}
+
+fn describe_key(key: &PKey<Public>) -> (String, u32) {
+    let algorithm = match key.id() {
+        Id::RSA => "RSA".to_string(),
+        Id::EC => key
+            .ec_key()
+            .ok()
+            .and_then(|k| k.group().curve_name())
+            .and_then(|n| n.short_name().ok())
+            .map(|n| format!("EC ({})", n))
+            .unwrap_or("EC".to_string()),
+        Id::ED25519 => "Ed25519".to_string(),
+        Id::ED448 => "Ed448".to_string(),
+        Id::DSA => "DSA".to_string(),
+        _ => "Unknown".to_string(),
+    };
+
+    (algorithm, key.bits())
+}
+
+fn split_list(list: &str) -> Vec<String> {
+    list.split(", ")
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn describe_general_names(names: &[GeneralName]) -> Vec<String> {
+    names
+        .iter()
+        .map(|name| match name {
+            GeneralName::DNSName(name) => name.to_string(),
+            GeneralName::RFC822Name(name) => name.to_string(),
+            GeneralName::URI(uri) => uri.to_string(),
+            GeneralName::IPAddress(ip) => match ip.len() {
+                4 => IpAddr::from(<[u8; 4]>::try_from(*ip).unwrap()).to_string(),
+                16 => IpAddr::from(<[u8; 16]>::try_from(*ip).unwrap()).to_string(),
+                _ => name.to_string(),
+            },
+            _ => name.to_string(),
+        })
+        .collect()
+}
+
+fn describe_extended_key_usage(eku: &ExtendedKeyUsage) -> Vec<String> {
+    let mut res = vec![];
+    for (set, name) in [
+        (eku.any, "anyExtendedKeyUsage"),
+        (eku.server_auth, "serverAuth"),
+        (eku.client_auth, "clientAuth"),
+        (eku.code_signing, "codeSigning"),
+        (eku.email_protection, "emailProtection"),
+        (eku.time_stamping, "timeStamping"),
+        (eku.ocsp_signing, "OCSPSigning"),
+    ] {
+        if set {
+            res.push(name.to_string());
+        }
+    }
+
+    res.extend(eku.other.iter().map(|oid| oid.to_id_string()));
+    res
+}
+
+fn extension_dto(ext: &X509Extension) -> ExtensionDto {
+    let value = match ext.parsed_extension() {
+        ParsedExtension::KeyUsage(ku) => Some(ku.to_string()),
+        ParsedExtension::ExtendedKeyUsage(eku) => Some(describe_extended_key_usage(eku).join(", ")),
+        ParsedExtension::BasicConstraints(bc) => Some(match bc.path_len_constraint {
+            Some(len) => format!("CA:{}, pathlen:{}", bc.ca, len),
+            None => format!("CA:{}", bc.ca),
+        }),
+        ParsedExtension::SubjectAlternativeName(san) => {
+            Some(describe_general_names(&san.general_names).join(", "))
+        }
+        ParsedExtension::SubjectKeyIdentifier(id) => Some(id.0.to_vec().to_hex_string(":")),
+        ParsedExtension::AuthorityKeyIdentifier(aki) => aki
+            .key_identifier
+            .as_ref()
+            .map(|id| id.0.to_vec().to_hex_string(":")),
+        _ => None,
+    };
+
+    ExtensionDto {
+        oid: ext.oid.to_id_string(),
+        name: oid2sn(&ext.oid, oid_registry())
+            .ok()
+            .map(|name| name.to_string()),
+        critical: ext.critical,
+        value: value.unwrap_or_else(|| ext.value.to_vec().to_hex_string(":")),
+    }
+}
+
+fn describe_oid(oid: &Oid) -> String {
+    oid2sn(oid, oid_registry())
+        .map(|name| name.to_string())
+        .unwrap_or_else(|_| oid.to_id_string())
+}
@@ -0,0 +1,14 @@
+use crate::error::http_response_error::MapHttpResponseError;
+use crate::util::types::WebResult;
+use chrono::Utc;
+use sea_orm::prelude::DateTimeWithTimeZone;
+
+/// Parse a date supplied in a request.
+///
+/// The date is converted to UTC as all dates are stored in UTC and SQLite
+/// compares dates as strings, which only works if they share the same offset.
+pub fn parse_date(date: &str) -> WebResult<DateTimeWithTimeZone> {
+    DateTimeWithTimeZone::parse_from_rfc3339(date)
+        .map(|d| d.with_timezone(&Utc).into())
+        .map_bad_request(Some("Invalid date supplied"))
+}
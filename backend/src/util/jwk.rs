@@ -0,0 +1,71 @@
+use crate::entity::token_key;
+use crate::entity::token_key::TokenKeyAlgorithm;
+use crate::model::jwk_dto::JwkDto;
+use openssl::base64;
+use openssl::bn::{BigNum, BigNumContext};
+use openssl::ec::{EcGroup, EcKey};
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private};
+use shared::util::types::BasicResult;
+
+/// The length of a P-256 coordinate in bytes
+const P256_COORDINATE_LENGTH: i32 = 32;
+
+/// Base64url encode without padding, as required by RFC 7518
+fn base64_url(data: &[u8]) -> String {
+    base64::encode_block(data)
+        .trim_end_matches('=')
+        .replace('+', "-")
+        .replace('/', "_")
+}
+
+/// Generate a new key pair for signing tokens
+pub fn generate_key_pair(algorithm: TokenKeyAlgorithm) -> BasicResult<PKey<Private>> {
+    match algorithm {
+        TokenKeyAlgorithm::Es256 => {
+            let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+            Ok(PKey::from_ec_key(EcKey::generate(&group)?)?)
+        }
+        TokenKeyAlgorithm::EdDsa => Ok(PKey::generate_ed25519()?),
+    }
+}
+
+/// The public key of a token key as a JSON Web Key
+pub fn public_jwk(key: &token_key::Model) -> BasicResult<JwkDto> {
+    let public = PKey::public_key_from_pem(key.public_key.as_bytes())?;
+    let (kty, alg, crv, x, y) = match key.algorithm {
+        TokenKeyAlgorithm::Es256 => {
+            let ec = public.ec_key()?;
+            let mut ctx = BigNumContext::new()?;
+            let mut x = BigNum::new()?;
+            let mut y = BigNum::new()?;
+            ec.public_key()
+                .affine_coordinates_gfp(ec.group(), &mut x, &mut y, &mut ctx)?;
+
+            (
+                "EC",
+                "ES256",
+                "P-256",
+                base64_url(&x.to_vec_padded(P256_COORDINATE_LENGTH)?),
+                Some(base64_url(&y.to_vec_padded(P256_COORDINATE_LENGTH)?)),
+            )
+        }
+        TokenKeyAlgorithm::EdDsa => (
+            "OKP",
+            "EdDSA",
+            "Ed25519",
+            base64_url(&public.raw_public_key()?),
+            None,
+        ),
+    };
+
+    Ok(JwkDto {
+        kty: kty.to_string(),
+        kid: key.id.to_string(),
+        alg: alg.to_string(),
+        key_use: "sig".to_string(),
+        crv: crv.to_string(),
+        x,
+        y,
+    })
+}
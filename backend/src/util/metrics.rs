@@ -0,0 +1,81 @@
+use lazy_static::lazy_static;
+use prometheus::{
+    register_histogram, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+};
+use std::future::Future;
+
+lazy_static! {
+    pub static ref CERTIFICATES_ISSUED: IntCounterVec = register_int_counter_vec!(
+        "ca_certificates_issued_total",
+        "Number of certificates issued",
+        &["client"]
+    )
+    .unwrap();
+    pub static ref SIGNING_DURATION: Histogram = register_histogram!(
+        "ca_signing_duration_seconds",
+        "Time it took to process certificate signing requests"
+    )
+    .unwrap();
+    pub static ref SIGNING_FAILURES: IntCounterVec = register_int_counter_vec!(
+        "ca_signing_failures_total",
+        "Number of failed certificate signing requests",
+        &["code"]
+    )
+    .unwrap();
+    pub static ref CERTIFICATES_REVOKED: IntCounter = register_int_counter!(
+        "ca_certificates_revoked_total",
+        "Number of certificates revoked"
+    )
+    .unwrap();
+    pub static ref KEYCLOAK_DURATION: HistogramVec = register_histogram_vec!(
+        "ca_keycloak_request_duration_seconds",
+        "Time it took to process requests to keycloak",
+        &["operation"]
+    )
+    .unwrap();
+    pub static ref KEYCLOAK_ERRORS: IntCounterVec = register_int_counter_vec!(
+        "ca_keycloak_request_errors_total",
+        "Number of failed requests to keycloak",
+        &["operation"]
+    )
+    .unwrap();
+    pub static ref DB_POOL_CONNECTIONS: IntGauge = register_int_gauge!(
+        "ca_db_pool_connections",
+        "Number of open database connections"
+    )
+    .unwrap();
+    pub static ref DB_POOL_IDLE_CONNECTIONS: IntGauge = register_int_gauge!(
+        "ca_db_pool_idle_connections",
+        "Number of idle database connections"
+    )
+    .unwrap();
+    pub static ref ROOT_EXPIRY: IntGauge = register_int_gauge!(
+        "ca_root_certificate_expiry_seconds",
+        "Seconds until the active root certificate expires"
+    )
+    .unwrap();
+    pub static ref INTERMEDIATE_EXPIRY: IntGauge = register_int_gauge!(
+        "ca_intermediate_certificate_expiry_seconds",
+        "Seconds until the active intermediate certificate expires"
+    )
+    .unwrap();
+}
+
+/// Record the duration and outcome of a request to keycloak
+pub async fn observe_keycloak<T, E, F>(operation: &str, future: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let timer = KEYCLOAK_DURATION
+        .with_label_values(&[operation])
+        .start_timer();
+    let res = future.await;
+    timer.observe_duration();
+
+    if res.is_err() {
+        KEYCLOAK_ERRORS.with_label_values(&[operation]).inc();
+    }
+
+    res
+}
@@ -1,5 +1,15 @@
 pub mod api_doc;
+pub mod backup_cipher;
 pub mod ca_certificate;
+pub mod ca_store;
+pub mod certificate_info;
+pub mod date;
+pub mod jwk;
 pub mod macros;
+pub mod metrics;
+pub mod pagination;
+pub mod pkcs12;
+#[cfg(test)]
+pub mod testing;
 pub mod traits;
 pub mod types;
@@ -0,0 +1,32 @@
+use crate::error::http_response_error::HttpResponseError;
+use crate::util::types::WebResult;
+
+/// The page size used if none is supplied
+pub const DEFAULT_PAGE_SIZE: u64 = 50;
+/// The maximum number of elements a single page may contain
+pub const MAX_PAGE_SIZE: u64 = 500;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Pagination {
+    /// The zero-based index of the page
+    pub page: u64,
+    /// The number of elements per page
+    pub page_size: u64,
+}
+
+impl Pagination {
+    pub fn new(page: Option<u64>, page_size: Option<u64>) -> WebResult<Self> {
+        let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+        if page_size == 0 || page_size > MAX_PAGE_SIZE {
+            return Err(HttpResponseError::bad_request(Some(format!(
+                "The page size must be between 1 and {}",
+                MAX_PAGE_SIZE
+            ))));
+        }
+
+        Ok(Self {
+            page: page.unwrap_or(0),
+            page_size,
+        })
+    }
+}
@@ -0,0 +1,44 @@
+use openssl::hash::MessageDigest;
+use openssl::pkcs12::Pkcs12;
+use openssl::pkey::{PKey, Private};
+use openssl::stack::Stack;
+use openssl::x509::{X509NameBuilder, X509Req, X509ReqBuilder, X509};
+use shared::util::types::BasicResult;
+
+/// Build a signing request for a key pair generated by the server
+pub fn signing_request(key_pair: &PKey<Private>, common_name: &str) -> BasicResult<X509Req> {
+    let mut req_builder = X509ReqBuilder::new()?;
+    req_builder.set_pubkey(key_pair)?;
+
+    let mut x509_name = X509NameBuilder::new()?;
+    x509_name.append_entry_by_text("CN", common_name)?;
+    let x509_name = x509_name.build();
+    req_builder.set_subject_name(&x509_name)?;
+
+    req_builder.sign(key_pair, MessageDigest::sha256())?;
+    Ok(req_builder.build())
+}
+
+/// Bundle a key pair, its certificate and the certificates of the
+/// issuing CAs into a password protected PKCS#12 archive (DER encoded)
+pub fn build_pkcs12(
+    key_pair: &PKey<Private>,
+    cert: &X509,
+    chain: Vec<X509>,
+    name: &str,
+    password: &str,
+) -> BasicResult<Vec<u8>> {
+    let mut ca = Stack::new()?;
+    for cert in chain {
+        ca.push(cert)?;
+    }
+
+    Pkcs12::builder()
+        .name(name)
+        .pkey(key_pair)
+        .cert(cert)
+        .ca(ca)
+        .build2(password)?
+        .to_der()
+        .map_err(|e| e.into())
+}
@@ -0,0 +1,62 @@
+use crate::entity::{client, signing_request, user};
+use crate::migration::Migrator;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ActiveValue, ConnectOptions, Database, DatabaseConnection};
+use sea_orm_migration::MigratorTrait;
+use shared::util::traits::u8_vec_to_string::U8VecToString;
+use uuid::Uuid;
+
+/// Open a new in-memory database with all migrations applied
+pub async fn connect() -> DatabaseConnection {
+    // Every connection opens a new in-memory database
+    let mut opts = ConnectOptions::new("sqlite::memory:".to_string());
+    opts.max_connections(1).min_connections(1);
+    let db = Database::connect(opts).await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+    db
+}
+
+/// Insert a user and a client owned by them
+pub async fn insert_client(
+    db: &DatabaseConnection,
+    requires_approval: bool,
+) -> (user::Model, client::Model) {
+    let user = user::ActiveModel {
+        name: ActiveValue::Set("user".to_string()),
+        external_id: ActiveValue::Set(Some("user".to_string())),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .unwrap();
+    let client = client::ActiveModel {
+        user_id: ActiveValue::Set(user.id),
+        name: ActiveValue::Set("client".to_string()),
+        requires_approval: ActiveValue::Set(requires_approval),
+        valid_until: ActiveValue::Set(Utc::now().into()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .unwrap();
+
+    (user, client)
+}
+
+/// A certificate issued to a client or, without a client, a personal certificate.
+/// The hash and the serial number are derived from `serial`.
+pub fn issued(client_id: Option<Uuid>, serial: u8) -> signing_request::ActiveModel {
+    let now = Utc::now();
+    signing_request::ActiveModel {
+        client_id: ActiveValue::Set(client_id),
+        requested_by: ActiveValue::Set(None),
+        hash: ActiveValue::Set(vec![serial; 32].to_hex_string(":")),
+        serial_number: ActiveValue::Set(format!("{:02x}", serial)),
+        subject_name: ActiveValue::Set("client".to_string()),
+        alternative_names: ActiveValue::Set(None),
+        issued_at: ActiveValue::Set(now.into()),
+        valid_until: ActiveValue::Set(now.into()),
+        revoked_at: ActiveValue::Set(None),
+        ..Default::default()
+    }
+}
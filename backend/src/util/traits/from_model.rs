@@ -1,4 +1,6 @@
-use crate::entity::signing_request;
+use crate::entity::approval_request::ApprovalStatus;
+use crate::entity::{approval_request, signing_request};
+use shared::model::certificate_status::CertificateStatus;
 use shared::model::signing_request_dto::SigningRequestDto;
 
 pub trait FromModel<T> {
@@ -8,11 +10,40 @@ pub trait FromModel<T> {
 impl FromModel<signing_request::Model> for SigningRequestDto {
     fn from_model(model: signing_request::Model) -> Self {
         SigningRequestDto {
-            client_id: model.client_id.to_string(),
-            hash: model.hash,
-            issued_at: model.issued_at.to_rfc3339(),
+            id: model.id,
+            client_id: model.client_id.map(|id| id.to_string()),
+            requested_by: model.requested_by.map(|id| id.to_string()),
+            status: model.status(),
+            alternative_names: model.alternative_names(),
+            hash: Some(model.hash),
+            issued_at: Some(model.issued_at.to_rfc3339()),
+            valid_until: Some(model.valid_until.to_rfc3339()),
+            revoked_at: model.revoked_at.map(|r| r.to_rfc3339()),
             certificate: None,
-            serial_number: model.serial_number,
+            serial_number: Some(model.serial_number),
+            subject_name: model.subject_name,
+        }
+    }
+}
+
+impl FromModel<approval_request::Model> for SigningRequestDto {
+    fn from_model(model: approval_request::Model) -> Self {
+        SigningRequestDto {
+            id: model.id,
+            client_id: Some(model.client_id.to_string()),
+            requested_by: model.requested_by.map(|id| id.to_string()),
+            status: match model.status {
+                ApprovalStatus::Pending => CertificateStatus::Pending,
+                ApprovalStatus::Approved => CertificateStatus::Valid,
+                ApprovalStatus::Rejected => CertificateStatus::Rejected,
+            },
+            alternative_names: model.alternative_names().unwrap_or_default(),
+            hash: None,
+            issued_at: None,
+            valid_until: None,
+            revoked_at: None,
+            certificate: model.certificate,
+            serial_number: None,
             subject_name: model.subject_name,
         }
     }
@@ -1,18 +1,69 @@
+use crate::certificate::Certificate;
 use crate::config::Config;
 use crate::timed_call::TimedCall;
+use derive_more::Display;
 use log::{debug, info};
+use openssl::base64;
 use openssl::x509::{X509Req, X509};
-use reqwest::Client;
+use reqwest::header::{CONTENT_TYPE, RETRY_AFTER};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use shared::model::certificate_status::CertificateStatus;
+use shared::model::consistency_proof_dto::ConsistencyProofDto;
+use shared::model::enrollment_request_dto::EnrollmentRequestDto;
+use shared::model::enrollment_result_dto::EnrollmentResultDto;
 use shared::model::health_info_dto::HealthInfoDto;
+use shared::model::inclusion_proof_dto::InclusionProofDto;
 use shared::model::new_signing_request_dto::NewSigningRequestDto;
+use shared::model::signed_tree_head_dto::SignedTreeHeadDto;
 use shared::model::signing_request_dto::SigningRequestDto;
+use shared::util::request_signature;
 use shared::util::traits::u8_vec_to_string::U8VecToString;
 use shared::util::types::BasicResult;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+/// The file the renew-only token received on enrollment is stored in
+const RENEWAL_TOKEN_FILE: &str = "renewal_token";
+/// The interval in which the status of a signing request awaiting approval is checked
+const APPROVAL_POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// The maximum time to wait for an approval before giving up on a single renewal attempt
+const APPROVAL_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// The API rejected a request because a rate limit or quota was exceeded
+#[derive(Debug, Display)]
+#[display(
+    fmt = "Rate limit exceeded, retry in {} seconds",
+    "retry_after.as_secs()"
+)]
+pub struct RateLimitedError {
+    pub retry_after: Duration,
+}
+
+impl std::error::Error for RateLimitedError {}
+
+/// The signing request requires the approval of an admin which hasn't been given yet
+#[derive(Debug, Display)]
+#[display(fmt = "Signing request {} is still awaiting approval", id)]
+pub struct PendingApprovalError {
+    pub id: i32,
+}
+
+impl std::error::Error for PendingApprovalError {}
 
 pub struct Api {
-    token: String,
+    token: Option<String>,
     api_url: String,
     client: Client,
+    /// The id of the signing request which is awaiting approval
+    pending_request: Mutex<Option<i32>>,
+    /// The timestamp of the last request signed with the certificate,
+    /// the API rejects signatures which reuse a timestamp
+    last_signed_at: Mutex<i64>,
 }
 
 impl Api {
@@ -21,6 +72,64 @@ impl Api {
             token: config.token.clone(),
             api_url: config.api_url.clone(),
             client: Client::new(),
+            pending_request: Mutex::new(None),
+            last_signed_at: Mutex::new(0),
+        }
+    }
+
+    pub fn token(&self) -> Option<&String> {
+        self.token.as_ref()
+    }
+
+    pub fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// The path signing requests are sent to, depending on whether
+    /// the request is authenticated using a certificate or the token
+    fn signing_path(certificate: Option<&Certificate>) -> &'static str {
+        match certificate {
+            Some(_) => "/api/v1/certificate/renew",
+            None => "/api/v1/certificate/sign",
+        }
+    }
+
+    /// Authenticate a request by signing it with the key of a certificate
+    /// or, if no certificate is given, using the token
+    fn authorize(
+        &self,
+        builder: RequestBuilder,
+        method: &str,
+        path: &str,
+        body: &[u8],
+        certificate: Option<&Certificate>,
+    ) -> BasicResult<RequestBuilder> {
+        match certificate {
+            Some(certificate) => {
+                let x509 = certificate
+                    .certificate()
+                    .ok_or("No certificate to authenticate with")?;
+                let timestamp = {
+                    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+                    let mut last = self.last_signed_at.lock().unwrap();
+                    *last = now.max(*last + 1);
+                    *last
+                };
+                let signature =
+                    request_signature::sign(certificate.key_pair(), method, path, timestamp, body)?;
+
+                Ok(builder
+                    .header(
+                        request_signature::CERTIFICATE_HEADER,
+                        base64::encode_block(&x509.to_der()?),
+                    )
+                    .header(request_signature::TIMESTAMP_HEADER, timestamp.to_string())
+                    .header(
+                        request_signature::SIGNATURE_HEADER,
+                        base64::encode_block(&signature),
+                    ))
+            }
+            None => Ok(builder.bearer_auth(self.token.as_ref().ok_or("No token configured")?)),
         }
     }
 
@@ -48,30 +157,234 @@ impl Api {
         Ok(())
     }
 
+    /// Request a certificate for a signing request. If `certificate` is given,
+    /// the request is authenticated using it instead of the token,
+    /// which is only possible for renewals of that certificate.
     pub async fn sign_certificate(
         &self,
-        csr: X509Req,
+        csr: &X509Req,
         alt_names: Option<Vec<String>>,
+        certificate: Option<&Certificate>,
     ) -> BasicResult<X509> {
+        let pending = *self.pending_request.lock().unwrap();
+        let res = match pending {
+            Some(id) => {
+                info!(
+                    "Resuming to wait for the approval of signing request {}",
+                    id
+                );
+                self.wait_for_approval(id, certificate).await?
+            }
+            None => {
+                self.send_signing_request(csr, alt_names, certificate)
+                    .await?
+            }
+        };
+
+        debug!("Certificate received: {:?}", res);
+        if let Some(cert) = res.certificate {
+            X509::from_pem(cert.as_bytes()).map_err(|e| e.into())
+        } else {
+            Err("No certificate received".into())
+        }
+    }
+
+    async fn send_signing_request(
+        &self,
+        csr: &X509Req,
+        alt_names: Option<Vec<String>>,
+        certificate: Option<&Certificate>,
+    ) -> BasicResult<SigningRequestDto> {
         let req = NewSigningRequestDto {
             request: csr.to_pem()?.to_string(),
             alternative_names: alt_names,
         };
 
         debug!("Sending signing request: {:?}", req);
+        let path = Self::signing_path(certificate);
+        let body = serde_json::to_vec(&req)?;
         let res = self
-            .client
-            .post(format!("{}/api/v1/certificate/sign", self.api_url).as_str())
-            .bearer_auth(self.token.clone())
-            .json(&req)
-            .timeout(std::time::Duration::from_secs(60))
+            .authorize(
+                self.client
+                    .post(format!("{}{}", self.api_url, path).as_str()),
+                "POST",
+                path,
+                &body,
+                certificate,
+            )?
+            .header(CONTENT_TYPE, "application/json")
+            .body(body)
+            .timeout(Duration::from_secs(60))
             .send()
-            .await?
+            .await?;
+
+        if res.status() == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = res
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|h| h.to_str().ok())
+                .and_then(|h| h.parse::<u64>().ok())
+                .unwrap_or(60 * 60);
+            return Err(Box::new(RateLimitedError {
+                retry_after: Duration::from_secs(retry_after),
+            }));
+        }
+
+        let status = res.status();
+        let res = res
             .error_for_status()?
             .json::<SigningRequestDto>()
             .await
             .map_err(|e| Box::new(e))?;
 
+        if status == StatusCode::ACCEPTED {
+            info!(
+                "Signing request {} requires the approval of an admin",
+                res.id
+            );
+            *self.pending_request.lock().unwrap() = Some(res.id);
+            return self.wait_for_approval(res.id, certificate).await;
+        }
+
+        Ok(res)
+    }
+
+    /// Poll the status of a signing request until it has been approved or rejected.
+    /// Returns a [`PendingApprovalError`] if no decision has been made in time,
+    /// the next call to [`Api::sign_certificate`] resumes waiting for the same request.
+    async fn wait_for_approval(
+        &self,
+        id: i32,
+        certificate: Option<&Certificate>,
+    ) -> BasicResult<SigningRequestDto> {
+        let started = Instant::now();
+        let path = format!("{}/{}", Self::signing_path(certificate), id);
+        loop {
+            let res = self
+                .authorize(
+                    self.client
+                        .get(format!("{}{}", self.api_url, path).as_str()),
+                    "GET",
+                    &path,
+                    &[],
+                    certificate,
+                )?
+                .timeout(Duration::from_secs(60))
+                .send()
+                .await?;
+
+            if res.status() == StatusCode::NOT_FOUND {
+                *self.pending_request.lock().unwrap() = None;
+                return Err(format!("Signing request {} does not exist anymore", id).into());
+            }
+
+            let status = res.status();
+            let res = res
+                .error_for_status()?
+                .json::<SigningRequestDto>()
+                .await
+                .map_err(Box::new)?;
+
+            if status != StatusCode::ACCEPTED {
+                *self.pending_request.lock().unwrap() = None;
+                return match res.status {
+                    CertificateStatus::Rejected => {
+                        Err(format!("Signing request {} has been rejected", id).into())
+                    }
+                    _ => Ok(res),
+                };
+            }
+
+            if started.elapsed() >= APPROVAL_TIMEOUT {
+                return Err(Box::new(PendingApprovalError { id }));
+            }
+
+            debug!("Signing request {} is still awaiting approval", id);
+            tokio::time::sleep(APPROVAL_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Use the renew-only token stored on enrollment if no token is configured
+    pub async fn load_renewal_token(&mut self, dir: String) -> BasicResult<()> {
+        let path = Path::new(&dir).join(RENEWAL_TOKEN_FILE);
+        if self.token.is_none() && fs::try_exists(&path).await? {
+            debug!("Loading the renewal token");
+            self.token = Some(fs::read_to_string(path).await?.trim().to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Store the renew-only token next to the certificate, only readable by the owner
+    async fn store_renewal_token(dir: String, token: &str) -> BasicResult<()> {
+        let out = Path::new(&dir);
+        if !fs::try_exists(out).await? {
+            fs::create_dir_all(out).await?;
+        }
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+
+        let mut file = options.open(out.join(RENEWAL_TOKEN_FILE)).await?;
+        // The mode only applies to new files, an existing file is restricted before writing
+        #[cfg(unix)]
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .await?;
+        file.write_all(token.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Redeem a one-time enrollment token for the first certificate.
+    /// A renew-only token is requested along with the certificate and stored in `dir`,
+    /// so a new certificate can be requested once the enrollment token has been used,
+    /// e.g. if the certificate expires or the approval is still pending after a restart.
+    pub async fn enroll(
+        &mut self,
+        csr: &X509Req,
+        alt_names: Option<Vec<String>>,
+        enrollment_token: &str,
+        dir: String,
+    ) -> BasicResult<X509> {
+        let req = EnrollmentRequestDto {
+            token: enrollment_token.to_string(),
+            request: csr.to_pem()?.to_string(),
+            alternative_names: alt_names,
+            renewal_token: Some(true),
+        };
+
+        debug!("Redeeming enrollment token");
+        let res = self
+            .client
+            .post(format!("{}/api/v1/certificate/enroll", self.api_url).as_str())
+            .json(&req)
+            .timeout(Duration::from_secs(60))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<EnrollmentResultDto>()
+            .await?;
+
+        if let Some(token) = res.token {
+            info!("Received a renewal token");
+            Self::store_renewal_token(dir, &token).await?;
+            self.token = Some(token);
+        }
+
+        let res = match res.certificate.status {
+            CertificateStatus::Pending => {
+                info!(
+                    "Signing request {} requires the approval of an admin",
+                    res.certificate.id
+                );
+                *self.pending_request.lock().unwrap() = Some(res.certificate.id);
+                self.wait_for_approval(res.certificate.id, None).await?
+            }
+            _ => res.certificate,
+        };
+
         debug!("Certificate received: {:?}", res);
         if let Some(cert) = res.certificate {
             X509::from_pem(cert.as_bytes()).map_err(|e| e.into())
@@ -79,4 +392,50 @@ impl Api {
             Err("No certificate received".into())
         }
     }
+
+    pub async fn get_signed_tree_head(&self) -> BasicResult<SignedTreeHeadDto> {
+        self.client
+            .get(format!("{}/api/v1/transparency/sth", self.api_url).as_str())
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<SignedTreeHeadDto>()
+            .await
+            .map_err(|e| e.into())
+    }
+
+    pub async fn get_inclusion_proof(
+        &self,
+        serial_number: &str,
+        tree_size: u64,
+    ) -> BasicResult<InclusionProofDto> {
+        self.client
+            .get(format!("{}/api/v1/transparency/proof/inclusion", self.api_url).as_str())
+            .query(&[
+                ("serialNumber", serial_number.to_string()),
+                ("treeSize", tree_size.to_string()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<InclusionProofDto>()
+            .await
+            .map_err(|e| e.into())
+    }
+
+    pub async fn get_consistency_proof(
+        &self,
+        first: u64,
+        second: u64,
+    ) -> BasicResult<ConsistencyProofDto> {
+        self.client
+            .get(format!("{}/api/v1/transparency/proof/consistency", self.api_url).as_str())
+            .query(&[("first", first), ("second", second)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ConsistencyProofDto>()
+            .await
+            .map_err(|e| e.into())
+    }
 }
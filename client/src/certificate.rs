@@ -1,11 +1,10 @@
 use crate::config::Config;
 use openssl::asn1::Asn1Time;
-use openssl::ec::{EcGroup, EcKey};
 use openssl::hash::MessageDigest;
-use openssl::nid::Nid;
 use openssl::pkey::{PKey, Private};
 use openssl::symm::Cipher;
 use openssl::x509::{X509NameBuilder, X509Req, X509ReqBuilder, X509};
+use shared::util::key_algorithm::KeyAlgorithm;
 use shared::util::types::BasicResult;
 use std::path::Path;
 use tokio::fs;
@@ -27,6 +26,23 @@ impl Certificate {
         }
     }
 
+    /// Whether a certificate has been issued which has not expired yet
+    pub fn is_valid(&self) -> BasicResult<bool> {
+        let now = Asn1Time::days_from_now(0)?;
+        Ok(match &self.cert {
+            Some(c) => c.not_after() > now,
+            None => false,
+        })
+    }
+
+    pub fn key_pair(&self) -> &PKey<Private> {
+        &self.key_pair
+    }
+
+    pub fn certificate(&self) -> Option<&X509> {
+        self.cert.as_ref()
+    }
+
     pub fn set_certificate(&mut self, cert: X509) {
         self.cert = Some(cert);
     }
@@ -55,13 +71,9 @@ impl Certificate {
         }
     }
 
-    pub fn generate() -> BasicResult<Self> {
-        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
-        let private = EcKey::generate(&group)?;
-        let key_pair = PKey::from_ec_key(private)?;
-
+    pub fn generate(algorithm: KeyAlgorithm) -> BasicResult<Self> {
         Ok(Self {
-            key_pair,
+            key_pair: algorithm.generate()?,
             cert: None,
         })
     }
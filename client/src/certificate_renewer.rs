@@ -1,14 +1,18 @@
-use crate::api::Api;
+use crate::api::{Api, RateLimitedError};
 use crate::certificate::Certificate;
 use crate::config::Config;
 use derive_more::Display;
 use futures::executor::block_on;
-use log::{error, info};
+use log::{error, info, warn};
+use reqwest::StatusCode;
 use shared::util::types::BasicResult;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
+/// The time to wait before retrying a failed renewal
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+
 #[derive(Debug, Copy, Clone, Display)]
 pub enum RenewalErrorCode {
     #[display(fmt = "API access failed")]
@@ -63,13 +67,57 @@ impl CertificateRenewer {
         self.data.lock().unwrap().last_error
     }
 
+    /// The time to wait before retrying a failed renewal,
+    /// honors the delay requested by the API if it rate limited the request
+    fn retry_delay(error: &(dyn std::error::Error + 'static)) -> Duration {
+        match error.downcast_ref::<RateLimitedError>() {
+            Some(e) => e.retry_after,
+            None => RETRY_DELAY,
+        }
+    }
+
+    /// Whether the API rejected a renewal using the current certificate,
+    /// e.g. because the certificate has been revoked or the subject has changed
+    fn is_rejected(error: &(dyn std::error::Error + 'static)) -> bool {
+        matches!(
+            error
+                .downcast_ref::<reqwest::Error>()
+                .and_then(|e| e.status()),
+            Some(StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN)
+        )
+    }
+
     async fn renew(data: &mut Data) -> BasicResult<()> {
         info!("Requesting certificate");
         let csr = data.certificate.get_signing_request(&data.config)?;
-        let signed = data
-            .api
-            .sign_certificate(csr, data.config.alt_names())
-            .await?;
+        let alt_names = data.config.alt_names();
+
+        // Renew using the current certificate while it is valid and fall back
+        // to a full signing request using the token if the API rejects the renewal
+        let signed = if data.certificate.is_valid()? {
+            info!("Authenticating using the current certificate");
+            match data
+                .api
+                .sign_certificate(&csr, alt_names.clone(), Some(&data.certificate))
+                .await
+            {
+                Ok(signed) => signed,
+                Err(e) if data.api.has_token() && Self::is_rejected(e.as_ref()) => {
+                    warn!("The renewal was rejected ({}), using the token instead", e);
+                    data.api.sign_certificate(&csr, alt_names, None).await?
+                }
+                Err(e) => return Err(e),
+            }
+        } else if data.api.has_token() {
+            data.api.sign_certificate(&csr, alt_names, None).await?
+        } else if let Some(enrollment_token) = data.config.enrollment_token.clone() {
+            info!("Redeeming the enrollment token");
+            data.api
+                .enroll(&csr, alt_names, &enrollment_token, "certs".into())
+                .await?
+        } else {
+            return Err("No token configured".into());
+        };
         info!("Storing certificate");
         data.certificate.set_certificate(signed);
         data.certificate
@@ -85,8 +133,21 @@ impl CertificateRenewer {
             loop {
                 let mut data = arc.lock().unwrap();
 
-                info!("Checking token");
-                if let Err(e) = jsonwebtoken::decode_header(&data.config.token) {
+                // The token is only needed if there is no valid certificate to renew with
+                let token = if matches!(data.certificate.is_valid(), Ok(true)) {
+                    Ok(())
+                } else {
+                    info!("Checking token");
+                    match data.api.token() {
+                        Some(token) => jsonwebtoken::decode_header(token)
+                            .map(|_| ())
+                            .map_err(|e| e.to_string()),
+                        None if data.config.enrollment_token.is_some() => Ok(()),
+                        None => Err("No token configured".to_string()),
+                    }
+                };
+
+                if let Err(e) = token {
                     error!("Token invalid: {}", e);
                     data.set_last_error(RenewalErrorCode::TokenInvalid);
 
@@ -106,6 +167,12 @@ impl CertificateRenewer {
                             if let Err(e) = block_on(Self::renew(&mut data)) {
                                 data.set_last_error(RenewalErrorCode::ApiAccessFailed);
                                 error!("Failed to renew certificate: {}", e);
+
+                                let delay = Self::retry_delay(e.as_ref());
+                                drop(data);
+                                info!("Retrying in {} seconds", delay.as_secs());
+                                thread::sleep(delay);
+                                continue;
                             } else {
                                 data.reset_last_error();
                             }
@@ -123,6 +190,12 @@ impl CertificateRenewer {
                         if let Err(e) = block_on(Self::renew(&mut data)) {
                             data.set_last_error(RenewalErrorCode::ApiAccessFailed);
                             error!("Failed to renew certificate: {}", e);
+
+                            let delay = Self::retry_delay(e.as_ref());
+                            drop(data);
+                            info!("Retrying in {} seconds", delay.as_secs());
+                            thread::sleep(delay);
+                            continue;
                         }
 
                         drop(data);
@@ -1,11 +1,17 @@
 use dotenv::dotenv;
 use envconfig::Envconfig;
+use shared::util::key_algorithm::KeyAlgorithm;
 use std::error::Error;
 
 #[derive(Debug, Clone, Envconfig)]
 pub struct Config {
+    /// The token used to request the first certificate,
+    /// renewals are authenticated using the current certificate while it is valid
     #[envconfig(from = "TOKEN")]
-    pub token: String,
+    pub token: Option<String>,
+    /// A one-time token redeemed for the first certificate if no token is configured
+    #[envconfig(from = "ENROLLMENT_TOKEN")]
+    pub enrollment_token: Option<String>,
     #[envconfig(from = "API_URL")]
     pub api_url: String,
     #[envconfig(from = "PASSPHRASE")]
@@ -24,6 +30,10 @@ pub struct Config {
     pub cert_common_name: String,
     #[envconfig(from = "CERT_EMAIL")]
     pub cert_email: Option<String>,
+    /// The algorithm used to generate the key pair,
+    /// one of ec-p256, ec-p384, rsa-2048 or rsa-4096
+    #[envconfig(from = "KEY_ALGORITHM", default = "ec-p256")]
+    pub key_algorithm: KeyAlgorithm,
     #[envconfig(from = "ALT_NAMES")]
     pub alt_names: Option<String>,
     #[envconfig(from = "RENEW_THRESHOLD_DAYS", default = "1")]
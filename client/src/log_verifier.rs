@@ -0,0 +1,151 @@
+use crate::api::Api;
+use log::{info, warn};
+use openssl::hash::MessageDigest;
+use openssl::sign::Verifier;
+use openssl::x509::X509;
+use shared::model::signed_tree_head_dto::SignedTreeHeadDto;
+use shared::util::hex::decode_hex;
+use shared::util::merkle;
+use shared::util::traits::u8_vec_to_string::U8VecToString;
+use shared::util::types::BasicResult;
+use std::path::PathBuf;
+use tokio::fs;
+
+const TREE_HEAD_FILE: &str = "tree_head.json";
+
+/// Checks that a certificate is contained in the CA's issuance log
+/// and that the log has only been appended to since the last check
+pub struct LogVerifier<'a> {
+    api: &'a Api,
+    dir: PathBuf,
+}
+
+impl<'a> LogVerifier<'a> {
+    /// Create a new verifier, the last verified tree head is stored in `dir`
+    pub fn new(api: &'a Api, dir: String) -> Self {
+        Self {
+            api,
+            dir: PathBuf::from(dir),
+        }
+    }
+
+    fn verify_signature(sth: &SignedTreeHeadDto) -> BasicResult<X509> {
+        let signer = X509::from_pem(sth.certificate.as_bytes())?;
+        let key = signer.public_key()?;
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &key)?;
+        verifier.update(
+            SignedTreeHeadDto::signature_input(sth.tree_size, sth.timestamp, &sth.root_hash)
+                .as_bytes(),
+        )?;
+
+        if verifier.verify(&decode_hex(&sth.signature)?)? {
+            Ok(signer)
+        } else {
+            Err("The signature of the tree head is invalid".into())
+        }
+    }
+
+    async fn load_tree_head(&self) -> BasicResult<Option<SignedTreeHeadDto>> {
+        match fs::read(self.dir.join(TREE_HEAD_FILE)).await {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn store_tree_head(&self, sth: &SignedTreeHeadDto) -> BasicResult<()> {
+        fs::write(self.dir.join(TREE_HEAD_FILE), serde_json::to_vec(sth)?)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Verify that the log is consistent with the previously verified tree head
+    async fn verify_consistency(
+        &self,
+        old: &SignedTreeHeadDto,
+        new: &SignedTreeHeadDto,
+    ) -> BasicResult<()> {
+        if old.tree_size > new.tree_size {
+            return Err(format!(
+                "The issuance log shrunk from {} to {} entries",
+                old.tree_size, new.tree_size
+            )
+            .into());
+        }
+
+        let proof = self
+            .api
+            .get_consistency_proof(old.tree_size, new.tree_size)
+            .await?
+            .proof
+            .iter()
+            .map(|h| merkle::decode_hash(h))
+            .collect::<BasicResult<Vec<_>>>()?;
+
+        if merkle::verify_consistency(
+            old.tree_size,
+            new.tree_size,
+            &merkle::decode_hash(&old.root_hash)?,
+            &merkle::decode_hash(&new.root_hash)?,
+            &proof,
+        ) {
+            info!(
+                "The issuance log is consistent with the tree head of size {}",
+                old.tree_size
+            );
+            Ok(())
+        } else {
+            Err("The issuance log is not consistent with the previously verified tree head".into())
+        }
+    }
+
+    /// Verify that the certificate has been logged
+    pub async fn verify(&self, cert: &X509) -> BasicResult<()> {
+        let sth = self.api.get_signed_tree_head().await?;
+        let signer = Self::verify_signature(&sth)?;
+        info!(
+            "Verified tree head of size {} signed by {:?}",
+            sth.tree_size,
+            signer.subject_name()
+        );
+        if signer.subject_name().to_der()? != cert.issuer_name().to_der()? {
+            warn!("The tree head was not signed by the issuer of the certificate");
+        }
+
+        if let Some(old) = self.load_tree_head().await? {
+            self.verify_consistency(&old, &sth).await?;
+        }
+
+        let serial_number = cert.serial_number().to_bn()?.to_hex_str()?.to_string();
+        let leaf = merkle::leaf_hash(&cert.digest(MessageDigest::sha256())?);
+        let proof = self
+            .api
+            .get_inclusion_proof(&serial_number, sth.tree_size)
+            .await?;
+
+        if proof.leaf_hash != leaf.to_vec().to_hex_string("") {
+            return Err("The logged certificate does not match the local certificate".into());
+        }
+
+        let path = proof
+            .audit_path
+            .iter()
+            .map(|h| merkle::decode_hash(h))
+            .collect::<BasicResult<Vec<_>>>()?;
+        if !merkle::verify_inclusion(
+            &leaf,
+            proof.leaf_index,
+            sth.tree_size,
+            &path,
+            &merkle::decode_hash(&sth.root_hash)?,
+        ) {
+            return Err("The inclusion proof of the certificate is invalid".into());
+        }
+
+        info!(
+            "Certificate {} is contained in the issuance log at index {}",
+            serial_number, proof.leaf_index
+        );
+        self.store_tree_head(&sth).await
+    }
+}
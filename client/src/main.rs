@@ -4,6 +4,7 @@ use crate::api::Api;
 use crate::certificate::Certificate;
 use crate::certificate_renewer::CertificateRenewer;
 use crate::config::Config;
+use crate::log_verifier::LogVerifier;
 use ::log::{debug, info};
 use log::LevelFilter;
 use shared::model::health_info_dto::HealthInfoDto;
@@ -19,6 +20,7 @@ mod api;
 mod certificate;
 mod certificate_renewer;
 mod config;
+mod log_verifier;
 mod timed_call;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -35,10 +37,22 @@ async fn main() -> BasicResult<()> {
     )?;
 
     debug!("{:?}", config);
-    let api = Api::new(&config);
+    let mut api = Api::new(&config);
+    api.load_renewal_token("certs".into()).await?;
 
     api.check_api().await?;
 
+    if std::env::args().nth(1).as_deref() == Some("verify-log") {
+        info!("Verifying that the certificate is contained in the issuance log");
+        let cert = Certificate::load("certs".into(), config.passphrase.as_ref()).await?;
+        return LogVerifier::new(&api, "certs".into())
+            .verify(
+                cert.certificate()
+                    .ok_or("No certificate has been issued yet")?,
+            )
+            .await;
+    }
+
     info!("Trying to load certificates");
     let cert = match Certificate::load("certs".into(), config.passphrase.as_ref()).await {
         Ok(cert) => {
@@ -48,7 +62,7 @@ async fn main() -> BasicResult<()> {
         Err(e) => {
             info!("Failed to load certificates: {}", e);
             info!("Generating new certificates");
-            let cert = Certificate::generate()?;
+            let cert = Certificate::generate(config.key_algorithm)?;
             info!("Storing certificates");
             cert.store("certs".into(), config.passphrase.as_ref())
                 .await?;
@@ -71,6 +85,7 @@ async fn main() -> BasicResult<()> {
                         status: renewer.get_last_error().map(|e| e.to_string()).unwrap_or("OK".into()),
                         ok: renewer.get_last_error().is_none(),
                         is_initialized: None,
+                        components: vec![],
                     })
                 },
                 _ => rouille::Response::empty_404()
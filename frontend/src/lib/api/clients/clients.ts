@@ -16,6 +16,7 @@ import type {
 } from '@tanstack/svelte-query';
 import type {
 	ClientDto,
+	ClientPageDto,
 	ErrorDto,
 	CreateClientDto,
 	ListClientsParams,
@@ -89,7 +90,7 @@ export const listClients = (
 	options?: SecondParameter<typeof customInstance>,
 	signal?: AbortSignal
 ) => {
-	return customInstance<ClientDto[]>(
+	return customInstance<ClientPageDto>(
 		{ url: `/api/v1/client/list`, method: 'get', params, signal },
 		options
 	);
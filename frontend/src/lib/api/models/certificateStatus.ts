@@ -0,0 +1,17 @@
+/**
+ * Generated by orval v6.12.1 🍺
+ * Do not edit manually.
+ * Certificate Authority API
+ * A simple API for managing certificates
+ * OpenAPI spec version: 0.0.1
+ */
+
+export type CertificateStatus =
+	(typeof CertificateStatus)[keyof typeof CertificateStatus];
+
+// eslint-disable-next-line @typescript-eslint/no-redeclare
+export const CertificateStatus = {
+	valid: 'valid',
+	expired: 'expired',
+	revoked: 'revoked',
+} as const;
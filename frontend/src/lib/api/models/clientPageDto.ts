@@ -0,0 +1,22 @@
+/**
+ * Generated by orval v6.12.1 🍺
+ * Do not edit manually.
+ * Certificate Authority API
+ * A simple API for managing certificates
+ * OpenAPI spec version: 0.0.1
+ */
+
+import type { ClientDto } from './clientDto';
+
+export interface ClientPageDto {
+	/** The elements on this page */
+	items: ClientDto[];
+	/** The zero-based index of this page */
+	page: number;
+	/** The maximum number of elements per page */
+	pageSize: number;
+	/** The total number of elements matching the query */
+	total: number;
+	/** The total number of pages */
+	totalPages: number;
+}
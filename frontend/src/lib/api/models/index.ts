@@ -1,5 +1,7 @@
 export * from './cACertificateDto';
+export * from './certificateStatus';
 export * from './clientDto';
+export * from './clientPageDto';
 export * from './createClientDto';
 export * from './createUserDto';
 export * from './deleteClientParams';
@@ -15,3 +17,4 @@ export * from './listUsersParams';
 export * from './newSigningRequestDto';
 export * from './signingRequestDto';
 export * from './userDto';
+export * from './userPageDto';
@@ -12,4 +12,14 @@ export type ListClientsParams = {
 Defaults to false.
  */
 	includeInactive?: boolean;
+	/**
+ * The zero-based index of the page to return.
+Defaults to 0.
+ */
+	page?: number;
+	/**
+ * The number of elements per page.
+Defaults to 50.
+ */
+	pageSize?: number;
 };
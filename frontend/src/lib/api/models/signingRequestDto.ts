@@ -6,11 +6,18 @@
  * OpenAPI spec version: 0.0.1
  */
 
+import type { CertificateStatus } from './certificateStatus';
+
 export interface SigningRequestDto {
+	alternativeNames: string[];
 	certificate?: string | null;
 	clientId: string;
 	hash: string;
+	id: number;
 	issuedAt: string;
+	revokedAt?: string | null;
 	serialNumber: string;
+	status: CertificateStatus;
 	subjectName: string;
+	validUntil: string;
 }
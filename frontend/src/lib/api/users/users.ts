@@ -16,6 +16,7 @@ import type {
 } from '@tanstack/svelte-query';
 import type {
 	UserDto,
+	UserPageDto,
 	ErrorDto,
 	CreateUserDto,
 	GetUserByNameParams,
@@ -152,7 +153,7 @@ export const listUsers = (
 	options?: SecondParameter<typeof customInstance>,
 	signal?: AbortSignal
 ) => {
-	return customInstance<UserDto[]>(
+	return customInstance<UserPageDto>(
 		{ url: `/api/v1/user/list`, method: 'get', params, signal },
 		options
 	);
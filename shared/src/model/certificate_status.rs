@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The status of a certificate issued by the CA
+/// or of a signing request which requires approval
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum CertificateStatus {
+    /// The certificate is neither expired nor revoked
+    Valid,
+    /// The certificate is past its expiry date
+    Expired,
+    /// The certificate has been revoked
+    Revoked,
+    /// The signing request is waiting for the approval of an admin
+    Pending,
+    /// The signing request has been rejected by an admin
+    Rejected,
+}
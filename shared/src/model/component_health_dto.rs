@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The status of a single component the API depends on
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ComponentStatus {
+    /// The component is working as expected
+    Up,
+    /// The component is working but requires attention
+    Degraded,
+    /// The component is not working
+    Down,
+}
+
+impl ComponentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComponentStatus::Up => "UP",
+            ComponentStatus::Degraded => "DEGRADED",
+            ComponentStatus::Down => "DOWN",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ComponentHealthDto {
+    /// The name of the component
+    #[schema(example = "database")]
+    pub name: String,
+    /// The status of the component
+    pub status: ComponentStatus,
+    /// Details on the status of the component
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// The number of days until the certificate expires,
+    /// only set for certificate components
+    #[serde(rename = "expiresInDays", skip_serializing_if = "Option::is_none")]
+    pub expires_in_days: Option<i64>,
+}
+
+impl ComponentHealthDto {
+    pub fn new<T: ToString>(name: T, status: ComponentStatus, message: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            message,
+            expires_in_days: None,
+        }
+    }
+
+    pub fn up<T: ToString>(name: T) -> Self {
+        Self::new(name, ComponentStatus::Up, None)
+    }
+
+    pub fn degraded<T: ToString, M: ToString>(name: T, message: M) -> Self {
+        Self::new(name, ComponentStatus::Degraded, Some(message.to_string()))
+    }
+
+    pub fn down<T: ToString, M: ToString>(name: T, message: M) -> Self {
+        Self::new(name, ComponentStatus::Down, Some(message.to_string()))
+    }
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Proof that an older state of the issuance log
+/// is a prefix of a newer one
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConsistencyProofDto {
+    /// The size of the older tree
+    pub first: u64,
+    /// The size of the newer tree
+    pub second: u64,
+    /// The hex encoded proof nodes
+    pub proof: Vec<String>,
+}
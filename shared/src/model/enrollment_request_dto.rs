@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A certificate signing request authenticated by a one-time enrollment token
+#[derive(Deserialize, Serialize, ToSchema, Debug)]
+pub struct EnrollmentRequestDto {
+    /// The enrollment token, it can only be redeemed once
+    pub token: String,
+    /// The client certificate
+    pub request: String,
+    /// Alternative names for the certificate
+    #[serde(rename = "alternativeNames", skip_serializing_if = "Option::is_none")]
+    pub alternative_names: Option<Vec<String>>,
+    /// Whether to issue a renew-only token along with the certificate.
+    /// Replaces the current token of the client.
+    /// Always issued if the client requires approval.
+    /// Defaults to false, renewals are then authenticated using the certificate.
+    #[serde(rename = "renewalToken", skip_serializing_if = "Option::is_none")]
+    pub renewal_token: Option<bool>,
+}
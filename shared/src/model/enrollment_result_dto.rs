@@ -0,0 +1,14 @@
+use crate::model::signing_request_dto::SigningRequestDto;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The result of redeeming an enrollment token
+#[derive(Deserialize, Serialize, ToSchema, Debug)]
+pub struct EnrollmentResultDto {
+    /// The issued certificate or, if the client requires approval,
+    /// the pending signing request
+    pub certificate: SigningRequestDto,
+    /// The renew-only token of the client, if requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
@@ -1,3 +1,4 @@
+use crate::model::component_health_dto::{ComponentHealthDto, ComponentStatus};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -6,12 +7,12 @@ pub struct HealthInfoDto {
     /// The current version of the API
     #[schema(example = "1.0.0")]
     pub version: String,
-    /// The current version of keycloak
+    /// The current version of keycloak, if it is used as identity provider
     #[schema(example = "1.0.0")]
     #[serde(rename = "keycloakVersion", skip_serializing_if = "Option::is_none")]
     pub keycloak_version: Option<String>,
     /// The current status of the API
-    #[schema(example = "OK")]
+    #[schema(example = "UP")]
     pub status: String,
     /// Whether the API is up and running
     #[schema(example = "true")]
@@ -19,4 +20,23 @@ pub struct HealthInfoDto {
     /// Whether the API is initialized
     #[serde(rename = "isInitialized", skip_serializing_if = "Option::is_none")]
     pub is_initialized: Option<bool>,
+    /// The status of the components the API depends on
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<ComponentHealthDto>,
+}
+
+impl HealthInfoDto {
+    /// The worst status of all components
+    pub fn overall_status(components: &[ComponentHealthDto]) -> ComponentStatus {
+        if components.iter().any(|c| c.status == ComponentStatus::Down) {
+            ComponentStatus::Down
+        } else if components
+            .iter()
+            .any(|c| c.status == ComponentStatus::Degraded)
+        {
+            ComponentStatus::Degraded
+        } else {
+            ComponentStatus::Up
+        }
+    }
 }
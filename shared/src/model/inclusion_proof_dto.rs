@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Proof that a certificate is contained in the issuance log
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InclusionProofDto {
+    /// The index of the certificate in the log
+    #[serde(rename = "leafIndex")]
+    pub leaf_index: u64,
+    /// The size of the tree the proof is for
+    #[serde(rename = "treeSize")]
+    pub tree_size: u64,
+    /// The hex encoded leaf hash of the certificate
+    #[serde(rename = "leafHash")]
+    pub leaf_hash: String,
+    /// The hex encoded audit path from the leaf to the root
+    #[serde(rename = "auditPath")]
+    pub audit_path: Vec<String>,
+}
@@ -1,3 +1,10 @@
+pub mod certificate_status;
+pub mod component_health_dto;
+pub mod consistency_proof_dto;
+pub mod enrollment_request_dto;
+pub mod enrollment_result_dto;
 pub mod health_info_dto;
+pub mod inclusion_proof_dto;
 pub mod new_signing_request_dto;
+pub mod signed_tree_head_dto;
 pub mod signing_request_dto;
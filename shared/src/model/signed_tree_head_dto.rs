@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The signed head of the certificate issuance log
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SignedTreeHeadDto {
+    /// The number of certificates in the log
+    #[serde(rename = "treeSize")]
+    pub tree_size: u64,
+    /// The hex encoded merkle tree root hash
+    #[serde(rename = "rootHash")]
+    pub root_hash: String,
+    /// The unix timestamp (seconds) of the tree head
+    pub timestamp: i64,
+    /// The hex encoded SHA-256 signature of `<treeSize>.<timestamp>.<rootHash>`
+    pub signature: String,
+    /// The PEM encoded CA certificate whose key signed the tree head
+    pub certificate: String,
+}
+
+impl SignedTreeHeadDto {
+    /// The data covered by the signature
+    pub fn signature_input(tree_size: u64, timestamp: i64, root_hash: &str) -> String {
+        format!("{}.{}.{}", tree_size, timestamp, root_hash)
+    }
+}
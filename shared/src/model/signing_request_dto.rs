@@ -1,17 +1,39 @@
+use crate::model::certificate_status::CertificateStatus;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SigningRequestDto {
-    #[serde(rename = "clientId")]
-    pub client_id: String,
-    pub hash: String,
-    #[serde(rename = "issuedAt")]
-    pub issued_at: String,
+    /// The id of the issued certificate,
+    /// or the id of the approval request while the status is pending or rejected
+    pub id: i32,
+    /// The id of the client the certificate was issued to,
+    /// not set for personal certificates of a user
+    #[serde(rename = "clientId", skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// The id of the user who requested the certificate,
+    /// not set if it was requested by a client using its token
+    #[serde(rename = "requestedBy", skip_serializing_if = "Option::is_none")]
+    pub requested_by: Option<String>,
+    /// Not set until the certificate is issued
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// Not set until the certificate is issued
+    #[serde(rename = "issuedAt", skip_serializing_if = "Option::is_none")]
+    pub issued_at: Option<String>,
+    /// Not set until the certificate is issued
+    #[serde(rename = "validUntil", skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<String>,
+    #[serde(rename = "revokedAt", skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<String>,
+    pub status: CertificateStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub certificate: Option<String>,
-    #[serde(rename = "serialNumber")]
-    pub serial_number: String,
+    /// Not set until the certificate is issued
+    #[serde(rename = "serialNumber", skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
     #[serde(rename = "subjectName")]
     pub subject_name: String,
+    #[serde(rename = "alternativeNames")]
+    pub alternative_names: Vec<String>,
 }
@@ -0,0 +1,13 @@
+use crate::util::types::BasicResult;
+
+/// Decode a hex string without separators
+pub fn decode_hex(hex: &str) -> BasicResult<Vec<u8>> {
+    if !hex.is_ascii() || hex.len() & 1 == 1 {
+        return Err("Invalid hex string".into());
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| e.into()))
+        .collect()
+}
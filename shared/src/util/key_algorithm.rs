@@ -0,0 +1,70 @@
+use crate::util::types::BasicResult;
+use openssl::ec::{EcGroup, EcKey};
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private};
+use openssl::rsa::Rsa;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The algorithm used to generate certificate key pairs
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum KeyAlgorithm {
+    /// ECDSA using the NIST P-256 curve
+    EcP256,
+    /// ECDSA using the NIST P-384 curve
+    EcP384,
+    Rsa2048,
+    Rsa4096,
+}
+
+impl KeyAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyAlgorithm::EcP256 => "ec-p256",
+            KeyAlgorithm::EcP384 => "ec-p384",
+            KeyAlgorithm::Rsa2048 => "rsa-2048",
+            KeyAlgorithm::Rsa4096 => "rsa-4096",
+        }
+    }
+
+    /// Generate a new key pair using this algorithm
+    pub fn generate(&self) -> BasicResult<PKey<Private>> {
+        let key_pair = match self {
+            KeyAlgorithm::EcP256 => Self::generate_ec(Nid::X9_62_PRIME256V1)?,
+            KeyAlgorithm::EcP384 => Self::generate_ec(Nid::SECP384R1)?,
+            KeyAlgorithm::Rsa2048 => PKey::from_rsa(Rsa::generate(2048)?)?,
+            KeyAlgorithm::Rsa4096 => PKey::from_rsa(Rsa::generate(4096)?)?,
+        };
+
+        Ok(key_pair)
+    }
+
+    fn generate_ec(curve: Nid) -> BasicResult<PKey<Private>> {
+        let group = EcGroup::from_curve_name(curve)?;
+        let private = EcKey::generate(&group)?;
+        PKey::from_ec_key(private).map_err(|e| e.into())
+    }
+}
+
+impl FromStr for KeyAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ec-p256" => Ok(KeyAlgorithm::EcP256),
+            "ec-p384" => Ok(KeyAlgorithm::EcP384),
+            "rsa-2048" => Ok(KeyAlgorithm::Rsa2048),
+            "rsa-4096" => Ok(KeyAlgorithm::Rsa4096),
+            _ => Err(format!(
+                "Unsupported key algorithm '{}', expected one of ec-p256, ec-p384, rsa-2048 or rsa-4096",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for KeyAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
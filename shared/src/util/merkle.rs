@@ -0,0 +1,483 @@
+//! Merkle tree hashing as specified in RFC 9162 (Certificate Transparency v2).
+//! Leaves are hashed as `SHA-256(0x00 || data)`, interior nodes
+//! as `SHA-256(0x01 || left || right)`.
+
+use crate::util::hex::decode_hex;
+use crate::util::types::BasicResult;
+use openssl::sha::{sha256, Sha256};
+
+pub type Hash = [u8; 32];
+
+pub fn leaf_hash(data: &[u8]) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(&[0x00]);
+    hasher.update(data);
+    hasher.finish()
+}
+
+pub fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(&[0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finish()
+}
+
+/// A perfect subtree of `2^level` leaves, the `index`-th one of its level.
+/// Level 0 contains the leaves themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Node {
+    pub level: u32,
+    pub index: u64,
+}
+
+impl Node {
+    pub fn new(level: u32, index: u64) -> Self {
+        Self { level, index }
+    }
+
+    /// The number of leaves of the subtree
+    pub fn size(&self) -> u64 {
+        1 << self.level
+    }
+
+    /// The index of the first leaf of the subtree
+    pub fn start(&self) -> u64 {
+        self.index << self.level
+    }
+}
+
+/// The largest power of two smaller than `n`, `n` must be greater than one
+fn split(n: u64) -> u64 {
+    let mut k = 1;
+    while k << 1 < n {
+        k <<= 1;
+    }
+    k
+}
+
+/// The root hash of a tree with the given leaf hashes
+pub fn root_hash(leaves: &[Hash]) -> Hash {
+    match leaves.len() {
+        0 => sha256(&[]),
+        1 => leaves[0],
+        n => {
+            let k = split(n as u64) as usize;
+            node_hash(&root_hash(&leaves[..k]), &root_hash(&leaves[k..]))
+        }
+    }
+}
+
+/// The perfect subtrees the subtree of the leaves `start..end` consists of,
+/// ordered from left to right. The range must be one of the subtrees
+/// a proof consists of, i.e. one returned by [`inclusion_path`] or
+/// [`consistency_path`], or start at index 0.
+pub fn subtrees(start: u64, end: u64) -> Vec<Node> {
+    let mut nodes = vec![];
+    let mut start = start;
+    while start < end {
+        let mut level = 0;
+        while start.is_multiple_of(2 << level) && start + (2 << level) <= end {
+            level += 1;
+        }
+
+        nodes.push(Node::new(level, start >> level));
+        start += 1 << level;
+    }
+
+    nodes
+}
+
+/// The root hash of a subtree, given the hashes of
+/// the perfect subtrees returned by [`subtrees`]
+pub fn fold_subtrees(hashes: &[Hash]) -> Hash {
+    match hashes.split_last() {
+        Some((last, rest)) => rest
+            .iter()
+            .rev()
+            .fold(*last, |hash, left| node_hash(left, &hash)),
+        None => sha256(&[]),
+    }
+}
+
+/// The ranges of leaves whose subtree hashes make up the audit
+/// path of the leaf at `index` in the tree with the given size
+pub fn inclusion_path(index: u64, tree_size: u64) -> Vec<(u64, u64)> {
+    let mut path = vec![];
+    if index < tree_size {
+        sub_inclusion_path(0, tree_size, index, &mut path);
+    }
+
+    path
+}
+
+fn sub_inclusion_path(start: u64, end: u64, index: u64, path: &mut Vec<(u64, u64)>) {
+    if end - start <= 1 {
+        return;
+    }
+
+    let k = start + split(end - start);
+    if index < k {
+        sub_inclusion_path(start, k, index, path);
+        path.push((k, end));
+    } else {
+        sub_inclusion_path(k, end, index, path);
+        path.push((start, k));
+    }
+}
+
+/// The ranges of leaves whose subtree hashes make up the proof that
+/// the tree of the first `size` leaves is a prefix of the tree with the given size
+pub fn consistency_path(size: u64, tree_size: u64) -> Vec<(u64, u64)> {
+    let mut path = vec![];
+    if size > 0 && size <= tree_size {
+        sub_consistency_path(0, tree_size, size, true, &mut path);
+    }
+
+    path
+}
+
+fn sub_consistency_path(
+    start: u64,
+    end: u64,
+    size: u64,
+    complete: bool,
+    path: &mut Vec<(u64, u64)>,
+) {
+    if size == end {
+        if !complete {
+            path.push((start, end));
+        }
+        return;
+    }
+
+    let k = start + split(end - start);
+    if size <= k {
+        sub_consistency_path(start, k, size, complete, path);
+        path.push((k, end));
+    } else {
+        sub_consistency_path(k, end, size, false, path);
+        path.push((start, k));
+    }
+}
+
+/// The audit path proving the inclusion of the leaf at `index`
+pub fn inclusion_proof(leaves: &[Hash], index: usize) -> Vec<Hash> {
+    inclusion_path(index as u64, leaves.len() as u64)
+        .into_iter()
+        .map(|(start, end)| root_hash(&leaves[start as usize..end as usize]))
+        .collect()
+}
+
+/// The proof that the tree of the first `size` leaves is a prefix of the whole tree
+pub fn consistency_proof(leaves: &[Hash], size: usize) -> Vec<Hash> {
+    consistency_path(size as u64, leaves.len() as u64)
+        .into_iter()
+        .map(|(start, end)| root_hash(&leaves[start as usize..end as usize]))
+        .collect()
+}
+
+/// Verify that `leaf` is the leaf at `index` of the tree with the given size and root
+pub fn verify_inclusion(
+    leaf: &Hash,
+    index: u64,
+    tree_size: u64,
+    proof: &[Hash],
+    root: &Hash,
+) -> bool {
+    if index >= tree_size {
+        return false;
+    }
+
+    let (mut fn_, mut sn) = (index, tree_size - 1);
+    let mut hash = *leaf;
+    for p in proof {
+        if sn == 0 {
+            return false;
+        }
+
+        if fn_ & 1 == 1 || fn_ == sn {
+            hash = node_hash(p, &hash);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            hash = node_hash(&hash, p);
+        }
+
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+
+    sn == 0 && &hash == root
+}
+
+/// Verify that the tree with `first_size` leaves and `first_root`
+/// is a prefix of the tree with `second_size` leaves and `second_root`
+pub fn verify_consistency(
+    first_size: u64,
+    second_size: u64,
+    first_root: &Hash,
+    second_root: &Hash,
+    proof: &[Hash],
+) -> bool {
+    if first_size > second_size {
+        return false;
+    } else if first_size == second_size {
+        return proof.is_empty() && first_root == second_root;
+    } else if first_size == 0 {
+        return proof.is_empty();
+    } else if proof.is_empty() {
+        return false;
+    }
+
+    let mut path = proof.to_vec();
+    if first_size.is_power_of_two() {
+        path.insert(0, *first_root);
+    }
+
+    let (mut fn_, mut sn) = (first_size - 1, second_size - 1);
+    while fn_ & 1 == 1 {
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+
+    let (mut first, mut second) = (path[0], path[0]);
+    for c in &path[1..] {
+        if sn == 0 {
+            return false;
+        }
+
+        if fn_ & 1 == 1 || fn_ == sn {
+            first = node_hash(c, &first);
+            second = node_hash(c, &second);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            second = node_hash(&second, c);
+        }
+
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+
+    sn == 0 && &first == first_root && &second == second_root
+}
+
+/// Parse a hex encoded hash
+pub fn decode_hash(hex: &str) -> BasicResult<Hash> {
+    decode_hex(hex)?
+        .try_into()
+        .map_err(|_| "Invalid hash length".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The leaf data of the test vectors of RFC 9162 / RFC 6962
+    const LEAVES: [&str; 8] = [
+        "",
+        "00",
+        "10",
+        "2021",
+        "3031",
+        "40414243",
+        "5051525354555657",
+        "606162636465666768696a6b6c6d6e6f",
+    ];
+
+    /// The root hashes of the trees of the first one to eight leaves
+    const ROOTS: [&str; 8] = [
+        "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+        "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+        "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77",
+        "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+        "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+        "76e67dadbcdf1e10e1b74ddc608abd2f98dfb16fbce75277b5232a127f2087ef",
+        "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c",
+        "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+    ];
+
+    fn leaves() -> Vec<Hash> {
+        LEAVES
+            .iter()
+            .map(|l| leaf_hash(&decode_hex(l).unwrap()))
+            .collect()
+    }
+
+    fn hashes(hex: &[&str]) -> Vec<Hash> {
+        hex.iter().map(|h| decode_hash(h).unwrap()).collect()
+    }
+
+    /// The root hash of the leaves `start..end`, computed from their perfect subtrees
+    fn subtree_hash(leaves: &[Hash], start: u64, end: u64) -> Hash {
+        let nodes = subtrees(start, end)
+            .into_iter()
+            .map(|n| root_hash(&leaves[n.start() as usize..(n.start() + n.size()) as usize]))
+            .collect::<Vec<_>>();
+        fold_subtrees(&nodes)
+    }
+
+    #[test]
+    fn computes_the_root_hashes() {
+        let leaves = leaves();
+        assert_eq!(
+            root_hash(&[]),
+            decode_hash("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+                .unwrap()
+        );
+
+        for (size, root) in ROOTS.iter().enumerate() {
+            let root = decode_hash(root).unwrap();
+            assert_eq!(root_hash(&leaves[..=size]), root);
+            assert_eq!(subtree_hash(&leaves, 0, size as u64 + 1), root);
+        }
+    }
+
+    #[test]
+    fn computes_the_inclusion_proofs() {
+        let leaves = leaves();
+        let vectors: [(usize, usize, &[&str]); 5] = [
+            (0, 1, &[]),
+            (
+                0,
+                8,
+                &[
+                    "96a296d224f285c67bee93c30f8a309157f0daa35dc5b87e410b78630a09cfc7",
+                    "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+                    "6b47aaf29ee3c2af9af889bc1fb9254dabd31177f16232dd6aab035ca39bf6e4",
+                ],
+            ),
+            (
+                5,
+                8,
+                &[
+                    "bc1a0643b12e4d2d7c77918f44e0f4f79a838b6cf9ec5b5c283e1f4d88599e6b",
+                    "ca854ea128ed050b41b35ffc1b87b8eb2bde461e9e3b5596ece6b9d5975a0ae0",
+                    "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+                ],
+            ),
+            (
+                2,
+                3,
+                &["fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125"],
+            ),
+            (
+                1,
+                5,
+                &[
+                    "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+                    "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+                    "bc1a0643b12e4d2d7c77918f44e0f4f79a838b6cf9ec5b5c283e1f4d88599e6b",
+                ],
+            ),
+        ];
+
+        for (index, size, expected) in vectors {
+            let expected = hashes(expected);
+            let root = decode_hash(ROOTS[size - 1]).unwrap();
+
+            assert_eq!(inclusion_proof(&leaves[..size], index), expected);
+            assert!(verify_inclusion(
+                &leaves[index],
+                index as u64,
+                size as u64,
+                &expected,
+                &root
+            ));
+            assert!(!verify_inclusion(
+                &leaves[(index + 1) % 8],
+                index as u64,
+                size as u64,
+                &expected,
+                &root
+            ));
+        }
+    }
+
+    #[test]
+    fn computes_the_consistency_proofs() {
+        let leaves = leaves();
+        let vectors: [(usize, usize, &[&str]); 4] = [
+            (1, 1, &[]),
+            (
+                1,
+                8,
+                &[
+                    "96a296d224f285c67bee93c30f8a309157f0daa35dc5b87e410b78630a09cfc7",
+                    "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+                    "6b47aaf29ee3c2af9af889bc1fb9254dabd31177f16232dd6aab035ca39bf6e4",
+                ],
+            ),
+            (
+                6,
+                8,
+                &[
+                    "0ebc5d3437fbe2db158b9f126a1d118e308181031d0a949f8dededebc558ef6a",
+                    "ca854ea128ed050b41b35ffc1b87b8eb2bde461e9e3b5596ece6b9d5975a0ae0",
+                    "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+                ],
+            ),
+            (
+                2,
+                5,
+                &[
+                    "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+                    "bc1a0643b12e4d2d7c77918f44e0f4f79a838b6cf9ec5b5c283e1f4d88599e6b",
+                ],
+            ),
+        ];
+
+        for (first, second, expected) in vectors {
+            let expected = hashes(expected);
+            let first_root = decode_hash(ROOTS[first - 1]).unwrap();
+            let second_root = decode_hash(ROOTS[second - 1]).unwrap();
+
+            assert_eq!(consistency_proof(&leaves[..second], first), expected);
+            assert!(verify_consistency(
+                first as u64,
+                second as u64,
+                &first_root,
+                &second_root,
+                &expected
+            ));
+            if first != second {
+                assert!(!verify_consistency(
+                    first as u64,
+                    second as u64,
+                    &second_root,
+                    &first_root,
+                    &expected
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn computes_the_proof_subtrees_from_perfect_subtrees() {
+        let leaves = (0..37u8).map(|i| leaf_hash(&[i])).collect::<Vec<_>>();
+        for size in 1..=leaves.len() as u64 {
+            for index in 0..size {
+                for (start, end) in inclusion_path(index, size) {
+                    assert_eq!(
+                        subtree_hash(&leaves, start, end),
+                        root_hash(&leaves[start as usize..end as usize])
+                    );
+                }
+            }
+
+            for first in 1..=size {
+                for (start, end) in consistency_path(first, size) {
+                    assert_eq!(
+                        subtree_hash(&leaves, start, end),
+                        root_hash(&leaves[start as usize..end as usize])
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,7 @@
+pub mod hex;
+pub mod key_algorithm;
 pub mod logger;
+pub mod merkle;
+pub mod request_signature;
 pub mod traits;
 pub mod types;
@@ -0,0 +1,73 @@
+use crate::util::traits::u8_vec_to_string::U8VecToString;
+use crate::util::types::BasicResult;
+use openssl::hash::MessageDigest;
+use openssl::pkey::{HasPublic, PKeyRef, Private};
+use openssl::sha::sha256;
+use openssl::sign::{Signer, Verifier};
+
+/// The header containing the base64 encoded DER certificate of the client
+pub const CERTIFICATE_HEADER: &str = "X-Client-Certificate";
+/// The header containing the unix timestamp the request was signed at
+pub const TIMESTAMP_HEADER: &str = "X-Client-Timestamp";
+/// The header containing the base64 encoded signature of the request
+pub const SIGNATURE_HEADER: &str = "X-Client-Signature";
+/// The maximum difference between the signing timestamp and the server time.
+/// Each timestamp can only be used once per certificate.
+pub const MAX_CLOCK_SKEW_SECS: i64 = 5 * 60;
+
+/// The message which is signed for a request, consisting of the method,
+/// the path, the timestamp and the hex encoded SHA-256 hash of the body
+pub fn message(method: &str, path: &str, timestamp: i64, body: &[u8]) -> String {
+    format!(
+        "{} {}\n{}\n{}",
+        method.to_uppercase(),
+        path,
+        timestamp,
+        sha256(body).to_vec().to_hex_string("")
+    )
+}
+
+/// Sign a request with the private key of a certificate
+pub fn sign(
+    key: &PKeyRef<Private>,
+    method: &str,
+    path: &str,
+    timestamp: i64,
+    body: &[u8],
+) -> BasicResult<Vec<u8>> {
+    let mut signer = Signer::new(MessageDigest::sha256(), key)?;
+    signer.update(message(method, path, timestamp, body).as_bytes())?;
+    Ok(signer.sign_to_vec()?)
+}
+
+/// Verify the signature of a request using the public key of a certificate
+pub fn verify<T: HasPublic>(
+    key: &PKeyRef<T>,
+    method: &str,
+    path: &str,
+    timestamp: i64,
+    body: &[u8],
+    signature: &[u8],
+) -> BasicResult<bool> {
+    let mut verifier = Verifier::new(MessageDigest::sha256(), key)?;
+    verifier.update(message(method, path, timestamp, body).as_bytes())?;
+    Ok(verifier.verify(signature)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+
+    #[test]
+    fn verifies_the_signed_request() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let signature = sign(&key, "POST", "/renew", 1000, b"{}").unwrap();
+
+        assert!(verify(&key, "post", "/renew", 1000, b"{}", &signature).unwrap());
+        assert!(!verify(&key, "POST", "/renew", 1000, b"{ }", &signature).unwrap());
+        assert!(!verify(&key, "POST", "/renew", 1001, b"{}", &signature).unwrap());
+        assert!(!verify(&key, "POST", "/renew/1", 1000, b"{}", &signature).unwrap());
+    }
+}